@@ -26,6 +26,7 @@ enum ParseError {
     InvalidUIDBase(String),
     InvalidUIDCount(String),
     InvalidUIDStep(String),
+    InvalidColumns(String),
 }
 
 fn is_range(name: &str) -> bool {
@@ -96,6 +97,11 @@ struct Table {
     uid_ranges: Vec<UIDRange>,
 }
 
+struct TableColumns {
+    table: String,
+    names: Vec<String>,
+}
+
 #[derive(Clone)]
 struct UID {
     name: String,
@@ -452,6 +458,27 @@ impl UIDRange {
     }
 }
 
+impl TableColumns {
+    fn parse(table: String, data: &Value) -> Result<Self, ParseError> {
+        let names = match data {
+            Value::Array(names) => names
+                .iter()
+                .map(|name| name.as_str().map(String::from).ok_or(ParseError::InvalidColumns(table.clone())))
+                .collect::<Result<Vec<_>, _>>(),
+            _ => Err(ParseError::InvalidColumns(table.clone())),
+        }?;
+        Ok(Self { table, names })
+    }
+
+    fn generate(&self) -> TokenStream2 {
+        let table = to_const_identifier(&self.table);
+        let names = &self.names;
+        quote! {
+            (root::core::all::table_id::#table.as_uid(), &[ #(#names),* ])
+        }
+    }
+}
+
 fn parse(spec_json: &Value) -> Result<Vec<Feature>, ParseError> {
     match spec_json {
         Value::Object(features) => features
@@ -462,6 +489,16 @@ fn parse(spec_json: &Value) -> Result<Vec<Feature>, ParseError> {
     }
 }
 
+fn parse_columns(columns_json: &Value) -> Result<Vec<TableColumns>, ParseError> {
+    match columns_json {
+        Value::Object(tables) => tables
+            .iter()
+            .map(|(name, value)| TableColumns::parse(name.clone(), value))
+            .collect::<Result<Vec<_>, _>>(),
+        _ => Err(ParseError::InvalidFile),
+    }
+}
+
 fn generate(features: &[Feature]) -> TokenStream2 {
     let features = features.iter().map(|x| x.generate());
     quote! {
@@ -469,18 +506,42 @@ fn generate(features: &[Feature]) -> TokenStream2 {
     }
 }
 
+fn generate_columns(columns: &[TableColumns]) -> TokenStream2 {
+    let num_tables = columns.len();
+    let tables = columns.iter().map(|x| x.generate());
+    quote! {
+        pub mod columns {
+            use super::root;
+
+            const COLUMN_NAMES: [(root::UID, &[&str]); #num_tables] = [ #(#tables),* ];
+
+            /// Returns the names of the columns of `table` in the order of their indices.
+            pub fn column_names(table: root::UID) -> &'static [&'static str] {
+                COLUMN_NAMES.iter().find(|(uid, _)| *uid == table).map(|(_, names)| *names).unwrap_or(&[])
+            }
+        }
+    }
+}
+
 fn main() -> Result<(), ()> {
     let spec_path = "src/spec/spec.json";
     let spec_file = File::open(spec_path).unwrap();
     let spec_json: Value = serde_json::from_reader(spec_file).unwrap();
     let spec_data = parse(&spec_json).unwrap();
 
-    let content = generate(&spec_data);
+    let columns_path = "src/spec/columns.json";
+    let columns_file = File::open(columns_path).unwrap();
+    let columns_json: Value = serde_json::from_reader(columns_file).unwrap();
+    let columns_data = parse_columns(&columns_json).unwrap();
+
+    let mut content = generate(&spec_data);
+    content.extend(generate_columns(&columns_data));
 
     let mut out_path = PathBuf::from(std::env::var("OUT_DIR").unwrap());
     out_path.push("spec.rs");
     let mut out_file = File::create(out_path).unwrap();
     out_file.write_all(content.to_string().as_bytes()).unwrap();
     println!("cargo::rerun-if-changed={spec_path}");
+    println!("cargo::rerun-if-changed={columns_path}");
     Ok(())
 }
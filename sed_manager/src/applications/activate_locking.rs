@@ -67,47 +67,50 @@ pub async fn verify_locking_activation(tper: &TPer, admin1_password: Option<&[u8
 mod tests {
     use std::sync::Arc;
 
-    use crate::{
-        fake_device::{FakeDevice, MSID_PASSWORD},
-        rpc::TokioRuntime,
-        tper::TPer,
-    };
+    use crate::applications::test_fixtures::{for_each_ssc, make_factory_device_of};
+    use crate::messaging::discovery::FeatureCode;
+    use crate::{fake_device::MSID_PASSWORD, rpc::TokioRuntime, tper::TPer};
 
     use super::*;
 
-    #[tokio::test]
-    async fn activate_locking_success_no_pw() -> Result<(), Error> {
+    async fn activate_locking_success(ssc: FeatureCode, new_password: Option<&[u8]>) -> Result<(), Error> {
         let sid_password = MSID_PASSWORD.as_bytes();
-        let new_password = None;
-        let device = Arc::new(FakeDevice::new());
+        let device = Arc::new(make_factory_device_of(ssc));
         let runtime = Arc::new(TokioRuntime::new());
         let tper = TPer::new_on_default_com_id(device, runtime)?;
-        activate_locking(&tper, sid_password, new_password).await?;
-        verify_locking_activation(&tper, Some(sid_password)).await?;
+        let result = activate_locking(&tper, sid_password, new_password).await;
+        if ssc == FeatureCode::Enterprise {
+            // The Enterprise Locking SP is issued in the factory, there is nothing to activate.
+            assert!(matches!(result, Err(Error::AlreadyActivated)));
+        } else {
+            result?;
+        }
+        verify_locking_activation(&tper, new_password.or(Some(sid_password))).await?;
         Ok(())
     }
 
     #[tokio::test]
-    async fn activate_locking_success_with_pw() -> Result<(), Error> {
-        let sid_password = MSID_PASSWORD.as_bytes();
-        let new_password = Some("macilaci".as_bytes());
-        let device = Arc::new(FakeDevice::new());
-        let runtime = Arc::new(TokioRuntime::new());
-        let tper = TPer::new_on_default_com_id(device, runtime)?;
-        activate_locking(&tper, sid_password, new_password).await?;
-        verify_locking_activation(&tper, new_password).await?;
-        Ok(())
+    async fn activate_locking_success_no_pw() {
+        for_each_ssc(async |ssc| activate_locking_success(ssc, None).await).await
     }
 
     #[tokio::test]
-    async fn activate_locking_already_locked() -> Result<(), Error> {
-        let sid_password = MSID_PASSWORD.as_bytes();
-        let new_password = Some("macilaci".as_bytes());
-        let device = Arc::new(FakeDevice::new());
-        let runtime = Arc::new(TokioRuntime::new());
-        let tper = TPer::new_on_default_com_id(device, runtime)?;
-        activate_locking(&tper, sid_password, new_password).await?;
-        assert!(activate_locking(&tper, sid_password, new_password).await.is_err());
-        Ok(())
+    async fn activate_locking_success_with_pw() {
+        for_each_ssc(async |ssc| activate_locking_success(ssc, Some("macilaci".as_bytes())).await).await
+    }
+
+    #[tokio::test]
+    async fn activate_locking_already_locked() {
+        for_each_ssc(async |ssc| {
+            let sid_password = MSID_PASSWORD.as_bytes();
+            let new_password = Some("macilaci".as_bytes());
+            let device = Arc::new(make_factory_device_of(ssc));
+            let runtime = Arc::new(TokioRuntime::new());
+            let tper = TPer::new_on_default_com_id(device, runtime)?;
+            let _ = activate_locking(&tper, sid_password, new_password).await;
+            assert!(activate_locking(&tper, sid_password, new_password).await.is_err(), "{ssc:?}");
+            Ok(())
+        })
+        .await
     }
 }
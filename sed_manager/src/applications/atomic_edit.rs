@@ -0,0 +1,45 @@
+//L-----------------------------------------------------------------------------
+//L Copyright (C) Péter Kardos
+//L Please refer to the full license distributed with this software.
+//L-----------------------------------------------------------------------------
+
+use core::future::Future;
+
+use super::Error;
+
+/// An editor session that can apply multi-step changes all at once.
+pub trait AtomicEdit: sealed::TransactionSession {
+    /// Applies all changes made by `f`, or none of them if `f` fails.
+    ///
+    /// If the TPer does not support transactions, `f` runs without one, and the changes made
+    /// before the failure remain. Check [`Self::is_atomic`] to warn the user about that.
+    fn atomic<Output>(
+        &self,
+        f: impl AsyncFnOnce(&Self) -> Result<Output, Error>,
+    ) -> impl Future<Output = Result<Output, Error>> {
+        async move {
+            if self.is_atomic() {
+                self.session().with_transaction(async |_| f(self).await).await
+            } else {
+                f(self).await
+            }
+        }
+    }
+
+    /// Tells if [`Self::atomic`] can roll back the changes when they fail.
+    fn is_atomic(&self) -> bool {
+        self.is_transaction_supported()
+    }
+}
+
+pub(super) mod sealed {
+    use crate::tper::Session;
+
+    /// Gives [`super::AtomicEdit`] access to the session without exposing it to users of the editor.
+    pub trait TransactionSession {
+        fn session(&self) -> &Session;
+        fn is_transaction_supported(&self) -> bool;
+    }
+}
+
+impl<T: sealed::TransactionSession> AtomicEdit for T {}
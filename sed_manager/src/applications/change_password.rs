@@ -130,7 +130,10 @@ pub async fn list_password_authorities(tper: &TPer) -> Result<Vec<(SPRef, Author
 mod tests {
     use std::sync::Arc;
 
-    use crate::applications::test_fixtures::{make_activated_device, setup_activated_tper, setup_factory_tper};
+    use crate::applications::test_fixtures::{
+        for_each_ssc, make_activated_device, make_owned_device_of, setup_activated_tper, setup_factory_tper,
+        SID_PASSWORD,
+    };
     use crate::fake_device::data::object_table::{AuthorityTable, CPINTable};
     use crate::fake_device::FakeDevice;
     use crate::rpc::{MethodStatus, TokioRuntime};
@@ -267,6 +270,28 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn change_password_sid_fake_devices() {
+        for_each_ssc(async |ssc| {
+            let runtime = Arc::new(TokioRuntime::new());
+            let device = Arc::new(make_owned_device_of(ssc));
+            let tper = TPer::new_on_default_com_id(device.clone(), runtime)?;
+            let authority = spec::core::authority::SID;
+            let sp = get_admin_sp(ssc)?;
+            let new_password = "kjgfjs".as_bytes();
+            let result = change_password(&tper, sp, authority, SID_PASSWORD.as_bytes(), new_password).await;
+            if ssc == FeatureCode::Enterprise {
+                // The Enterprise Admin SP does not let SID read its own Authority object to find the credential.
+                assert_eq!(result, Err(Error::RPCError(MethodStatus::NotAuthorized.into())));
+            } else {
+                result?;
+                assert_eq!(get_pin(&device, sp, authority), new_password, "{ssc:?}");
+            }
+            Ok(())
+        })
+        .await
+    }
+
     #[tokio::test]
     async fn change_password_failed_authentication() -> Result<(), Error> {
         let runtime = Arc::new(TokioRuntime::new());
@@ -0,0 +1,197 @@
+//L-----------------------------------------------------------------------------
+//L Copyright (C) Péter Kardos
+//L Please refer to the full license distributed with this software.
+//L-----------------------------------------------------------------------------
+
+use crate::messaging::discovery::{Discovery, FeatureCode, LockingDescriptor};
+use crate::messaging::uid::TableUID;
+use crate::rpc::{Error as RPCError, Properties};
+use crate::spec;
+use crate::spec::basic_types::List;
+use crate::spec::column_types::{ACEOperand, ACERef, AuthorityRef, TableDescRef};
+use crate::spec::objects::{TableDesc, ACE};
+use crate::spec::table_id;
+use crate::tper::{Session, TPer};
+
+use super::utility::{has_permission, max_chunk_len, max_read_len, start_admin1_session, update_permission_expr};
+use super::Error;
+
+pub fn is_data_store_supported(discovery: &Discovery) -> bool {
+    // The DataStore tables and their ACEs are only defined for the Opal family.
+    const SUPPORTED_SSCS: [FeatureCode; 6] = [
+        FeatureCode::OpalV1,
+        FeatureCode::OpalV2,
+        FeatureCode::Opalite,
+        FeatureCode::PyriteV1,
+        FeatureCode::PyriteV2,
+        FeatureCode::Ruby,
+    ];
+    let Some(ssc) = discovery.get_primary_ssc() else {
+        return false;
+    };
+    let Some(locking_desc) = discovery.get::<LockingDescriptor>() else {
+        return false;
+    };
+    SUPPORTED_SSCS.contains(&ssc.feature_code()) && locking_desc.locking_enabled
+}
+
+pub struct DataStoreSession {
+    session: Session,
+    properties: Properties,
+}
+
+impl DataStoreSession {
+    pub async fn start(tper: &TPer, admin1_password: &[u8]) -> Result<Self, Error> {
+        let properties = tper.current_properties().await;
+        Ok(Self { session: start_admin1_session(tper, admin1_password).await?, properties })
+    }
+
+    pub async fn end(self) -> Result<(), Error> {
+        Ok(self.session.end_session().await?)
+    }
+
+    /// Lists the DataStore table and the additional DataStore tables, if any.
+    pub async fn list_tables(&self) -> Result<Vec<TableUID>, Error> {
+        let descriptors = self.session.next(table_id::TABLE, None, None).await?;
+        let data_store_descriptors = spec::data_store::locking::table::DATA_STORE;
+        Ok(descriptors
+            .into_iter()
+            .filter_map(|uid| TableDescRef::try_from(uid).ok())
+            .filter(|descriptor| data_store_descriptors.contains(*descriptor))
+            .filter_map(|descriptor| descriptor.to_table())
+            .collect())
+    }
+
+    pub async fn get_size(&self, table: TableUID) -> Result<u64, Error> {
+        Ok(self.session.get(table.to_descriptor().as_uid(), TableDesc::ROWS).await?)
+    }
+
+    /// Reads `len` bytes from the DataStore `table` starting at `position`.
+    pub async fn read(&self, table: TableUID, position: u64, len: u64) -> Result<Vec<u8>, Error> {
+        let chunk_len = max_read_len(&self.properties) as u64;
+        let mut data = Vec::with_capacity(len as usize);
+        while (data.len() as u64) < len {
+            let read_len = core::cmp::min(chunk_len, len - data.len() as u64);
+            let chunk = self.session.read(table, position + data.len() as u64, read_len).await?;
+            if chunk.len() as u64 != read_len {
+                return Err(RPCError::ResultTypeMismatch.into()); // Would loop forever on an empty chunk.
+            }
+            data.extend_from_slice(&chunk);
+        }
+        Ok(data)
+    }
+
+    /// Writes `data` to the DataStore `table` starting at `position`.
+    pub async fn write(&self, table: TableUID, position: u64, data: &[u8]) -> Result<(), Error> {
+        let chunk_len = max_chunk_len(&self.properties);
+        for (idx, chunk) in data.chunks(chunk_len).enumerate() {
+            self.session.write(table, position + (idx * chunk_len) as u64, chunk).await?;
+        }
+        Ok(())
+    }
+
+    pub async fn get_read_permission(&self, user: AuthorityRef, table: TableUID) -> Result<bool, Error> {
+        let (get_all, _) = get_aces(table)?;
+        let ace_expr: List<ACEOperand> = self.session.get(get_all.as_uid(), ACE::BOOLEAN_EXPR).await?;
+        Ok(has_permission(&self.session, ace_expr, user).await)
+    }
+
+    pub async fn get_write_permission(&self, user: AuthorityRef, table: TableUID) -> Result<bool, Error> {
+        let (_, set_all) = get_aces(table)?;
+        let ace_expr: List<ACEOperand> = self.session.get(set_all.as_uid(), ACE::BOOLEAN_EXPR).await?;
+        Ok(has_permission(&self.session, ace_expr, user).await)
+    }
+
+    pub async fn set_read_permission(&self, user: AuthorityRef, table: TableUID, permitted: bool) -> Result<(), Error> {
+        let (get_all, _) = get_aces(table)?;
+        self.set_permission(get_all, user, permitted).await
+    }
+
+    pub async fn set_write_permission(
+        &self,
+        user: AuthorityRef,
+        table: TableUID,
+        permitted: bool,
+    ) -> Result<(), Error> {
+        let (_, set_all) = get_aces(table)?;
+        self.set_permission(set_all, user, permitted).await
+    }
+
+    async fn set_permission(&self, ace: ACERef, user: AuthorityRef, permitted: bool) -> Result<(), Error> {
+        let ace_expr: List<ACEOperand> = self.session.get(ace.as_uid(), ACE::BOOLEAN_EXPR).await?;
+        let updated_expr = update_permission_expr(ace_expr, user, permitted)?;
+        Ok(self.session.set(ace.as_uid(), ACE::BOOLEAN_EXPR, List(updated_expr)).await?)
+    }
+}
+
+/// Returns the Get_All and Set_All ACEs of a DataStore table.
+fn get_aces(table: TableUID) -> Result<(ACERef, ACERef), Error> {
+    let idx = spec::data_store::locking::table::DATA_STORE
+        .index_of(table.to_descriptor())
+        .ok_or(Error::IncompatibleSSC)?;
+    let get_all = spec::data_store::locking::ace::DATA_STORE_GET_ALL.nth(idx).ok_or(Error::IncompatibleSSC)?;
+    let set_all = spec::data_store::locking::ace::DATA_STORE_SET_ALL.nth(idx).ok_or(Error::IncompatibleSSC)?;
+    Ok((get_all, set_all))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::applications::test_fixtures::{setup_activated_tper, LOCKING_ADMIN1_PASSWORD};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn list_tables() -> Result<(), Error> {
+        let tper = setup_activated_tper();
+        let session = DataStoreSession::start(&tper, LOCKING_ADMIN1_PASSWORD.as_bytes()).await?;
+        let tables = session.list_tables().await?;
+        assert_eq!(tables.len(), 2);
+        assert!(tables.contains(&table_id::DATA_STORE));
+        for table in tables {
+            assert_eq!(session.get_size(table).await?, 0x00A00000);
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn read_write() -> Result<(), Error> {
+        let tper = setup_activated_tper();
+        let session = DataStoreSession::start(&tper, LOCKING_ADMIN1_PASSWORD.as_bytes()).await?;
+        let tables = session.list_tables().await?;
+        let data: Vec<u8> = (0..150_000).map(|i| (i % 251) as u8).collect();
+        for table in tables {
+            session.write(table, 1000, &data).await?;
+            assert_eq!(session.read(table, 1000, data.len() as u64).await?, data);
+            assert_eq!(session.read(table, 0, 1000).await?, vec![0; 1000]);
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn read_permission() -> Result<(), Error> {
+        let tper = setup_activated_tper();
+        let session = DataStoreSession::start(&tper, LOCKING_ADMIN1_PASSWORD.as_bytes()).await?;
+        let user = spec::opal::locking::authority::USER.nth(1).unwrap();
+        for table in session.list_tables().await? {
+            assert!(!session.get_read_permission(user, table).await?);
+            session.set_read_permission(user, table, true).await?;
+            assert!(session.get_read_permission(user, table).await?);
+            assert!(!session.get_write_permission(user, table).await?);
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn write_permission() -> Result<(), Error> {
+        let tper = setup_activated_tper();
+        let session = DataStoreSession::start(&tper, LOCKING_ADMIN1_PASSWORD.as_bytes()).await?;
+        let user = spec::opal::locking::authority::USER.nth(1).unwrap();
+        for table in session.list_tables().await? {
+            session.set_write_permission(user, table, true).await?;
+            assert!(session.get_write_permission(user, table).await?);
+            session.set_write_permission(user, table, false).await?;
+            assert!(!session.get_write_permission(user, table).await?);
+        }
+        Ok(())
+    }
+}
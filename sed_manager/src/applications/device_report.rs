@@ -0,0 +1,45 @@
+//L-----------------------------------------------------------------------------
+//L Copyright (C) Péter Kardos
+//L Please refer to the full license distributed with this software.
+//L-----------------------------------------------------------------------------
+
+use crate::device::Device;
+use crate::rpc::discover;
+use serde_json::{json, Value};
+
+/// Describes the identity and the discovery data of a device as a JSON object.
+///
+/// Devices that fail discovery still get a report: `discovery` is null and
+/// `error` holds the reason.
+pub fn get_device_report(device: &dyn Device) -> Value {
+    let (discovery, error) = match discover(device) {
+        Ok(discovery) => (Value::from(&discovery), Value::Null),
+        Err(error) => (Value::Null, Value::from(error.to_string())),
+    };
+    json!({
+        "path": device.path(),
+        "interface": device.interface().to_string(),
+        "model_number": device.model_number(),
+        "serial_number": device.serial_number(),
+        "firmware_revision": device.firmware_revision(),
+        "discovery": discovery,
+        "error": error,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fake_device::FakeDevice;
+
+    #[test]
+    fn fake_device_report() {
+        let report = get_device_report(&FakeDevice::new());
+        assert_eq!(report.get("model_number"), Some(&Value::from("Virtual Test Device")));
+        assert_eq!(report.get("serial_number"), Some(&Value::from("SN123456")));
+        assert_eq!(report.get("firmware_revision"), Some(&Value::from("FW1234")));
+        assert_eq!(report.get("error"), Some(&Value::Null));
+        let discovery = report.get("discovery").unwrap();
+        assert_eq!(discovery.get("primary_ssc"), Some(&Value::from("opal_v2")));
+    }
+}
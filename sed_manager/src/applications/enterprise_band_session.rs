@@ -0,0 +1,242 @@
+//L-----------------------------------------------------------------------------
+//L Copyright (C) Péter Kardos
+//L Please refer to the full license distributed with this software.
+//L-----------------------------------------------------------------------------
+
+use crate::messaging::discovery::{Discovery, FeatureCode, LockingDescriptor};
+use crate::spec::column_types::{AuthorityRef, LockingRangeRef, ResetTypes};
+use crate::spec::enterprise::locking::{authority, locking};
+use crate::spec::objects::LockingRange;
+use crate::spec::table_id;
+use crate::tper::{Session, TPer};
+
+use super::utility::get_locking_sp;
+use super::Error;
+
+pub fn is_band_editor_supported(discovery: &Discovery) -> bool {
+    let Some(ssc) = discovery.get_primary_ssc() else {
+        return false;
+    };
+    let Some(locking_desc) = discovery.get::<LockingDescriptor>() else {
+        return false;
+    };
+    ssc.feature_code() == FeatureCode::Enterprise && locking_desc.locking_supported
+}
+
+/// Lists the bands of the Enterprise Locking SP. Band0 is the global range.
+pub async fn list_bands(tper: &TPer) -> Result<Vec<LockingRangeRef>, Error> {
+    let locking_sp = get_locking_sp(FeatureCode::Enterprise)?;
+    let session = tper.session(locking_sp).read_only().start().await?;
+    let result = session.next(table_id::LOCKING, None, None).await;
+    let _ = session.end_session().await;
+    Ok(result?.into_iter().filter_map(|uid| LockingRangeRef::try_from(uid).ok()).collect())
+}
+
+/// Cryptographically erases a band and disables its locking.
+///
+/// On Enterprise, only the EraseMaster may erase bands.
+pub async fn erase_band(tper: &TPer, band: LockingRangeRef, erase_master_password: &[u8]) -> Result<(), Error> {
+    let locking_sp = get_locking_sp(FeatureCode::Enterprise)?;
+    let session = tper.start_session(locking_sp, Some(authority::ERASE_MASTER), Some(erase_master_password)).await?;
+    let result = session.erase(band).await;
+    let _ = session.end_session().await;
+    Ok(result?)
+}
+
+/// Edits a single band of the Enterprise SSC.
+///
+/// Unlike Opal, Enterprise has no Admin authority for the Locking SP, so
+/// the session is authenticated as the BandMaster of the band.
+pub struct EnterpriseBandSession {
+    session: Session,
+    band: LockingRangeRef,
+}
+
+impl EnterpriseBandSession {
+    pub async fn start(tper: &TPer, band: LockingRangeRef, band_master_password: &[u8]) -> Result<Self, Error> {
+        let locking_sp = get_locking_sp(FeatureCode::Enterprise)?;
+        let band_master = get_band_master(band)?;
+        let session = tper.start_session(locking_sp, Some(band_master), Some(band_master_password)).await?;
+        Ok(Self { session, band })
+    }
+
+    pub async fn end(self) -> Result<(), Error> {
+        Ok(self.session.end_session().await?)
+    }
+
+    pub fn band(&self) -> LockingRangeRef {
+        self.band
+    }
+
+    pub async fn get_band(&self) -> Result<LockingRange, Error> {
+        let columns = LockingRange::RANGE_START..=LockingRange::LOCK_ON_RESET;
+        let (
+            range_start,
+            range_length,
+            read_lock_enabled,
+            write_lock_enabled,
+            read_locked,
+            write_locked,
+            lock_on_reset,
+        ) = self
+            .session
+            .get_multiple::<(u64, u64, bool, bool, bool, bool, ResetTypes)>(self.band.as_uid(), columns)
+            .await?;
+
+        Ok(LockingRange {
+            uid: self.band,
+            range_start,
+            range_length,
+            read_lock_enabled,
+            write_lock_enabled,
+            read_locked,
+            write_locked,
+            lock_on_reset,
+            ..Default::default()
+        })
+    }
+
+    /// Writes the geometry, the locking configuration and the LockOnReset of the band.
+    ///
+    /// The geometry of the global range (Band0) is fixed, so it's not written.
+    pub async fn set_band(&self, band: &LockingRange) -> Result<(), Error> {
+        if band.uid != self.band {
+            return Err(Error::InvalidBand);
+        }
+        let is_global_range = band.uid == locking::GLOBAL_RANGE;
+        if !is_global_range {
+            let columns: [u16; 7] = core::array::from_fn(|i| LockingRange::RANGE_START + (i as u16));
+            let values = (
+                band.range_start,
+                band.range_length,
+                band.read_lock_enabled,
+                band.write_lock_enabled,
+                band.read_locked,
+                band.write_locked,
+                band.lock_on_reset.clone(),
+            );
+            Ok(self.session.set_multiple(band.uid.as_uid(), columns, values).await?)
+        } else {
+            let columns: [u16; 5] = core::array::from_fn(|i| LockingRange::READ_LOCK_ENABLED + (i as u16));
+            let values = (
+                band.read_lock_enabled,
+                band.write_lock_enabled,
+                band.read_locked,
+                band.write_locked,
+                band.lock_on_reset.clone(),
+            );
+            Ok(self.session.set_multiple(band.uid.as_uid(), columns, values).await?)
+        }
+    }
+}
+
+fn get_band_master(band: LockingRangeRef) -> Result<AuthorityRef, Error> {
+    let index = locking::BAND.index_of(band).ok_or(Error::InvalidBand)?;
+    authority::BAND_MASTER.nth(index).ok_or(Error::InvalidBand)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::applications::test_fixtures::{make_activated_device_of, LOCKING_ADMIN1_PASSWORD};
+    use crate::fake_device::MSID_PASSWORD;
+    use crate::rpc::TokioRuntime;
+    use crate::spec::column_types::ResetType;
+
+    use super::*;
+
+    fn setup_enterprise_tper() -> TPer {
+        let runtime = Arc::new(TokioRuntime::new());
+        let device = Arc::new(make_activated_device_of(FeatureCode::Enterprise));
+        TPer::new_on_default_com_id(device, runtime).unwrap()
+    }
+
+    #[tokio::test]
+    async fn list_all_bands() -> Result<(), Error> {
+        let tper = setup_enterprise_tper();
+        let bands = list_bands(&tper).await?;
+        assert_eq!(bands.len(), 9);
+        assert!(bands.contains(&locking::GLOBAL_RANGE));
+        assert!(bands.contains(&locking::BAND.nth(8).unwrap()));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn set_get_global_range() -> Result<(), Error> {
+        let tper = setup_enterprise_tper();
+        let session =
+            EnterpriseBandSession::start(&tper, locking::GLOBAL_RANGE, LOCKING_ADMIN1_PASSWORD.as_bytes()).await?;
+        let mut band = session.get_band().await?;
+        band.read_lock_enabled = true;
+        band.write_locked = true;
+        band.lock_on_reset = ResetTypes::new();
+        session.set_band(&band).await?;
+        let modified = session.get_band().await?;
+        assert_eq!(modified, band);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn set_get_band() -> Result<(), Error> {
+        let tper = setup_enterprise_tper();
+        let band1 = locking::BAND.nth(1).unwrap();
+        let session = EnterpriseBandSession::start(&tper, band1, MSID_PASSWORD.as_bytes()).await?;
+        let band = LockingRange {
+            uid: band1,
+            range_start: 1024,
+            range_length: 2048,
+            read_lock_enabled: true,
+            write_lock_enabled: true,
+            read_locked: false,
+            write_locked: true,
+            lock_on_reset: [ResetType::PowerCycle].into_iter().collect(),
+            ..Default::default()
+        };
+        session.set_band(&band).await?;
+        let modified = session.get_band().await?;
+        assert_eq!(modified, band);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn set_band_of_other_band_master() -> Result<(), Error> {
+        let tper = setup_enterprise_tper();
+        let session =
+            EnterpriseBandSession::start(&tper, locking::BAND.nth(1).unwrap(), MSID_PASSWORD.as_bytes()).await?;
+        let band = LockingRange { uid: locking::BAND.nth(2).unwrap(), ..Default::default() };
+        assert_eq!(session.set_band(&band).await, Err(Error::InvalidBand));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn start_with_wrong_password() -> Result<(), Error> {
+        let tper = setup_enterprise_tper();
+        let result = EnterpriseBandSession::start(&tper, locking::GLOBAL_RANGE, MSID_PASSWORD.as_bytes()).await;
+        assert!(result.is_err());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn erase_band_success() -> Result<(), Error> {
+        let tper = setup_enterprise_tper();
+        let band1 = locking::BAND.nth(1).unwrap();
+        let session = EnterpriseBandSession::start(&tper, band1, MSID_PASSWORD.as_bytes()).await?;
+        let band = LockingRange { read_lock_enabled: true, read_locked: true, ..session.get_band().await? };
+        session.set_band(&band).await?;
+        erase_band(&tper, band1, MSID_PASSWORD.as_bytes()).await?;
+        let erased = session.get_band().await?;
+        session.end().await?;
+        assert!(!erased.read_lock_enabled);
+        assert!(!erased.read_locked);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn erase_band_not_authorized() -> Result<(), Error> {
+        let tper = setup_enterprise_tper();
+        let result = erase_band(&tper, locking::BAND.nth(1).unwrap(), LOCKING_ADMIN1_PASSWORD.as_bytes()).await;
+        assert!(result.is_err());
+        Ok(())
+    }
+}
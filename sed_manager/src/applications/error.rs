@@ -3,8 +3,11 @@
 //L Please refer to the full license distributed with this software.
 //L-----------------------------------------------------------------------------
 
+use crate::partitions::Error as PartitionTableError;
 use crate::rpc::Error as RPCError;
 
+use super::range_validation::RangeProblem;
+
 #[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
 pub enum Error {
     #[error("{}", .0)]
@@ -25,10 +28,26 @@ pub enum Error {
     FileNotOpen,
     #[error("Cannot read file")]
     FileReadError,
+    #[error("Cannot write file")]
+    FileWriteError,
     #[error("File is too large")]
     FileTooLarge,
+    #[error("The data read back from the device does not match the data written")]
+    VerificationFailed,
     #[error("Invalid ACE expression")]
     InvalidACEExpression,
+    #[error("Invalid configuration: {}", .0)]
+    InvalidConfig(String),
+    #[error("The locking range is not a band of the Enterprise Locking SP")]
+    InvalidBand,
+    #[error("All key tags of the device are in use")]
+    NoFreeKeyTag,
+    #[error("No key is injected for the key tag")]
+    KeyTagNotAllocated,
+    #[error("Invalid locking range: {}", .0)]
+    InvalidRange(RangeProblem),
+    #[error("{}", .0)]
+    PartitionTableError(PartitionTableError),
 }
 
 impl From<RPCError> for Error {
@@ -36,3 +55,9 @@ impl From<RPCError> for Error {
         Self::RPCError(value)
     }
 }
+
+impl From<PartitionTableError> for Error {
+    fn from(value: PartitionTableError) -> Self {
+        Self::PartitionTableError(value)
+    }
+}
@@ -0,0 +1,282 @@
+//L-----------------------------------------------------------------------------
+//L Copyright (C) Péter Kardos
+//L Please refer to the full license distributed with this software.
+//L-----------------------------------------------------------------------------
+
+use crate::messaging::discovery::{Discovery, FeatureCode, LockingDescriptor};
+use crate::spec::column_types::{Key256, KeyEncryptionKeyRef, KeyTagAllocationRef, Name};
+use crate::spec::kpio::key_per_io::kpio_policies;
+use crate::spec::objects::{KPIOPolicies, KeyEncryptionKey, KeyTagAllocation};
+use crate::spec::table_id;
+use crate::tper::{Session, TPer};
+
+use super::utility::start_admin1_session;
+use super::Error;
+
+pub fn is_key_per_io_supported(discovery: &Discovery) -> bool {
+    let Some(ssc) = discovery.get_primary_ssc() else {
+        return false;
+    };
+    let Some(locking_desc) = discovery.get::<LockingDescriptor>() else {
+        return false;
+    };
+    ssc.feature_code() == FeatureCode::KeyPerIO && locking_desc.locking_enabled
+}
+
+/// Manages the media encryption keys of the KeyPerIO SP.
+///
+/// Keys are injected through the KeyTagAllocation table: each row binds a key
+/// tag of a namespace to a key wrapped by one of the KEKs. A row whose KEK is
+/// null is free. The keys themselves can only be written, never read back.
+pub struct KeyPerIOSession {
+    session: Session,
+}
+
+impl KeyPerIOSession {
+    pub async fn start(tper: &TPer, admin1_password: &[u8]) -> Result<Self, Error> {
+        Ok(Self { session: start_admin1_session(tper, admin1_password).await? })
+    }
+
+    pub async fn end(self) -> Result<(), Error> {
+        Ok(self.session.end_session().await?)
+    }
+
+    pub async fn is_policy_enabled(&self) -> Result<bool, Error> {
+        let policies = kpio_policies::KPIO_POLICIES.as_uid();
+        Ok(self.session.get(policies, KPIOPolicies::KPIO_ENABLED).await?)
+    }
+
+    /// Enables or disables Key per I/O. While disabled, I/O commands may not reference key tags.
+    pub async fn set_policy_enabled(&self, enabled: bool) -> Result<(), Error> {
+        let policies = kpio_policies::KPIO_POLICIES.as_uid();
+        Ok(self.session.set(policies, KPIOPolicies::KPIO_ENABLED, enabled).await?)
+    }
+
+    pub async fn list_keks(&self) -> Result<Vec<KeyEncryptionKeyRef>, Error> {
+        let keks = self.session.next(table_id::KEY_ENCRYPTION_KEY, None, None).await?;
+        Ok(keks.into_iter().filter_map(|uid| KeyEncryptionKeyRef::try_from(uid).ok()).collect())
+    }
+
+    /// Provisions the KEK in plaintext. Injected keys must be wrapped with it.
+    pub async fn set_kek(&self, kek: KeyEncryptionKeyRef, key: Key256) -> Result<(), Error> {
+        Ok(self.session.set(kek.as_uid(), KeyEncryptionKey::KEY, key).await?)
+    }
+
+    /// Lists the key tags that have a key injected.
+    pub async fn list_key_tags(&self) -> Result<Vec<KeyTagAllocation>, Error> {
+        let slots = self.get_slots().await?;
+        Ok(slots.into_iter().filter(|slot| !slot.kek.is_null()).collect())
+    }
+
+    /// Injects the key for `key_tag` of the namespace, replacing the previous key of the key tag.
+    ///
+    /// The key must be wrapped with `kek`.
+    pub async fn inject_key(
+        &self,
+        namespace_id: u32,
+        key_tag: u16,
+        kek: KeyEncryptionKeyRef,
+        wrapped_key: &[u8],
+    ) -> Result<KeyTagAllocationRef, Error> {
+        let slots = self.get_slots().await?;
+        let slot = slots
+            .iter()
+            .find(|slot| !slot.kek.is_null() && slot.namespace_id == namespace_id && slot.key_tag == key_tag)
+            .or_else(|| slots.iter().find(|slot| slot.kek.is_null()))
+            .ok_or(Error::NoFreeKeyTag)?;
+        let columns = [
+            KeyTagAllocation::NAMESPACE_ID,
+            KeyTagAllocation::KEY_TAG,
+            KeyTagAllocation::KEK,
+            KeyTagAllocation::KEY,
+        ];
+        let values = (namespace_id, key_tag, kek, wrapped_key);
+        self.session.set_multiple(slot.uid.as_uid(), columns, values).await?;
+        Ok(slot.uid)
+    }
+
+    /// Removes the key of `key_tag` from the namespace and frees the key tag.
+    pub async fn purge_key(&self, namespace_id: u32, key_tag: u16) -> Result<(), Error> {
+        let slots = self.list_key_tags().await?;
+        let slot = slots
+            .iter()
+            .find(|slot| slot.namespace_id == namespace_id && slot.key_tag == key_tag)
+            .ok_or(Error::KeyTagNotAllocated)?;
+        self.free_slots(&[slot.uid]).await
+    }
+
+    /// Removes all injected keys of all namespaces.
+    pub async fn clear_all_keys(&self) -> Result<(), Error> {
+        let slots = self.list_key_tags().await?;
+        let uids: Vec<_> = slots.into_iter().map(|slot| slot.uid).collect();
+        self.free_slots(&uids).await
+    }
+
+    async fn get_slots(&self) -> Result<Vec<KeyTagAllocation>, Error> {
+        let uids = self.session.next(table_id::KEY_TAG_ALLOCATION, None, None).await?;
+        let uids: Vec<_> = uids.into_iter().filter_map(|uid| KeyTagAllocationRef::try_from(uid).ok()).collect();
+        let mut batch = self.session.batch();
+        let columns: Vec<_> = uids
+            .iter()
+            .map(|uid| {
+                batch.get_multiple::<(Name, Name, u32, u16, KeyEncryptionKeyRef)>(
+                    uid.as_uid(),
+                    KeyTagAllocation::NAME..=KeyTagAllocation::KEK,
+                )
+            })
+            .collect();
+        let mut results = batch.execute().await;
+        let mut slots = Vec::new();
+        for (uid, columns) in uids.into_iter().zip(columns) {
+            let (name, common_name, namespace_id, key_tag, kek) = results.take(columns)?;
+            slots.push(KeyTagAllocation { uid, name, common_name, namespace_id, key_tag, kek, ..Default::default() });
+        }
+        Ok(slots)
+    }
+
+    async fn free_slots(&self, slots: &[KeyTagAllocationRef]) -> Result<(), Error> {
+        let columns = [
+            KeyTagAllocation::NAMESPACE_ID,
+            KeyTagAllocation::KEY_TAG,
+            KeyTagAllocation::KEK,
+            KeyTagAllocation::KEY,
+        ];
+        let mut batch = self.session.batch();
+        let calls: Vec<_> = slots
+            .iter()
+            .map(|slot| {
+                let values = (0_u32, 0_u16, KeyEncryptionKeyRef::null(), &[] as &[u8]);
+                batch.set_multiple(slot.as_uid(), columns, values)
+            })
+            .collect();
+        let mut results = batch.execute().await;
+        for call in calls {
+            results.take(call)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::applications::test_fixtures::{make_activated_device_of, LOCKING_ADMIN1_PASSWORD};
+    use crate::messaging::discovery::KeyPerIODescriptor;
+    use crate::rpc::TokioRuntime;
+    use crate::spec::kpio::key_per_io::key_encryption_key;
+
+    use super::*;
+
+    fn setup_key_per_io_tper() -> TPer {
+        let runtime = Arc::new(TokioRuntime::new());
+        let device = Arc::new(make_activated_device_of(FeatureCode::KeyPerIO));
+        TPer::new_on_default_com_id(device, runtime).unwrap()
+    }
+
+    async fn start_session(tper: &TPer) -> Result<KeyPerIOSession, Error> {
+        KeyPerIOSession::start(tper, LOCKING_ADMIN1_PASSWORD.as_bytes()).await
+    }
+
+    #[tokio::test]
+    async fn enable_disable_policy() -> Result<(), Error> {
+        let tper = setup_key_per_io_tper();
+        let session = start_session(&tper).await?;
+        assert!(!session.is_policy_enabled().await?);
+        session.set_policy_enabled(true).await?;
+        assert!(session.is_policy_enabled().await?);
+        session.end().await?;
+        let discovery = tper.discover().await?;
+        assert!(discovery.get::<KeyPerIODescriptor>().unwrap().kpio_enabled);
+        let session = start_session(&tper).await?;
+        session.set_policy_enabled(false).await?;
+        assert!(!session.is_policy_enabled().await?);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn inject_and_list_keys() -> Result<(), Error> {
+        let tper = setup_key_per_io_tper();
+        let session = start_session(&tper).await?;
+        let keks = session.list_keks().await?;
+        assert_eq!(keks.len(), 4);
+        let kek = keks[0];
+        session.set_kek(kek, Key256::Bytes32([0xA5; 32])).await?;
+        assert!(session.list_key_tags().await?.is_empty());
+        let slot1 = session.inject_key(1, 7, kek, &[0x11; 40]).await?;
+        let slot2 = session.inject_key(2, 7, kek, &[0x22; 40]).await?;
+        assert_ne!(slot1, slot2);
+        let key_tags = session.list_key_tags().await?;
+        assert_eq!(key_tags.len(), 2);
+        assert!(key_tags.iter().any(|slot| slot.uid == slot1 && slot.namespace_id == 1 && slot.key_tag == 7));
+        assert!(key_tags.iter().any(|slot| slot.uid == slot2 && slot.namespace_id == 2 && slot.key_tag == 7));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn inject_key_replaces_existing() -> Result<(), Error> {
+        let tper = setup_key_per_io_tper();
+        let session = start_session(&tper).await?;
+        let kek1 = key_encryption_key::KEK.nth(1).unwrap();
+        let kek2 = key_encryption_key::KEK.nth(2).unwrap();
+        let slot = session.inject_key(1, 3, kek1, &[0x11; 40]).await?;
+        assert_eq!(session.inject_key(1, 3, kek2, &[0x22; 40]).await?, slot);
+        let key_tags = session.list_key_tags().await?;
+        assert_eq!(key_tags.len(), 1);
+        assert_eq!(key_tags[0].kek, kek2);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn inject_key_no_free_key_tag() -> Result<(), Error> {
+        let tper = setup_key_per_io_tper();
+        let session = start_session(&tper).await?;
+        let kek = key_encryption_key::KEK.nth(1).unwrap();
+        for key_tag in 0..16 {
+            session.inject_key(1, key_tag, kek, &[0x11; 40]).await?;
+        }
+        assert_eq!(session.inject_key(1, 16, kek, &[0x11; 40]).await, Err(Error::NoFreeKeyTag));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn purge_key() -> Result<(), Error> {
+        let tper = setup_key_per_io_tper();
+        let session = start_session(&tper).await?;
+        let kek = key_encryption_key::KEK.nth(1).unwrap();
+        session.inject_key(1, 3, kek, &[0x11; 40]).await?;
+        session.inject_key(1, 4, kek, &[0x22; 40]).await?;
+        session.purge_key(1, 3).await?;
+        let key_tags = session.list_key_tags().await?;
+        assert_eq!(key_tags.len(), 1);
+        assert_eq!(key_tags[0].key_tag, 4);
+        assert_eq!(session.purge_key(1, 3).await, Err(Error::KeyTagNotAllocated));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn clear_all_keys() -> Result<(), Error> {
+        let tper = setup_key_per_io_tper();
+        let session = start_session(&tper).await?;
+        let kek = key_encryption_key::KEK.nth(1).unwrap();
+        session.inject_key(1, 3, kek, &[0x11; 40]).await?;
+        session.inject_key(2, 4, kek, &[0x22; 40]).await?;
+        session.clear_all_keys().await?;
+        assert!(session.list_key_tags().await?.is_empty());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn keys_are_write_only() -> Result<(), Error> {
+        let tper = setup_key_per_io_tper();
+        let session = start_session(&tper).await?;
+        let kek = key_encryption_key::KEK.nth(1).unwrap();
+        session.set_kek(kek, Key256::Bytes32([0xA5; 32])).await?;
+        let slot = session.inject_key(1, 3, kek, &[0x11; 40]).await?;
+        let kek_key = session.session.get::<Key256>(kek.as_uid(), KeyEncryptionKey::KEY).await;
+        assert!(kek_key.is_err());
+        let key = session.session.get::<Vec<u8>>(slot.as_uid(), KeyTagAllocation::KEY).await;
+        assert!(key.is_err());
+        Ok(())
+    }
+}
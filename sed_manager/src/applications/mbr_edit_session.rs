@@ -4,14 +4,14 @@
 //L-----------------------------------------------------------------------------
 
 use crate::messaging::discovery::{Discovery, FeatureCode, LockingDescriptor};
-use crate::messaging::packet::{PACKET_HEADER_LEN, SUB_PACKET_HEADER_LEN};
-use crate::rpc::Properties;
+use crate::rpc::{Error as RPCError, Properties};
 use crate::spec;
 use crate::spec::objects::{MBRControl, TableDesc};
 use crate::spec::table_id;
 use crate::tper::{Session, TPer};
 
-use super::{utility::start_admin1_session, Error};
+use super::utility::{max_chunk_len, max_read_len, start_admin1_session};
+use super::Error;
 
 pub fn is_mbr_editor_supported(discovery: &Discovery) -> bool {
     // Enterprise and KPIO never support MBR shadowing.
@@ -83,11 +83,7 @@ impl MBREditSession {
         mut progress: impl FnMut(u64),
         mut cancelled: impl FnMut() -> bool,
     ) -> Result<(), Error> {
-        const CALL_LEN: usize = 128; // An upper bound for the encoding of the Set call that wraps the data token.
-        let chunk_len = core::cmp::min(
-            self.properties.max_gross_packet_size - PACKET_HEADER_LEN - SUB_PACKET_HEADER_LEN - CALL_LEN,
-            self.properties.max_ind_token_size - 4,
-        );
+        let chunk_len = max_chunk_len(&self.properties);
         let mut chunk = vec![0; chunk_len];
         let mut position: u64 = 0;
         while !cancelled() {
@@ -104,12 +100,134 @@ impl MBREditSession {
         }
         Err(Error::Cancelled)
     }
+
+    /// Upload data to the MBR table, skipping unchanged chunks and verifying the written ones.
+    ///
+    /// Each chunk is compared with the current contents of the MBR table first, and it is
+    /// only written if it differs. Written chunks are then read back and compared again.
+    /// Re-uploading an image after a small update only writes the chunks that changed.
+    ///
+    /// Every call compares the whole image from the start of the table, which costs one read
+    /// per chunk even if nothing changed. Repeating an interrupted upload therefore compares
+    /// the chunks that were already written again, but does not rewrite them.
+    ///
+    /// The table beyond the end of the image is left untouched. When the image is shorter
+    /// than the previous one, the tail of the previous image remains in the table.
+    ///
+    /// The arguments are the same as for [`Self::upload`]. Returns the number of bytes written.
+    pub async fn upload_verified(
+        &self,
+        mut read: impl AsyncFnMut(&mut [u8]) -> Result<usize, Error>,
+        mut progress: impl FnMut(u64),
+        mut cancelled: impl FnMut() -> bool,
+    ) -> Result<u64, Error> {
+        let chunk_len = core::cmp::min(max_chunk_len(&self.properties), max_read_len(&self.properties));
+        let mut chunk = vec![0; chunk_len];
+        let mut position: u64 = 0;
+        let mut num_written: u64 = 0;
+        while !cancelled() {
+            let read_result = read(chunk.as_mut_slice()).await;
+            let read_chunk_len = match read_result {
+                Ok(0) => return Ok(num_written),
+                Ok(n) => n,
+                Err(err) => return Err(err),
+            };
+            let read_chunk = &chunk[0..read_chunk_len];
+            let current = self.session.read(table_id::MBR, position, read_chunk_len as u64).await?;
+            if current != read_chunk {
+                self.session.write(table_id::MBR, position, read_chunk).await?;
+                let written = self.session.read(table_id::MBR, position, read_chunk_len as u64).await?;
+                if written != read_chunk {
+                    return Err(Error::VerificationFailed);
+                }
+                num_written += read_chunk_len as u64;
+            }
+            position += read_chunk_len as u64;
+            progress(position);
+        }
+        Err(Error::Cancelled)
+    }
+
+    /// Download data from the MBR table.
+    ///
+    /// The arguments are similar to [`Self::upload`]:
+    ///
+    /// * `len`: The number of bytes to download from the start of the table, typically [`Self::get_size`].
+    /// * `trim`: Omit the trailing blocks of the table that contain only zeros.
+    /// * `write`: Writes the next chunk of data. Similar to std::io::Write::write_all.
+    /// * `progress`: Periodically called with the number of bytes read from the table.
+    /// * `cancelled`: Periodically called an should return true to request a cancel.
+    ///
+    /// Returns the number of bytes passed to `write`.
+    pub async fn download(
+        &self,
+        len: u64,
+        trim: bool,
+        mut write: impl AsyncFnMut(&[u8]) -> Result<(), Error>,
+        mut progress: impl FnMut(u64),
+        mut cancelled: impl FnMut() -> bool,
+    ) -> Result<u64, Error> {
+        let chunk_len = max_read_len(&self.properties);
+        let mut position: u64 = 0;
+        let mut num_written: u64 = 0;
+        let mut num_held_zeros: u64 = 0; // Zeros that are only written if followed by data.
+        while !cancelled() {
+            if position >= len {
+                let padding = (TRIM_BLOCK_LEN - num_written % TRIM_BLOCK_LEN) % TRIM_BLOCK_LEN;
+                let padding = core::cmp::min(padding, num_held_zeros);
+                write_zeros(&mut write, padding, chunk_len).await?;
+                return Ok(num_written + padding);
+            }
+            let read_len = core::cmp::min(chunk_len as u64, len - position);
+            let chunk = self.session.read(table_id::MBR, position, read_len).await?;
+            if chunk.len() as u64 != read_len {
+                return Err(RPCError::ResultTypeMismatch.into()); // The backup would be shifted or truncated.
+            }
+            let data_len = if trim {
+                chunk.iter().rposition(|byte| *byte != 0).map(|idx| idx + 1).unwrap_or(0)
+            } else {
+                chunk.len()
+            };
+            if data_len != 0 {
+                write_zeros(&mut write, num_held_zeros, chunk_len).await?;
+                write(&chunk[0..data_len]).await?;
+                num_written += num_held_zeros + data_len as u64;
+                num_held_zeros = 0;
+            }
+            num_held_zeros += (chunk.len() - data_len) as u64;
+            position += read_len;
+            progress(position);
+        }
+        Err(Error::Cancelled)
+    }
+}
+
+/// Trailing zeros are trimmed in whole blocks of this size to keep the image sector-aligned.
+const TRIM_BLOCK_LEN: u64 = 512;
+
+/// Passes `count` zero bytes to `write` in pieces of at most `chunk_len`.
+async fn write_zeros(
+    write: &mut impl AsyncFnMut(&[u8]) -> Result<(), Error>,
+    count: u64,
+    chunk_len: usize,
+) -> Result<(), Error> {
+    let zeros = vec![0; core::cmp::min(count, chunk_len as u64) as usize];
+    let mut remaining = count;
+    while remaining != 0 {
+        let len = core::cmp::min(remaining, zeros.len() as u64);
+        write(&zeros[0..len as usize]).await?;
+        remaining -= len;
+    }
+    Ok(())
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::applications::test_fixtures::{setup_activated_tper, LOCKING_ADMIN1_PASSWORD};
-    use crate::messaging::discovery::LockingDescriptor;
+    use std::sync::Arc;
+
+    use crate::applications::test_fixtures::{make_activated_device_of, setup_activated_tper, LOCKING_ADMIN1_PASSWORD};
+    use crate::messaging::discovery::{FeatureCode, LockingDescriptor};
+    use crate::rpc::TokioRuntime;
 
     use super::*;
 
@@ -189,4 +307,138 @@ mod tests {
         let file = make_simulated_file(1 * 1024 * 1024); // 1 megabyte
         session.upload(file, |_| (), || false).await
     }
+
+    #[tokio::test]
+    async fn upload_verified_success() -> Result<(), Error> {
+        const SIZE: u64 = 256 * 1024;
+        let tper = setup_activated_tper();
+        let session = MBREditSession::start(&tper, LOCKING_ADMIN1_PASSWORD.as_bytes()).await?;
+        let written = session.upload_verified(make_simulated_file(SIZE), |_| (), || false).await?;
+        assert_eq!(written, SIZE);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn upload_verified_skip_unchanged() -> Result<(), Error> {
+        const SIZE: u64 = 256 * 1024;
+        let tper = setup_activated_tper();
+        let session = MBREditSession::start(&tper, LOCKING_ADMIN1_PASSWORD.as_bytes()).await?;
+        session.upload_verified(make_simulated_file(SIZE), |_| (), || false).await?;
+        let written = session.upload_verified(make_simulated_file(SIZE), |_| (), || false).await?;
+        assert_eq!(written, 0);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn upload_verified_partial_change() -> Result<(), Error> {
+        const SIZE: u64 = 256 * 1024;
+        let tper = setup_activated_tper();
+        let session = MBREditSession::start(&tper, LOCKING_ADMIN1_PASSWORD.as_bytes()).await?;
+        session.upload_verified(make_simulated_file(SIZE), |_| (), || false).await?;
+        let mut file = make_simulated_file(SIZE);
+        let mut chunk_idx = 0;
+        let changed_file = async move |out: &mut [u8]| -> Result<usize, Error> {
+            let len = file(out).await?;
+            if chunk_idx == 1 {
+                out[0] = !out[0];
+            }
+            chunk_idx += 1;
+            Ok(len)
+        };
+        let chunk_len = core::cmp::min(max_chunk_len(&session.properties), max_read_len(&session.properties));
+        let written = session.upload_verified(changed_file, |_| (), || false).await?;
+        assert_eq!(written, chunk_len as u64);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn upload_verified_resume() -> Result<(), Error> {
+        const SIZE: u64 = 256 * 1024;
+        let tper = setup_activated_tper();
+        let session = MBREditSession::start(&tper, LOCKING_ADMIN1_PASSWORD.as_bytes()).await?;
+        let uploaded = core::cell::Cell::new(0);
+        let interrupted = session
+            .upload_verified(make_simulated_file(SIZE), |position| uploaded.set(position), || uploaded.get() != 0)
+            .await;
+        assert_eq!(interrupted, Err(Error::Cancelled));
+        let written = session.upload_verified(make_simulated_file(SIZE), |_| (), || false).await?;
+        assert_eq!(written, SIZE - uploaded.get());
+        Ok(())
+    }
+
+    async fn download_into_vec(session: &MBREditSession, len: u64, trim: bool) -> Result<Vec<u8>, Error> {
+        let mut image = Vec::new();
+        let write = async |data: &[u8]| -> Result<(), Error> {
+            image.extend_from_slice(data);
+            Ok(())
+        };
+        let num_written = session.download(len, trim, write, |_| (), || false).await?;
+        assert_eq!(num_written, image.len() as u64);
+        Ok(image)
+    }
+
+    #[tokio::test]
+    async fn download_full() -> Result<(), Error> {
+        const SIZE: u64 = 256 * 1024;
+        let tper = setup_activated_tper();
+        let session = MBREditSession::start(&tper, LOCKING_ADMIN1_PASSWORD.as_bytes()).await?;
+        session.session.write(table_id::MBR, 0, &[0xA5; 1000]).await?;
+        session.session.write(table_id::MBR, 70000, &[0x5A]).await?;
+        let image = download_into_vec(&session, SIZE, false).await?;
+        let mut expected = vec![0; SIZE as usize];
+        expected[0..1000].fill(0xA5);
+        expected[70000] = 0x5A;
+        assert_eq!(image, expected);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn download_trimmed() -> Result<(), Error> {
+        const SIZE: u64 = 256 * 1024;
+        let tper = setup_activated_tper();
+        let session = MBREditSession::start(&tper, LOCKING_ADMIN1_PASSWORD.as_bytes()).await?;
+        session.session.write(table_id::MBR, 0, &[0xA5; 1000]).await?;
+        session.session.write(table_id::MBR, 70000, &[0x5A]).await?;
+        let image = download_into_vec(&session, SIZE, true).await?;
+        let mut expected = vec![0; 70144]; // Rounded up to 512-byte blocks.
+        expected[0..1000].fill(0xA5);
+        expected[70000] = 0x5A;
+        assert_eq!(image, expected);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn download_trimmed_empty() -> Result<(), Error> {
+        let tper = setup_activated_tper();
+        let session = MBREditSession::start(&tper, LOCKING_ADMIN1_PASSWORD.as_bytes()).await?;
+        let image = download_into_vec(&session, 256 * 1024, true).await?;
+        assert!(image.is_empty());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn download_short_read() -> Result<(), Error> {
+        let runtime = Arc::new(TokioRuntime::new());
+        let device = Arc::new(make_activated_device_of(FeatureCode::OpalV2));
+        let tper = TPer::new_on_default_com_id(device.clone(), runtime)?;
+        let session = MBREditSession::start(&tper, LOCKING_ADMIN1_PASSWORD.as_bytes()).await?;
+        device.shorten_byte_reads(1);
+        let write = async |_: &[u8]| -> Result<(), Error> { Ok(()) };
+        let result = session.download(256 * 1024, false, write, |_| (), || false).await;
+        assert_eq!(result, Err(RPCError::ResultTypeMismatch.into()));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn download_cancelled() -> Result<(), Error> {
+        let tper = setup_activated_tper();
+        let session = MBREditSession::start(&tper, LOCKING_ADMIN1_PASSWORD.as_bytes()).await?;
+        let downloaded = core::cell::Cell::new(0);
+        let write = async |_: &[u8]| -> Result<(), Error> { Ok(()) };
+        let result = session
+            .download(256 * 1024, false, write, |position| downloaded.set(position), || downloaded.get() != 0)
+            .await;
+        assert_eq!(result, Err(Error::Cancelled));
+        Ok(())
+    }
 }
@@ -4,11 +4,21 @@
 //L-----------------------------------------------------------------------------
 
 mod activate_locking;
+mod atomic_edit;
 mod change_password;
+mod data_store_session;
+#[cfg(feature = "serde")]
+mod device_report;
+mod enterprise_band_session;
 pub mod error;
+mod key_per_io_session;
 mod mbr_edit_session;
+mod partition_ranges;
 mod permission_session;
+mod programmatic_reset;
+mod provisioning;
 mod range_edit_session;
+mod range_validation;
 mod revert;
 mod take_ownership;
 pub mod test_fixtures;
@@ -16,12 +26,76 @@ mod user_edit_session;
 mod utility;
 
 pub use activate_locking::{activate_locking, is_activating_locking_supported, verify_locking_activation};
+pub use atomic_edit::AtomicEdit;
 pub use change_password::{change_password, is_change_password_supported, list_password_authorities};
+pub use data_store_session::{is_data_store_supported, DataStoreSession};
+#[cfg(feature = "serde")]
+pub use device_report::get_device_report;
+pub use enterprise_band_session::{erase_band, is_band_editor_supported, list_bands, EnterpriseBandSession};
 pub use error::Error;
+pub use key_per_io_session::{is_key_per_io_supported, KeyPerIOSession};
 pub use mbr_edit_session::{is_mbr_editor_supported, MBREditSession};
+pub use partition_ranges::{fit_partition, get_partition_ranges, PartitionRange};
 pub use permission_session::{is_permission_editor_supported, PermissionEditSession};
+pub use programmatic_reset::{
+    is_programmatic_reset_enabled, is_programmatic_reset_supported, set_programmatic_reset_enabled,
+};
+pub use provisioning::{
+    apply_provisioning, plan_provisioning, MBRConfig, ProvisioningConfig, ProvisioningStep, RangeConfig, Secret,
+    UserConfig,
+};
 pub use range_edit_session::{is_range_editor_supported, RangeEditSession};
+pub use range_validation::{snap_range, validate_range, RangeProblem};
 pub use revert::{is_revert_supported, revert};
 pub use take_ownership::{is_taking_ownership_supported, take_ownership, verify_ownership};
 pub use user_edit_session::{is_user_editor_supported, UserEditSession};
 pub use utility::{get_admin_sp, get_feature_lookup, get_general_lookup, get_locking_admins, get_locking_sp};
+
+#[cfg(test)]
+mod tests {
+    use crate::fake_device::FakeDevice;
+    use crate::messaging::discovery::{Discovery, FeatureCode};
+
+    use super::test_fixtures::{
+        discover, for_each_ssc, make_activated_device_of, make_factory_device_of, make_owned_device_of,
+    };
+    use super::*;
+
+    use FeatureCode::{Enterprise, KeyPerIO, OpalV2, Opalite, PyriteV2, Ruby};
+
+    type IsSupported = fn(&Discovery) -> bool;
+    type MakeDevice = fn(FeatureCode) -> FakeDevice;
+
+    const ALL: &[FeatureCode] = &[OpalV2, Opalite, PyriteV2, Ruby, Enterprise, KeyPerIO];
+    const NOT_ENTERPRISE: &[FeatureCode] = &[OpalV2, Opalite, PyriteV2, Ruby, KeyPerIO];
+    const NOT_ENTERPRISE_OR_KPIO: &[FeatureCode] = &[OpalV2, Opalite, PyriteV2, Ruby];
+
+    /// Each `is_*_supported` function, the state of the device it is checked on, and the SSCs it accepts.
+    const SUPPORTED_SSCS: [(&str, IsSupported, MakeDevice, &[FeatureCode]); 13] = [
+        ("take ownership", is_taking_ownership_supported, make_factory_device_of, ALL),
+        ("take ownership", is_taking_ownership_supported, make_owned_device_of, &[]),
+        ("activate locking", is_activating_locking_supported, make_factory_device_of, NOT_ENTERPRISE),
+        ("activate locking", is_activating_locking_supported, make_activated_device_of, &[]),
+        ("range editor", is_range_editor_supported, make_activated_device_of, NOT_ENTERPRISE_OR_KPIO),
+        ("band editor", is_band_editor_supported, make_activated_device_of, &[Enterprise]),
+        ("user editor", is_user_editor_supported, make_activated_device_of, NOT_ENTERPRISE),
+        ("permissions", is_permission_editor_supported, make_activated_device_of, NOT_ENTERPRISE_OR_KPIO),
+        ("MBR editor", is_mbr_editor_supported, make_activated_device_of, NOT_ENTERPRISE_OR_KPIO),
+        ("DataStore", is_data_store_supported, make_activated_device_of, NOT_ENTERPRISE_OR_KPIO),
+        ("programmatic reset", is_programmatic_reset_supported, make_activated_device_of, NOT_ENTERPRISE),
+        ("key per I/O", is_key_per_io_supported, make_owned_device_of, &[]),
+        ("key per I/O", is_key_per_io_supported, make_activated_device_of, &[KeyPerIO]),
+    ];
+
+    #[tokio::test]
+    async fn is_supported_fake_devices() {
+        for (name, is_supported, make_device, expected) in SUPPORTED_SSCS {
+            for_each_ssc(async |ssc| {
+                let discovery = discover(make_device(ssc)).await;
+                assert_eq!(is_supported(&discovery), expected.contains(&ssc), "{name}: {ssc:?}");
+                Ok(())
+            })
+            .await
+        }
+    }
+}
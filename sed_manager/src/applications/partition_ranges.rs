@@ -0,0 +1,101 @@
+//L-----------------------------------------------------------------------------
+//L Copyright (C) Péter Kardos
+//L Please refer to the full license distributed with this software.
+//L-----------------------------------------------------------------------------
+
+use crate::device::Device;
+use crate::messaging::discovery::GeometryDescriptor;
+use crate::partitions::{read_partition_table, Partition};
+
+use super::range_validation::snap_range;
+use super::Error;
+
+/// A ready-made locking range for a partition of the drive.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PartitionRange {
+    pub partition: Partition,
+    pub range_start: u64,
+    pub range_length: u64,
+}
+
+/// Reads the GUID partition table of the drive and suggests a locking range for each partition.
+pub fn get_partition_ranges(
+    device: &dyn Device,
+    geometry: Option<&GeometryDescriptor>,
+) -> Result<Vec<PartitionRange>, Error> {
+    let partitions = read_partition_table(device)?;
+    Ok(partitions
+        .into_iter()
+        .map(|partition| {
+            let (range_start, range_length) = fit_partition(&partition, geometry);
+            PartitionRange { partition, range_start, range_length }
+        })
+        .collect())
+}
+
+/// Returns the start and length of the smallest locking range that covers the partition and
+/// satisfies the alignment requirements.
+///
+/// Partitions are normally aligned, but if not, the range extends into the neighbouring LBAs.
+/// Such ranges may overlap each other, which [`super::validate_range`] reports.
+pub fn fit_partition(partition: &Partition, geometry: Option<&GeometryDescriptor>) -> (u64, u64) {
+    let end = partition.last_lba + 1;
+    match geometry.filter(|geometry| geometry.align) {
+        Some(geometry) => {
+            let granularity = core::cmp::max(1, geometry.alignment_granularity);
+            let (start, _) = snap_range(partition.first_lba, 0, geometry);
+            (start, end.saturating_sub(start).div_ceil(granularity) * granularity)
+        }
+        None => (partition.first_lba, end - partition.first_lba),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fake_device::FakeDevice;
+    use crate::partitions::{self, make_gpt};
+
+    use super::*;
+
+    const GEOMETRY: GeometryDescriptor =
+        GeometryDescriptor { align: true, logical_block_size: 512, alignment_granularity: 8, lowest_aligned_lba: 4 };
+
+    fn make_partition(first_lba: u64, last_lba: u64) -> Partition {
+        Partition { number: 1, type_guid: [0xAF; 16], unique_guid: [1; 16], first_lba, last_lba, name: String::new() }
+    }
+
+    #[test]
+    fn fit_partition_aligned() {
+        assert_eq!(fit_partition(&make_partition(2052, 4099), Some(&GEOMETRY)), (2052, 2048));
+    }
+
+    #[test]
+    fn fit_partition_misaligned() {
+        assert_eq!(fit_partition(&make_partition(2054, 4100), Some(&GEOMETRY)), (2052, 2056));
+    }
+
+    #[test]
+    fn fit_partition_no_align_required() {
+        let geometry = GeometryDescriptor { align: false, ..GEOMETRY };
+        assert_eq!(fit_partition(&make_partition(2054, 4100), Some(&geometry)), (2054, 2047));
+        assert_eq!(fit_partition(&make_partition(2054, 4100), None), (2054, 2047));
+    }
+
+    #[test]
+    fn get_partition_ranges_fake_device() -> Result<(), Error> {
+        let device = FakeDevice::new();
+        device.write_blocks(0, &make_gpt(&[(2052, 4099, "Boot"), (4100, 1_000_003, "Root")])).unwrap();
+        let ranges = get_partition_ranges(&device, Some(&GEOMETRY))?;
+        let extents: Vec<_> = ranges.iter().map(|range| (range.range_start, range.range_length)).collect();
+        assert_eq!(extents, vec![(2052, 2048), (4100, 995_904)]);
+        assert_eq!(ranges[1].partition.name, "Root");
+        Ok(())
+    }
+
+    #[test]
+    fn get_partition_ranges_no_partition_table() {
+        let device = FakeDevice::new();
+        let result = get_partition_ranges(&device, Some(&GEOMETRY));
+        assert_eq!(result, Err(Error::PartitionTableError(partitions::Error::NoPartitionTable)));
+    }
+}
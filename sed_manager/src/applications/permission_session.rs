@@ -10,7 +10,9 @@ use crate::spec::objects::{ACEExpr, Authority, LockingRange, ACE};
 use crate::spec::{self, method_id, table_id};
 use crate::tper::{Session, TPer};
 
-use super::{utility::start_admin1_session, Error};
+use super::atomic_edit::sealed::TransactionSession;
+use super::utility::{has_permission, is_transaction_supported, start_admin1_session, update_permission_expr};
+use super::Error;
 
 pub fn is_permission_editor_supported(discovery: &Discovery) -> bool {
     super::is_user_editor_supported(discovery) && super::is_range_editor_supported(discovery)
@@ -19,6 +21,7 @@ pub fn is_permission_editor_supported(discovery: &Discovery) -> bool {
 pub struct PermissionEditSession {
     session: Session,
     is_mbr_supported: bool,
+    is_transaction_supported: bool,
 }
 
 impl PermissionEditSession {
@@ -26,7 +29,8 @@ impl PermissionEditSession {
         let discovery = tper.discover().await?;
         let locking_desc = discovery.get::<LockingDescriptor>().ok_or(Error::IncompatibleSSC)?;
         let is_mbr_supported = !locking_desc.mbr_shadowing_not_supported;
-        Ok(Self { session: start_admin1_session(tper, admin1_password).await?, is_mbr_supported })
+        let session = start_admin1_session(tper, admin1_password).await?;
+        Ok(Self { session, is_mbr_supported, is_transaction_supported: is_transaction_supported(tper).await })
     }
 
     pub async fn end(self) -> Result<(), Error> {
@@ -34,11 +38,18 @@ impl PermissionEditSession {
     }
 
     pub async fn list_users(&self) -> Result<Vec<AuthorityRef>, Error> {
+        let users_class = spec::opal::locking::authority::USERS; // The UID of Users is the same for all relevant SSCs.
         let authorities = self.session.next(table_id::AUTHORITY, None, None).await?;
-        let authorities = authorities.into_iter().filter_map(|uid| AuthorityRef::try_from(uid).ok());
+        let authorities: Vec<_> = authorities.into_iter().filter_map(|uid| AuthorityRef::try_from(uid).ok()).collect();
+        let mut batch = self.session.batch();
+        let classes: Vec<_> = authorities
+            .iter()
+            .map(|authority| batch.get::<AuthorityRef>(authority.as_uid(), Authority::CLASS))
+            .collect();
+        let mut results = batch.execute().await;
         let mut users = Vec::new();
-        for authority in authorities {
-            if self.is_user(authority).await? {
+        for (authority, class) in authorities.into_iter().zip(classes) {
+            if results.take(class)? == users_class || authority == users_class {
                 users.push(authority);
             }
         }
@@ -122,34 +133,24 @@ impl PermissionEditSession {
         Err(Error::IncompatibleSSC)
     }
 
-    async fn is_user(&self, authority: AuthorityRef) -> Result<bool, Error> {
-        let users_class = spec::opal::locking::authority::USERS;
-        let class: AuthorityRef = self.session.get(authority.as_uid(), Authority::CLASS).await?;
-        Ok(class == users_class || authority == users_class) // The UID of Users is the same for all relevant SSCs.
-    }
-
     async fn has_permission(&self, ace_expr: impl ACEExpr, authority: AuthorityRef) -> bool {
-        let anybody = spec::core::authority::ANYBODY;
-        let class: AuthorityRef =
-            self.session.get(authority.as_uid(), Authority::CLASS).await.unwrap_or(AuthorityRef::null());
-        ace_expr.eval(&[anybody, class, authority]).unwrap_or(false)
+        has_permission(&self.session, ace_expr, authority).await
     }
 }
 
-fn update_permission_expr(
-    ace_expr: impl ACEExpr,
-    user: AuthorityRef,
-    permitted: bool,
-) -> Result<Vec<ACEOperand>, Error> {
-    match permitted {
-        true => ace_expr.allow_authority(user).ok_or(Error::InvalidACEExpression),
-        false => ace_expr.deny_authority(user).ok_or(Error::InvalidACEExpression),
+impl TransactionSession for PermissionEditSession {
+    fn session(&self) -> &Session {
+        &self.session
+    }
+
+    fn is_transaction_supported(&self) -> bool {
+        self.is_transaction_supported
     }
 }
 
 #[cfg(test)]
 mod tests {
-
+    use crate::applications::atomic_edit::AtomicEdit as _;
     use crate::applications::test_fixtures::{setup_activated_tper, LOCKING_ADMIN1_PASSWORD};
     use crate::spec;
 
@@ -216,4 +217,21 @@ mod tests {
         assert_eq!(updated, true);
         Ok(())
     }
+
+    #[tokio::test]
+    async fn atomic_rollback() -> Result<(), Error> {
+        let tper = setup_activated_tper();
+        let session = PermissionEditSession::start(&tper, LOCKING_ADMIN1_PASSWORD.as_bytes()).await?;
+        let user = spec::opal::locking::authority::USER.nth(1).unwrap();
+        let range = spec::opal::locking::locking::GLOBAL_RANGE;
+        let result = session
+            .atomic(async |session| {
+                session.set_read_permission(user, range, true).await?;
+                session.set_write_permission(user, LockingRangeRef::null(), true).await
+            })
+            .await;
+        assert!(result.is_err());
+        assert!(!session.get_read_permission(user, range).await?);
+        Ok(())
+    }
 }
@@ -0,0 +1,150 @@
+//L-----------------------------------------------------------------------------
+//L Copyright (C) Péter Kardos
+//L Please refer to the full license distributed with this software.
+//L-----------------------------------------------------------------------------
+
+use crate::applications::utility::get_admin_sp;
+use crate::messaging::discovery::{Discovery, FeatureCode};
+use crate::spec;
+use crate::spec::objects::TPerInfo;
+use crate::tper::TPer;
+
+use super::error::Error;
+
+pub fn is_programmatic_reset_supported(discovery: &Discovery) -> bool {
+    // Opal 1.0 predates the TPer Reset command, and Enterprise has no such command.
+    const SUPPORTED_SSCS: [FeatureCode; 6] = [
+        FeatureCode::OpalV2,
+        FeatureCode::Opalite,
+        FeatureCode::PyriteV1,
+        FeatureCode::PyriteV2,
+        FeatureCode::Ruby,
+        FeatureCode::KeyPerIO,
+    ];
+    discovery.get_primary_ssc().is_some_and(|ssc| SUPPORTED_SSCS.contains(&ssc.feature_code()))
+}
+
+pub async fn is_programmatic_reset_enabled(tper: &TPer) -> Result<bool, Error> {
+    use spec::core::t_per_info::T_PER_INFO;
+
+    let discovery = tper.discover().await?;
+    let ssc = discovery.get_primary_ssc().ok_or(Error::NoAvailableSSC)?;
+    let admin_sp = get_admin_sp(ssc.feature_code())?;
+
+    let anybody_session = tper.session(admin_sp).read_only().start().await?;
+    Ok(anybody_session
+        .with(async |session| session.get(T_PER_INFO.as_uid(), TPerInfo::PROGRAMMATIC_RESET_ENABLE).await)
+        .await?)
+}
+
+/// Allows or disallows the TPer Reset command.
+///
+/// The TPer ignores [`TPer::tper_reset`] unless it's enabled.
+pub async fn set_programmatic_reset_enabled(tper: &TPer, sid_password: &[u8], enabled: bool) -> Result<(), Error> {
+    use spec::core::authority::SID;
+    use spec::core::t_per_info::T_PER_INFO;
+
+    let discovery = tper.discover().await?;
+    let ssc = discovery.get_primary_ssc().ok_or(Error::NoAvailableSSC)?;
+    let admin_sp = get_admin_sp(ssc.feature_code())?;
+
+    let sid_session = tper.start_session(admin_sp, Some(SID), Some(sid_password)).await?;
+    Ok(sid_session
+        .with(async |session| session.set(T_PER_INFO.as_uid(), TPerInfo::PROGRAMMATIC_RESET_ENABLE, enabled).await)
+        .await?)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::applications::test_fixtures::{
+        for_each_ssc, make_activated_device_of, setup_activated_tper, LOCKING_ADMIN1_PASSWORD, SID_PASSWORD,
+    };
+    use crate::applications::RangeEditSession;
+    use crate::rpc::{Error as RPCError, MethodStatus, TokioRuntime};
+    use crate::spec::column_types::{ResetType, ResetTypes};
+    use crate::spec::objects::LockingRange;
+
+    use super::*;
+
+    async fn unlock_global_range(tper: &TPer, lock_on_reset: ResetTypes) -> Result<(), Error> {
+        let session = RangeEditSession::start(tper, LOCKING_ADMIN1_PASSWORD.as_bytes()).await?;
+        let uid = spec::opal::locking::locking::GLOBAL_RANGE;
+        let range = session.get_range(uid).await?;
+        let modified = LockingRange { read_lock_enabled: true, write_lock_enabled: true, ..range };
+        session.set_range(&modified).await?;
+        session.set_lock_on_reset(uid, &lock_on_reset).await?;
+        session.end().await
+    }
+
+    async fn get_global_range(tper: &TPer) -> Result<LockingRange, Error> {
+        let session = RangeEditSession::start(tper, LOCKING_ADMIN1_PASSWORD.as_bytes()).await?;
+        let range = session.get_range(spec::opal::locking::locking::GLOBAL_RANGE).await?;
+        session.end().await?;
+        Ok(range)
+    }
+
+    #[tokio::test]
+    async fn set_programmatic_reset_enabled_success() {
+        for_each_ssc(async |ssc| {
+            if ssc == FeatureCode::Enterprise {
+                return Ok(());
+            }
+            let runtime = Arc::new(TokioRuntime::new());
+            let device = Arc::new(make_activated_device_of(ssc));
+            let tper = TPer::new_on_default_com_id(device, runtime)?;
+            assert!(!is_programmatic_reset_enabled(&tper).await?, "{ssc:?}");
+            set_programmatic_reset_enabled(&tper, SID_PASSWORD.as_bytes(), true).await?;
+            assert!(is_programmatic_reset_enabled(&tper).await?, "{ssc:?}");
+            set_programmatic_reset_enabled(&tper, SID_PASSWORD.as_bytes(), false).await?;
+            assert!(!is_programmatic_reset_enabled(&tper).await?, "{ssc:?}");
+            Ok(())
+        })
+        .await
+    }
+
+    #[tokio::test]
+    async fn set_programmatic_reset_enabled_wrong_password() -> Result<(), Error> {
+        let tper = setup_activated_tper();
+        let result = set_programmatic_reset_enabled(&tper, "wrong".as_bytes(), true).await;
+        assert_eq!(result, Err(Error::RPCError(RPCError::MethodFailed(MethodStatus::NotAuthorized))));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn tper_reset_locks_range() -> Result<(), Error> {
+        let tper = setup_activated_tper();
+        unlock_global_range(&tper, [ResetType::PowerCycle, ResetType::Programmatic].into()).await?;
+        set_programmatic_reset_enabled(&tper, SID_PASSWORD.as_bytes(), true).await?;
+        tper.tper_reset().await?;
+        let range = get_global_range(&tper).await?;
+        assert!(range.read_locked);
+        assert!(range.write_locked);
+        assert_eq!(range.lock_on_reset, [ResetType::PowerCycle, ResetType::Programmatic].into());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn tper_reset_skips_range() -> Result<(), Error> {
+        let tper = setup_activated_tper();
+        unlock_global_range(&tper, [ResetType::PowerCycle].into()).await?;
+        set_programmatic_reset_enabled(&tper, SID_PASSWORD.as_bytes(), true).await?;
+        tper.tper_reset().await?;
+        let range = get_global_range(&tper).await?;
+        assert!(!range.read_locked);
+        assert!(!range.write_locked);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn tper_reset_disabled() -> Result<(), Error> {
+        let tper = setup_activated_tper();
+        unlock_global_range(&tper, [ResetType::PowerCycle, ResetType::Programmatic].into()).await?;
+        tper.tper_reset().await?;
+        let range = get_global_range(&tper).await?;
+        assert!(!range.read_locked);
+        assert!(!range.write_locked);
+        Ok(())
+    }
+}
@@ -0,0 +1,780 @@
+//L-----------------------------------------------------------------------------
+//L Copyright (C) Péter Kardos
+//L Please refer to the full license distributed with this software.
+//L-----------------------------------------------------------------------------
+
+use std::io::Read as _;
+
+use crate::messaging::discovery::{Discovery, FeatureCode, LockingDescriptor};
+use crate::spec::column_types::{AuthorityRef, LockingRangeRef};
+use crate::spec::objects::LockingRange;
+use crate::spec::table_id;
+use crate::tper::TPer;
+
+use super::utility::{get_general_lookup, get_locking_admins, get_locking_sp};
+use super::{
+    activate_locking, is_activating_locking_supported, is_taking_ownership_supported, take_ownership, Error,
+    MBREditSession, PermissionEditSession, RangeEditSession, UserEditSession,
+};
+
+/// The desired end state of a drive.
+///
+/// Only the settings that are present are enforced, everything else is left as it is on the drive.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize), serde(deny_unknown_fields))]
+pub struct ProvisioningConfig {
+    /// The SID password. Ownership is taken with this password if the drive is not yet owned.
+    pub sid_password: Secret,
+    /// Activate the locking SP if it's not yet active.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub activate_locking: bool,
+    /// The Admin1 password of the locking SP. Defaults to the SID password.
+    pub admin1_password: Option<Secret>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub ranges: Vec<RangeConfig>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub users: Vec<UserConfig>,
+    pub mbr: Option<MBRConfig>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize), serde(deny_unknown_fields))]
+pub struct RangeConfig {
+    /// The name of the range, e.g. GlobalRange or Range1.
+    pub name: String,
+    pub start: Option<u64>,
+    pub length: Option<u64>,
+    pub read_lock_enabled: Option<bool>,
+    pub write_lock_enabled: Option<bool>,
+    pub read_locked: Option<bool>,
+    pub write_locked: Option<bool>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize), serde(deny_unknown_fields))]
+pub struct UserConfig {
+    /// The name of the authority, e.g. User1.
+    pub name: String,
+    pub enabled: Option<bool>,
+    pub common_name: Option<String>,
+    pub password: Option<Secret>,
+    /// The ranges the user can read-lock and read-unlock. Permissions to other ranges are revoked.
+    pub read_ranges: Option<Vec<String>>,
+    /// The ranges the user can write-lock and write-unlock. Permissions to other ranges are revoked.
+    pub write_ranges: Option<Vec<String>>,
+    /// Whether the user can set the shadow MBR done.
+    pub mbr: Option<bool>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize), serde(deny_unknown_fields))]
+pub struct MBRConfig {
+    pub enabled: Option<bool>,
+    pub done: Option<bool>,
+    /// Path to a file to upload into the shadow MBR.
+    pub image: Option<String>,
+}
+
+/// A password, given either literally, or as the name of an environment variable or a file that holds it.
+#[derive(Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize), serde(untagged))]
+pub enum Secret {
+    Literal(String),
+    Env { env: String },
+    File { file: String },
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ProvisioningStep {
+    TakeOwnership,
+    ActivateLocking,
+    SetRange { name: String, range: LockingRange },
+    SetUserEnabled { name: String, user: AuthorityRef, enabled: bool },
+    SetUserCommonName { name: String, user: AuthorityRef, common_name: String },
+    SetUserPassword { name: String, user: AuthorityRef },
+    SetReadPermission { name: String, user: AuthorityRef, range_name: String, range: LockingRangeRef, permitted: bool },
+    SetWritePermission { name: String, user: AuthorityRef, range_name: String, range: LockingRangeRef, permitted: bool },
+    SetMBRPermission { name: String, user: AuthorityRef, permitted: bool },
+    UploadMBR { image: String },
+    SetMBREnabled { enabled: bool },
+    SetMBRDone { done: bool },
+}
+
+impl ProvisioningConfig {
+    #[cfg(feature = "serde")]
+    pub fn from_toml(text: &str) -> Result<Self, Error> {
+        toml::from_str(text).map_err(|err| Error::InvalidConfig(err.message().into()))
+    }
+
+    #[cfg(feature = "serde")]
+    pub fn from_json(text: &str) -> Result<Self, Error> {
+        serde_json::from_str(text).map_err(|err| Error::InvalidConfig(err.to_string()))
+    }
+
+    fn has_locking_settings(&self) -> bool {
+        !self.ranges.is_empty() || !self.users.is_empty() || self.mbr.is_some()
+    }
+
+    fn admin1_password(&self) -> Result<Vec<u8>, Error> {
+        self.admin1_password.as_ref().unwrap_or(&self.sid_password).resolve()
+    }
+}
+
+impl Secret {
+    pub fn resolve(&self) -> Result<Vec<u8>, Error> {
+        match self {
+            Secret::Literal(password) => Ok(password.as_bytes().into()),
+            Secret::Env { env } => std::env::var(env)
+                .map(|password| password.into_bytes())
+                .map_err(|_| Error::InvalidConfig(format!("environment variable `{env}` is not set"))),
+            Secret::File { file } => {
+                let password = std::fs::read(file).map_err(|_| Error::FileNotOpen)?;
+                let len = password.iter().rposition(|c| *c != b'\n' && *c != b'\r').map(|pos| pos + 1).unwrap_or(0);
+                Ok(password[..len].into())
+            }
+        }
+    }
+}
+
+impl core::fmt::Debug for Secret {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Secret::Literal(_) => write!(f, "Literal(***)"),
+            Secret::Env { env } => write!(f, "Env({env})"),
+            Secret::File { file } => write!(f, "File({file})"),
+        }
+    }
+}
+
+/// Compute the steps that bring the drive from its current state to the configured state.
+///
+/// When the locking SP is not active yet, the locking SP's steps are computed
+/// against its factory state, as it will be right after activation.
+pub async fn plan_provisioning(tper: &TPer, config: &ProvisioningConfig) -> Result<Vec<ProvisioningStep>, Error> {
+    let discovery = tper.discover().await?;
+    let mut steps = plan_ownership(&discovery, config)?;
+    let live = !steps.contains(&ProvisioningStep::ActivateLocking);
+    if config.has_locking_settings() {
+        let admin1_password = config.admin1_password()?;
+        let admin1_password = live.then_some(admin1_password.as_slice());
+        steps.extend(plan_locking_sp(tper, &discovery, config, admin1_password).await?);
+    }
+    Ok(steps)
+}
+
+/// Bring the drive to the configured state. `on_step` is called before each step is carried out.
+pub async fn apply_provisioning(
+    tper: &TPer,
+    config: &ProvisioningConfig,
+    mut on_step: impl FnMut(&ProvisioningStep),
+) -> Result<(), Error> {
+    let discovery = tper.discover().await?;
+    let sid_password = config.sid_password.resolve()?;
+    for step in plan_ownership(&discovery, config)? {
+        on_step(&step);
+        match step {
+            ProvisioningStep::TakeOwnership => take_ownership(tper, &sid_password).await?,
+            ProvisioningStep::ActivateLocking => {
+                let admin1_password = config.admin1_password.as_ref().map(|secret| secret.resolve()).transpose()?;
+                activate_locking(tper, &sid_password, admin1_password.as_deref()).await?
+            }
+            _ => return Err(Error::InternalError),
+        }
+    }
+    if config.has_locking_settings() {
+        let discovery = tper.discover().await?;
+        let admin1_password = config.admin1_password()?;
+        let steps = plan_locking_sp(tper, &discovery, config, Some(&admin1_password)).await?;
+        apply_locking_sp(tper, config, &admin1_password, steps, &mut on_step).await?;
+    }
+    Ok(())
+}
+
+fn plan_ownership(discovery: &Discovery, config: &ProvisioningConfig) -> Result<Vec<ProvisioningStep>, Error> {
+    let mut steps = Vec::new();
+    if is_taking_ownership_supported(discovery) {
+        steps.push(ProvisioningStep::TakeOwnership);
+    }
+    let locking_desc = discovery.get::<LockingDescriptor>();
+    let is_locking_active = locking_desc.is_some_and(|desc| desc.locking_enabled);
+    if config.activate_locking && is_activating_locking_supported(discovery) {
+        steps.push(ProvisioningStep::ActivateLocking);
+    } else if config.has_locking_settings() && !is_locking_active {
+        return Err(Error::InvalidConfig(
+            "ranges, users, and the shadow MBR can only be configured with activate_locking".into(),
+        ));
+    }
+    Ok(steps)
+}
+
+struct Names {
+    ssc: FeatureCode,
+}
+
+impl Names {
+    fn find_range(&self, name: &str) -> Result<LockingRangeRef, Error> {
+        let locking_sp = get_locking_sp(self.ssc)?;
+        get_general_lookup(self.ssc)
+            .by_name(name, table_id::LOCKING.as_uid(), Some(locking_sp.as_uid()))
+            .and_then(|uid| LockingRangeRef::try_from(uid).ok())
+            .ok_or(Error::InvalidConfig(format!("no locking range named `{name}`")))
+    }
+
+    fn find_user(&self, name: &str) -> Result<AuthorityRef, Error> {
+        let locking_sp = get_locking_sp(self.ssc)?;
+        get_general_lookup(self.ssc)
+            .by_name(name, table_id::AUTHORITY.as_uid(), Some(locking_sp.as_uid()))
+            .and_then(|uid| AuthorityRef::try_from(uid).ok())
+            .ok_or(Error::InvalidConfig(format!("no user named `{name}`")))
+    }
+}
+
+/// Computes the steps for the locking SP. The locking SP is assumed to be in
+/// its factory state when `admin1_password` is `None`.
+async fn plan_locking_sp(
+    tper: &TPer,
+    discovery: &Discovery,
+    config: &ProvisioningConfig,
+    admin1_password: Option<&[u8]>,
+) -> Result<Vec<ProvisioningStep>, Error> {
+    let ssc = discovery.get_primary_ssc().ok_or(Error::NoAvailableSSC)?.feature_code();
+    let _ = get_locking_admins(ssc)?;
+    let names = Names { ssc };
+    let mut steps = Vec::new();
+    if !config.ranges.is_empty() {
+        let session = match admin1_password {
+            Some(password) => Some(RangeEditSession::start(tper, password).await?),
+            None => None,
+        };
+        let result = plan_ranges(session.as_ref(), &names, &config.ranges).await;
+        if let Some(session) = session {
+            let _ = session.end().await;
+        }
+        steps.extend(result?);
+    }
+    if !config.users.is_empty() {
+        let session = match admin1_password {
+            Some(password) => Some(UserEditSession::start(tper, password).await?),
+            None => None,
+        };
+        let result = plan_users(session.as_ref(), &names, &config.users).await;
+        if let Some(session) = session {
+            let _ = session.end().await;
+        }
+        steps.extend(result?);
+
+        let session = match admin1_password {
+            Some(password) => Some(PermissionEditSession::start(tper, password).await?),
+            None => None,
+        };
+        let is_mbr_supported =
+            discovery.get::<LockingDescriptor>().is_some_and(|desc| !desc.mbr_shadowing_not_supported);
+        let result = plan_permissions(session.as_ref(), &names, &config.users, is_mbr_supported).await;
+        if let Some(session) = session {
+            let _ = session.end().await;
+        }
+        steps.extend(result?);
+    }
+    if let Some(mbr) = &config.mbr {
+        let session = match admin1_password {
+            Some(password) => Some(MBREditSession::start(tper, password).await?),
+            None => None,
+        };
+        let result = plan_mbr(session.as_ref(), mbr).await;
+        if let Some(session) = session {
+            let _ = session.end().await;
+        }
+        steps.extend(result?);
+    }
+    Ok(steps)
+}
+
+async fn plan_ranges(
+    session: Option<&RangeEditSession>,
+    names: &Names,
+    ranges: &[RangeConfig],
+) -> Result<Vec<ProvisioningStep>, Error> {
+    let mut steps = Vec::new();
+    for config in ranges {
+        let uid = names.find_range(&config.name)?;
+        let is_global_range = uid == crate::spec::opal::locking::locking::GLOBAL_RANGE;
+        if is_global_range && (config.start.is_some() || config.length.is_some()) {
+            return Err(Error::InvalidConfig(format!("the start and length of `{}` cannot be changed", config.name)));
+        }
+        let current = match session {
+            Some(session) => session.get_range(uid).await?,
+            None => LockingRange { uid, ..Default::default() },
+        };
+        let desired = LockingRange {
+            range_start: config.start.unwrap_or(current.range_start),
+            range_length: config.length.unwrap_or(current.range_length),
+            read_lock_enabled: config.read_lock_enabled.unwrap_or(current.read_lock_enabled),
+            write_lock_enabled: config.write_lock_enabled.unwrap_or(current.write_lock_enabled),
+            read_locked: config.read_locked.unwrap_or(current.read_locked),
+            write_locked: config.write_locked.unwrap_or(current.write_locked),
+            ..Default::default()
+        };
+        let is_changed = (desired.range_start, desired.range_length) != (current.range_start, current.range_length)
+            || (desired.read_lock_enabled, desired.write_lock_enabled)
+                != (current.read_lock_enabled, current.write_lock_enabled)
+            || (desired.read_locked, desired.write_locked) != (current.read_locked, current.write_locked);
+        if is_changed {
+            steps
+                .push(ProvisioningStep::SetRange { name: config.name.clone(), range: LockingRange { uid, ..desired } });
+        }
+    }
+    Ok(steps)
+}
+
+async fn plan_users(
+    session: Option<&UserEditSession>,
+    names: &Names,
+    users: &[UserConfig],
+) -> Result<Vec<ProvisioningStep>, Error> {
+    let mut steps = Vec::new();
+    for config in users {
+        let name = config.name.clone();
+        let user = names.find_user(&config.name)?;
+        let (enabled, common_name) = match session {
+            Some(session) => {
+                let authority = session.get_user(user).await?;
+                (authority.enabled, String::from_utf8_lossy(authority.common_name.as_slice()).into_owned())
+            }
+            None => (false, String::new()),
+        };
+        if let Some(desired) = &config.common_name {
+            if desired != &common_name {
+                steps.push(ProvisioningStep::SetUserCommonName {
+                    name: name.clone(),
+                    user,
+                    common_name: desired.clone(),
+                });
+            }
+        }
+        if config.password.is_some() {
+            // Passwords cannot be read back, they are always set.
+            steps.push(ProvisioningStep::SetUserPassword { name: name.clone(), user });
+        }
+        if let Some(desired) = config.enabled {
+            if desired != enabled {
+                steps.push(ProvisioningStep::SetUserEnabled { name: name.clone(), user, enabled: desired });
+            }
+        }
+    }
+    Ok(steps)
+}
+
+async fn plan_permissions(
+    session: Option<&PermissionEditSession>,
+    names: &Names,
+    users: &[UserConfig],
+    is_mbr_supported: bool,
+) -> Result<Vec<ProvisioningStep>, Error> {
+    // In factory state, only the admins have permissions to the ranges, so only the grants are needed.
+    let all_ranges = match session {
+        Some(session) => session.list_ranges().await?,
+        None => Vec::new(),
+    };
+    let mut steps = Vec::new();
+    for config in users {
+        let name = config.name.clone();
+        let user = names.find_user(&config.name)?;
+        for (desired_ranges, is_read) in [(&config.read_ranges, true), (&config.write_ranges, false)] {
+            let Some(desired_ranges) = desired_ranges else {
+                continue;
+            };
+            let desired_ranges = desired_ranges
+                .iter()
+                .map(|range_name| Ok((names.find_range(range_name)?, range_name.clone())))
+                .collect::<Result<Vec<_>, Error>>()?;
+            let ranges = all_ranges.iter().copied().chain(desired_ranges.iter().map(|(range, _)| *range));
+            let mut visited = Vec::new();
+            for range in ranges {
+                if visited.contains(&range) {
+                    continue;
+                }
+                visited.push(range);
+                let desired = desired_ranges.iter().any(|(desired, _)| *desired == range);
+                let current = match (session, is_read) {
+                    (Some(session), true) => session.get_read_permission(user, range).await?,
+                    (Some(session), false) => session.get_write_permission(user, range).await?,
+                    (None, _) => false,
+                };
+                if desired != current {
+                    let range_name = match desired_ranges.iter().find(|(desired, _)| *desired == range) {
+                        Some((_, range_name)) => range_name.clone(),
+                        None => get_general_lookup(names.ssc)
+                            .by_uid(range.as_uid(), None)
+                            .unwrap_or(range.as_uid().to_string()),
+                    };
+                    let name = name.clone();
+                    steps.push(match is_read {
+                        true => {
+                            ProvisioningStep::SetReadPermission { name, user, range_name, range, permitted: desired }
+                        }
+                        false => {
+                            ProvisioningStep::SetWritePermission { name, user, range_name, range, permitted: desired }
+                        }
+                    });
+                }
+            }
+        }
+        if let Some(desired) = config.mbr {
+            if !is_mbr_supported {
+                return Err(Error::IncompatibleSSC);
+            }
+            let current = match session {
+                Some(session) => session.get_mbr_permission(user).await?,
+                None => false,
+            };
+            if desired != current {
+                steps.push(ProvisioningStep::SetMBRPermission { name: name.clone(), user, permitted: desired });
+            }
+        }
+    }
+    Ok(steps)
+}
+
+async fn plan_mbr(session: Option<&MBREditSession>, config: &MBRConfig) -> Result<Vec<ProvisioningStep>, Error> {
+    let mut steps = Vec::new();
+    if let Some(image) = &config.image {
+        // The MBR cannot be compared to the image without reading it back, so it's always uploaded.
+        steps.push(ProvisioningStep::UploadMBR { image: image.clone() });
+    }
+    let (enabled, done) = match session {
+        Some(session) => (session.get_enabled().await?, session.get_done().await?),
+        None => (false, false),
+    };
+    if let Some(desired) = config.enabled.filter(|desired| *desired != enabled) {
+        steps.push(ProvisioningStep::SetMBREnabled { enabled: desired });
+    }
+    if let Some(desired) = config.done.filter(|desired| *desired != done) {
+        steps.push(ProvisioningStep::SetMBRDone { done: desired });
+    }
+    Ok(steps)
+}
+
+async fn apply_locking_sp(
+    tper: &TPer,
+    config: &ProvisioningConfig,
+    admin1_password: &[u8],
+    steps: Vec<ProvisioningStep>,
+    on_step: &mut impl FnMut(&ProvisioningStep),
+) -> Result<(), Error> {
+    for step in steps {
+        on_step(&step);
+        match &step {
+            ProvisioningStep::SetRange { range, .. } => {
+                let session = RangeEditSession::start(tper, admin1_password).await?;
+                let result = session.set_range(range).await;
+                let _ = session.end().await;
+                result?;
+            }
+            ProvisioningStep::SetUserEnabled { user, enabled, .. } => {
+                let session = UserEditSession::start(tper, admin1_password).await?;
+                let result = session.set_enabled(*user, *enabled).await;
+                let _ = session.end().await;
+                result?;
+            }
+            ProvisioningStep::SetUserCommonName { user, common_name, .. } => {
+                let session = UserEditSession::start(tper, admin1_password).await?;
+                let result = session.set_name(*user, common_name).await;
+                let _ = session.end().await;
+                result?;
+            }
+            ProvisioningStep::SetUserPassword { name, user } => {
+                let secret =
+                    config.users.iter().find(|user| &user.name == name).and_then(|user| user.password.as_ref());
+                let password = secret.ok_or(Error::InternalError)?.resolve()?;
+                let session = UserEditSession::start(tper, admin1_password).await?;
+                let result = session.set_password(*user, &password).await;
+                let _ = session.end().await;
+                result?;
+            }
+            ProvisioningStep::SetReadPermission { user, range, permitted, .. } => {
+                let session = PermissionEditSession::start(tper, admin1_password).await?;
+                let result = session.set_read_permission(*user, *range, *permitted).await;
+                let _ = session.end().await;
+                result?;
+            }
+            ProvisioningStep::SetWritePermission { user, range, permitted, .. } => {
+                let session = PermissionEditSession::start(tper, admin1_password).await?;
+                let result = session.set_write_permission(*user, *range, *permitted).await;
+                let _ = session.end().await;
+                result?;
+            }
+            ProvisioningStep::SetMBRPermission { user, permitted, .. } => {
+                let session = PermissionEditSession::start(tper, admin1_password).await?;
+                let result = session.set_mbr_permission(*user, *permitted).await;
+                let _ = session.end().await;
+                result?;
+            }
+            ProvisioningStep::UploadMBR { image } => {
+                let session = MBREditSession::start(tper, admin1_password).await?;
+                let result = upload_mbr(&session, image).await;
+                let _ = session.end().await;
+                result?;
+            }
+            ProvisioningStep::SetMBREnabled { enabled } => {
+                let session = MBREditSession::start(tper, admin1_password).await?;
+                let result = session.set_enabled(*enabled).await;
+                let _ = session.end().await;
+                result?;
+            }
+            ProvisioningStep::SetMBRDone { done } => {
+                let session = MBREditSession::start(tper, admin1_password).await?;
+                let result = session.set_done(*done).await;
+                let _ = session.end().await;
+                result?;
+            }
+            ProvisioningStep::TakeOwnership | ProvisioningStep::ActivateLocking => return Err(Error::InternalError),
+        }
+    }
+    Ok(())
+}
+
+async fn upload_mbr(session: &MBREditSession, image: &str) -> Result<(), Error> {
+    let mut file = std::fs::File::open(image).map_err(|_| Error::FileNotOpen)?;
+    let file_size = file.metadata().map_err(|_| Error::FileReadError)?.len();
+    if file_size > session.get_size().await? {
+        return Err(Error::FileTooLarge);
+    }
+    let read = async |buffer: &mut [u8]| file.read(buffer).map_err(|_| Error::FileReadError);
+    session.upload(read, |_| (), || false).await
+}
+
+fn yes_or_no(value: bool) -> &'static str {
+    match value {
+        true => "yes",
+        false => "no",
+    }
+}
+
+fn grant_or_revoke(permitted: bool) -> &'static str {
+    match permitted {
+        true => "grant",
+        false => "revoke",
+    }
+}
+
+impl core::fmt::Display for ProvisioningStep {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            ProvisioningStep::TakeOwnership => write!(f, "take ownership"),
+            ProvisioningStep::ActivateLocking => write!(f, "activate the locking SP"),
+            ProvisioningStep::SetRange { name, range } => write!(
+                f,
+                "configure {name}: start={}, length={}, read lock enabled={}, write lock enabled={}, read locked={}, write locked={}",
+                range.range_start,
+                range.range_length,
+                yes_or_no(range.read_lock_enabled),
+                yes_or_no(range.write_lock_enabled),
+                yes_or_no(range.read_locked),
+                yes_or_no(range.write_locked),
+            ),
+            ProvisioningStep::SetUserEnabled { name, enabled: true, .. } => write!(f, "enable {name}"),
+            ProvisioningStep::SetUserEnabled { name, enabled: false, .. } => write!(f, "disable {name}"),
+            ProvisioningStep::SetUserCommonName { name, common_name, .. } => {
+                write!(f, "rename {name} to \"{common_name}\"")
+            }
+            ProvisioningStep::SetUserPassword { name, .. } => write!(f, "set the password of {name}"),
+            ProvisioningStep::SetReadPermission { name, range_name, permitted, .. } => {
+                write!(f, "{} {name} the permission to read-lock {range_name}", grant_or_revoke(*permitted))
+            }
+            ProvisioningStep::SetWritePermission { name, range_name, permitted, .. } => {
+                write!(f, "{} {name} the permission to write-lock {range_name}", grant_or_revoke(*permitted))
+            }
+            ProvisioningStep::SetMBRPermission { name, permitted, .. } => {
+                write!(f, "{} {name} the permission to set the shadow MBR done", grant_or_revoke(*permitted))
+            }
+            ProvisioningStep::UploadMBR { image } => write!(f, "upload {image} into the shadow MBR"),
+            ProvisioningStep::SetMBREnabled { enabled } => write!(f, "set shadow MBR enabled={}", yes_or_no(*enabled)),
+            ProvisioningStep::SetMBRDone { done } => write!(f, "set shadow MBR done={}", yes_or_no(*done)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::applications::test_fixtures::{
+        setup_activated_tper, setup_factory_tper, LOCKING_ADMIN1_PASSWORD, SID_PASSWORD,
+    };
+    use crate::spec;
+
+    use super::*;
+
+    fn make_config() -> ProvisioningConfig {
+        ProvisioningConfig {
+            sid_password: Secret::Literal(SID_PASSWORD.into()),
+            activate_locking: true,
+            admin1_password: Some(Secret::Literal(LOCKING_ADMIN1_PASSWORD.into())),
+            ranges: vec![RangeConfig {
+                name: "Range1".into(),
+                start: Some(0),
+                length: Some(1024),
+                read_lock_enabled: Some(true),
+                write_lock_enabled: Some(true),
+                read_locked: None,
+                write_locked: None,
+            }],
+            users: vec![UserConfig {
+                name: "User1".into(),
+                enabled: Some(true),
+                common_name: Some("alice".into()),
+                password: Some(Secret::Literal("user1_password".into())),
+                read_ranges: Some(vec!["Range1".into()]),
+                write_ranges: Some(vec!["Range1".into()]),
+                mbr: Some(true),
+            }],
+            mbr: Some(MBRConfig { enabled: Some(true), done: None, image: None }),
+        }
+    }
+
+    #[tokio::test]
+    async fn plan_factory_device() -> Result<(), Error> {
+        let tper = setup_factory_tper();
+        let steps = plan_provisioning(&tper, &make_config()).await?;
+        let user1 = spec::opal::locking::authority::USER.nth(1).unwrap();
+        let range1 = spec::opal::locking::locking::RANGE.nth(1).unwrap();
+        assert_eq!(steps[0], ProvisioningStep::TakeOwnership);
+        assert_eq!(steps[1], ProvisioningStep::ActivateLocking);
+        assert!(steps.contains(&ProvisioningStep::SetUserEnabled { name: "User1".into(), user: user1, enabled: true }));
+        assert!(steps.contains(&ProvisioningStep::SetReadPermission {
+            name: "User1".into(),
+            user: user1,
+            range_name: "Range1".into(),
+            range: range1,
+            permitted: true
+        }));
+        assert!(steps.contains(&ProvisioningStep::SetMBREnabled { enabled: true }));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn plan_does_not_change_device() -> Result<(), Error> {
+        let tper = setup_factory_tper();
+        let _ = plan_provisioning(&tper, &make_config()).await?;
+        assert!(is_taking_ownership_supported(&tper.discover().await?));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn apply_factory_device() -> Result<(), Error> {
+        let tper = setup_factory_tper();
+        let config = make_config();
+        let mut applied = Vec::new();
+        apply_provisioning(&tper, &config, |step| applied.push(step.to_string())).await?;
+        assert!(applied.len() > 2);
+
+        let session = RangeEditSession::start(&tper, LOCKING_ADMIN1_PASSWORD.as_bytes()).await?;
+        let range = session.get_range(spec::opal::locking::locking::RANGE.nth(1).unwrap()).await?;
+        session.end().await?;
+        assert_eq!((range.range_start, range.range_length), (0, 1024));
+        assert!(range.read_lock_enabled && range.write_lock_enabled);
+
+        let user1 = spec::opal::locking::authority::USER.nth(1).unwrap();
+        let session = UserEditSession::start(&tper, LOCKING_ADMIN1_PASSWORD.as_bytes()).await?;
+        let user = session.get_user(user1).await?;
+        session.end().await?;
+        assert!(user.enabled);
+        assert_eq!(user.common_name.as_slice(), "alice".as_bytes());
+
+        let locking_sp = spec::opal::admin::sp::LOCKING;
+        let user_session = tper.start_session(locking_sp, Some(user1), Some("user1_password".as_bytes())).await?;
+        user_session.end_session().await?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn apply_is_idempotent() -> Result<(), Error> {
+        let tper = setup_factory_tper();
+        let config = ProvisioningConfig {
+            users: vec![UserConfig { password: None, ..make_config().users.remove(0) }],
+            ..make_config()
+        };
+        apply_provisioning(&tper, &config, |_| ()).await?;
+        assert_eq!(plan_provisioning(&tper, &config).await?, vec![]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn revoke_unlisted_permission() -> Result<(), Error> {
+        let tper = setup_activated_tper();
+        let config = ProvisioningConfig {
+            activate_locking: false,
+            ranges: vec![],
+            mbr: None,
+            users: vec![UserConfig { password: None, mbr: None, ..make_config().users.remove(0) }],
+            ..make_config()
+        };
+        apply_provisioning(&tper, &config, |_| ()).await?;
+        let config = ProvisioningConfig {
+            users: vec![UserConfig { read_ranges: Some(vec![]), ..config.users[0].clone() }],
+            ..config
+        };
+        let steps = plan_provisioning(&tper, &config).await?;
+        assert_eq!(steps.len(), 1);
+        assert!(matches!(steps[0], ProvisioningStep::SetReadPermission { permitted: false, .. }));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn locking_settings_need_activation() -> Result<(), Error> {
+        let tper = setup_factory_tper();
+        let config = ProvisioningConfig { activate_locking: false, ..make_config() };
+        assert!(matches!(plan_provisioning(&tper, &config).await, Err(Error::InvalidConfig(_))));
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn parse_toml() -> Result<(), Error> {
+        let text = r#"
+            sid_password = "sid"
+            activate_locking = true
+            admin1_password = { env = "ADMIN1_PASSWORD" }
+
+            [[ranges]]
+            name = "Range1"
+            start = 0
+            length = 1024
+            read_lock_enabled = true
+
+            [[users]]
+            name = "User1"
+            enabled = true
+            password = { file = "user1.txt" }
+            read_ranges = ["Range1"]
+
+            [mbr]
+            done = true
+        "#;
+        let config = ProvisioningConfig::from_toml(text)?;
+        assert_eq!(config.sid_password, Secret::Literal("sid".into()));
+        assert_eq!(config.admin1_password, Some(Secret::Env { env: "ADMIN1_PASSWORD".into() }));
+        assert_eq!(config.ranges[0].length, Some(1024));
+        assert_eq!(config.ranges[0].write_locked, None);
+        assert_eq!(config.users[0].password, Some(Secret::File { file: "user1.txt".into() }));
+        assert_eq!(config.users[0].read_ranges, Some(vec!["Range1".into()]));
+        assert_eq!(config.mbr.as_ref().and_then(|mbr| mbr.done), Some(true));
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn parse_json() -> Result<(), Error> {
+        let text = r#"{ "sid_password": "sid", "users": [{ "name": "User2", "enabled": false }] }"#;
+        let config = ProvisioningConfig::from_json(text)?;
+        assert!(!config.activate_locking);
+        assert_eq!(config.users[0].enabled, Some(false));
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn parse_unknown_field() {
+        let text = r#"sid_password = "sid"
+            sid_pasword = "typo""#;
+        assert!(matches!(ProvisioningConfig::from_toml(text), Err(Error::InvalidConfig(_))));
+    }
+}
@@ -3,13 +3,16 @@
 //L Please refer to the full license distributed with this software.
 //L-----------------------------------------------------------------------------
 
-use crate::messaging::discovery::{Discovery, FeatureCode, LockingDescriptor};
-use crate::spec::column_types::{CredentialRef, LockingRangeRef, MediaKeyRef};
+use crate::messaging::discovery::{Discovery, FeatureCode, GeometryDescriptor, LockingDescriptor};
+use crate::spec;
+use crate::spec::column_types::{CredentialRef, LockingRangeRef, MediaKeyRef, ResetTypes};
 use crate::spec::objects::LockingRange;
 use crate::spec::table_id;
 use crate::tper::{Session, TPer};
 
-use super::utility::start_admin1_session;
+use super::atomic_edit::sealed::TransactionSession;
+use super::range_validation::{validate_range, RangeProblem};
+use super::utility::{is_transaction_supported, start_admin1_session};
 use super::Error;
 
 pub fn is_range_editor_supported(discovery: &Discovery) -> bool {
@@ -34,11 +37,15 @@ pub fn is_range_editor_supported(discovery: &Discovery) -> bool {
 
 pub struct RangeEditSession {
     session: Session,
+    is_transaction_supported: bool,
+    geometry: Option<GeometryDescriptor>,
 }
 
 impl RangeEditSession {
     pub async fn start(tper: &TPer, admin1_password: &[u8]) -> Result<Self, Error> {
-        Ok(Self { session: start_admin1_session(tper, admin1_password).await? })
+        let geometry = tper.discover().await?.get::<GeometryDescriptor>().cloned();
+        let session = start_admin1_session(tper, admin1_password).await?;
+        Ok(Self { session, is_transaction_supported: is_transaction_supported(tper).await, geometry })
     }
 
     pub async fn end(self) -> Result<(), Error> {
@@ -51,9 +58,19 @@ impl RangeEditSession {
     }
 
     pub async fn get_range(&self, range: LockingRangeRef) -> Result<LockingRange, Error> {
-        let columns = LockingRange::RANGE_START..=LockingRange::WRITE_LOCKED;
-        let (range_start, range_length, read_lock_enabled, write_lock_enabled, read_locked, write_locked) =
-            self.session.get_multiple::<(u64, u64, bool, bool, bool, bool)>(range.as_uid(), columns).await?;
+        let columns = LockingRange::RANGE_START..=LockingRange::LOCK_ON_RESET;
+        let (
+            range_start,
+            range_length,
+            read_lock_enabled,
+            write_lock_enabled,
+            read_locked,
+            write_locked,
+            lock_on_reset,
+        ) = self
+            .session
+            .get_multiple::<(u64, u64, bool, bool, bool, bool, ResetTypes)>(range.as_uid(), columns)
+            .await?;
 
         Ok(LockingRange {
             uid: range,
@@ -63,13 +80,59 @@ impl RangeEditSession {
             write_lock_enabled,
             read_locked,
             write_locked,
+            lock_on_reset,
             ..Default::default()
         })
     }
 
+    pub fn get_geometry(&self) -> Option<&GeometryDescriptor> {
+        self.geometry.as_ref()
+    }
+
+    /// Checks `range` against the geometry of the device and the current extents of the other ranges.
+    ///
+    /// An empty list means that the TPer should accept the start and length of `range`.
+    pub async fn validate_range(&self, range: &LockingRange) -> Result<Vec<RangeProblem>, Error> {
+        let global_range = spec::opal::locking::locking::GLOBAL_RANGE;
+        if range.uid == global_range {
+            return Ok(validate_range(range, &[], self.geometry.as_ref()));
+        }
+        let ranges = self.list_ranges().await?;
+        let uids: Vec<_> = ranges.into_iter().filter(|uid| *uid != range.uid && *uid != global_range).collect();
+        let mut batch = self.session.batch();
+        let columns = LockingRange::RANGE_START..=LockingRange::RANGE_LENGTH;
+        let extents: Vec<_> =
+            uids.iter().map(|uid| batch.get_multiple::<(u64, u64)>(uid.as_uid(), columns.clone())).collect();
+        let mut results = batch.execute().await;
+        let mut others = Vec::new();
+        for (uid, extent) in uids.into_iter().zip(extents) {
+            let (range_start, range_length) = results.take(extent)?;
+            others.push(LockingRange { uid, range_start, range_length, ..Default::default() });
+        }
+        Ok(validate_range(range, &others, self.geometry.as_ref()))
+    }
+
+    /// Sets the extent and the lock states of the range.
+    ///
+    /// The extent is validated with [`Self::validate_range`] first, and the first problem is returned
+    /// as [`Error::InvalidRange`] without sending anything to the TPer.
     pub async fn set_range(&self, range: &LockingRange) -> Result<(), Error> {
-        let is_global_range = range.uid == crate::spec::opal::locking::locking::GLOBAL_RANGE;
+        match self.try_set_range(range).await?.into_iter().next() {
+            Some(problem) => Err(Error::InvalidRange(problem)),
+            None => Ok(()),
+        }
+    }
+
+    /// Same as [`Self::set_range`], but returns all problems found by the validation.
+    ///
+    /// The range is only changed if the returned list is empty.
+    pub async fn try_set_range(&self, range: &LockingRange) -> Result<Vec<RangeProblem>, Error> {
+        let is_global_range = range.uid == spec::opal::locking::locking::GLOBAL_RANGE;
         if !is_global_range {
+            let problems = self.validate_range(range).await?;
+            if !problems.is_empty() {
+                return Ok(problems);
+            }
             let columns: [u16; 6] = core::array::from_fn(|i| LockingRange::RANGE_START + (i as u16));
             let values = (
                 range.range_start,
@@ -79,12 +142,21 @@ impl RangeEditSession {
                 range.read_locked,
                 range.write_locked,
             );
-            Ok(self.session.set_multiple(range.uid.as_uid(), columns, values).await?)
+            self.session.set_multiple(range.uid.as_uid(), columns, values).await?;
         } else {
             let columns: [u16; 4] = core::array::from_fn(|i| LockingRange::READ_LOCK_ENABLED + (i as u16));
             let values = (range.read_lock_enabled, range.write_lock_enabled, range.read_locked, range.write_locked);
-            Ok(self.session.set_multiple(range.uid.as_uid(), columns, values).await?)
+            self.session.set_multiple(range.uid.as_uid(), columns, values).await?;
         }
+        Ok(Vec::new())
+    }
+
+    /// Selects the reset events that lock the range.
+    ///
+    /// Add [`ResetType::Programmatic`](crate::spec::column_types::ResetType::Programmatic)
+    /// to make [`TPer::tper_reset`] lock the range.
+    pub async fn set_lock_on_reset(&self, range: LockingRangeRef, lock_on_reset: &ResetTypes) -> Result<(), Error> {
+        Ok(self.session.set(range.as_uid(), LockingRange::LOCK_ON_RESET, lock_on_reset.clone()).await?)
     }
 
     pub async fn erase_range(&self, range: LockingRangeRef) -> Result<(), Error> {
@@ -93,8 +165,19 @@ impl RangeEditSession {
     }
 }
 
+impl TransactionSession for RangeEditSession {
+    fn session(&self) -> &Session {
+        &self.session
+    }
+
+    fn is_transaction_supported(&self) -> bool {
+        self.is_transaction_supported
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use crate::applications::atomic_edit::AtomicEdit as _;
     use crate::applications::test_fixtures::{setup_activated_tper, LOCKING_ADMIN1_PASSWORD};
     use crate::spec;
 
@@ -142,6 +225,75 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn set_misaligned_range() -> Result<(), Error> {
+        let tper = setup_activated_tper();
+        let session = RangeEditSession::start(&tper, LOCKING_ADMIN1_PASSWORD.as_bytes()).await?;
+        let uid = spec::opal::locking::locking::RANGE.nth(1).unwrap();
+        let range = session.get_range(uid).await?;
+        let modified = LockingRange { range_length: 10001, ..range.clone() };
+        let result = session.set_range(&modified).await;
+        let problem = RangeProblem::MisalignedLength { granularity: 16, suggested: 10000 };
+        assert_eq!(result, Err(Error::InvalidRange(problem)));
+        assert_eq!(session.get_range(uid).await?, range);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn try_set_misaligned_range() -> Result<(), Error> {
+        let tper = setup_activated_tper();
+        let session = RangeEditSession::start(&tper, LOCKING_ADMIN1_PASSWORD.as_bytes()).await?;
+        let uid = spec::opal::locking::locking::RANGE.nth(1).unwrap();
+        let range = session.get_range(uid).await?;
+        let modified = LockingRange { range_start: 17, range_length: 10001, ..range.clone() };
+        let problems = session.try_set_range(&modified).await?;
+        assert_eq!(
+            problems,
+            vec![
+                RangeProblem::MisalignedStart { granularity: 16, lowest_aligned_lba: 0, suggested: 16 },
+                RangeProblem::MisalignedLength { granularity: 16, suggested: 10000 },
+            ]
+        );
+        assert_eq!(session.get_range(uid).await?, range);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn set_overlapping_range() -> Result<(), Error> {
+        let tper = setup_activated_tper();
+        let session = RangeEditSession::start(&tper, LOCKING_ADMIN1_PASSWORD.as_bytes()).await?;
+        let uid1 = spec::opal::locking::locking::RANGE.nth(1).unwrap();
+        let uid2 = spec::opal::locking::locking::RANGE.nth(2).unwrap();
+        let range1 = LockingRange { range_start: 0, range_length: 10000, ..session.get_range(uid1).await? };
+        session.set_range(&range1).await?;
+        let range2 = LockingRange { range_start: 9984, range_length: 32, ..session.get_range(uid2).await? };
+        let problems = session.validate_range(&range2).await?;
+        assert_eq!(problems, vec![RangeProblem::Overlap { other: uid1, start: 0, end: 10000 }]);
+        assert!(session.set_range(&range2).await.is_err());
+        let range2 = LockingRange { range_start: 10000, ..range2 };
+        session.set_range(&range2).await?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn atomic_rollback() -> Result<(), Error> {
+        let tper = setup_activated_tper();
+        let session = RangeEditSession::start(&tper, LOCKING_ADMIN1_PASSWORD.as_bytes()).await?;
+        let uid = spec::opal::locking::locking::RANGE.nth(1).unwrap();
+        assert!(session.is_atomic());
+        let range = session.get_range(uid).await?;
+        let modified = LockingRange { range_length: 10000, read_lock_enabled: true, ..range.clone() };
+        let result = session
+            .atomic(async |session| {
+                session.set_range(&modified).await?;
+                session.set_range(&LockingRange { uid: LockingRangeRef::null(), ..modified.clone() }).await
+            })
+            .await;
+        assert!(result.is_err());
+        assert_eq!(session.get_range(uid).await?, range);
+        Ok(())
+    }
+
     #[tokio::test]
     async fn erase_range() -> Result<(), Error> {
         let tper = setup_activated_tper();
@@ -0,0 +1,196 @@
+//L-----------------------------------------------------------------------------
+//L Copyright (C) Péter Kardos
+//L Please refer to the full license distributed with this software.
+//L-----------------------------------------------------------------------------
+
+use crate::messaging::discovery::GeometryDescriptor;
+use crate::spec;
+use crate::spec::column_types::LockingRangeRef;
+use crate::spec::objects::LockingRange;
+
+/// A reason for the TPer to reject a locking range, or for the range to conflict with another one.
+///
+/// Alignment problems come with the closest value that satisfies the geometry of the device.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum RangeProblem {
+    #[error("the start is not aligned to {granularity} blocks from LBA {lowest_aligned_lba}, try {suggested}")]
+    MisalignedStart { granularity: u64, lowest_aligned_lba: u64, suggested: u64 },
+    #[error("the length is not a multiple of {granularity} blocks, try {suggested}")]
+    MisalignedLength { granularity: u64, suggested: u64 },
+    #[error("the range overlaps LBAs {start}..{end} of another range")]
+    Overlap { other: LockingRangeRef, start: u64, end: u64 },
+}
+
+/// Checks the start and length of `range` against the geometry of the device and the `others` ranges.
+///
+/// The global range is not checked as its extent cannot be changed, and neither are ranges of zero length,
+/// as they cover no LBAs. When checking for overlaps, the global range, disabled ranges of zero length,
+/// and `range` itself are skipped in `others`.
+///
+/// The logical block size of the geometry is not checked: starts, lengths and the alignment
+/// granularity are all counted in logical blocks, so any block size is already accounted for.
+pub fn validate_range(
+    range: &LockingRange,
+    others: &[LockingRange],
+    geometry: Option<&GeometryDescriptor>,
+) -> Vec<RangeProblem> {
+    let global_range = spec::opal::locking::locking::GLOBAL_RANGE; // The same for all relevant SSCs.
+    if range.uid == global_range {
+        return Vec::new();
+    }
+    if range.range_length == 0 {
+        return Vec::new();
+    }
+    let mut problems = Vec::new();
+    if let Some(geometry) = geometry.filter(|geometry| geometry.align) {
+        let granularity = core::cmp::max(1, geometry.alignment_granularity);
+        let (start, length) = snap_range(range.range_start, range.range_length, geometry);
+        if start != range.range_start {
+            let lowest_aligned_lba = geometry.lowest_aligned_lba;
+            problems.push(RangeProblem::MisalignedStart { granularity, lowest_aligned_lba, suggested: start });
+        }
+        if length != range.range_length {
+            problems.push(RangeProblem::MisalignedLength { granularity, suggested: length });
+        }
+    }
+    let end = range.range_start.saturating_add(range.range_length);
+    for other in others {
+        if other.uid == range.uid || other.uid == global_range || other.range_length == 0 {
+            continue;
+        }
+        let other_end = other.range_start.saturating_add(other.range_length);
+        if range.range_start < other_end && other.range_start < end {
+            problems.push(RangeProblem::Overlap { other: other.uid, start: other.range_start, end: other_end });
+        }
+    }
+    problems
+}
+
+/// Returns the closest start and length to the arguments that satisfy the alignment requirements.
+///
+/// Values are rounded down, except that a start before the first aligned LBA is moved up to it,
+/// and a non-zero length is never rounded down to zero.
+pub fn snap_range(range_start: u64, range_length: u64, geometry: &GeometryDescriptor) -> (u64, u64) {
+    let granularity = core::cmp::max(1, geometry.alignment_granularity); // Zero means no alignment.
+    let offset = geometry.lowest_aligned_lba % granularity;
+    let start = match range_start.checked_sub(offset) {
+        Some(base) => offset + base / granularity * granularity,
+        None => offset,
+    };
+    let length = match range_length / granularity * granularity {
+        0 if range_length != 0 => granularity,
+        length => length,
+    };
+    (start, length)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GEOMETRY: GeometryDescriptor =
+        GeometryDescriptor { align: true, logical_block_size: 512, alignment_granularity: 8, lowest_aligned_lba: 4 };
+
+    fn make_range(idx: u64, range_start: u64, range_length: u64) -> LockingRange {
+        let uid = spec::opal::locking::locking::RANGE.nth(idx).unwrap();
+        LockingRange { uid, range_start, range_length, ..Default::default() }
+    }
+
+    #[test]
+    fn snap_range_zero_alignment() {
+        let geometry = GeometryDescriptor { alignment_granularity: 0, lowest_aligned_lba: 7, ..GEOMETRY };
+        assert_eq!(snap_range(11, 5, &geometry), (11, 5));
+    }
+
+    #[test]
+    fn snap_range_offset_aligned() {
+        let geometry = GeometryDescriptor { alignment_granularity: 4, lowest_aligned_lba: 7, ..GEOMETRY };
+        assert_eq!(snap_range(11, 8, &geometry), (11, 8));
+    }
+
+    #[test]
+    fn snap_range_offset_misaligned() {
+        let geometry = GeometryDescriptor { alignment_granularity: 4, lowest_aligned_lba: 7, ..GEOMETRY };
+        assert_eq!(snap_range(13, 10, &geometry), (11, 8));
+    }
+
+    #[test]
+    fn snap_range_nooffset_misaligned() {
+        let geometry = GeometryDescriptor { alignment_granularity: 4, lowest_aligned_lba: 0, ..GEOMETRY };
+        assert_eq!(snap_range(13, 10, &geometry), (12, 8));
+    }
+
+    #[test]
+    fn snap_range_below_offset() {
+        assert_eq!(snap_range(0, 0, &GEOMETRY), (4, 0));
+    }
+
+    #[test]
+    fn snap_range_short_length() {
+        assert_eq!(snap_range(4, 3, &GEOMETRY), (4, 8));
+    }
+
+    #[test]
+    fn validate_aligned() {
+        let range = make_range(1, 12, 16);
+        assert_eq!(validate_range(&range, &[], Some(&GEOMETRY)), vec![]);
+    }
+
+    #[test]
+    fn validate_misaligned() {
+        let range = make_range(1, 13, 10);
+        let problems = validate_range(&range, &[], Some(&GEOMETRY));
+        assert_eq!(
+            problems,
+            vec![
+                RangeProblem::MisalignedStart { granularity: 8, lowest_aligned_lba: 4, suggested: 12 },
+                RangeProblem::MisalignedLength { granularity: 8, suggested: 8 },
+            ]
+        );
+    }
+
+    #[test]
+    fn validate_logical_block_size() {
+        let geometry = GeometryDescriptor { logical_block_size: 4096, ..GEOMETRY };
+        let range = make_range(1, 13, 10);
+        assert_eq!(validate_range(&range, &[], Some(&geometry)), validate_range(&range, &[], Some(&GEOMETRY)));
+    }
+
+    #[test]
+    fn validate_zero_length() {
+        let range = make_range(1, 0, 0);
+        let others = [make_range(2, 0, 1000)];
+        assert_eq!(validate_range(&range, &others, Some(&GEOMETRY)), vec![]);
+    }
+
+    #[test]
+    fn validate_no_align_required() {
+        let geometry = GeometryDescriptor { align: false, ..GEOMETRY };
+        let range = make_range(1, 13, 10);
+        assert_eq!(validate_range(&range, &[], Some(&geometry)), vec![]);
+        assert_eq!(validate_range(&range, &[], None), vec![]);
+    }
+
+    #[test]
+    fn validate_overlap() {
+        let range = make_range(1, 12, 16);
+        let others = [
+            make_range(1, 12, 16),   // Itself.
+            make_range(2, 4, 8),     // Adjacent before.
+            make_range(3, 28, 8),    // Adjacent after.
+            make_range(4, 20, 1000), // Overlapping.
+            make_range(5, 12, 0),    // Disabled.
+        ];
+        let other = spec::opal::locking::locking::RANGE.nth(4).unwrap();
+        let problems = validate_range(&range, &others, Some(&GEOMETRY));
+        assert_eq!(problems, vec![RangeProblem::Overlap { other, start: 20, end: 1020 }]);
+    }
+
+    #[test]
+    fn validate_global_range() {
+        let global_range = spec::opal::locking::locking::GLOBAL_RANGE;
+        let range = LockingRange { uid: global_range, range_start: 13, range_length: 10, ..Default::default() };
+        let others = [make_range(1, 0, 1000)];
+        assert_eq!(validate_range(&range, &others, Some(&GEOMETRY)), vec![]);
+    }
+}
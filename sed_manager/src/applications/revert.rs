@@ -33,11 +33,12 @@ pub async fn revert(tper: &TPer, authority: AuthorityRef, password: &[u8], sp: S
 mod tests {
     use std::sync::Arc;
 
-    use crate::applications::test_fixtures::{make_activated_device, SID_PASSWORD};
+    use crate::applications::test_fixtures::{for_each_ssc, make_activated_device_of, new_controller, SID_PASSWORD};
+    use crate::applications::utility::get_locking_sp;
     use crate::fake_device::{data::object_table::CPINTable, FakeDevice};
+    use crate::messaging::discovery::FeatureCode;
     use crate::rpc::TokioRuntime;
     use crate::spec;
-    use crate::spec::column_types::LifeCycleState;
     use crate::spec::table_id;
     use crate::tper::TPer;
 
@@ -53,34 +54,56 @@ mod tests {
         })
     }
 
-    pub fn is_locking_in_factory_state(device: &FakeDevice) -> bool {
+    pub fn is_locking_in_factory_state(device: &FakeDevice, ssc: FeatureCode) -> bool {
+        let factory = new_controller(ssc);
+        let locking_sp = get_locking_sp(ssc).unwrap();
+        let factory_c_pins: &CPINTable =
+            factory.get_sp(locking_sp).unwrap().get_object_table_specific(table_id::C_PIN).unwrap();
         device.with_tper(|tper| {
-            println!("{:?}", tper.ssc.get_life_cycle_state(spec::opal::admin::sp::LOCKING));
-            tper.ssc.get_life_cycle_state(spec::opal::admin::sp::LOCKING) == Ok(LifeCycleState::ManufacturedInactive)
+            let c_pins: &CPINTable =
+                tper.ssc.get_sp(locking_sp).unwrap().get_object_table_specific(table_id::C_PIN).unwrap();
+            let same_c_pins = c_pins.values().all(|c_pin| factory_c_pins.get(&c_pin.uid).unwrap().pin == c_pin.pin);
+            let same_life_cycle = tper.ssc.get_life_cycle_state(locking_sp) == factory.get_life_cycle_state(locking_sp);
+            same_c_pins && same_life_cycle
         })
     }
 
-    #[tokio::test]
-    async fn revert_success_admin() -> Result<(), Error> {
+    async fn revert_success_admin(ssc: FeatureCode) -> Result<(), Error> {
         let runtime = Arc::new(TokioRuntime::new());
-        let device = Arc::new(make_activated_device());
+        let device = Arc::new(make_activated_device_of(ssc));
         let tper = TPer::new_on_default_com_id(device.clone(), runtime)?;
-        assert!(!is_admin_in_factory_state(&*device));
-        assert!(!is_locking_in_factory_state(&*device));
-        revert(&tper, spec::core::authority::SID, SID_PASSWORD.as_bytes(), spec::opal::admin::sp::ADMIN).await?;
-        assert!(is_admin_in_factory_state(&*device));
-        assert!(is_locking_in_factory_state(&*device));
+        assert!(!is_admin_in_factory_state(&device));
+        assert!(!is_locking_in_factory_state(&device, ssc));
+        revert(&tper, spec::core::authority::SID, SID_PASSWORD.as_bytes(), get_admin_sp(ssc)?).await?;
+        assert!(is_admin_in_factory_state(&device));
+        assert!(is_locking_in_factory_state(&device, ssc));
         Ok(())
     }
 
-    #[tokio::test]
-    async fn revert_success_locking() -> Result<(), Error> {
+    async fn revert_success_locking(ssc: FeatureCode) -> Result<(), Error> {
         let runtime = Arc::new(TokioRuntime::new());
-        let device = Arc::new(make_activated_device());
+        let device = Arc::new(make_activated_device_of(ssc));
         let tper = TPer::new_on_default_com_id(device.clone(), runtime)?;
-        assert!(!is_locking_in_factory_state(&*device));
-        revert(&tper, spec::core::authority::SID, SID_PASSWORD.as_bytes(), spec::opal::admin::sp::LOCKING).await?;
-        assert!(is_locking_in_factory_state(&*device));
+        assert!(!is_locking_in_factory_state(&device, ssc));
+        let result = revert(&tper, spec::core::authority::SID, SID_PASSWORD.as_bytes(), get_locking_sp(ssc)?).await;
+        if ssc == FeatureCode::Enterprise {
+            // Enterprise can only revert the whole TPer through the Admin SP.
+            assert!(result.is_err());
+            assert!(!is_locking_in_factory_state(&device, ssc));
+        } else {
+            result?;
+            assert!(is_locking_in_factory_state(&device, ssc));
+        }
         Ok(())
     }
+
+    #[tokio::test]
+    async fn revert_success_admin_fake_devices() {
+        for_each_ssc(revert_success_admin).await
+    }
+
+    #[tokio::test]
+    async fn revert_success_locking_fake_devices() {
+        for_each_ssc(revert_success_locking).await
+    }
 }
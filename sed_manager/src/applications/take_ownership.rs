@@ -38,7 +38,7 @@ pub async fn take_ownership(tper: &TPer, new_password: &[u8]) -> Result<(), Erro
     let ssc = discovery.get_primary_ssc().ok_or(Error::NoAvailableSSC)?;
     let admin_sp = get_admin_sp(ssc.feature_code())?;
 
-    let anybody_session = tper.start_session(admin_sp, None, None).await?;
+    let anybody_session = tper.session(admin_sp).read_only().start().await?;
     let msid_password: Password =
         anybody_session.with(async |session| session.get(c_pin::MSID.as_uid(), CPIN::PIN).await).await?;
     let sid_session = tper.start_session(admin_sp, Some(authority::SID), Some(&msid_password)).await?;
@@ -62,29 +62,36 @@ pub async fn verify_ownership(tper: &TPer, sid_password: &[u8]) -> Result<bool,
 mod tests {
     use std::sync::Arc;
 
-    use crate::{fake_device::FakeDevice, rpc::TokioRuntime, tper::TPer};
+    use crate::applications::test_fixtures::{for_each_ssc, make_factory_device_of};
+    use crate::{rpc::TokioRuntime, tper::TPer};
 
     use super::*;
 
     #[tokio::test]
-    async fn take_ownership_success() -> Result<(), Error> {
-        let new_password = "macilaci".as_bytes();
-        let runtime = Arc::new(TokioRuntime::new());
-        let device = Arc::new(FakeDevice::new());
-        let tper = TPer::new_on_default_com_id(device, runtime)?;
-        take_ownership(&tper, new_password).await?;
-        assert!(verify_ownership(&tper, new_password).await?);
-        Ok(())
+    async fn take_ownership_success() {
+        for_each_ssc(async |ssc| {
+            let new_password = "macilaci".as_bytes();
+            let runtime = Arc::new(TokioRuntime::new());
+            let device = Arc::new(make_factory_device_of(ssc));
+            let tper = TPer::new_on_default_com_id(device, runtime)?;
+            take_ownership(&tper, new_password).await?;
+            assert!(verify_ownership(&tper, new_password).await?, "{ssc:?}");
+            Ok(())
+        })
+        .await
     }
 
     #[tokio::test]
-    async fn take_ownership_already_taken() -> Result<(), Error> {
-        let new_password = "macilaci".as_bytes();
-        let runtime = Arc::new(TokioRuntime::new());
-        let device = Arc::new(FakeDevice::new());
-        let tper = TPer::new_on_default_com_id(device, runtime)?;
-        take_ownership(&tper, new_password).await?;
-        assert!(take_ownership(&tper, "zsiroskenyer".as_bytes()).await.is_err());
-        Ok(())
+    async fn take_ownership_already_taken() {
+        for_each_ssc(async |ssc| {
+            let new_password = "macilaci".as_bytes();
+            let runtime = Arc::new(TokioRuntime::new());
+            let device = Arc::new(make_factory_device_of(ssc));
+            let tper = TPer::new_on_default_com_id(device, runtime)?;
+            take_ownership(&tper, new_password).await?;
+            assert!(take_ownership(&tper, "zsiroskenyer".as_bytes()).await.is_err(), "{ssc:?}");
+            Ok(())
+        })
+        .await
     }
 }
@@ -5,21 +5,56 @@
 
 use std::sync::Arc;
 
+use crate::applications::Error;
 use crate::fake_device::data::object_table::CPINTable;
+use crate::fake_device::data::{self, SecuritySubsystemClass};
 use crate::fake_device::FakeDevice;
+use crate::messaging::discovery::{Discovery, FeatureCode};
 use crate::rpc::TokioRuntime;
+use crate::spec::column_types::{CPINRef, LifeCycleState};
 use crate::spec::{self, table_id};
 use crate::tper::TPer;
 
 pub const SID_PASSWORD: &str = "sid_password";
 pub const LOCKING_ADMIN1_PASSWORD: &str = "L_admin1_pw";
 
+/// Every SSC the fake device has preconfigured data for.
+pub const EMULATED_SSCS: [FeatureCode; 6] = [
+    FeatureCode::OpalV2,
+    FeatureCode::Opalite,
+    FeatureCode::PyriteV2,
+    FeatureCode::Ruby,
+    FeatureCode::Enterprise,
+    FeatureCode::KeyPerIO,
+];
+
+/// Runs `test` for each of the [`EMULATED_SSCS`] and panics with the name of the first SSC it fails for.
+pub async fn for_each_ssc(test: impl AsyncFn(FeatureCode) -> Result<(), Error>) {
+    for ssc in EMULATED_SSCS {
+        if let Err(error) = test(ssc).await {
+            panic!("{ssc:?}: {error:?}");
+        }
+    }
+}
+
+pub fn new_controller(ssc: FeatureCode) -> SecuritySubsystemClass {
+    data::new_controller(ssc).unwrap_or_else(|| panic!("the fake device does not emulate {ssc:?}"))
+}
+
 pub fn make_factory_device() -> FakeDevice {
-    FakeDevice::new()
+    make_factory_device_of(FeatureCode::OpalV2)
+}
+
+pub fn make_factory_device_of(ssc: FeatureCode) -> FakeDevice {
+    FakeDevice::with_ssc(new_controller(ssc))
 }
 
 pub fn make_owned_device() -> FakeDevice {
-    let device = FakeDevice::new();
+    make_owned_device_of(FeatureCode::OpalV2)
+}
+
+pub fn make_owned_device_of(ssc: FeatureCode) -> FakeDevice {
+    let device = make_factory_device_of(ssc);
     device.with_tper_mut(|tper| {
         let admin_sp = tper.ssc.get_admin_sp_mut().unwrap();
         let c_pin_table: &mut CPINTable = admin_sp.get_object_table_specific_mut(table_id::C_PIN).unwrap();
@@ -30,17 +65,39 @@ pub fn make_owned_device() -> FakeDevice {
 }
 
 pub fn make_activated_device() -> FakeDevice {
-    let device = make_owned_device();
+    make_activated_device_of(FeatureCode::OpalV2)
+}
+
+pub fn make_activated_device_of(ssc: FeatureCode) -> FakeDevice {
+    let device = make_owned_device_of(ssc);
+    // The Enterprise Locking SP is always active, its first BandMaster stands in for Admin1.
+    let (locking_sp_ref, admin1_c_pin_ref): (_, CPINRef) = match ssc {
+        FeatureCode::Enterprise => {
+            (spec::enterprise::admin::sp::LOCKING, spec::enterprise::locking::c_pin::BAND_MASTER.nth(0).unwrap())
+        }
+        FeatureCode::KeyPerIO => {
+            (spec::kpio::admin::sp::KEY_PER_IO, spec::kpio::key_per_io::c_pin::ADMIN.nth(1).unwrap())
+        }
+        _ => (spec::opal::admin::sp::LOCKING, spec::opal::locking::c_pin::ADMIN.nth(1).unwrap()),
+    };
     device.with_tper_mut(|tper| {
-        tper.ssc.activate_sp(spec::opal::admin::sp::LOCKING).unwrap();
-        let locking_sp = tper.ssc.get_sp_mut(spec::opal::admin::sp::LOCKING).unwrap();
+        if tper.ssc.get_life_cycle_state(locking_sp_ref) == Ok(LifeCycleState::ManufacturedInactive) {
+            tper.ssc.activate_sp(locking_sp_ref).unwrap();
+        }
+        let locking_sp = tper.ssc.get_sp_mut(locking_sp_ref).unwrap();
         let c_pin_table: &mut CPINTable = locking_sp.get_object_table_specific_mut(table_id::C_PIN).unwrap();
-        let admin1_c_pin = c_pin_table.get_mut(&spec::opal::locking::c_pin::ADMIN.nth(1).unwrap()).unwrap();
+        let admin1_c_pin = c_pin_table.get_mut(&admin1_c_pin_ref).unwrap();
         admin1_c_pin.pin = LOCKING_ADMIN1_PASSWORD.into();
     });
     device
 }
 
+pub async fn discover(device: FakeDevice) -> Discovery {
+    let runtime = Arc::new(TokioRuntime::new());
+    let tper = TPer::new_on_default_com_id(Arc::new(device), runtime).unwrap();
+    tper.discover().await.unwrap()
+}
+
 pub fn setup_factory_tper() -> TPer {
     let runtime = Arc::new(TokioRuntime::new());
     let device = Arc::new(make_factory_device());
@@ -4,7 +4,7 @@
 //L-----------------------------------------------------------------------------
 
 use crate::messaging::discovery::{Discovery, FeatureCode, LockingDescriptor};
-use crate::spec::column_types::{AuthMethod, AuthorityRef, CPINRef};
+use crate::spec::column_types::{AuthMethod, AuthorityRef, CPINRef, Name};
 use crate::spec::objects::{Authority, CPIN};
 use crate::spec::table_id;
 use crate::tper::{Session, TPer};
@@ -46,10 +46,16 @@ impl UserEditSession {
 
     pub async fn list_users(&self) -> Result<Vec<AuthorityRef>, Error> {
         let authorities = self.session.next(table_id::AUTHORITY, None, None).await?;
-        let authorities = authorities.into_iter().filter_map(|uid| AuthorityRef::try_from(uid).ok());
+        let authorities: Vec<_> = authorities.into_iter().filter_map(|uid| AuthorityRef::try_from(uid).ok()).collect();
+        let mut batch = self.session.batch();
+        let operations: Vec<_> = authorities
+            .iter()
+            .map(|authority| batch.get::<AuthMethod>(authority.as_uid(), Authority::OPERATION))
+            .collect();
+        let mut results = batch.execute().await;
         let mut users = Vec::new();
-        for authority in authorities {
-            if self.is_user(authority).await? {
+        for (authority, operation) in authorities.into_iter().zip(operations) {
+            if results.take(operation)? == AuthMethod::Password {
                 users.push(authority);
             }
         }
@@ -57,10 +63,29 @@ impl UserEditSession {
     }
 
     pub async fn get_user(&self, user: AuthorityRef) -> Result<Authority, Error> {
-        let common_name = self.session.get(user.as_uid(), Authority::COMMON_NAME).await?;
-        let enabled = self.session.get(user.as_uid(), Authority::ENABLED).await?;
+        let mut users = self.get_users(&[user]).await?;
+        Ok(users.remove(0))
+    }
 
-        Ok(Authority { uid: user, common_name, enabled, ..Default::default() })
+    /// Same as [`Self::get_user`], but queries all users at once.
+    pub async fn get_users(&self, users: &[AuthorityRef]) -> Result<Vec<Authority>, Error> {
+        let mut batch = self.session.batch();
+        let columns: Vec<_> = users
+            .iter()
+            .map(|user| {
+                let common_name = batch.get::<Name>(user.as_uid(), Authority::COMMON_NAME);
+                let enabled = batch.get::<bool>(user.as_uid(), Authority::ENABLED);
+                (common_name, enabled)
+            })
+            .collect();
+        let mut results = batch.execute().await;
+        let mut authorities = Vec::new();
+        for (user, (common_name, enabled)) in users.iter().zip(columns) {
+            let common_name = results.take(common_name)?;
+            let enabled = results.take(enabled)?;
+            authorities.push(Authority { uid: *user, common_name, enabled, ..Default::default() });
+        }
+        Ok(authorities)
     }
 
     pub async fn set_enabled(&self, user: AuthorityRef, enabled: bool) -> Result<(), Error> {
@@ -75,11 +100,6 @@ impl UserEditSession {
         let credential: CPINRef = self.session.get(user.as_uid(), Authority::CREDENTIAL).await?;
         Ok(self.session.set(credential.as_uid(), CPIN::PIN, password).await?)
     }
-
-    async fn is_user(&self, authority: AuthorityRef) -> Result<bool, Error> {
-        let operation: AuthMethod = self.session.get(authority.as_uid(), Authority::OPERATION).await?;
-        Ok(operation == AuthMethod::Password)
-    }
 }
 
 #[cfg(test)]
@@ -102,6 +122,24 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn get_users() -> Result<(), Error> {
+        let tper = setup_activated_tper();
+        let session = UserEditSession::start(&tper, LOCKING_ADMIN1_PASSWORD.as_bytes()).await?;
+        let user1 = spec::opal::locking::authority::USER.nth(1).unwrap();
+        let user2 = spec::opal::locking::authority::USER.nth(2).unwrap();
+        session.set_enabled(user2, true).await?;
+        session.set_name(user2, "Piglet").await?;
+        let users = session.get_users(&[user1, user2]).await?;
+        assert_eq!(users.len(), 2);
+        assert_eq!(users[0].uid, user1);
+        assert!(!users[0].enabled);
+        assert_eq!(users[1].uid, user2);
+        assert!(users[1].enabled);
+        assert_eq!(users[1].common_name.as_slice(), "Piglet".as_bytes());
+        Ok(())
+    }
+
     #[tokio::test]
     async fn set_enabled() -> Result<(), Error> {
         let tper = setup_activated_tper();
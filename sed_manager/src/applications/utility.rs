@@ -4,13 +4,22 @@
 //L-----------------------------------------------------------------------------
 
 use crate::messaging::discovery::FeatureCode;
+use crate::messaging::packet::{COM_PACKET_HEADER_LEN, PACKET_HEADER_LEN, SUB_PACKET_HEADER_LEN};
 use crate::messaging::uid_range::ObjectUIDRange;
-use crate::spec::column_types::{AuthorityRefRange, CPINRefRange, SPRef};
+use crate::rpc::Properties;
+use crate::spec::column_types::{ACEOperand, AuthorityRef, AuthorityRefRange, CPINRef, CPINRefRange, SPRef};
+use crate::spec::objects::{ACEExpr, Authority};
 use crate::spec::{self, ObjectLookup};
 use crate::tper::{Session, TPer};
 
 use super::error::Error;
 
+// Opalite only has Admin1, but the range still has to start at the base UID so that `nth(1)` is Admin1.
+const OPALITE_LOCKING_ADMINS: AuthorityRefRange =
+    ObjectUIDRange::new_count(AuthorityRef::new(spec::opalite::locking::authority::ADMIN1.as_u64() - 1), 2, 1);
+const OPALITE_LOCKING_ADMIN_C_PINS: CPINRefRange =
+    ObjectUIDRange::new_count(CPINRef::new(spec::opalite::locking::c_pin::ADMIN1.as_u64() - 1), 2, 1);
+
 pub fn get_admin_sp(ssc: FeatureCode) -> Result<SPRef, Error> {
     match ssc {
         FeatureCode::Enterprise => Ok(spec::enterprise::admin::sp::ADMIN),
@@ -44,7 +53,7 @@ pub fn get_locking_admins(ssc: FeatureCode) -> Result<AuthorityRefRange, Error>
         FeatureCode::Enterprise => Err(Error::IncompatibleSSC),
         FeatureCode::OpalV1 => Ok(spec::opal::locking::authority::ADMIN),
         FeatureCode::OpalV2 => Ok(spec::opal::locking::authority::ADMIN),
-        FeatureCode::Opalite => Ok(ObjectUIDRange::new_count(spec::opalite::locking::authority::ADMIN1, 1, 1)),
+        FeatureCode::Opalite => Ok(OPALITE_LOCKING_ADMINS),
         FeatureCode::PyriteV1 => Ok(spec::pyrite::locking::authority::ADMIN),
         FeatureCode::PyriteV2 => Ok(spec::pyrite::locking::authority::ADMIN),
         FeatureCode::Ruby => Ok(spec::ruby::locking::authority::ADMIN),
@@ -58,7 +67,7 @@ pub fn get_locking_admin_c_pins(ssc: FeatureCode) -> Result<CPINRefRange, Error>
         FeatureCode::Enterprise => Err(Error::IncompatibleSSC),
         FeatureCode::OpalV1 => Ok(spec::opal::locking::c_pin::ADMIN),
         FeatureCode::OpalV2 => Ok(spec::opal::locking::c_pin::ADMIN),
-        FeatureCode::Opalite => Ok(ObjectUIDRange::new_count(spec::opalite::locking::c_pin::ADMIN1, 1, 1)),
+        FeatureCode::Opalite => Ok(OPALITE_LOCKING_ADMIN_C_PINS),
         FeatureCode::PyriteV1 => Ok(spec::pyrite::locking::c_pin::ADMIN),
         FeatureCode::PyriteV2 => Ok(spec::pyrite::locking::c_pin::ADMIN),
         FeatureCode::Ruby => Ok(spec::ruby::locking::c_pin::ADMIN),
@@ -93,3 +102,117 @@ pub async fn start_admin1_session(tper: &TPer, admin1_password: &[u8]) -> Result
     let admin1 = get_locking_admins(ssc.feature_code())?.nth(1).unwrap();
     Ok(tper.start_session(locking_sp, Some(admin1), Some(admin1_password)).await?)
 }
+
+pub async fn is_transaction_supported(tper: &TPer) -> bool {
+    tper.current_properties().await.max_transactions > 0
+}
+
+/// Adds `user` to or removes it from the list of authorities allowed by `ace_expr`.
+pub fn update_permission_expr(
+    ace_expr: impl ACEExpr,
+    user: AuthorityRef,
+    permitted: bool,
+) -> Result<Vec<ACEOperand>, Error> {
+    match permitted {
+        true => ace_expr.allow_authority(user).ok_or(Error::InvalidACEExpression),
+        false => ace_expr.deny_authority(user).ok_or(Error::InvalidACEExpression),
+    }
+}
+
+/// Evaluates `ace_expr` for `authority` authenticated alongside Anybody and its class.
+pub async fn has_permission(session: &Session, ace_expr: impl ACEExpr, authority: AuthorityRef) -> bool {
+    let anybody = spec::core::authority::ANYBODY;
+    let class: AuthorityRef = session.get(authority.as_uid(), Authority::CLASS).await.unwrap_or(AuthorityRef::null());
+    ace_expr.eval(&[anybody, class, authority]).unwrap_or(false)
+}
+
+/// The largest chunk of data that a single Set call can write to a byte table.
+pub fn max_chunk_len(properties: &Properties) -> usize {
+    const CALL_LEN: usize = 128; // An upper bound for the encoding of the Set call that wraps the data token.
+    let packet_len = properties.max_gross_packet_size - PACKET_HEADER_LEN - SUB_PACKET_HEADER_LEN - CALL_LEN;
+    if properties.continued_tokens {
+        // The data is split into continued tokens, each with a header of at most 4 bytes.
+        let num_tokens = packet_len.div_ceil(properties.max_ind_token_size);
+        core::cmp::min(packet_len - 4 * num_tokens, properties.max_agg_token_size)
+    } else {
+        core::cmp::min(packet_len, properties.max_ind_token_size - 4)
+    }
+}
+
+/// The largest chunk of data that a single Get call can read from a byte table.
+pub fn max_read_len(properties: &Properties) -> usize {
+    const RESULT_LEN: usize = 128; // An upper bound for the encoding of the method status and the result list.
+    let response_len = properties.max_gross_compacket_response_size
+        - COM_PACKET_HEADER_LEN
+        - PACKET_HEADER_LEN
+        - SUB_PACKET_HEADER_LEN
+        - RESULT_LEN;
+    if properties.continued_tokens {
+        let num_tokens = response_len.div_ceil(properties.max_ind_token_size);
+        core::cmp::min(response_len - 4 * num_tokens, properties.max_agg_token_size)
+    } else {
+        core::cmp::min(response_len, properties.max_ind_token_size - 4)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::applications::test_fixtures::{
+        discover, for_each_ssc, make_activated_device_of, make_factory_device_of, LOCKING_ADMIN1_PASSWORD,
+    };
+    use crate::rpc::TokioRuntime;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn primary_ssc_of_fake_devices() {
+        for_each_ssc(async |ssc| {
+            let discovery = discover(make_factory_device_of(ssc)).await;
+            assert_eq!(discovery.get_primary_ssc().map(|desc| desc.feature_code()), Some(ssc));
+            Ok(())
+        })
+        .await
+    }
+
+    #[tokio::test]
+    async fn get_admin_sp_of_fake_devices() {
+        for_each_ssc(async |ssc| {
+            let runtime = Arc::new(TokioRuntime::new());
+            let tper = TPer::new_on_default_com_id(Arc::new(make_factory_device_of(ssc)), runtime)?;
+            let session = tper.start_session(get_admin_sp(ssc)?, None, None).await?;
+            session.end_session().await?;
+            Ok(())
+        })
+        .await
+    }
+
+    #[tokio::test]
+    async fn get_locking_sp_of_fake_devices() {
+        for_each_ssc(async |ssc| {
+            let runtime = Arc::new(TokioRuntime::new());
+            let tper = TPer::new_on_default_com_id(Arc::new(make_activated_device_of(ssc)), runtime)?;
+            let session = tper.start_session(get_locking_sp(ssc)?, None, None).await?;
+            session.end_session().await?;
+            Ok(())
+        })
+        .await
+    }
+
+    #[tokio::test]
+    async fn get_locking_admins_of_fake_devices() {
+        for_each_ssc(async |ssc| {
+            let runtime = Arc::new(TokioRuntime::new());
+            let tper = TPer::new_on_default_com_id(Arc::new(make_activated_device_of(ssc)), runtime)?;
+            let result = start_admin1_session(&tper, LOCKING_ADMIN1_PASSWORD.as_bytes()).await;
+            if ssc == FeatureCode::Enterprise {
+                assert!(matches!(result, Err(Error::IncompatibleSSC)));
+            } else {
+                result?.end_session().await?;
+            }
+            Ok(())
+        })
+        .await
+    }
+}
@@ -26,6 +26,15 @@ pub trait Device: Send + Sync {
     fn is_security_supported(&self) -> bool;
     fn security_send(&self, security_protocol: u8, protocol_specific: [u8; 2], data: &[u8]) -> Result<(), Error>;
     fn security_recv(&self, security_protocol: u8, protocol_specific: [u8; 2], len: usize) -> Result<Vec<u8>, Error>;
+
+    fn logical_block_size(&self) -> Result<u32, Error> {
+        Err(Error::NotSupported)
+    }
+
+    /// Reads `count` logical blocks of user data starting at `lba`.
+    fn read_blocks(&self, _lba: u64, _count: u64) -> Result<Vec<u8>, Error> {
+        Err(Error::NotSupported)
+    }
 }
 
 impl core::fmt::Display for Interface {
@@ -3,18 +3,17 @@
 //L Please refer to the full license distributed with this software.
 //L-----------------------------------------------------------------------------
 
-//! Implements support for identify & security send/receive\* commands for ATA devices.
+//! Implements support for identify & trusted send/receive commands for ATA devices.
 //!
-//! \* Currently, only identify is implemented, so device information is displayed properly
-//! but encryption is not actually supported. The official SCSI specs define
-//! an ATA passthrough command. This appears to be exposed by Linux's `SG_IO` ioctl
-//! and `ATA_12`/`ATA_16` SCSI opcodes. Support can be implemented with the `SG_IO`
-//! ioctl and the `sg_io_hdr` structure. `hdparm`'s source code might be helpful.
+//! Linux exposes (S)ATA drives as SCSI devices. The ATA commands are wrapped into
+//! SCSI ATA PASS-THROUGH (16) commands as defined by the SCSI/ATA Translation (SAT)
+//! specification, and issued through the `SG_IO` ioctl. libata and most USB
+//! bridges translate these back to the native ATA commands.
 
-use nix::ioctl_read_bad;
-
-use crate::device::linux::utility::FileHandle;
-use crate::device::shared::ata::IdentifyDevice;
+use crate::device::linux::utility::{block_io, sg_io, DataTransfer, FileHandle};
+use crate::device::shared::aligned_array::AlignedArray;
+use crate::device::shared::ata::{ATAError, IdentifyDevice, Input, PassThroughProtocol};
+use crate::device::shared::scsi::check_sense_info;
 use crate::device::{Device, Error as DeviceError, Interface};
 use crate::serialization::DeserializeBinary;
 
@@ -48,49 +47,116 @@ impl Device for ATADevice {
         self.cached_desc.trusted_computing_supported
     }
 
-    fn security_send(
-        &self,
-        _security_protocol: u8,
-        _protocol_specific: [u8; 2],
-        _data: &[u8],
-    ) -> Result<(), DeviceError> {
-        if self.is_security_supported() {
-            Err(DeviceError::NotImplemented)
-        } else {
-            Err(DeviceError::SecurityNotSupported)
+    fn security_send(&self, security_protocol: u8, protocol_specific: [u8; 2], data: &[u8]) -> Result<(), DeviceError> {
+        if !self.is_security_supported() {
+            return Err(DeviceError::SecurityNotSupported);
         }
+        let aligned_data = AlignedArray::from_slice_padded(data, ALIGNMENT, PADDING).unwrap();
+        let protocol_specific = u16::from_be_bytes(protocol_specific);
+        trusted_send(&self.file, security_protocol, protocol_specific, aligned_data.as_padded_slice())
     }
 
     fn security_recv(
         &self,
-        _security_protocol: u8,
-        _protocol_specific: [u8; 2],
-        _len: usize,
+        security_protocol: u8,
+        protocol_specific: [u8; 2],
+        len: usize,
     ) -> Result<Vec<u8>, DeviceError> {
-        if self.is_security_supported() {
-            Err(DeviceError::NotImplemented)
-        } else {
-            Err(DeviceError::SecurityNotSupported)
+        if !self.is_security_supported() {
+            return Err(DeviceError::SecurityNotSupported);
         }
+        let mut data = AlignedArray::zeroed_padded(len, ALIGNMENT, PADDING).unwrap();
+        let protocol_specific = u16::from_be_bytes(protocol_specific);
+        trusted_receive(&self.file, security_protocol, protocol_specific, data.as_padded_mut_slice())?;
+        Ok(data.into_vec())
+    }
+
+    fn logical_block_size(&self) -> Result<u32, DeviceError> {
+        block_io::logical_block_size(&self.file)
+    }
+
+    fn read_blocks(&self, lba: u64, count: u64) -> Result<Vec<u8>, DeviceError> {
+        block_io::read_blocks(&self.file, lba, count)
     }
 }
 
 impl ATADevice {
     pub fn open(path: &str) -> Result<Self, DeviceError> {
         let file = FileHandle::open(path)?;
-        let desc = query_description(&file)?;
+        let desc = identify_device(&file)?;
         Ok(Self { file, cached_desc: desc })
     }
 }
 
-fn query_description(file: &FileHandle) -> Result<IdentifyDevice, DeviceError> {
-    let mut identity = [0_u8; 512];
-    let _ = unsafe { hdio_get_identity(file.handle(), &mut identity as *mut [u8; 512]) }?;
-    let identity = IdentifyDevice::from_bytes(identity.into()).map_err(|_| DeviceError::InvalidArgument)?;
+fn identify_device(file: &FileHandle) -> Result<IdentifyDevice, DeviceError> {
+    let mut identity = vec![0_u8; 512];
+    let input = Input::identify_device();
+    // Devices that reject the pass-through command are not ATA devices, or not behind a SAT layer.
+    execute(file, &input, PassThroughProtocol::PIODataIn, DataTransfer::FromDevice(&mut identity)).map_err(|err| {
+        match err {
+            DeviceError::PlatformError(_) | DeviceError::SCSIError(_) => DeviceError::InterfaceNotSupported,
+            err => err,
+        }
+    })?;
+    let identity = IdentifyDevice::from_bytes(identity).map_err(|_| DeviceError::InvalidArgument)?;
     if identity.not_ata_device {
         return Err(DeviceError::InterfaceNotSupported);
     }
     Ok(identity)
 }
 
-ioctl_read_bad!(hdio_get_identity, 0x030d, [u8; 512]);
+fn trusted_send(
+    file_handle: &FileHandle,
+    security_protocol: u8,
+    security_protocol_specific: u16,
+    data_out: &[u8],
+) -> Result<(), DeviceError> {
+    let input = Input::trusted_send_dma(security_protocol, security_protocol_specific, data_out.len() as u32)?;
+    execute(file_handle, &input, PassThroughProtocol::DMAData, DataTransfer::ToDevice(data_out))
+}
+
+fn trusted_receive(
+    file_handle: &FileHandle,
+    security_protocol: u8,
+    security_protocol_specific: u16,
+    data_in: &mut [u8],
+) -> Result<(), DeviceError> {
+    let input = Input::trusted_receive_dma(security_protocol, security_protocol_specific, data_in.len() as u32)?;
+    execute(file_handle, &input, PassThroughProtocol::DMAData, DataTransfer::FromDevice(data_in))
+}
+
+/// Execute an ATA command wrapped in ATA PASS-THROUGH (16).
+///
+/// Errors reported by the ATA device are returned as [`DeviceError::ATAError`],
+/// errors of the SCSI layer as [`DeviceError::SCSIError`].
+fn execute(
+    file_handle: &FileHandle,
+    input: &Input,
+    protocol: PassThroughProtocol,
+    data: DataTransfer,
+) -> Result<(), DeviceError> {
+    let data_in = matches!(data, DataTransfer::FromDevice(_));
+    let cdb = input.to_pass_through_16(protocol, data_in);
+    let mut sense_info = [0_u8; SENSE_LENGTH];
+    let status = sg_io(file_handle, &cdb, data, &mut sense_info)?;
+    let sense_info = &sense_info[..status.sense_len];
+    if let Some(ata_error) = ATAError::from_sense_info(sense_info) {
+        if !ata_error.success() {
+            return Err(DeviceError::ATAError(ata_error));
+        }
+    } else {
+        check_sense_info(status.scsi_status, sense_info)?;
+    }
+    if !status.is_delivered() {
+        return Err(DeviceError::Unspecified);
+    }
+    Ok(())
+}
+
+/// SG_IO does not need aligned buffers, but the padding is simpler with [`AlignedArray`].
+const ALIGNMENT: usize = 8;
+
+/// ATA trusted commands must have input and output buffers in 512 blocks.
+const PADDING: usize = 512;
+
+const SENSE_LENGTH: usize = 64;
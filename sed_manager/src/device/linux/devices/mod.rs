@@ -7,9 +7,11 @@ use crate::device::{Device, Error};
 
 mod ata;
 mod nvme;
+mod scsi;
 
 use ata::ATADevice;
 use nvme::NVMeDevice;
+use scsi::SCSIDevice;
 
 fn replace_error(error: &mut Option<Error>, new_error: Error) {
     let is_only_mismatch = error.as_ref().is_some_and(|value| value == &Error::InterfaceNotSupported);
@@ -29,6 +31,10 @@ pub fn open_device(drive_path: &str) -> Result<Box<dyn Device>, Error> {
         Ok(device) => return Ok(Box::new(device)),
         Err(new_error) => replace_error(&mut error, new_error),
     }
+    match SCSIDevice::open(drive_path) {
+        Ok(device) => return Ok(Box::new(device)),
+        Err(new_error) => replace_error(&mut error, new_error),
+    }
 
     Err(error.unwrap())
 }
@@ -11,7 +11,7 @@ use core::ptr::null_mut;
 
 use nix::ioctl_readwrite;
 
-use crate::device::linux::utility::FileHandle;
+use crate::device::linux::utility::{block_io, FileHandle};
 use crate::device::shared::nvme::{GenericStatusCode, IdentifyController, Opcode, StatusCode};
 use crate::device::{Device, Error, Interface};
 use crate::serialization::DeserializeBinary;
@@ -63,6 +63,16 @@ impl Device for NVMeDevice {
             Err(Error::SecurityNotSupported)
         }
     }
+
+    fn logical_block_size(&self) -> Result<u32, Error> {
+        let namespace = FileHandle::open(&get_namespace_path(self.file.path()))?;
+        block_io::logical_block_size(&namespace)
+    }
+
+    fn read_blocks(&self, lba: u64, count: u64) -> Result<Vec<u8>, Error> {
+        let namespace = FileHandle::open(&get_namespace_path(self.file.path()))?;
+        block_io::read_blocks(&namespace, lba, count)
+    }
 }
 
 impl NVMeDevice {
@@ -73,6 +83,17 @@ impl NVMeDevice {
     }
 }
 
+/// User data is read from the block device of a namespace, e.g. `/dev/nvme0n1`, not from the controller.
+///
+/// If `path` is a controller, the first namespace is used.
+fn get_namespace_path(path: &str) -> String {
+    let name = path.rsplit('/').next().unwrap_or(path);
+    match name.strip_prefix("nvme") {
+        Some(rest) if !rest.contains('n') => format!("{path}n1"),
+        _ => path.into(),
+    }
+}
+
 fn identify_controller(file: &FileHandle) -> Result<IdentifyController, Error> {
     let mut identity = vec![0_u8; 4096];
     let mut command = NVMeAdminCommand {
@@ -201,6 +222,17 @@ impl Default for NVMeAdminCommand {
 mod tests {
     use super::*;
 
+    #[test]
+    fn get_namespace_path_controller() {
+        assert_eq!(get_namespace_path("/dev/nvme0"), "/dev/nvme0n1");
+        assert_eq!(get_namespace_path("/dev/nvme12"), "/dev/nvme12n1");
+    }
+
+    #[test]
+    fn get_namespace_path_namespace() {
+        assert_eq!(get_namespace_path("/dev/nvme0n2"), "/dev/nvme0n2");
+    }
+
     #[test]
     fn check_ioctl_err_success() {
         let ioctl_err = 0b000_00000000;
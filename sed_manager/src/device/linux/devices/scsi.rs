@@ -0,0 +1,171 @@
+//L-----------------------------------------------------------------------------
+//L Copyright (C) Péter Kardos
+//L Please refer to the full license distributed with this software.
+//L-----------------------------------------------------------------------------
+
+//! Implements support for inquiry & security protocol in/out commands for SCSI devices.
+//!
+//! Commands are issued through Linux's `SG_IO` ioctl, which works on both the
+//! SCSI generic (`/dev/sg*`) and the SCSI disk (`/dev/sd*`) device nodes. This
+//! covers SAS drives and USB bridges that pass the security protocol commands through.
+
+use crate::device::linux::utility::{block_io, sg_io, DataTransfer, FileHandle};
+use crate::device::shared::aligned_array::AlignedArray;
+use crate::device::shared::scsi::{
+    check_sense_info, get_inc_512_flag, parse_unit_serial_number, Inquiry, InquiryData, SecurityProtocolIn,
+    SecurityProtocolOut,
+};
+use crate::device::{Device, Error as DeviceError, Interface};
+use crate::serialization::{DeserializeBinary, SerializeBinary};
+
+pub struct SCSIDevice {
+    file: FileHandle,
+    cached_desc: InquiryData,
+    cached_serial: String,
+}
+
+impl Device for SCSIDevice {
+    fn path(&self) -> Option<String> {
+        Some(self.file.path().into())
+    }
+
+    fn interface(&self) -> Interface {
+        Interface::SCSI
+    }
+
+    fn model_number(&self) -> String {
+        let vendor = self.cached_desc.vendor_identification_as_str();
+        let product = self.cached_desc.product_identification_as_str();
+        if vendor.is_empty() {
+            product
+        } else {
+            format!("{vendor} {product}")
+        }
+    }
+
+    fn serial_number(&self) -> String {
+        self.cached_serial.clone()
+    }
+
+    fn firmware_revision(&self) -> String {
+        self.cached_desc.product_revision_level_as_str()
+    }
+
+    fn is_security_supported(&self) -> bool {
+        // Like on Windows, we don't check the supported operation codes,
+        // the security protocol commands will simply fail if they are not supported.
+        true
+    }
+
+    fn security_send(&self, security_protocol: u8, protocol_specific: [u8; 2], data: &[u8]) -> Result<(), DeviceError> {
+        let aligned_data = AlignedArray::from_slice_padded(data, ALIGNMENT, PADDING).unwrap();
+        let protocol_specific = u16::from_be_bytes(protocol_specific);
+        security_protocol_out(
+            &self.file,
+            security_protocol,
+            protocol_specific,
+            aligned_data.as_padded_slice(),
+            get_inc_512_flag(security_protocol),
+        )
+    }
+
+    fn security_recv(
+        &self,
+        security_protocol: u8,
+        protocol_specific: [u8; 2],
+        len: usize,
+    ) -> Result<Vec<u8>, DeviceError> {
+        let mut data = AlignedArray::zeroed_padded(len, ALIGNMENT, PADDING).unwrap();
+        let protocol_specific = u16::from_be_bytes(protocol_specific);
+        security_protocol_in(
+            &self.file,
+            security_protocol,
+            protocol_specific,
+            data.as_padded_mut_slice(),
+            get_inc_512_flag(security_protocol),
+        )?;
+        Ok(data.into_vec())
+    }
+
+    fn logical_block_size(&self) -> Result<u32, DeviceError> {
+        block_io::logical_block_size(&self.file)
+    }
+
+    fn read_blocks(&self, lba: u64, count: u64) -> Result<Vec<u8>, DeviceError> {
+        block_io::read_blocks(&self.file, lba, count)
+    }
+}
+
+impl SCSIDevice {
+    pub fn open(path: &str) -> Result<Self, DeviceError> {
+        let file = FileHandle::open(path)?;
+        let desc = inquiry_standard(&file)?;
+        // Not all devices implement the serial number page, it's not a reason to reject them.
+        let serial = inquiry_unit_serial_number(&file).unwrap_or_default();
+        Ok(Self { file, cached_desc: desc, cached_serial: serial })
+    }
+}
+
+fn inquiry_standard(file: &FileHandle) -> Result<InquiryData, DeviceError> {
+    let mut data = vec![0_u8; Inquiry::STANDARD_DATA_LEN as usize];
+    let cdb = Inquiry::standard(data.len() as u16).to_bytes().expect("command serialization should be infallible");
+    // Devices that don't understand SG_IO at all are of a different interface.
+    execute(file, &cdb, DataTransfer::FromDevice(&mut data)).map_err(|err| match err {
+        DeviceError::PlatformError(_) => DeviceError::InterfaceNotSupported,
+        err => err,
+    })?;
+    InquiryData::from_bytes(data).map_err(|_| DeviceError::InterfaceNotSupported)
+}
+
+fn inquiry_unit_serial_number(file: &FileHandle) -> Result<String, DeviceError> {
+    let mut page = vec![0_u8; 256];
+    let cdb = Inquiry::vital_product_data(Inquiry::UNIT_SERIAL_NUMBER_PAGE, page.len() as u16)
+        .to_bytes()
+        .expect("command serialization should be infallible");
+    execute(file, &cdb, DataTransfer::FromDevice(&mut page))?;
+    parse_unit_serial_number(&page).ok_or(DeviceError::InterfaceNotSupported)
+}
+
+pub fn security_protocol_in(
+    file_handle: &FileHandle,
+    security_protocol: u8,
+    security_protocol_specific: u16,
+    data_in: &mut [u8],
+    inc_512: bool,
+) -> Result<(), DeviceError> {
+    let command = SecurityProtocolIn::new(security_protocol, security_protocol_specific, data_in.len() as u32, inc_512);
+    let cdb = command.to_bytes().expect("command serialization should be infallible");
+    execute(file_handle, &cdb, DataTransfer::FromDevice(data_in))
+}
+
+pub fn security_protocol_out(
+    file_handle: &FileHandle,
+    security_protocol: u8,
+    security_protocol_specific: u16,
+    data_out: &[u8],
+    inc_512: bool,
+) -> Result<(), DeviceError> {
+    let command =
+        SecurityProtocolOut::new(security_protocol, security_protocol_specific, data_out.len() as u32, inc_512);
+    let cdb = command.to_bytes().expect("command serialization should be infallible");
+    execute(file_handle, &cdb, DataTransfer::ToDevice(data_out))
+}
+
+/// Execute a SCSI command and translate the SCSI status and the transport status into an error.
+fn execute(file_handle: &FileHandle, cdb: &[u8], data: DataTransfer) -> Result<(), DeviceError> {
+    let mut sense_info = [0_u8; SENSE_LENGTH];
+    let status = sg_io(file_handle, cdb, data, &mut sense_info)?;
+    check_sense_info(status.scsi_status, &sense_info[..status.sense_len])?;
+    if !status.is_delivered() {
+        return Err(DeviceError::Unspecified);
+    }
+    Ok(())
+}
+
+/// SG_IO does not need aligned buffers, but the padding is simpler with [`AlignedArray`].
+const ALIGNMENT: usize = 8;
+
+/// Pad the size of the data to be a multiple of 512, as required by the INC_512 flag.
+const PADDING: usize = 512;
+
+const SENSE_LENGTH: usize = 128;
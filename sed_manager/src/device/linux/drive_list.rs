@@ -29,12 +29,25 @@ pub fn get_nvme_controller(device: PathBuf) -> PathBuf {
     }
 }
 
+fn list_block_devices() -> Result<Vec<PathBuf>, DeviceError> {
+    const BLOCK_FOLDER: &str = "/sys/block";
+    const VIRTUAL_PREFIXES: [&str; 6] = ["loop", "ram", "zram", "dm-", "md", "sr"];
+
+    let block_iter = fs::read_dir(BLOCK_FOLDER).map_err(|_| LinuxError::NoDiskFolder)?;
+    let names = block_iter.filter_map(|entry| entry.ok().map(|entry| entry.file_name().to_string_lossy().to_string()));
+    let names = names.filter(|name| !VIRTUAL_PREFIXES.iter().any(|prefix| name.starts_with(prefix)));
+    Ok(names.map(|name| PathBuf::from("/dev").join(name)).collect())
+}
+
 pub fn list_physical_drives() -> Result<Vec<String>, DeviceError> {
     const DISK_FOLDER: &str = "/dev/disk/by-id";
 
-    // Get all drives in the by-id folder.
-    let drive_iter = fs::read_dir(DISK_FOLDER).map_err(|_| LinuxError::NoDiskFolder)?;
-    let drives = drive_iter.filter_map(|entry| entry.ok().map(|entry| entry.path()));
+    // Get all drives in the by-id folder. This folder is maintained by udev, so in
+    // minimal environments without udev, we fall back to the kernel's list of block devices.
+    let drives: Vec<_> = match fs::read_dir(DISK_FOLDER) {
+        Ok(drive_iter) => drive_iter.filter_map(|entry| entry.ok().map(|entry| entry.path())).collect(),
+        Err(_) => list_block_devices()?,
+    };
 
     // Canonicalize all drives: this removes symlinks so we get `/dev/nvme0n1` instead of `/dev/disk/by-id/nvme-****-1`.
     let drives = drives.into_iter().filter_map(|path| fs::canonicalize(path).ok());
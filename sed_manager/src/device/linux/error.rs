@@ -11,7 +11,7 @@ use crate::device;
 pub enum Error {
     #[error("{}", .0)]
     Errno(Errno),
-    #[error("Could not open /dev/disk/by-id to list devices")]
+    #[error("Could not open /dev/disk/by-id or /sys/block to list devices")]
     NoDiskFolder,
 }
 
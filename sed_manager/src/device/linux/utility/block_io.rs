@@ -0,0 +1,55 @@
+//L-----------------------------------------------------------------------------
+//L Copyright (C) Péter Kardos
+//L Please refer to the full license distributed with this software.
+//L-----------------------------------------------------------------------------
+
+//! Reads user data from block devices.
+
+use nix::errno::Errno;
+use nix::ioctl_read_bad;
+use nix::libc::{c_int, c_void, off_t, pread};
+use nix::sys::stat::{fstat, SFlag};
+
+use crate::device::Error as DeviceError;
+
+use super::FileHandle;
+
+const BLKSSZGET: u64 = 0x1268;
+
+ioctl_read_bad!(blksszget, BLKSSZGET, c_int);
+
+/// SCSI devices may be opened through their generic (`/dev/sg*`) node,
+/// which is a character device that has neither a block size nor user data to read.
+fn check_block_device(file: &FileHandle) -> Result<(), DeviceError> {
+    let stat = fstat(file.handle())?;
+    match SFlag::from_bits_truncate(stat.st_mode) & SFlag::S_IFMT {
+        SFlag::S_IFBLK => Ok(()),
+        _ => Err(DeviceError::NotSupported),
+    }
+}
+
+pub fn logical_block_size(file: &FileHandle) -> Result<u32, DeviceError> {
+    check_block_device(file)?;
+    let mut block_size: c_int = 0;
+    unsafe { blksszget(file.handle(), &mut block_size as *mut c_int) }?;
+    Ok(block_size as u32)
+}
+
+pub fn read_blocks(file: &FileHandle, lba: u64, count: u64) -> Result<Vec<u8>, DeviceError> {
+    let block_size = logical_block_size(file)? as u64;
+    let offset = lba.checked_mul(block_size).ok_or(Errno::EINVAL)?;
+    let len = count.checked_mul(block_size).ok_or(Errno::EINVAL)?;
+    let mut data = vec![0_u8; usize::try_from(len).map_err(|_| Errno::EINVAL)?];
+    let mut num_read = 0;
+    while num_read < data.len() {
+        let position = off_t::try_from(offset + num_read as u64).map_err(|_| Errno::EINVAL)?;
+        let buffer = data[num_read..].as_mut_ptr() as *mut c_void;
+        let result = unsafe { pread(file.handle(), buffer, data.len() - num_read, position) };
+        match result {
+            0 => return Err(Errno::EINVAL.into()), // Reading past the end of the device.
+            n if n < 0 => return Err(Errno::last().into()),
+            n => num_read += n as usize,
+        }
+    }
+    Ok(data)
+}
@@ -3,6 +3,9 @@
 //L Please refer to the full license distributed with this software.
 //L-----------------------------------------------------------------------------
 
+pub mod block_io;
 mod file_handle;
+mod sg_io;
 
 pub use file_handle::FileHandle;
+pub use sg_io::{sg_io, DataTransfer};
@@ -0,0 +1,151 @@
+//L-----------------------------------------------------------------------------
+//L Copyright (C) Péter Kardos
+//L Please refer to the full license distributed with this software.
+//L-----------------------------------------------------------------------------
+
+//! A thin wrapper around Linux's `SG_IO` ioctl that executes SCSI commands.
+//!
+//! The SCSI status and the sense data are returned as-is, because their interpretation
+//! depends on the command set: native SCSI commands report errors via the regular sense data,
+//! while ATA pass-through commands carry the ATA task file in the sense data.
+
+use core::ffi::{c_int, c_uint, c_ushort, c_void};
+use core::ptr::null_mut;
+
+use nix::ioctl_readwrite_bad;
+
+use crate::device::linux::Error as LinuxError;
+
+use super::FileHandle;
+
+pub enum DataTransfer<'data> {
+    ToDevice(&'data [u8]),
+    FromDevice(&'data mut [u8]),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Status {
+    pub scsi_status: u8,
+    pub sense_len: usize,
+    pub host_status: u16,
+    pub driver_status: u16,
+}
+
+impl Status {
+    /// Whether the command reached the device, regardless of whether the device executed it successfully.
+    pub fn is_delivered(&self) -> bool {
+        self.host_status == 0 && (self.driver_status & !DRIVER_SENSE) == 0
+    }
+}
+
+/// Execute a SCSI command. The sense data is written into `sense_info`.
+pub fn sg_io(
+    file_handle: &FileHandle,
+    cdb: &[u8],
+    data: DataTransfer,
+    sense_info: &mut [u8],
+) -> Result<Status, LinuxError> {
+    assert!(cdb.len() <= 16);
+    let (dxfer_direction, dxferp, dxfer_len) = match data {
+        DataTransfer::ToDevice(data) => (SG_DXFER_TO_DEV, data.as_ptr() as *mut c_void, data.len()), // Data is not modified by the kernel.
+        DataTransfer::FromDevice(data) => (SG_DXFER_FROM_DEV, data.as_mut_ptr() as *mut c_void, data.len()),
+    };
+    let mut command = SgIoHdr {
+        dxfer_direction,
+        cmd_len: cdb.len() as u8,
+        mx_sb_len: sense_info.len().min(u8::MAX as usize) as u8,
+        dxfer_len: dxfer_len as c_uint,
+        dxferp,
+        cmdp: cdb.as_ptr() as *mut u8, // CDB is not modified by the kernel.
+        sbp: sense_info.as_mut_ptr(),
+        ..Default::default()
+    };
+    let _ = unsafe { sg_io_ioctl(file_handle.handle(), &mut command as *mut SgIoHdr) }?;
+    Ok(Status {
+        scsi_status: command.status,
+        sense_len: command.sb_len_wr as usize,
+        host_status: command.host_status,
+        driver_status: command.driver_status,
+    })
+}
+
+/// Generous timeout as some security commands, like reverting the TPer, erase the whole drive.
+const TIMEOUT_MS: c_uint = 60_000;
+
+const SG_INTERFACE_ID_ORIG: c_int = b'S' as c_int;
+const SG_DXFER_TO_DEV: c_int = -2;
+const SG_DXFER_FROM_DEV: c_int = -3;
+/// Set in `driver_status` when the sense buffer was filled, this alone is not an error.
+const DRIVER_SENSE: c_ushort = 0x08;
+
+ioctl_readwrite_bad!(sg_io_ioctl, 0x2285, SgIoHdr);
+
+/// The `sg_io_hdr` structure from Linux's `scsi/sg.h`.
+#[derive(Debug)]
+#[repr(C)]
+struct SgIoHdr {
+    interface_id: c_int,
+    dxfer_direction: c_int,
+    cmd_len: u8,
+    mx_sb_len: u8,
+    iovec_count: c_ushort,
+    dxfer_len: c_uint,
+    dxferp: *mut c_void,
+    cmdp: *mut u8,
+    sbp: *mut u8,
+    timeout: c_uint,
+    flags: c_uint,
+    pack_id: c_int,
+    usr_ptr: *mut c_void,
+    status: u8,
+    masked_status: u8,
+    msg_status: u8,
+    sb_len_wr: u8,
+    host_status: c_ushort,
+    driver_status: c_ushort,
+    resid: c_int,
+    duration: c_uint,
+    info: c_uint,
+}
+
+impl Default for SgIoHdr {
+    fn default() -> Self {
+        Self {
+            interface_id: SG_INTERFACE_ID_ORIG,
+            dxfer_direction: 0,
+            cmd_len: 0,
+            mx_sb_len: 0,
+            iovec_count: 0,
+            dxfer_len: 0,
+            dxferp: null_mut(),
+            cmdp: null_mut(),
+            sbp: null_mut(),
+            timeout: TIMEOUT_MS,
+            flags: 0,
+            pack_id: 0,
+            usr_ptr: null_mut(),
+            status: 0,
+            masked_status: 0,
+            msg_status: 0,
+            sb_len_wr: 0,
+            host_status: 0,
+            driver_status: 0,
+            resid: 0,
+            duration: 0,
+            info: 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(target_pointer_width = "64")]
+    fn sg_io_hdr_layout() {
+        assert_eq!(core::mem::size_of::<SgIoHdr>(), 88);
+        assert_eq!(core::mem::offset_of!(SgIoHdr, status), 64);
+        assert_eq!(core::mem::offset_of!(SgIoHdr, info), 80);
+    }
+}
@@ -6,6 +6,8 @@
 mod device;
 mod error;
 mod shared;
+#[cfg(feature = "serde")]
+pub mod trace;
 
 #[cfg(target_os = "windows")]
 mod windows;
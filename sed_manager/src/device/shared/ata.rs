@@ -34,6 +34,16 @@ pub struct IdentifyDevice {
     pub serial_ata_capabilities: u16, // This is a bit field, but we only care if it's ATA or SATA.
 }
 
+/// The ATA protocols for the SCSI ATA PASS-THROUGH commands, as defined by SAT.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum PassThroughProtocol {
+    NonData = 3,
+    PIODataIn = 4,
+    PIODataOut = 5,
+    DMAData = 6,
+}
+
 pub struct Input {
     feature: u8,
     count: u8,
@@ -120,8 +130,55 @@ impl Input {
         ]
     }
 
+    /// The number of 512 byte blocks the command transfers.
+    fn transfer_len(&self) -> u16 {
+        match self.command {
+            // The trusted commands keep the high byte of the length in LBA (7:0).
+            Command::TrustedReceiveDMA | Command::TrustedSendDMA => {
+                u16::from_be_bytes([self.lba.to_le_bytes()[0], self.count])
+            }
+            Command::IdentifyDevice => self.count as u16,
+        }
+    }
+
+    /// Wrap the command into a SCSI ATA PASS-THROUGH (16) command.
+    ///
+    /// The transfer length is taken from the count field in units of 512 byte blocks.
+    /// Transfers over 255 blocks set EXTEND so that the bridge sees both bytes of the count,
+    /// the device itself executes the 28-bit command and ignores the extended registers.
+    pub fn to_pass_through_16(&self, protocol: PassThroughProtocol, data_in: bool) -> [u8; 16] {
+        const OPCODE: u8 = 0x85;
+        const EXTEND: u8 = 1;
+        const T_DIR_IN: u8 = 1 << 3;
+        const BYT_BLOK: u8 = 1 << 2;
+        const T_LENGTH_COUNT: u8 = 0b10;
+        let lba = self.lba.to_le_bytes();
+        let [count_high, count_low] = self.transfer_len().to_be_bytes();
+        let extend = if count_high != 0 { EXTEND } else { 0 };
+        let transfer = if data_in { T_DIR_IN } else { 0 } | BYT_BLOK | T_LENGTH_COUNT;
+        [
+            OPCODE,
+            (protocol as u8) << 1 | extend,
+            transfer,
+            0,
+            self.feature,
+            count_high,
+            count_low,
+            0,
+            lba[0],
+            0,
+            lba[1],
+            0,
+            lba[2],
+            lba[3] & 0x0F,
+            self.command as u8,
+            0,
+        ]
+    }
+
     pub fn identify_device() -> Self {
-        Self { feature: 0, count: 0, lba: 0, command: Command::IdentifyDevice }
+        // Count is N/A for IDENTIFY DEVICE, but SAT takes the transfer length from it.
+        Self { feature: 0, count: 1, lba: 0, command: Command::IdentifyDevice }
     }
 
     pub fn trusted_send_dma(
@@ -159,6 +216,39 @@ impl ATAError {
         Self { status, error }
     }
 
+    /// Extract the ATA status from the sense data of a failed ATA PASS-THROUGH command.
+    ///
+    /// Returns `None` if the sense data does not carry the ATA status, for example because the
+    /// SCSI layer rejected the command before it was passed on to the device.
+    pub fn from_sense_info(sense_info: &[u8]) -> Option<Self> {
+        const ATA_STATUS_RETURN: u8 = 0x09;
+        let response_code = sense_info.first()? & 0b0111_1111;
+        let (error_reg, status_reg) = match response_code {
+            0x72 | 0x73 => {
+                let additional_len = *sense_info.get(7)? as usize;
+                let descriptors = sense_info.get(8..(8 + additional_len).min(sense_info.len()))?;
+                let mut offset = 0;
+                loop {
+                    let descriptor = descriptors.get(offset..)?;
+                    let (code, len) = (*descriptor.first()?, *descriptor.get(1)? as usize);
+                    if code == ATA_STATUS_RETURN {
+                        break (*descriptor.get(3)?, *descriptor.get(13)?);
+                    }
+                    offset += 2 + len;
+                }
+            }
+            0x70 | 0x71 => {
+                // ATA PASS-THROUGH INFORMATION AVAILABLE: the information field holds the registers.
+                if sense_info.get(12..14)? != [0x00, 0x1D] {
+                    return None;
+                }
+                (*sense_info.get(3)?, *sense_info.get(4)?)
+            }
+            _ => return None,
+        };
+        Some(Self::from_task_file([error_reg, 0, 0, 0, 0, 0, status_reg, 0]))
+    }
+
     pub fn with_error_bit() -> Self {
         Self { error: ErrorField::default(), status: StatusField { error_or_check: true, ..Default::default() } }
     }
@@ -236,8 +326,6 @@ fn convert_buffer_len(num_bytes: u32) -> Result<u16, DeviceError> {
 
 fn parse_ata_string(ata_string: &[u8]) -> String {
     let mut swapped = Vec::from(ata_string);
-    // On Linux, the IOCTL does this magically. Go figure...
-    #[cfg(not(target_os = "linux"))]
     for i in (0..swapped.len()).step_by(2) {
         if i + 1 < swapped.len() {
             swapped.swap(i, i + 1);
@@ -275,4 +363,65 @@ mod tests {
         assert_eq!(input.feature, 0x02);
         assert_eq!(input.lba, 0x00_0AFF_34);
     }
+
+    #[test]
+    fn pass_through_16_trusted_receive() {
+        let input = Input::trusted_receive_dma(0x01, 0x07FE, 0x0102 * 512).unwrap();
+        let cdb = input.to_pass_through_16(PassThroughProtocol::DMAData, true);
+        let expected = [
+            0x85, 0x0D, 0x0E, 0, 0x01, 0x01, 0x02, 0, 0x01, 0, 0xFE, 0, 0x07, 0, 0x5D, 0,
+        ];
+        assert_eq!(cdb, expected);
+    }
+
+    #[test]
+    fn pass_through_16_trusted_send() {
+        let input = Input::trusted_send_dma(0x02, 0x0004, 512).unwrap();
+        let cdb = input.to_pass_through_16(PassThroughProtocol::DMAData, false);
+        let expected = [
+            0x85, 0x0C, 0x06, 0, 0x02, 0, 0x01, 0, 0x00, 0, 0x04, 0, 0x00, 0, 0x5F, 0,
+        ];
+        assert_eq!(cdb, expected);
+    }
+
+    #[test]
+    fn pass_through_16_identify() {
+        let cdb = Input::identify_device().to_pass_through_16(PassThroughProtocol::PIODataIn, true);
+        let expected = [
+            0x85, 0x08, 0x0E, 0, 0, 0, 0x01, 0, 0, 0, 0, 0, 0, 0, 0xEC, 0,
+        ];
+        assert_eq!(cdb, expected);
+    }
+
+    #[test]
+    fn ata_error_from_descriptor_sense() {
+        let mut sense = vec![0x72, 0x01, 0x00, 0x1D, 0, 0, 0, 22];
+        sense.extend([0x02, 0x06, 0, 0, 0, 0, 0, 0]); // Some other descriptor first.
+        sense.extend([0x09, 0x0C, 0x00, 0x04, 0, 0, 0, 0, 0, 0, 0, 0, 0x40, 0x51]);
+        let error = ATAError::from_sense_info(&sense).unwrap();
+        assert!(!error.success());
+        assert!(error.error.abort);
+    }
+
+    #[test]
+    fn ata_error_from_fixed_sense() {
+        let mut sense = vec![0_u8; 18];
+        sense[0] = 0x70;
+        sense[3] = 0x04;
+        sense[4] = 0x51;
+        sense[13] = 0x1D;
+        let error = ATAError::from_sense_info(&sense).unwrap();
+        assert!(!error.success());
+        assert!(error.error.abort);
+    }
+
+    #[test]
+    fn ata_error_from_unrelated_sense() {
+        let mut sense = vec![0_u8; 18];
+        sense[0] = 0x70;
+        sense[2] = 0x05;
+        sense[12] = 0x20;
+        assert_eq!(ATAError::from_sense_info(&sense), None);
+        assert_eq!(ATAError::from_sense_info(&[]), None);
+    }
 }
@@ -5,11 +5,13 @@
 
 use sed_manager_macros::Deserialize;
 
-use crate::serialization::Serialize;
+use crate::device::Error as DeviceError;
+use crate::serialization::{DeserializeBinary, Serialize};
 
 #[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
 #[repr(u8)]
 pub enum Opcode {
+    Inquiry = 0x12,
     SecurityProtocolOut = 0xB5,
     SecurityProtocolIn = 0xA2,
 }
@@ -40,6 +42,26 @@ pub struct SecurityProtocolOut {
     control: u8,
 }
 
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct Inquiry {
+    opcode: Opcode,
+    #[layout(offset = 1, bit_field(u8, 0))]
+    enable_vital_product_data: bool,
+    #[layout(offset = 2)]
+    page_code: u8,
+    allocation_length: u16,
+    control: u8,
+}
+
+/// The standard data returned by the INQUIRY command when EVPD is not set.
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct InquiryData {
+    #[layout(offset = 8)]
+    pub vendor_identification: [u8; 8],
+    pub product_identification: [u8; 16],
+    pub product_revision_level: [u8; 4],
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct SCSIError {
     pub sense_key: SenseKey,
@@ -151,6 +173,114 @@ impl SecurityProtocolOut {
     }
 }
 
+impl Inquiry {
+    /// The minimum allocation length that fits the fields of [`InquiryData`].
+    pub const STANDARD_DATA_LEN: u16 = 36;
+    /// The page code of the Unit Serial Number vital product data page.
+    pub const UNIT_SERIAL_NUMBER_PAGE: u8 = 0x80;
+
+    pub fn standard(alloc_len_bytes: u16) -> Self {
+        Self {
+            opcode: Opcode::Inquiry,
+            enable_vital_product_data: false,
+            page_code: 0,
+            allocation_length: alloc_len_bytes,
+            control: 0,
+        }
+    }
+
+    pub fn vital_product_data(page_code: u8, alloc_len_bytes: u16) -> Self {
+        Self {
+            opcode: Opcode::Inquiry,
+            enable_vital_product_data: true,
+            page_code,
+            allocation_length: alloc_len_bytes,
+            control: 0,
+        }
+    }
+}
+
+impl InquiryData {
+    pub fn vendor_identification_as_str(&self) -> String {
+        String::from_utf8_lossy(&self.vendor_identification).trim().to_string()
+    }
+    pub fn product_identification_as_str(&self) -> String {
+        String::from_utf8_lossy(&self.product_identification).trim().to_string()
+    }
+    pub fn product_revision_level_as_str(&self) -> String {
+        String::from_utf8_lossy(&self.product_revision_level).trim().to_string()
+    }
+}
+
+/// Extract the product serial number from the Unit Serial Number VPD page.
+pub fn parse_unit_serial_number(page: &[u8]) -> Option<String> {
+    if page.len() < 4 || page[1] != Inquiry::UNIT_SERIAL_NUMBER_PAGE {
+        return None;
+    }
+    let page_length = page[3] as usize;
+    let serial_number = page.get(4..(4 + page_length).min(page.len()))?;
+    let serial_number = String::from_utf8_lossy(serial_number);
+    Some(serial_number.trim_matches(|c: char| c.is_whitespace() || c == '\0').to_string())
+}
+
+/// Turn the SCSI status and the sense data returned with it into an error.
+pub fn check_sense_info(scsi_result: u8, sense_info: &[u8]) -> Result<(), DeviceError> {
+    if scsi_result != 0 {
+        let raw_response_code = sense_info.first().map(|value| value & 0b0111_1111).unwrap_or(0); // Bit 7 is reserved. See the sense info data structures above.
+        let response_code = SenseResponseCode::try_from(raw_response_code).unwrap_or(SenseResponseCode::Unrecognized);
+        match response_code {
+            SenseResponseCode::CurrentFixed => Err(parse_fixed_sense_info(sense_info)),
+            SenseResponseCode::DeferredFixed => Ok(()),
+            SenseResponseCode::CurrentDescriptor => Err(parse_descriptor_sense_info(sense_info)),
+            SenseResponseCode::DeferredDescriptor => Ok(()),
+            SenseResponseCode::VendorSpecific => {
+                Err(SCSIError { sense_key: SenseKey::VendorSpecific, ..Default::default() })
+            }
+            _ => Err(SCSIError { parse_failed: true, ..Default::default() }),
+        }
+        .map_err(DeviceError::SCSIError)
+    } else {
+        Ok(())
+    }
+}
+
+fn parse_fixed_sense_info(sense_info: &[u8]) -> SCSIError {
+    let Ok(sense_data) = FixedSenseData::from_bytes(sense_info.into()) else {
+        return SCSIError { parse_failed: true, ..Default::default() };
+    };
+    SCSIError {
+        sense_key: sense_data.sense_key,
+        additional_sense_code: sense_data.additional_sense_code,
+        additional_sense_code_qualifier: sense_data.additional_sense_code_qualifier,
+        ..Default::default()
+    }
+}
+
+fn parse_descriptor_sense_info(sense_info: &[u8]) -> SCSIError {
+    let Ok(sense_data) = DescriptorSenseData::from_bytes(sense_info.into()) else {
+        return SCSIError { parse_failed: true, ..Default::default() };
+    };
+    SCSIError {
+        sense_key: sense_data.sense_key,
+        additional_sense_code: sense_data.additional_sense_code,
+        additional_sense_code_qualifier: sense_data.additional_sense_code_qualifier,
+        ..Default::default()
+    }
+}
+
+/// Get the required INC_512 flag for SCSI security protocol in/out commands.
+///
+/// The values can be found in the TCG Storage Interface Interactions Specification.
+pub const fn get_inc_512_flag(security_protocol: u8) -> bool {
+    match security_protocol {
+        0x00 => true,
+        0x01 => true,
+        0x02 => true,
+        0x06 => false,
+        _ => panic!("unknown security protocol"),
+    }
+}
+
 impl core::error::Error for SCSIError {}
 
 impl core::fmt::Display for SCSIError {
@@ -181,6 +311,7 @@ impl Default for SCSIError {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::serialization::SerializeBinary;
 
     #[test]
     fn security_protocol_out_new_bytes() {
@@ -221,4 +352,81 @@ mod tests {
     fn security_protocol_in_new_512_err() {
         let _ = SecurityProtocolIn::new(0, 0, 235, true);
     }
+
+    #[test]
+    fn inquiry_standard_bytes() {
+        let value = Inquiry::standard(Inquiry::STANDARD_DATA_LEN);
+        assert_eq!(value.to_bytes().unwrap(), vec![0x12, 0x00, 0x00, 0x00, 36, 0x00]);
+    }
+
+    #[test]
+    fn inquiry_vital_product_data_bytes() {
+        let value = Inquiry::vital_product_data(Inquiry::UNIT_SERIAL_NUMBER_PAGE, 0x0120);
+        assert_eq!(value.to_bytes().unwrap(), vec![0x12, 0x01, 0x80, 0x01, 0x20, 0x00]);
+    }
+
+    #[test]
+    fn inquiry_data_strings() {
+        let mut bytes = vec![0_u8; 36];
+        bytes[8..16].copy_from_slice(b"SEAGATE ");
+        bytes[16..32].copy_from_slice(b"ST4000NM0025    ");
+        bytes[32..36].copy_from_slice(b"E003");
+        let value = InquiryData::from_bytes(bytes).unwrap();
+        assert_eq!(value.vendor_identification_as_str(), "SEAGATE");
+        assert_eq!(value.product_identification_as_str(), "ST4000NM0025");
+        assert_eq!(value.product_revision_level_as_str(), "E003");
+    }
+
+    #[test]
+    fn unit_serial_number_ok() {
+        let page = [
+            0x00, 0x80, 0x00, 0x0A, b' ', b' ', b'Z', b'C', b'1', b'2', b'3', b'4', 0, 0, 0xFF,
+        ];
+        assert_eq!(parse_unit_serial_number(&page), Some(String::from("ZC1234")));
+    }
+
+    #[test]
+    fn unit_serial_number_wrong_page() {
+        let page = [0x00, 0x83, 0x00, 0x02, b'A', b'B'];
+        assert_eq!(parse_unit_serial_number(&page), None);
+    }
+
+    #[test]
+    fn check_sense_info_good() {
+        assert_eq!(check_sense_info(0, &[0; 32]), Ok(()));
+    }
+
+    #[test]
+    fn check_sense_info_fixed() {
+        let mut sense = [0_u8; 32];
+        sense[0] = 0x70;
+        sense[2] = 0x05;
+        sense[12] = 0x24;
+        sense[13] = 0x01;
+        let expected = SCSIError {
+            sense_key: SenseKey::IllegalRequest,
+            additional_sense_code: 0x24,
+            additional_sense_code_qualifier: 0x01,
+            parse_failed: false,
+        };
+        assert_eq!(check_sense_info(0x02, &sense), Err(DeviceError::SCSIError(expected)));
+    }
+
+    #[test]
+    fn check_sense_info_descriptor() {
+        let sense = [0x72, 0x05, 0x20, 0x00, 0, 0, 0, 0];
+        let expected = SCSIError {
+            sense_key: SenseKey::IllegalRequest,
+            additional_sense_code: 0x20,
+            additional_sense_code_qualifier: 0x00,
+            parse_failed: false,
+        };
+        assert_eq!(check_sense_info(0x02, &sense), Err(DeviceError::SCSIError(expected)));
+    }
+
+    #[test]
+    fn check_sense_info_unrecognized() {
+        let expected = SCSIError { parse_failed: true, ..Default::default() };
+        assert_eq!(check_sense_info(0x02, &[]), Err(DeviceError::SCSIError(expected)));
+    }
 }
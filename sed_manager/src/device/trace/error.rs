@@ -0,0 +1,24 @@
+//L-----------------------------------------------------------------------------
+//L Copyright (C) Péter Kardos
+//L Please refer to the full license distributed with this software.
+//L-----------------------------------------------------------------------------
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum TraceError {
+    #[error("Could not access the trace: {}", .0)]
+    IOError(String),
+    #[error("Invalid trace (line {line}): {message}")]
+    InvalidFormat { line: usize, message: String },
+    #[error("Trace version {} is not supported", .0)]
+    UnsupportedVersion(u32),
+    #[error("The commands diverged from the trace at exchange #{index}: {message}")]
+    Mismatch { index: usize, message: String },
+    #[error("{} recorded exchanges were not replayed", .0)]
+    Incomplete(usize),
+}
+
+impl From<std::io::Error> for TraceError {
+    fn from(value: std::io::Error) -> Self {
+        Self::IOError(value.to_string())
+    }
+}
@@ -0,0 +1,233 @@
+//L-----------------------------------------------------------------------------
+//L Copyright (C) Péter Kardos
+//L Please refer to the full license distributed with this software.
+//L-----------------------------------------------------------------------------
+
+use std::io::BufRead;
+
+use serde::{Deserialize, Serialize};
+
+use crate::device::{Device, Error as DeviceError, Interface};
+
+use super::error::TraceError;
+
+pub const TRACE_VERSION: u32 = 1;
+
+/// The first line of a trace, describing the recorded device.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Header {
+    pub version: u32,
+    pub path: Option<String>,
+    pub interface: String,
+    pub model_number: String,
+    pub serial_number: String,
+    pub firmware_revision: String,
+    pub security_supported: bool,
+}
+
+/// A security send or receive command and its outcome.
+///
+/// Trailing zeros of the data are not stored: the padding of the IF-SEND buffers
+/// and the unused part of the IF-RECV buffers would otherwise make up most of the trace.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "command", rename_all = "snake_case")]
+pub enum Exchange {
+    Send {
+        protocol: u8,
+        protocol_specific: u16,
+        #[serde(with = "hex")]
+        data: Vec<u8>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        error: Option<String>,
+    },
+    Recv {
+        protocol: u8,
+        protocol_specific: u16,
+        len: usize,
+        #[serde(with = "hex")]
+        data: Vec<u8>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        error: Option<String>,
+    },
+}
+
+impl Header {
+    pub fn new(device: &dyn Device) -> Self {
+        Self {
+            version: TRACE_VERSION,
+            path: device.path(),
+            interface: device.interface().to_string(),
+            model_number: device.model_number(),
+            serial_number: device.serial_number(),
+            firmware_revision: device.firmware_revision(),
+            security_supported: device.is_security_supported(),
+        }
+    }
+
+    pub fn interface(&self) -> Interface {
+        [
+            Interface::ATA,
+            Interface::SATA,
+            Interface::SCSI,
+            Interface::NVMe,
+            Interface::SD,
+            Interface::MMC,
+        ]
+        .into_iter()
+        .find(|interface| interface.to_string() == self.interface)
+        .unwrap_or(Interface::Other)
+    }
+}
+
+impl Exchange {
+    pub fn send(protocol: u8, protocol_specific: [u8; 2], data: &[u8], result: &Result<(), DeviceError>) -> Self {
+        Self::Send {
+            protocol,
+            protocol_specific: u16::from_be_bytes(protocol_specific),
+            data: trim_zeros(data).into(),
+            error: result.as_ref().err().map(encode_error),
+        }
+    }
+
+    pub fn recv(protocol: u8, protocol_specific: [u8; 2], len: usize, result: &Result<Vec<u8>, DeviceError>) -> Self {
+        Self::Recv {
+            protocol,
+            protocol_specific: u16::from_be_bytes(protocol_specific),
+            len,
+            data: result.as_ref().map(|data| trim_zeros(data).to_vec()).unwrap_or_default(),
+            error: result.as_ref().err().map(encode_error),
+        }
+    }
+
+    pub fn protocol(&self) -> u8 {
+        match self {
+            Exchange::Send { protocol, .. } => *protocol,
+            Exchange::Recv { protocol, .. } => *protocol,
+        }
+    }
+
+    pub fn protocol_specific(&self) -> u16 {
+        match self {
+            Exchange::Send { protocol_specific, .. } => *protocol_specific,
+            Exchange::Recv { protocol_specific, .. } => *protocol_specific,
+        }
+    }
+
+    pub fn data(&self) -> &[u8] {
+        match self {
+            Exchange::Send { data, .. } => data,
+            Exchange::Recv { data, .. } => data,
+        }
+    }
+}
+
+/// Read a trace that was written by a [`super::RecordingDevice`].
+pub fn read_trace(reader: impl BufRead) -> Result<(Header, Vec<Exchange>), TraceError> {
+    let mut lines = reader.lines().enumerate().filter(|(_, line)| !matches!(line, Ok(line) if line.trim().is_empty()));
+    let Some((_, header)) = lines.next() else {
+        return Err(TraceError::InvalidFormat { line: 1, message: String::from("missing header") });
+    };
+    let header: Header = parse_line(1, &header?)?;
+    if header.version != TRACE_VERSION {
+        return Err(TraceError::UnsupportedVersion(header.version));
+    }
+    let mut exchanges = Vec::new();
+    for (index, line) in lines {
+        exchanges.push(parse_line(index + 1, &line?)?);
+    }
+    Ok((header, exchanges))
+}
+
+fn parse_line<T: for<'de> Deserialize<'de>>(line: usize, text: &str) -> Result<T, TraceError> {
+    serde_json::from_str(text).map_err(|err| TraceError::InvalidFormat { line, message: err.to_string() })
+}
+
+pub fn trim_zeros(data: &[u8]) -> &[u8] {
+    let len = data.iter().rposition(|byte| *byte != 0).map(|pos| pos + 1).unwrap_or(0);
+    &data[..len]
+}
+
+fn encode_error(error: &DeviceError) -> String {
+    format!("{error:?}")
+}
+
+/// Restore the errors that carry no platform-specific information.
+/// Everything else is replayed as [`DeviceError::Unspecified`].
+pub fn decode_error(error: &str) -> DeviceError {
+    [
+        DeviceError::BufferTooShort,
+        DeviceError::BufferTooLarge,
+        DeviceError::InvalidAlignment,
+        DeviceError::DeviceNotFound,
+        DeviceError::InvalidArgument,
+        DeviceError::InvalidProtocolOrComID,
+        DeviceError::NotImplemented,
+        DeviceError::NotSupported,
+        DeviceError::PermissionDenied,
+        DeviceError::InterfaceNotSupported,
+        DeviceError::SecurityNotSupported,
+    ]
+    .into_iter()
+    .find(|candidate| encode_error(candidate) == error)
+    .unwrap_or(DeviceError::Unspecified)
+}
+
+mod hex {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(data: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        let text: String = data.iter().map(|byte| format!("{byte:02x}")).collect();
+        serializer.serialize_str(&text)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        if !text.is_ascii() || text.len() % 2 != 0 {
+            return Err(serde::de::Error::custom("expected an even number of hex digits"));
+        }
+        (0..text.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&text[i..i + 2], 16).map_err(serde::de::Error::custom))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exchange_roundtrip() {
+        let exchange = Exchange::recv(0x01, [0x00, 0x01], 2048, &Ok(vec![0x00, 0x10, 0xAB, 0x00, 0x00]));
+        let text = serde_json::to_string(&exchange).unwrap();
+        assert_eq!(text, r#"{"command":"recv","protocol":1,"protocol_specific":1,"len":2048,"data":"0010ab"}"#);
+        assert_eq!(serde_json::from_str::<Exchange>(&text).unwrap(), exchange);
+    }
+
+    #[test]
+    fn exchange_error() {
+        let exchange = Exchange::send(0x02, [0x00, 0x04], &[1, 2, 3], &Err(DeviceError::SecurityNotSupported));
+        let text = serde_json::to_string(&exchange).unwrap();
+        let Exchange::Send { error: Some(error), .. } = serde_json::from_str::<Exchange>(&text).unwrap() else {
+            panic!("expected a send with an error");
+        };
+        assert_eq!(decode_error(&error), DeviceError::SecurityNotSupported);
+        assert_eq!(decode_error("Something(Else)"), DeviceError::Unspecified);
+    }
+
+    #[test]
+    fn read_trace_invalid_hex() {
+        let text = format!(
+            "{}\n{}\n",
+            r#"{"version":1,"path":null,"interface":"NVMe","model_number":"","serial_number":"","firmware_revision":"","security_supported":true}"#,
+            r#"{"command":"send","protocol":1,"protocol_specific":1,"data":"abc"}"#
+        );
+        assert!(matches!(read_trace(text.as_bytes()), Err(TraceError::InvalidFormat { line: 2, .. })));
+    }
+
+    #[test]
+    fn read_trace_version() {
+        let text = r#"{"version":99,"path":null,"interface":"NVMe","model_number":"","serial_number":"","firmware_revision":"","security_supported":true}"#;
+        assert_eq!(read_trace(text.as_bytes()), Err(TraceError::UnsupportedVersion(99)));
+    }
+}
@@ -0,0 +1,21 @@
+//L-----------------------------------------------------------------------------
+//L Copyright (C) Péter Kardos
+//L Please refer to the full license distributed with this software.
+//L-----------------------------------------------------------------------------
+
+//! Recording and replaying the security send/receive traffic of a device.
+//!
+//! A trace file is a JSON Lines document: the first line describes the device,
+//! and every subsequent line is one security send or receive command with its outcome.
+//! Traces recorded with [`RecordingDevice`] on real drives can be replayed with
+//! [`ReplayDevice`] to reproduce firmware quirks without the hardware.
+
+mod error;
+mod format;
+mod recording;
+mod replay;
+
+pub use error::TraceError;
+pub use format::{read_trace, Exchange, Header, TRACE_VERSION};
+pub use recording::RecordingDevice;
+pub use replay::ReplayDevice;
@@ -0,0 +1,103 @@
+//L-----------------------------------------------------------------------------
+//L Copyright (C) Péter Kardos
+//L Please refer to the full license distributed with this software.
+//L-----------------------------------------------------------------------------
+
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use crate::device::{Device, Error as DeviceError, Interface};
+
+use super::error::TraceError;
+use super::format::{Exchange, Header};
+
+/// Forwards all commands to a device and writes them to a trace.
+///
+/// Every exchange is flushed right away so that the trace is usable even if
+/// the application crashes in the middle of an operation.
+pub struct RecordingDevice {
+    device: Arc<dyn Device>,
+    writer: Mutex<Box<dyn Write + Send>>,
+}
+
+impl RecordingDevice {
+    pub fn new(device: Arc<dyn Device>, writer: impl Write + Send + 'static) -> Result<Self, TraceError> {
+        let mut writer: Box<dyn Write + Send> = Box::new(writer);
+        write_line(&mut writer, &Header::new(&*device))?;
+        Ok(Self { device, writer: Mutex::new(writer) })
+    }
+
+    pub fn create(device: Arc<dyn Device>, path: impl AsRef<Path>) -> Result<Self, TraceError> {
+        Self::new(device, File::create(path)?)
+    }
+
+    fn record(&self, exchange: &Exchange) {
+        let mut writer = self.writer.lock().unwrap();
+        if let Err(err) = write_line(&mut *writer, exchange) {
+            // Losing the trace must not break the operation on the device.
+            tracing::event!(tracing::Level::WARN, "Failed to record exchange: {err}");
+        }
+    }
+}
+
+fn write_line(writer: &mut dyn Write, value: &impl serde::Serialize) -> Result<(), TraceError> {
+    let mut line = serde_json::to_vec(value).map_err(|err| TraceError::IOError(err.to_string()))?;
+    line.push(b'\n');
+    writer.write_all(&line)?;
+    writer.flush()?;
+    Ok(())
+}
+
+impl Device for RecordingDevice {
+    fn path(&self) -> Option<String> {
+        self.device.path()
+    }
+
+    fn interface(&self) -> Interface {
+        self.device.interface()
+    }
+
+    fn model_number(&self) -> String {
+        self.device.model_number()
+    }
+
+    fn serial_number(&self) -> String {
+        self.device.serial_number()
+    }
+
+    fn firmware_revision(&self) -> String {
+        self.device.firmware_revision()
+    }
+
+    fn is_security_supported(&self) -> bool {
+        self.device.is_security_supported()
+    }
+
+    fn security_send(&self, security_protocol: u8, protocol_specific: [u8; 2], data: &[u8]) -> Result<(), DeviceError> {
+        let result = self.device.security_send(security_protocol, protocol_specific, data);
+        self.record(&Exchange::send(security_protocol, protocol_specific, data, &result));
+        result
+    }
+
+    fn security_recv(
+        &self,
+        security_protocol: u8,
+        protocol_specific: [u8; 2],
+        len: usize,
+    ) -> Result<Vec<u8>, DeviceError> {
+        let result = self.device.security_recv(security_protocol, protocol_specific, len);
+        self.record(&Exchange::recv(security_protocol, protocol_specific, len, &result));
+        result
+    }
+
+    fn logical_block_size(&self) -> Result<u32, DeviceError> {
+        self.device.logical_block_size()
+    }
+
+    // User data is not part of the trace, it's only read to help the user configure the drive.
+    fn read_blocks(&self, lba: u64, count: u64) -> Result<Vec<u8>, DeviceError> {
+        self.device.read_blocks(lba, count)
+    }
+}
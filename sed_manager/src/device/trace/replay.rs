@@ -0,0 +1,170 @@
+//L-----------------------------------------------------------------------------
+//L Copyright (C) Péter Kardos
+//L Please refer to the full license distributed with this software.
+//L-----------------------------------------------------------------------------
+
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+use std::sync::Mutex;
+
+use crate::device::{Device, Error as DeviceError, Interface};
+
+use super::error::TraceError;
+use super::format::{decode_error, read_trace, trim_zeros, Exchange, Header};
+
+/// Plays back a trace written by [`super::RecordingDevice`].
+///
+/// The commands must be issued in the same order as they were recorded. When a
+/// command does not match the trace, it fails with [`DeviceError::Unspecified`],
+/// and so does every command after it. The cause can be queried via [`ReplayDevice::finish`].
+pub struct ReplayDevice {
+    header: Header,
+    exchanges: Vec<Exchange>,
+    state: Mutex<ReplayState>,
+}
+
+struct ReplayState {
+    next: usize,
+    mismatch: Option<TraceError>,
+}
+
+impl ReplayDevice {
+    pub fn new(header: Header, exchanges: Vec<Exchange>) -> Self {
+        Self { header, exchanges, state: Mutex::new(ReplayState { next: 0, mismatch: None }) }
+    }
+
+    pub fn from_reader(reader: impl BufRead) -> Result<Self, TraceError> {
+        let (header, exchanges) = read_trace(reader)?;
+        Ok(Self::new(header, exchanges))
+    }
+
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, TraceError> {
+        Self::from_reader(BufReader::new(File::open(path)?))
+    }
+
+    pub fn header(&self) -> &Header {
+        &self.header
+    }
+
+    /// The number of recorded exchanges that have not been replayed yet.
+    pub fn remaining(&self) -> usize {
+        let state = self.state.lock().unwrap();
+        self.exchanges.len() - state.next
+    }
+
+    /// Check that the commands matched the trace and that the whole trace was replayed.
+    pub fn finish(&self) -> Result<(), TraceError> {
+        let state = self.state.lock().unwrap();
+        if let Some(mismatch) = &state.mismatch {
+            Err(mismatch.clone())
+        } else if state.next < self.exchanges.len() {
+            Err(TraceError::Incomplete(self.exchanges.len() - state.next))
+        } else {
+            Ok(())
+        }
+    }
+
+    fn replay(&self, check: impl FnOnce(&Exchange) -> Result<(), String>) -> Result<&Exchange, DeviceError> {
+        let mut state = self.state.lock().unwrap();
+        if state.mismatch.is_some() {
+            return Err(DeviceError::Unspecified);
+        }
+        let index = state.next;
+        let result = match self.exchanges.get(index) {
+            Some(exchange) => check(exchange).map(|_| exchange),
+            None => Err(String::from("the trace has ended")),
+        };
+        match result {
+            Ok(exchange) => {
+                state.next += 1;
+                Ok(exchange)
+            }
+            Err(message) => {
+                state.mismatch = Some(TraceError::Mismatch { index, message });
+                Err(DeviceError::Unspecified)
+            }
+        }
+    }
+}
+
+fn check_route(exchange: &Exchange, security_protocol: u8, protocol_specific: [u8; 2]) -> Result<(), String> {
+    let protocol_specific = u16::from_be_bytes(protocol_specific);
+    if exchange.protocol() != security_protocol || exchange.protocol_specific() != protocol_specific {
+        return Err(format!(
+            "expected protocol {:02x}h/{:04x}h, got {security_protocol:02x}h/{protocol_specific:04x}h",
+            exchange.protocol(),
+            exchange.protocol_specific()
+        ));
+    }
+    Ok(())
+}
+
+// Traces only contain the security exchanges, the user data of the drive is not recorded,
+// so the block I/O methods are left at their NotSupported defaults.
+impl Device for ReplayDevice {
+    fn path(&self) -> Option<String> {
+        self.header.path.clone()
+    }
+
+    fn interface(&self) -> Interface {
+        self.header.interface()
+    }
+
+    fn model_number(&self) -> String {
+        self.header.model_number.clone()
+    }
+
+    fn serial_number(&self) -> String {
+        self.header.serial_number.clone()
+    }
+
+    fn firmware_revision(&self) -> String {
+        self.header.firmware_revision.clone()
+    }
+
+    fn is_security_supported(&self) -> bool {
+        self.header.security_supported
+    }
+
+    fn security_send(&self, security_protocol: u8, protocol_specific: [u8; 2], data: &[u8]) -> Result<(), DeviceError> {
+        let exchange = self.replay(|exchange| {
+            let Exchange::Send { data: recorded, .. } = exchange else {
+                return Err(String::from("expected a security receive, got a security send"));
+            };
+            check_route(exchange, security_protocol, protocol_specific)?;
+            if recorded.as_slice() != trim_zeros(data) {
+                return Err(String::from("the sent data differs from the recorded data"));
+            }
+            Ok(())
+        })?;
+        let Exchange::Send { error, .. } = exchange else { unreachable!() };
+        match error {
+            Some(error) => Err(decode_error(error)),
+            None => Ok(()),
+        }
+    }
+
+    fn security_recv(
+        &self,
+        security_protocol: u8,
+        protocol_specific: [u8; 2],
+        len: usize,
+    ) -> Result<Vec<u8>, DeviceError> {
+        let exchange = self.replay(|exchange| {
+            let Exchange::Recv { .. } = exchange else {
+                return Err(String::from("expected a security send, got a security receive"));
+            };
+            check_route(exchange, security_protocol, protocol_specific)
+        })?;
+        let Exchange::Recv { data, error, .. } = exchange else { unreachable!() };
+        match error {
+            Some(error) => Err(decode_error(error)),
+            None => {
+                let mut data = data.clone();
+                data.resize(len, 0);
+                Ok(data)
+            }
+        }
+    }
+}
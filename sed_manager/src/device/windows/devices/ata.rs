@@ -11,6 +11,7 @@ use winapi::shared::ntddscsi::{
 
 use crate::device::shared::aligned_array::AlignedArray;
 use crate::device::shared::ata::{ATAError, IdentifyDevice, Input};
+use crate::device::windows::utility::block_io;
 use crate::device::windows::utility::file_handle::FileHandle;
 use crate::device::windows::utility::ioctl::ioctl_in_out;
 use crate::device::{Device, Error as DeviceError, Interface};
@@ -92,6 +93,14 @@ impl Device for ATADevice {
         trusted_receive(&self.file, security_protocol, protocol_specific, data.as_padded_mut_slice())?;
         Ok(data.into_vec())
     }
+
+    fn logical_block_size(&self) -> Result<u32, DeviceError> {
+        Ok(block_io::logical_block_size(&self.file)?)
+    }
+
+    fn read_blocks(&self, lba: u64, count: u64) -> Result<Vec<u8>, DeviceError> {
+        Ok(block_io::read_blocks(&self.file, lba, count)?)
+    }
 }
 
 fn identify_device(file_handle: &FileHandle) -> Result<IdentifyDevice, DeviceError> {
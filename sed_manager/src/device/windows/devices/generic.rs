@@ -17,6 +17,7 @@ use winapi::{
 };
 
 use crate::device::windows::error::get_last_error;
+use crate::device::windows::utility::block_io;
 use crate::device::windows::utility::file_handle::FileHandle;
 use crate::device::windows::utility::ioctl::{STORAGE_BUS_TYPE, STORAGE_DEVICE_DESCRIPTOR};
 use crate::device::{shared::string::FromNullTerminated, Device, Error, Interface};
@@ -73,6 +74,14 @@ impl Device for GenericDevice {
         // The generic device does not support security commands.
         Err(Error::NotImplemented)
     }
+
+    fn logical_block_size(&self) -> Result<u32, Error> {
+        Ok(block_io::logical_block_size(&self.file)?)
+    }
+
+    fn read_blocks(&self, lba: u64, count: u64) -> Result<Vec<u8>, Error> {
+        Ok(block_io::read_blocks(&self.file, lba, count)?)
+    }
 }
 
 impl GenericDevice {
@@ -7,6 +7,7 @@ use crate::device::device::{Device, Interface};
 use crate::device::shared::aligned_array::AlignedArray;
 use crate::device::shared::memory::write_nonoverlapping;
 use crate::device::shared::nvme::IdentifyController;
+use crate::device::windows::utility::block_io;
 use crate::device::windows::utility::file_handle::FileHandle;
 use crate::device::windows::utility::ioctl::{ioctl_in_out, STORAGE_PROTOCOL_SPECIFIC_DATA, STORAGE_PROTOCOL_TYPE};
 use crate::device::windows::Error as WindowsError;
@@ -107,6 +108,14 @@ impl Device for NVMeDevice {
         )?;
         Ok(data.into_vec())
     }
+
+    fn logical_block_size(&self) -> Result<u32, DeviceError> {
+        Ok(block_io::logical_block_size(&self.file)?)
+    }
+
+    fn read_blocks(&self, lba: u64, count: u64) -> Result<Vec<u8>, DeviceError> {
+        Ok(block_io::read_blocks(&self.file, lba, count)?)
+    }
 }
 
 fn identify_controller(handle: HANDLE) -> Result<IdentifyController, WindowsError> {
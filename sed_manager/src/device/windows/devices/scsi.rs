@@ -10,13 +10,10 @@ use winapi::shared::ntddscsi::{
 };
 
 use crate::device::shared::aligned_array::AlignedArray;
-use crate::device::shared::scsi::{
-    DescriptorSenseData, FixedSenseData, SCSIError, SecurityProtocolIn, SecurityProtocolOut, SenseKey,
-    SenseResponseCode,
-};
-use crate::device::windows::utility::{file_handle::FileHandle, ioctl::ioctl_in_out};
+use crate::device::shared::scsi::{check_sense_info, get_inc_512_flag, SecurityProtocolIn, SecurityProtocolOut};
+use crate::device::windows::utility::{block_io, file_handle::FileHandle, ioctl::ioctl_in_out};
 use crate::device::{Device, Error as DeviceError, Interface};
-use crate::serialization::{DeserializeBinary, SerializeBinary};
+use crate::serialization::SerializeBinary;
 
 use super::GenericDevice;
 
@@ -101,6 +98,14 @@ impl Device for SCSIDevice {
         )?;
         Ok(data.into_vec())
     }
+
+    fn logical_block_size(&self) -> Result<u32, DeviceError> {
+        Ok(block_io::logical_block_size(&self.file)?)
+    }
+
+    fn read_blocks(&self, lba: u64, count: u64) -> Result<Vec<u8>, DeviceError> {
+        Ok(block_io::read_blocks(&self.file, lba, count)?)
+    }
 }
 
 pub fn security_protocol_in(
@@ -196,50 +201,6 @@ impl CommandWithSense {
     }
 }
 
-fn check_sense_info(scsi_result: u8, sense_info: &[u8]) -> Result<(), DeviceError> {
-    if scsi_result != 0 {
-        let raw_response_code = sense_info[0] & 0b0111_1111; // Bit 7 is reserved. See the sense info data structures in the shared scsi `mod`.
-        let response_code = SenseResponseCode::try_from(raw_response_code).unwrap_or(SenseResponseCode::Unrecognized);
-        match response_code {
-            SenseResponseCode::CurrentFixed => Err(parse_fixed_sense_info(sense_info)),
-            SenseResponseCode::DeferredFixed => Ok(()),
-            SenseResponseCode::CurrentDescriptor => Err(parse_descriptor_sense_info(sense_info)),
-            SenseResponseCode::DeferredDescriptor => Ok(()),
-            SenseResponseCode::VendorSpecific => {
-                Err(SCSIError { sense_key: SenseKey::VendorSpecific, ..Default::default() })
-            }
-            _ => Err(SCSIError { parse_failed: true, ..Default::default() }),
-        }
-        .map_err(|err| DeviceError::SCSIError(err))
-    } else {
-        Ok(())
-    }
-}
-
-fn parse_fixed_sense_info(sense_info: &[u8]) -> SCSIError {
-    let Ok(sense_data) = FixedSenseData::from_bytes(sense_info.into()) else {
-        return SCSIError { parse_failed: true, ..Default::default() };
-    };
-    SCSIError {
-        sense_key: sense_data.sense_key,
-        additional_sense_code: sense_data.additional_sense_code,
-        additional_sense_code_qualifier: sense_data.additional_sense_code_qualifier,
-        ..Default::default()
-    }
-}
-
-fn parse_descriptor_sense_info(sense_info: &[u8]) -> SCSIError {
-    let Ok(sense_data) = DescriptorSenseData::from_bytes(sense_info.into()) else {
-        return SCSIError { parse_failed: true, ..Default::default() };
-    };
-    SCSIError {
-        sense_key: sense_data.sense_key,
-        additional_sense_code: sense_data.additional_sense_code,
-        additional_sense_code_qualifier: sense_data.additional_sense_code_qualifier,
-        ..Default::default()
-    }
-}
-
 const DEFAULT_SENSE_LENGTH: u8 = 128;
 
 /// Align the IOCTL buffers to 8 bytes. I don't fully understand this, because
@@ -253,16 +214,3 @@ const ALIGNMENT: usize = 8;
 /// INC_512 flag needs to be on for some security protocols, required
 /// a buffer of a multiple of 512 bytes.
 const PADDING: usize = 512;
-
-/// Get the required INC_512 flag for SCSI security protocol in/out commands.
-///
-/// The values can be found in the TCG Storage Interface Interactions Specification.
-const fn get_inc_512_flag(security_protocol: u8) -> bool {
-    match security_protocol {
-        0x00 => true,
-        0x01 => true,
-        0x02 => true,
-        0x06 => false,
-        _ => panic!("unknown security protocol"),
-    }
-}
@@ -0,0 +1,57 @@
+//L-----------------------------------------------------------------------------
+//L Copyright (C) Péter Kardos
+//L Please refer to the full license distributed with this software.
+//L-----------------------------------------------------------------------------
+
+//! Reads user data from physical drives.
+
+use core::ffi::c_void;
+use core::mem::{size_of, zeroed};
+use core::ptr::null_mut;
+
+use winapi::shared::minwindef::{DWORD, FALSE};
+use winapi::shared::winerror::{ERROR_HANDLE_EOF, ERROR_INVALID_PARAMETER};
+use winapi::um::fileapi::{ReadFile, SetFilePointerEx};
+use winapi::um::winbase::FILE_BEGIN;
+use winapi::um::winioctl::{DISK_GEOMETRY, IOCTL_DISK_GET_DRIVE_GEOMETRY};
+use winapi::um::winnt::LARGE_INTEGER;
+
+use crate::device::windows::error::{get_last_error, Error};
+
+use super::file_handle::FileHandle;
+use super::ioctl::ioctl_in_out;
+
+pub fn logical_block_size(file: &FileHandle) -> Result<u32, Error> {
+    let mut buffer = vec![0_u8; size_of::<DISK_GEOMETRY>()];
+    let _ = ioctl_in_out(file.handle(), IOCTL_DISK_GET_DRIVE_GEOMETRY, &mut buffer)?;
+    let geometry = unsafe { (buffer.as_ptr() as *const DISK_GEOMETRY).read_unaligned() };
+    Ok(geometry.BytesPerSector)
+}
+
+pub fn read_blocks(file: &FileHandle, lba: u64, count: u64) -> Result<Vec<u8>, Error> {
+    let block_size = logical_block_size(file)? as u64;
+    let invalid_parameter = Error::Win32(ERROR_INVALID_PARAMETER);
+    let offset = lba.checked_mul(block_size).ok_or(invalid_parameter)?;
+    let len = count.checked_mul(block_size).ok_or(invalid_parameter)?;
+    let mut data = vec![0_u8; usize::try_from(len).map_err(|_| invalid_parameter)?];
+    let mut distance: LARGE_INTEGER = unsafe { zeroed() };
+    unsafe { *distance.QuadPart_mut() = i64::try_from(offset).map_err(|_| invalid_parameter)? };
+    if unsafe { SetFilePointerEx(file.handle(), distance, null_mut(), FILE_BEGIN) } == FALSE {
+        get_last_error()?;
+    }
+    let mut num_read = 0;
+    while num_read < data.len() {
+        // ReadFile takes a 32-bit length, and reads on physical drives must be a multiple of the sector size.
+        let chunk_len = core::cmp::min(data.len() - num_read, (DWORD::MAX as u64 / block_size * block_size) as usize);
+        let buffer = data[num_read..].as_mut_ptr() as *mut c_void;
+        let mut bytes_read: DWORD = 0;
+        if unsafe { ReadFile(file.handle(), buffer, chunk_len as DWORD, &mut bytes_read, null_mut()) } == FALSE {
+            get_last_error()?;
+        }
+        if bytes_read == 0 {
+            return Err(Error::Win32(ERROR_HANDLE_EOF));
+        }
+        num_read += bytes_read as usize;
+    }
+    Ok(data)
+}
@@ -3,6 +3,7 @@
 //L Please refer to the full license distributed with this software.
 //L-----------------------------------------------------------------------------
 
+pub mod block_io;
 pub mod com_interface;
 pub mod com_ptr;
 pub mod file_handle;
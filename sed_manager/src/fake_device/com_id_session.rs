@@ -12,8 +12,9 @@ use crate::messaging::com_id::{
     ComIdRequestCode, ComIdState, HandleComIdRequest, HandleComIdResponse, StackResetResponsePayload, StackResetStatus,
     VerifyComIdValidResponsePayload,
 };
-use crate::messaging::packet::ComPacket;
+use crate::messaging::packet::{ComPacket, Packet};
 use crate::messaging::value::Bytes;
+use crate::rpc::{SessionIdentifier, CONTROL_SESSION_ID};
 use crate::serialization::vec_with_len::VecWithLen;
 use crate::serialization::{DeserializeBinary, SerializeBinary};
 
@@ -22,17 +23,41 @@ pub struct ComIDSession {
     com_id_ext: u16,
     com_queue: Queue<HandleComIdResponse>,
     packet_queue: Queue<ComPacket>,
+    num_lost_incoming: usize,
+    num_lost_outgoing: usize,
 }
 
 impl ComIDSession {
     pub fn new(com_id: u16, com_id_ext: u16) -> Self {
-        Self { com_id, com_id_ext, com_queue: Queue::new(), packet_queue: Queue::new() }
+        Self {
+            com_id,
+            com_id_ext,
+            com_queue: Queue::new(),
+            packet_queue: Queue::new(),
+            num_lost_incoming: 0,
+            num_lost_outgoing: 0,
+        }
     }
 
     pub fn com_id(&self) -> u16 {
         self.com_id
     }
 
+    /// Drops the next `count` packets of SP sessions sent by the host.
+    pub fn lose_incoming_packets(&mut self, count: usize) {
+        self.num_lost_incoming += count;
+    }
+
+    /// Drops the next `count` packets of SP sessions sent by the TPer.
+    pub fn lose_outgoing_packets(&mut self, count: usize) {
+        self.num_lost_outgoing += count;
+    }
+
+    pub fn reset(&mut self) {
+        self.com_queue.clear();
+        self.packet_queue.clear();
+    }
+
     pub fn on_security_send_com(&mut self, firmware: &mut TPer, data: &[u8]) -> Result<(), Error> {
         let Ok(request) = HandleComIdRequest::from_bytes(data.into()) else {
             return Ok(());
@@ -97,13 +122,13 @@ impl ComIDSession {
     }
 
     fn process_com_packet(&mut self, firmware: &mut TPer, com_packet: ComPacket) -> Vec<ComPacket> {
-        let responses: Vec<_> = com_packet
-            .payload
-            .into_vec()
-            .into_iter()
-            .map(|packet| dispatch(firmware, packet))
-            .flatten()
-            .collect();
+        let mut responses = Vec::new();
+        for packet in com_packet.payload.into_vec() {
+            if !lose_packet(&packet, &mut self.num_lost_incoming) {
+                responses.extend(dispatch(firmware, packet));
+            }
+        }
+        responses.retain(|packet| !lose_packet(packet, &mut self.num_lost_outgoing));
         let com_packets = responses
             .into_iter()
             .map(|packet| ComPacket {
@@ -121,8 +146,7 @@ impl ComIDSession {
         // In order to reset other sessions' stacks, the sessions would have to know about each other.
         // This is permitted by the spec, but I don't see a reason to implemented for only testing purposes.
         let payload = if com_id == self.com_id && self.com_id_ext == com_id_ext {
-            self.com_queue.clear();
-            self.packet_queue.clear();
+            self.reset();
             firmware.protocol_stack.reset();
             StackResetResponsePayload { stack_reset_status: StackResetStatus::Success }
         } else {
@@ -153,6 +177,15 @@ impl ComIDSession {
     }
 }
 
+fn lose_packet(packet: &Packet, num_lost: &mut usize) -> bool {
+    // The control session has no sequence numbers, so the host could not recover its lost packets.
+    let is_lost = *num_lost != 0 && SessionIdentifier::from(packet) != CONTROL_SESSION_ID;
+    if is_lost {
+        *num_lost -= 1;
+    }
+    is_lost
+}
+
 fn no_com_id_response(com_id: u16, com_id_ext: u16) -> HandleComIdResponse {
     HandleComIdResponse {
         com_id,
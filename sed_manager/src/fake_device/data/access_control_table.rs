@@ -16,6 +16,7 @@ pub struct AccessControlRef {
     pub method_id: MethodRef,
 }
 
+#[derive(Clone)]
 pub struct AccessControlEntry {
     pub common_name: Name,
     pub acl: List<ACERef>,
@@ -31,6 +32,7 @@ pub struct AccessControlEntry {
     pub log_to: LogListRef,
 }
 
+#[derive(Clone)]
 pub struct AccessControlTable(BTreeMap<AccessControlRef, AccessControlEntry>);
 
 impl AccessControlRef {
@@ -3,15 +3,19 @@
 //L Please refer to the full license distributed with this software.
 //L-----------------------------------------------------------------------------
 
+use std::sync::Arc;
+
 use crate::rpc::MethodStatus;
 
+/// Clones share the data until one of them is written, so snapshots of large tables are cheap.
+#[derive(Clone)]
 pub struct ByteTable {
-    data: Vec<u8>,
+    data: Arc<Vec<u8>>,
 }
 
 impl ByteTable {
     pub fn new(size: usize) -> Self {
-        Self { data: vec![0; size] }
+        Self { data: Arc::new(vec![0; size]) }
     }
 
     pub fn len(&self) -> usize {
@@ -22,7 +26,7 @@ impl ByteTable {
         let first = where_;
         let last = first + bytes.len();
         if last <= self.data.len() {
-            self.data[first..last].copy_from_slice(bytes);
+            Arc::make_mut(&mut self.data)[first..last].copy_from_slice(bytes);
             Ok(())
         } else {
             Err(MethodStatus::InsufficientRows)
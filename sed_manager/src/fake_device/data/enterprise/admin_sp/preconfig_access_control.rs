@@ -0,0 +1,60 @@
+//L-----------------------------------------------------------------------------
+//L Copyright (C) Péter Kardos
+//L Please refer to the full license distributed with this software.
+//L-----------------------------------------------------------------------------
+
+use crate::fake_device::data::access_control_table::{AccessControlEntry, AccessControlRef, AccessControlTable};
+use crate::spec::enterprise::admin::*;
+use crate::spec::invoking_id;
+
+pub fn preconfig_access_control() -> AccessControlTable {
+    use crate::spec::{method_id, table_id};
+    // The Authority table is not enumerable by Anybody on Enterprise.
+    let items = vec![
+        // Table
+        (
+            AccessControlRef::new(table_id::TABLE.into(), method_id::NEXT),
+            AccessControlEntry { acl: vec![ace::ANYBODY].into(), ..Default::default() },
+        ),
+        (
+            AccessControlRef::new(table_id::TABLE.into(), method_id::GET),
+            AccessControlEntry { acl: vec![ace::ANYBODY].into(), ..Default::default() },
+        ),
+        // C_PIN
+        (
+            AccessControlRef::new(c_pin::SID.into(), method_id::SET),
+            AccessControlEntry { acl: vec![ace::SID_SET_SELF].into(), ..Default::default() },
+        ),
+        (
+            AccessControlRef::new(c_pin::MSID.into(), method_id::GET),
+            AccessControlEntry { acl: vec![ace::MSID_GET].into(), ..Default::default() },
+        ),
+        // SP
+        (
+            AccessControlRef::new(invoking_id::THIS_SP, method_id::AUTHENTICATE),
+            AccessControlEntry { acl: vec![ace::ANYBODY].into(), ..Default::default() },
+        ),
+        (
+            AccessControlRef::new(invoking_id::THIS_SP, method_id::RANDOM),
+            AccessControlEntry { acl: vec![ace::ANYBODY].into(), ..Default::default() },
+        ),
+        (
+            AccessControlRef::new(table_id::SP.into(), method_id::NEXT),
+            AccessControlEntry { acl: vec![ace::ANYBODY].into(), ..Default::default() },
+        ),
+        (
+            AccessControlRef::new(table_id::SP.into(), method_id::GET),
+            AccessControlEntry { acl: vec![ace::ANYBODY].into(), ..Default::default() },
+        ),
+        // Only the whole TPer can be reverted, the Locking SP has no Revert of its own.
+        (
+            AccessControlRef::new(sp::ADMIN.into(), method_id::REVERT),
+            AccessControlEntry { acl: vec![ace::SID].into(), ..Default::default() },
+        ),
+    ];
+
+    let count = items.len();
+    let access_control_table: AccessControlTable = items.into_iter().collect();
+    assert_eq!(access_control_table.len(), count);
+    access_control_table
+}
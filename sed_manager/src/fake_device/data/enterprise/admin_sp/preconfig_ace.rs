@@ -0,0 +1,53 @@
+//L-----------------------------------------------------------------------------
+//L Copyright (C) Péter Kardos
+//L Please refer to the full license distributed with this software.
+//L-----------------------------------------------------------------------------
+
+use crate::fake_device::data::object_table::ACETable;
+use crate::spec::enterprise::admin::*;
+use crate::spec::objects::{ace::ace_expr, ACE, CPIN};
+
+macro_rules! all_columns {
+    () => {
+        [].into_iter().collect()
+    };
+}
+
+pub fn preconfig_ace() -> ACETable {
+    let items = [
+        // Base ACEs
+        ACE {
+            uid: ace::ANYBODY,
+            boolean_expr: ace_expr!((authority::ANYBODY)),
+            columns: all_columns!(),
+            ..Default::default()
+        },
+        ACE {
+            uid: ace::MAKERS,
+            boolean_expr: ace_expr!((authority::MAKERS)),
+            columns: all_columns!(),
+            ..Default::default()
+        },
+        ACE {
+            uid: ace::SID,
+            boolean_expr: ace_expr!((authority::SID)),
+            columns: all_columns!(),
+            ..Default::default()
+        },
+        // C_PIN table
+        ACE {
+            uid: ace::SID_SET_SELF,
+            boolean_expr: ace_expr!((authority::SID)),
+            columns: [CPIN::PIN].into(),
+            ..Default::default()
+        },
+        ACE {
+            uid: ace::MSID_GET,
+            boolean_expr: ace_expr!((authority::ANYBODY)),
+            columns: [CPIN::UID, CPIN::PIN].into(),
+            ..Default::default()
+        },
+    ];
+
+    items.into_iter().collect()
+}
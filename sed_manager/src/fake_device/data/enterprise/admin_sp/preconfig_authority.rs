@@ -0,0 +1,26 @@
+//L-----------------------------------------------------------------------------
+//L Copyright (C) Péter Kardos
+//L Please refer to the full license distributed with this software.
+//L-----------------------------------------------------------------------------
+
+use crate::fake_device::data::object_table::AuthorityTable;
+use crate::spec::column_types::{AuthMethod, CredentialRef};
+use crate::spec::enterprise::admin::*;
+use crate::spec::objects::Authority;
+
+pub fn preconfig_authority() -> AuthorityTable {
+    let items = [
+        Authority { uid: authority::ANYBODY, name: "Anybody".into(), is_class: false, ..Default::default() },
+        Authority { uid: authority::MAKERS, name: "Makers".into(), is_class: true, ..Default::default() },
+        Authority {
+            uid: authority::SID,
+            name: "SID".into(),
+            is_class: false,
+            operation: AuthMethod::Password,
+            credential: CredentialRef::new_other(c_pin::SID),
+            ..Default::default()
+        },
+    ];
+
+    items.into_iter().collect()
+}
@@ -0,0 +1,17 @@
+//L-----------------------------------------------------------------------------
+//L Copyright (C) Péter Kardos
+//L Please refer to the full license distributed with this software.
+//L-----------------------------------------------------------------------------
+
+use crate::fake_device::{data::object_table::CPINTable, MSID_PASSWORD};
+use crate::spec::enterprise::admin::*;
+use crate::spec::objects::CPIN;
+
+pub fn preconfig_c_pin() -> CPINTable {
+    let items = [
+        CPIN { uid: c_pin::SID, pin: MSID_PASSWORD.into(), ..Default::default() },
+        CPIN { uid: c_pin::MSID, pin: MSID_PASSWORD.into(), ..Default::default() },
+    ];
+
+    items.into_iter().collect()
+}
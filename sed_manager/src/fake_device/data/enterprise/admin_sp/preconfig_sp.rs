@@ -0,0 +1,27 @@
+//L-----------------------------------------------------------------------------
+//L Copyright (C) Péter Kardos
+//L Please refer to the full license distributed with this software.
+//L-----------------------------------------------------------------------------
+
+use crate::fake_device::data::object_table::SPTable;
+use crate::spec::{column_types::LifeCycleState, enterprise::admin::*, objects::SP};
+
+pub fn preconfig_sp() -> SPTable {
+    // Unlike Opal, the Enterprise Locking SP is issued in the factory and cannot be activated.
+    let items = [
+        SP {
+            uid: sp::ADMIN,
+            name: "Admin".into(),
+            life_cycle_state: LifeCycleState::Manufactured,
+            ..Default::default()
+        },
+        SP {
+            uid: sp::LOCKING,
+            name: "Locking".into(),
+            life_cycle_state: LifeCycleState::Manufactured,
+            ..Default::default()
+        },
+    ];
+
+    items.into_iter().collect()
+}
@@ -0,0 +1,53 @@
+//L-----------------------------------------------------------------------------
+//L Copyright (C) Péter Kardos
+//L Please refer to the full license distributed with this software.
+//L-----------------------------------------------------------------------------
+
+use crate::fake_device::data::object_table::GenericTable;
+use crate::fake_device::data::security_provider::SecurityProvider;
+use crate::fake_device::god_authority::{append_god_access_control, append_god_ace, append_god_authority};
+
+mod preconfig_access_control;
+mod preconfig_ace;
+mod preconfig_authority;
+mod preconfig_c_pin;
+mod preconfig_k_aes_256;
+mod preconfig_locking;
+mod preconfig_table;
+
+/// Band 0 is the global range, and each band has its own BandMaster.
+const BAND_IDX: core::ops::RangeInclusive<u64> = 0_u64..=8_u64;
+
+pub fn new_locking_sp() -> SecurityProvider {
+    let access_control = append_god_access_control(preconfig_access_control::preconfig_access_control());
+    let object_tables = [
+        Box::new(preconfig_table::preconfig_table()) as Box<dyn GenericTable>,
+        Box::new(append_god_ace(preconfig_ace::preconfig_ace())) as Box<dyn GenericTable>,
+        Box::new(append_god_authority(preconfig_authority::preconfig_authority())) as Box<dyn GenericTable>,
+        Box::new(preconfig_c_pin::preconfig_c_pin()) as Box<dyn GenericTable>,
+        Box::new(preconfig_locking::preconfig_locking()) as Box<dyn GenericTable>,
+        Box::new(preconfig_k_aes_256::preconfig_k_aes_256()) as Box<dyn GenericTable>,
+    ];
+    SecurityProvider {
+        access_control,
+        object_tables: object_tables.into_iter().map(|x| (x.uid(), x)).collect(),
+        byte_tables: [].into_iter().collect(),
+    }
+}
+
+// Locking SP tables:
+// --- Basic ---
+// - Table
+// - SPInfo
+// - SPTemplates
+// - MethodID
+// - AccessControl
+// - ACE
+// - Authority
+// - C_PIN
+// --- SP-specific ---
+// - SecretProtect
+// - LockingInfo
+// - Locking
+// - K_AES_128
+// - K_AES_256
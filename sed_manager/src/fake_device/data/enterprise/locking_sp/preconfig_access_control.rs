@@ -0,0 +1,96 @@
+//L-----------------------------------------------------------------------------
+//L Copyright (C) Péter Kardos
+//L Please refer to the full license distributed with this software.
+//L-----------------------------------------------------------------------------
+
+use crate::fake_device::data::access_control_table::{AccessControlEntry, AccessControlRef, AccessControlTable};
+use crate::spec::enterprise::locking::*;
+use crate::spec::invoking_id;
+
+use super::BAND_IDX;
+
+pub fn preconfig_access_control() -> AccessControlTable {
+    use crate::spec::{method_id, table_id};
+    let mut items = vec![
+        // SP
+        (
+            AccessControlRef::new(invoking_id::THIS_SP, method_id::RANDOM),
+            AccessControlEntry { acl: vec![ace::ANYBODY].into(), ..Default::default() },
+        ),
+        // Table
+        (
+            AccessControlRef::new(table_id::TABLE.into(), method_id::NEXT),
+            AccessControlEntry { acl: vec![ace::ANYBODY].into(), ..Default::default() },
+        ),
+        (
+            AccessControlRef::new(table_id::TABLE.into(), method_id::GET),
+            AccessControlEntry { acl: vec![ace::ANYBODY].into(), ..Default::default() },
+        ),
+        // Authority
+        (
+            AccessControlRef::new(table_id::AUTHORITY.into(), method_id::NEXT),
+            AccessControlEntry { acl: vec![ace::ANYBODY].into(), ..Default::default() },
+        ),
+        (
+            AccessControlRef::new(table_id::AUTHORITY.into(), method_id::GET),
+            AccessControlEntry { acl: vec![ace::ANYBODY].into(), ..Default::default() },
+        ),
+        // C_PIN
+        (
+            AccessControlRef::new(table_id::C_PIN.into(), method_id::NEXT),
+            AccessControlEntry { acl: vec![ace::ANYBODY].into(), ..Default::default() },
+        ),
+        (
+            AccessControlRef::new(c_pin::ERASE_MASTER.into(), method_id::SET),
+            AccessControlEntry { acl: vec![ace::ERASE_MASTER_SET_SELF].into(), ..Default::default() },
+        ),
+        // Locking
+        (
+            AccessControlRef::new(table_id::LOCKING.into(), method_id::NEXT),
+            AccessControlEntry { acl: vec![ace::ANYBODY].into(), ..Default::default() },
+        ),
+    ];
+
+    // Bands
+    for index in BAND_IDX {
+        // Authority
+        items.push((
+            AccessControlRef::new(authority::BAND_MASTER.nth(index).unwrap().into(), method_id::SET),
+            AccessControlEntry { acl: vec![ace::ERASE_MASTER].into(), ..Default::default() },
+        ));
+        // C_PIN
+        items.push((
+            AccessControlRef::new(c_pin::BAND_MASTER.nth(index).unwrap().into(), method_id::SET),
+            AccessControlEntry {
+                acl: vec![ace::BAND_MASTER_SET_SELF.nth(index).unwrap()].into(),
+                ..Default::default()
+            },
+        ));
+        // Locking
+        items.push((
+            AccessControlRef::new(locking::BAND.nth(index).unwrap().into(), method_id::GET),
+            AccessControlEntry { acl: vec![ace::BAND_MASTER.nth(index).unwrap()].into(), ..Default::default() },
+        ));
+        items.push((
+            AccessControlRef::new(locking::BAND.nth(index).unwrap().into(), method_id::SET),
+            AccessControlEntry {
+                acl: vec![ace::BAND_MASTER_SET_BAND.nth(index).unwrap()].into(),
+                ..Default::default()
+            },
+        ));
+        items.push((
+            AccessControlRef::new(locking::BAND.nth(index).unwrap().into(), method_id::ERASE),
+            AccessControlEntry { acl: vec![ace::ERASE_MASTER].into(), ..Default::default() },
+        ));
+        // K_AES_256
+        items.push((
+            AccessControlRef::new(k_aes_256::BAND_KEY.nth(index).unwrap().into(), method_id::GEN_KEY),
+            AccessControlEntry { acl: vec![ace::BAND_MASTER.nth(index).unwrap()].into(), ..Default::default() },
+        ));
+    }
+
+    let count = items.len();
+    let out: AccessControlTable = items.into_iter().collect();
+    assert_eq!(out.len(), count);
+    out
+}
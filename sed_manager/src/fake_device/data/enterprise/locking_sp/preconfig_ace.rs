@@ -0,0 +1,80 @@
+//L-----------------------------------------------------------------------------
+//L Copyright (C) Péter Kardos
+//L Please refer to the full license distributed with this software.
+//L-----------------------------------------------------------------------------
+
+use crate::fake_device::data::object_table::ACETable;
+use crate::spec::enterprise::locking::*;
+use crate::spec::objects::{ace::ace_expr, ACE};
+use crate::spec::objects::{LockingRange, CPIN};
+
+use super::BAND_IDX;
+
+macro_rules! all_columns {
+    () => {
+        (0..32).into_iter().collect()
+    };
+}
+
+pub fn preconfig_ace() -> ACETable {
+    let mut items = vec![
+        // Base ACEs
+        ACE {
+            uid: ace::ANYBODY,
+            boolean_expr: ace_expr!((authority::ANYBODY)),
+            columns: all_columns!(),
+            ..Default::default()
+        },
+        ACE {
+            uid: ace::BAND_MASTERS,
+            boolean_expr: ace_expr!((authority::BAND_MASTERS)),
+            columns: all_columns!(),
+            ..Default::default()
+        },
+        ACE {
+            uid: ace::ANY_MASTER,
+            boolean_expr: ace_expr!((authority::BAND_MASTERS) (authority::ERASE_MASTER) ||),
+            columns: all_columns!(),
+            ..Default::default()
+        },
+        // EraseMaster
+        ACE {
+            uid: ace::ERASE_MASTER,
+            boolean_expr: ace_expr!((authority::ERASE_MASTER)),
+            columns: all_columns!(),
+            ..Default::default()
+        },
+        ACE {
+            uid: ace::ERASE_MASTER_SET_SELF,
+            boolean_expr: ace_expr!((authority::ERASE_MASTER)),
+            columns: [CPIN::PIN].into(),
+            ..Default::default()
+        },
+    ];
+
+    // Bands
+    let range_start_to_lor = LockingRange::RANGE_START..=LockingRange::LOCK_ON_RESET;
+    for index in BAND_IDX {
+        let band_master = authority::BAND_MASTER.nth(index).unwrap();
+        items.push(ACE {
+            uid: ace::BAND_MASTER.nth(index).unwrap(),
+            boolean_expr: ace_expr!((band_master)),
+            columns: all_columns!(),
+            ..Default::default()
+        });
+        items.push(ACE {
+            uid: ace::BAND_MASTER_SET_SELF.nth(index).unwrap(),
+            boolean_expr: ace_expr!((band_master)),
+            columns: [CPIN::PIN].into(),
+            ..Default::default()
+        });
+        items.push(ACE {
+            uid: ace::BAND_MASTER_SET_BAND.nth(index).unwrap(),
+            boolean_expr: ace_expr!((band_master)),
+            columns: range_start_to_lor.clone().collect(),
+            ..Default::default()
+        });
+    }
+
+    items.into_iter().collect()
+}
@@ -0,0 +1,48 @@
+//L-----------------------------------------------------------------------------
+//L Copyright (C) Péter Kardos
+//L Please refer to the full license distributed with this software.
+//L-----------------------------------------------------------------------------
+
+use crate::fake_device::data::object_table::AuthorityTable;
+use crate::spec::column_types::{AuthMethod, CredentialRef};
+use crate::spec::enterprise::locking::*;
+use crate::spec::objects::Authority;
+
+use super::BAND_IDX;
+
+pub fn preconfig_authority() -> AuthorityTable {
+    let mut items = vec![
+        Authority { uid: authority::ANYBODY, name: "Anybody".into(), is_class: false, ..Default::default() },
+        Authority {
+            uid: authority::BAND_MASTERS,
+            name: "BandMasters".into(),
+            is_class: true,
+            enabled: true,
+            ..Default::default()
+        },
+        Authority {
+            uid: authority::ERASE_MASTER,
+            name: "EraseMaster".into(),
+            is_class: false,
+            enabled: true,
+            operation: AuthMethod::Password,
+            credential: CredentialRef::new_other(c_pin::ERASE_MASTER),
+            ..Default::default()
+        },
+    ];
+
+    for index in BAND_IDX {
+        items.push(Authority {
+            uid: authority::BAND_MASTER.nth(index).unwrap(),
+            name: format!("BandMaster{}", index).into(),
+            is_class: false,
+            class: authority::BAND_MASTERS,
+            enabled: true,
+            operation: AuthMethod::Password,
+            credential: CredentialRef::new_other(c_pin::BAND_MASTER.nth(index).unwrap()),
+            ..Default::default()
+        });
+    }
+
+    items.into_iter().collect()
+}
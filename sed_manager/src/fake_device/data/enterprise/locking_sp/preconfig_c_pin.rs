@@ -0,0 +1,25 @@
+//L-----------------------------------------------------------------------------
+//L Copyright (C) Péter Kardos
+//L Please refer to the full license distributed with this software.
+//L-----------------------------------------------------------------------------
+
+use crate::fake_device::{data::object_table::CPINTable, MSID_PASSWORD};
+use crate::spec::enterprise::locking::*;
+use crate::spec::objects::CPIN;
+
+use super::BAND_IDX;
+
+pub fn preconfig_c_pin() -> CPINTable {
+    // Enterprise drives ship with the MSID password on all Locking SP credentials.
+    let mut items = vec![CPIN { uid: c_pin::ERASE_MASTER, pin: MSID_PASSWORD.into(), ..Default::default() }];
+
+    for index in BAND_IDX {
+        items.push(CPIN {
+            uid: c_pin::BAND_MASTER.nth(index).unwrap(),
+            pin: MSID_PASSWORD.into(),
+            ..Default::default()
+        });
+    }
+
+    items.into_iter().collect()
+}
@@ -0,0 +1,19 @@
+//L-----------------------------------------------------------------------------
+//L Copyright (C) Péter Kardos
+//L Please refer to the full license distributed with this software.
+//L-----------------------------------------------------------------------------
+
+use crate::spec::enterprise::locking::*;
+use crate::{fake_device::data::object_table::KAES256Table, spec::objects::KAES256};
+
+use super::BAND_IDX;
+
+pub fn preconfig_k_aes_256() -> KAES256Table {
+    let mut items = vec![];
+
+    for index in BAND_IDX {
+        items.push(KAES256 { uid: k_aes_256::BAND_KEY.nth(index).unwrap(), ..Default::default() });
+    }
+
+    items.into_iter().collect()
+}
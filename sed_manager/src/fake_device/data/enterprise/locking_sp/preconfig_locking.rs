@@ -0,0 +1,29 @@
+//L-----------------------------------------------------------------------------
+//L Copyright (C) Péter Kardos
+//L Please refer to the full license distributed with this software.
+//L-----------------------------------------------------------------------------
+
+use crate::spec::enterprise::locking::*;
+use crate::spec::objects::LockingRange;
+use crate::{fake_device::data::object_table::LockingTable, messaging::uid::ObjectUID};
+
+use super::BAND_IDX;
+
+pub fn preconfig_locking() -> LockingTable {
+    let mut items = vec![];
+
+    for index in BAND_IDX {
+        let name = match index {
+            0 => "GlobalRange".to_string(),
+            _ => format!("Band{}", index),
+        };
+        items.push(LockingRange {
+            uid: locking::BAND.nth(index).unwrap(),
+            name: name.into(),
+            active_key: ObjectUID::new_other(k_aes_256::BAND_KEY.nth(index).unwrap()),
+            ..Default::default()
+        });
+    }
+
+    items.into_iter().collect()
+}
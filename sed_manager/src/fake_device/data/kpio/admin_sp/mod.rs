@@ -0,0 +1,28 @@
+//L-----------------------------------------------------------------------------
+//L Copyright (C) Péter Kardos
+//L Please refer to the full license distributed with this software.
+//L-----------------------------------------------------------------------------
+
+use crate::fake_device::data::opal_v2::{self, Profile};
+use crate::fake_device::data::security_provider::SecurityProvider;
+use crate::messaging::discovery::FeatureCode;
+use crate::spec::table_id;
+
+mod preconfig_sp;
+
+// The Admin SP is the same as Opal's, except that it issues the KeyPerIO SP instead of the Locking SP.
+const PROFILE: Profile = Profile {
+    feature_code: FeatureCode::KeyPerIO,
+    admin_sp_admins: 4,
+    locking_admins: 0,
+    locking_users: 0,
+    locking_ranges: 0,
+    media_encryption: false,
+    data_store_tables: 0,
+};
+
+pub fn new_admin_sp() -> SecurityProvider {
+    let mut admin_sp = opal_v2::new_admin_sp(&PROFILE);
+    admin_sp.object_tables.insert(table_id::SP, Box::new(preconfig_sp::preconfig_sp()));
+    admin_sp
+}
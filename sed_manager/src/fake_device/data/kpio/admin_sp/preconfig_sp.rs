@@ -0,0 +1,26 @@
+//L-----------------------------------------------------------------------------
+//L Copyright (C) Péter Kardos
+//L Please refer to the full license distributed with this software.
+//L-----------------------------------------------------------------------------
+
+use crate::fake_device::data::object_table::SPTable;
+use crate::spec::{column_types::LifeCycleState, kpio::admin::*, objects::SP};
+
+pub fn preconfig_sp() -> SPTable {
+    let items = [
+        SP {
+            uid: sp::ADMIN,
+            name: "Admin".into(),
+            life_cycle_state: LifeCycleState::Manufactured,
+            ..Default::default()
+        },
+        SP {
+            uid: sp::KEY_PER_IO,
+            name: "KeyPerIO".into(),
+            life_cycle_state: LifeCycleState::ManufacturedInactive,
+            ..Default::default()
+        },
+    ];
+
+    items.into_iter().collect()
+}
@@ -0,0 +1,52 @@
+//L-----------------------------------------------------------------------------
+//L Copyright (C) Péter Kardos
+//L Please refer to the full license distributed with this software.
+//L-----------------------------------------------------------------------------
+
+use crate::fake_device::data::object_table::GenericTable;
+use crate::fake_device::data::security_provider::SecurityProvider;
+use crate::fake_device::god_authority::{append_god_access_control, append_god_ace, append_god_authority};
+
+mod preconfig_access_control;
+mod preconfig_ace;
+mod preconfig_authority;
+mod preconfig_c_pin;
+mod preconfig_key_encryption_key;
+mod preconfig_key_tag_allocation;
+mod preconfig_kpio_policies;
+mod preconfig_table;
+
+pub fn new_key_per_io_sp() -> SecurityProvider {
+    let access_control = append_god_access_control(preconfig_access_control::preconfig_access_control());
+    let object_tables = [
+        Box::new(preconfig_table::preconfig_table()) as Box<dyn GenericTable>,
+        Box::new(append_god_ace(preconfig_ace::preconfig_ace())) as Box<dyn GenericTable>,
+        Box::new(append_god_authority(preconfig_authority::preconfig_authority())) as Box<dyn GenericTable>,
+        Box::new(preconfig_c_pin::preconfig_c_pin()) as Box<dyn GenericTable>,
+        Box::new(preconfig_kpio_policies::preconfig_kpio_policies()) as Box<dyn GenericTable>,
+        Box::new(preconfig_key_tag_allocation::preconfig_key_tag_allocation()) as Box<dyn GenericTable>,
+        Box::new(preconfig_key_encryption_key::preconfig_key_encryption_key()) as Box<dyn GenericTable>,
+    ];
+    SecurityProvider {
+        access_control,
+        object_tables: object_tables.into_iter().map(|x| (x.uid(), x)).collect(),
+        byte_tables: [].into_iter().collect(),
+    }
+}
+
+// KeyPerIO SP tables:
+// --- Basic ---
+// - Table
+// - SPInfo
+// - SPTemplates
+// - MethodID
+// - AccessControl
+// - ACE
+// - Authority
+// - C_PIN
+// --- SP-specific ---
+// - SecretProtect
+// - KPIOPolicies
+// - KeyTagAllocation
+// - KeyEncryptionKey
+// - DataStore
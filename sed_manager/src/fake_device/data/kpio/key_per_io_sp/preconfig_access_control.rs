@@ -0,0 +1,132 @@
+//L-----------------------------------------------------------------------------
+//L Copyright (C) Péter Kardos
+//L Please refer to the full license distributed with this software.
+//L-----------------------------------------------------------------------------
+
+use crate::fake_device::data::access_control_table::{AccessControlEntry, AccessControlRef, AccessControlTable};
+use crate::fake_device::data::kpio::{ADMIN_IDX, KEK_IDX, KEY_TAG_IDX};
+use crate::spec::invoking_id;
+use crate::spec::kpio::key_per_io::*;
+
+pub fn preconfig_access_control() -> AccessControlTable {
+    use crate::spec::{method_id, table_id};
+    let mut items = vec![
+        // SP
+        (
+            AccessControlRef::new(invoking_id::THIS_SP, method_id::RANDOM),
+            AccessControlEntry { acl: vec![ace::ANYBODY].into(), ..Default::default() },
+        ),
+        (
+            AccessControlRef::new(invoking_id::THIS_SP, method_id::REVERT_SP),
+            AccessControlEntry { acl: vec![ace::ADMIN].into(), ..Default::default() },
+        ),
+        // Table
+        (
+            AccessControlRef::new(table_id::TABLE.into(), method_id::NEXT),
+            AccessControlEntry { acl: vec![ace::ANYBODY].into(), ..Default::default() },
+        ),
+        (
+            AccessControlRef::new(table_id::TABLE.into(), method_id::GET),
+            AccessControlEntry { acl: vec![ace::ANYBODY].into(), ..Default::default() },
+        ),
+        // ACE
+        (
+            AccessControlRef::new(table_id::ACE.into(), method_id::NEXT),
+            AccessControlEntry { acl: vec![ace::ANYBODY].into(), ..Default::default() },
+        ),
+        (
+            AccessControlRef::new(table_id::ACE.into(), method_id::GET),
+            AccessControlEntry { acl: vec![ace::ACE_GET_ALL].into(), ..Default::default() },
+        ),
+        // Authority
+        (
+            AccessControlRef::new(table_id::AUTHORITY.into(), method_id::NEXT),
+            AccessControlEntry { acl: vec![ace::ANYBODY].into(), ..Default::default() },
+        ),
+        (
+            AccessControlRef::new(table_id::AUTHORITY.into(), method_id::GET),
+            AccessControlEntry {
+                acl: vec![ace::AUTHORITY_GET_ALL, ace::ANYBODY_GET_COMMON_NAME].into(),
+                ..Default::default()
+            },
+        ),
+        // C_PIN
+        (
+            AccessControlRef::new(table_id::C_PIN.into(), method_id::NEXT),
+            AccessControlEntry { acl: vec![ace::ANYBODY].into(), ..Default::default() },
+        ),
+        // KPIOPolicies
+        (
+            AccessControlRef::new(kpio_policies::KPIO_POLICIES.into(), method_id::GET),
+            AccessControlEntry { acl: vec![ace::KPIO_POLICIES_ADMIN_GET].into(), ..Default::default() },
+        ),
+        (
+            AccessControlRef::new(kpio_policies::KPIO_POLICIES.into(), method_id::SET),
+            AccessControlEntry { acl: vec![ace::KPIO_POLICIES_ADMIN_SET].into(), ..Default::default() },
+        ),
+        // KeyTagAllocation
+        (
+            AccessControlRef::new(table_id::KEY_TAG_ALLOCATION.into(), method_id::NEXT),
+            AccessControlEntry { acl: vec![ace::ANYBODY].into(), ..Default::default() },
+        ),
+        // KeyEncryptionKey
+        (
+            AccessControlRef::new(table_id::KEY_ENCRYPTION_KEY.into(), method_id::NEXT),
+            AccessControlEntry { acl: vec![ace::ANYBODY].into(), ..Default::default() },
+        ),
+    ];
+
+    // Admins
+    for admin_idx in ADMIN_IDX {
+        // Authority
+        let admin_set_acl = if admin_idx == 1 {
+            vec![ace::ADMINS_SET_COMMON_NAME]
+        } else {
+            vec![ace::ADMINS_SET_COMMON_NAME, ace::AUTHORITY_SET_ENABLED]
+        };
+        items.push((
+            AccessControlRef::new(authority::ADMIN.nth(admin_idx).unwrap().into(), method_id::SET),
+            AccessControlEntry { acl: admin_set_acl.into(), ..Default::default() },
+        ));
+        // C_PIN
+        items.push((
+            AccessControlRef::new(c_pin::ADMIN.nth(admin_idx).unwrap().into(), method_id::GET),
+            AccessControlEntry { acl: vec![ace::C_PIN_ADMINS_GET_ALL_NOPIN].into(), ..Default::default() },
+        ));
+        items.push((
+            AccessControlRef::new(c_pin::ADMIN.nth(admin_idx).unwrap().into(), method_id::SET),
+            AccessControlEntry { acl: vec![ace::C_PIN_ADMINS_SET_PIN].into(), ..Default::default() },
+        ));
+    }
+
+    // Key tags
+    for key_tag_idx in KEY_TAG_IDX {
+        let key_tag = key_tag_allocation::KEY_TAG.nth(key_tag_idx).unwrap();
+        items.push((
+            AccessControlRef::new(key_tag.into(), method_id::GET),
+            AccessControlEntry { acl: vec![ace::KEY_TAG_ALLOCATION_ADMIN_GET].into(), ..Default::default() },
+        ));
+        items.push((
+            AccessControlRef::new(key_tag.into(), method_id::SET),
+            AccessControlEntry { acl: vec![ace::KEY_TAG_ALLOCATION_ADMIN_SET].into(), ..Default::default() },
+        ));
+    }
+
+    // KEKs
+    for kek_idx in KEK_IDX {
+        let kek = key_encryption_key::KEK.nth(kek_idx).unwrap();
+        items.push((
+            AccessControlRef::new(kek.into(), method_id::GET),
+            AccessControlEntry { acl: vec![ace::KEY_ENCRYPTION_KEY_ADMIN_GET].into(), ..Default::default() },
+        ));
+        items.push((
+            AccessControlRef::new(kek.into(), method_id::SET),
+            AccessControlEntry { acl: vec![ace::KEY_ENCRYPTION_KEY_ADMIN_SET].into(), ..Default::default() },
+        ));
+    }
+
+    let count = items.len();
+    let out: AccessControlTable = items.into_iter().collect();
+    assert_eq!(out.len(), count);
+    out
+}
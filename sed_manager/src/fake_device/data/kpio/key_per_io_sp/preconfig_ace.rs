@@ -0,0 +1,137 @@
+//L-----------------------------------------------------------------------------
+//L Copyright (C) Péter Kardos
+//L Please refer to the full license distributed with this software.
+//L-----------------------------------------------------------------------------
+
+use crate::fake_device::data::object_table::ACETable;
+use crate::spec::kpio::key_per_io::*;
+use crate::spec::objects::{ace::ace_expr, Authority, KPIOPolicies, KeyEncryptionKey, KeyTagAllocation, ACE, CPIN};
+
+macro_rules! all_columns {
+    () => {
+        (0..32).into_iter().collect()
+    };
+}
+
+pub fn preconfig_ace() -> ACETable {
+    let items = [
+        // Base ACEs
+        ACE {
+            uid: ace::ANYBODY,
+            boolean_expr: ace_expr!((authority::ANYBODY)),
+            columns: all_columns!(),
+            ..Default::default()
+        },
+        ACE {
+            uid: ace::ADMIN,
+            boolean_expr: ace_expr!((authority::ADMINS)),
+            columns: all_columns!(),
+            ..Default::default()
+        },
+        ACE {
+            uid: ace::ANYBODY_GET_COMMON_NAME,
+            boolean_expr: ace_expr!((authority::ANYBODY)),
+            columns: [0, 2].into(), // UID, CommonName
+            ..Default::default()
+        },
+        ACE {
+            uid: ace::ADMINS_SET_COMMON_NAME,
+            boolean_expr: ace_expr!((authority::ADMINS)),
+            columns: [2].into(), // CommonName
+            ..Default::default()
+        },
+        // ACE
+        ACE {
+            uid: ace::ACE_GET_ALL,
+            boolean_expr: ace_expr!((authority::ADMINS)),
+            columns: all_columns!(),
+            ..Default::default()
+        },
+        ACE {
+            uid: ace::ACE_SET_BOOLEAN_EXPRESSION,
+            boolean_expr: ace_expr!((authority::ADMINS)),
+            columns: [ACE::BOOLEAN_EXPR].into(),
+            ..Default::default()
+        },
+        // Authority
+        ACE {
+            uid: ace::AUTHORITY_GET_ALL,
+            boolean_expr: ace_expr!((authority::ADMINS)),
+            columns: all_columns!(),
+            ..Default::default()
+        },
+        ACE {
+            uid: ace::AUTHORITY_SET_ENABLED,
+            boolean_expr: ace_expr!((authority::ADMINS)),
+            columns: [Authority::ENABLED].into(),
+            ..Default::default()
+        },
+        // C_PIN
+        ACE {
+            uid: ace::C_PIN_ADMINS_GET_ALL_NOPIN,
+            boolean_expr: ace_expr!((authority::ADMINS)),
+            columns: [
+                CPIN::UID,
+                CPIN::CHAR_SET,
+                CPIN::TRY_LIMIT,
+                CPIN::TRIES,
+                CPIN::PERSISTENCE,
+            ]
+            .into(),
+            ..Default::default()
+        },
+        ACE {
+            uid: ace::C_PIN_ADMINS_SET_PIN,
+            boolean_expr: ace_expr!((authority::ADMINS)),
+            columns: [CPIN::PIN].into(),
+            ..Default::default()
+        },
+        // KPIOPolicies
+        ACE {
+            uid: ace::KPIO_POLICIES_ADMIN_SET,
+            boolean_expr: ace_expr!((authority::ADMINS)),
+            columns: [KPIOPolicies::KPIO_ENABLED].into(),
+            ..Default::default()
+        },
+        ACE {
+            uid: ace::KPIO_POLICIES_ADMIN_GET,
+            boolean_expr: ace_expr!((authority::ADMINS)),
+            columns: all_columns!(),
+            ..Default::default()
+        },
+        // KeyTagAllocation: the wrapped key can be written, but never read back.
+        ACE {
+            uid: ace::KEY_TAG_ALLOCATION_ADMIN_SET,
+            boolean_expr: ace_expr!((authority::ADMINS)),
+            columns: [
+                KeyTagAllocation::NAMESPACE_ID,
+                KeyTagAllocation::KEY_TAG,
+                KeyTagAllocation::KEK,
+                KeyTagAllocation::KEY,
+            ]
+            .into(),
+            ..Default::default()
+        },
+        ACE {
+            uid: ace::KEY_TAG_ALLOCATION_ADMIN_GET,
+            boolean_expr: ace_expr!((authority::ADMINS)),
+            columns: (KeyTagAllocation::UID..=KeyTagAllocation::KEK).collect(),
+            ..Default::default()
+        },
+        // KeyEncryptionKey: same for the KEK.
+        ACE {
+            uid: ace::KEY_ENCRYPTION_KEY_ADMIN_SET,
+            boolean_expr: ace_expr!((authority::ADMINS)),
+            columns: [KeyEncryptionKey::KEY].into(),
+            ..Default::default()
+        },
+        ACE {
+            uid: ace::KEY_ENCRYPTION_KEY_ADMIN_GET,
+            boolean_expr: ace_expr!((authority::ADMINS)),
+            columns: (KeyEncryptionKey::UID..=KeyEncryptionKey::COMMON_NAME).collect(),
+            ..Default::default()
+        },
+    ];
+
+    items.into_iter().collect()
+}
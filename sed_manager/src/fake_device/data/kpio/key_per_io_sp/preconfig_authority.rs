@@ -0,0 +1,38 @@
+//L-----------------------------------------------------------------------------
+//L Copyright (C) Péter Kardos
+//L Please refer to the full license distributed with this software.
+//L-----------------------------------------------------------------------------
+
+use crate::fake_device::data::kpio::ADMIN_IDX;
+use crate::fake_device::data::object_table::AuthorityTable;
+use crate::spec::column_types::{AuthMethod, CredentialRef};
+use crate::spec::kpio::key_per_io::*;
+use crate::spec::objects::Authority;
+
+pub fn preconfig_authority() -> AuthorityTable {
+    let mut items = vec![
+        Authority { uid: authority::ANYBODY, name: "Anybody".into(), is_class: false, ..Default::default() },
+        Authority {
+            uid: authority::ADMINS,
+            name: "Admins".into(),
+            is_class: true,
+            enabled: true,
+            ..Default::default()
+        },
+    ];
+
+    for index in ADMIN_IDX {
+        items.push(Authority {
+            uid: authority::ADMIN.nth(index).unwrap(),
+            name: format!("Admin{}", index).into(),
+            is_class: false,
+            class: authority::ADMINS,
+            enabled: (index == 1),
+            operation: AuthMethod::Password,
+            credential: CredentialRef::new_other(c_pin::ADMIN.nth(index).unwrap()),
+            ..Default::default()
+        });
+    }
+
+    items.into_iter().collect()
+}
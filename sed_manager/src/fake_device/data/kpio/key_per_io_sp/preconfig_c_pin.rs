@@ -0,0 +1,22 @@
+//L-----------------------------------------------------------------------------
+//L Copyright (C) Péter Kardos
+//L Please refer to the full license distributed with this software.
+//L-----------------------------------------------------------------------------
+
+use crate::fake_device::data::kpio::ADMIN_IDX;
+use crate::spec::kpio::key_per_io::*;
+use crate::{fake_device::data::object_table::CPINTable, spec::objects::CPIN};
+
+pub fn preconfig_c_pin() -> CPINTable {
+    let mut items = vec![];
+
+    for index in ADMIN_IDX {
+        items.push(CPIN {
+            uid: c_pin::ADMIN.nth(index).unwrap(),
+            pin: "8965823nz987gt346".into(),
+            ..Default::default()
+        });
+    }
+
+    items.into_iter().collect()
+}
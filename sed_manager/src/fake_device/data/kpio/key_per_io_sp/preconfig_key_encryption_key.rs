@@ -0,0 +1,23 @@
+//L-----------------------------------------------------------------------------
+//L Copyright (C) Péter Kardos
+//L Please refer to the full license distributed with this software.
+//L-----------------------------------------------------------------------------
+
+use crate::fake_device::data::kpio::KEK_IDX;
+use crate::fake_device::data::object_table::KeyEncryptionKeyTable;
+use crate::spec::kpio::key_per_io::*;
+use crate::spec::objects::KeyEncryptionKey;
+
+pub fn preconfig_key_encryption_key() -> KeyEncryptionKeyTable {
+    let mut items = vec![];
+
+    for index in KEK_IDX {
+        items.push(KeyEncryptionKey {
+            uid: key_encryption_key::KEK.nth(index).unwrap(),
+            name: format!("KEK{}", index).into(),
+            ..Default::default()
+        });
+    }
+
+    items.into_iter().collect()
+}
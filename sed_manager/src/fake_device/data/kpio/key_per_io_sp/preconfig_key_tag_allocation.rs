@@ -0,0 +1,23 @@
+//L-----------------------------------------------------------------------------
+//L Copyright (C) Péter Kardos
+//L Please refer to the full license distributed with this software.
+//L-----------------------------------------------------------------------------
+
+use crate::fake_device::data::kpio::KEY_TAG_IDX;
+use crate::fake_device::data::object_table::KeyTagAllocationTable;
+use crate::spec::kpio::key_per_io::*;
+use crate::spec::objects::KeyTagAllocation;
+
+pub fn preconfig_key_tag_allocation() -> KeyTagAllocationTable {
+    let mut items = vec![];
+
+    for index in KEY_TAG_IDX {
+        items.push(KeyTagAllocation {
+            uid: key_tag_allocation::KEY_TAG.nth(index).unwrap(),
+            name: format!("KeyTag{}", index).into(),
+            ..Default::default()
+        });
+    }
+
+    items.into_iter().collect()
+}
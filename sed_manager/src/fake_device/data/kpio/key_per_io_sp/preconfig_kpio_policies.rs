@@ -0,0 +1,12 @@
+//L-----------------------------------------------------------------------------
+//L Copyright (C) Péter Kardos
+//L Please refer to the full license distributed with this software.
+//L-----------------------------------------------------------------------------
+
+use crate::fake_device::data::object_table::KPIOPoliciesTable;
+use crate::spec::kpio::key_per_io::*;
+use crate::spec::objects::KPIOPolicies;
+
+pub fn preconfig_kpio_policies() -> KPIOPoliciesTable {
+    [KPIOPolicies { uid: kpio_policies::KPIO_POLICIES, kpio_enabled: false }].into_iter().collect()
+}
@@ -0,0 +1,10 @@
+//L-----------------------------------------------------------------------------
+//L Copyright (C) Péter Kardos
+//L Please refer to the full license distributed with this software.
+//L-----------------------------------------------------------------------------
+
+use crate::fake_device::data::object_table::TableTable;
+
+pub fn preconfig_table() -> TableTable {
+    TableTable::new()
+}
@@ -0,0 +1,36 @@
+//L-----------------------------------------------------------------------------
+//L Copyright (C) Péter Kardos
+//L Please refer to the full license distributed with this software.
+//L-----------------------------------------------------------------------------
+
+mod admin_sp;
+mod key_per_io_sp;
+
+pub use admin_sp::new_admin_sp;
+pub use key_per_io_sp::new_key_per_io_sp;
+
+use crate::fake_device::data::security_provider::SecurityProvider;
+use crate::fake_device::data::SecuritySubsystemClass;
+use crate::messaging::discovery::FeatureCode;
+use crate::spec::column_types::SPRef;
+use crate::spec::kpio;
+
+const ADMIN_IDX: core::ops::RangeInclusive<u64> = 1_u64..=4_u64;
+const KEK_IDX: core::ops::RangeInclusive<u64> = 1_u64..=4_u64;
+const KEY_TAG_IDX: core::ops::RangeInclusive<u64> = 1_u64..=16_u64;
+
+pub fn new_controller() -> SecuritySubsystemClass {
+    SecuritySubsystemClass::new(
+        FeatureCode::KeyPerIO,
+        sp_factory,
+        &[kpio::admin::sp::ADMIN, kpio::admin::sp::KEY_PER_IO],
+    )
+}
+
+fn sp_factory(sp_ref: SPRef) -> SecurityProvider {
+    match sp_ref {
+        kpio::admin::sp::ADMIN => new_admin_sp(),
+        kpio::admin::sp::KEY_PER_IO => new_key_per_io_sp(),
+        _ => unreachable!("this factory should never be passed to a Controller with any other SPs"),
+    }
+}
@@ -7,10 +7,30 @@ mod access_control_table;
 pub mod god_authority;
 
 pub mod byte_table;
+pub mod enterprise;
+pub mod kpio;
 pub mod object;
 pub mod object_table;
 pub mod opal_v2;
+pub mod opalite;
+pub mod pyrite;
+pub mod ruby;
 pub mod security_provider;
 pub mod ssc;
 
 pub use ssc::SecuritySubsystemClass;
+
+use crate::messaging::discovery::FeatureCode;
+
+/// Create the factory state of the given security subsystem class, if the fake device can emulate it.
+pub fn new_controller(feature_code: FeatureCode) -> Option<SecuritySubsystemClass> {
+    match feature_code {
+        FeatureCode::OpalV2 => Some(opal_v2::new_controller()),
+        FeatureCode::Opalite => Some(opalite::new_controller()),
+        FeatureCode::PyriteV2 => Some(pyrite::new_controller()),
+        FeatureCode::Ruby => Some(ruby::new_controller()),
+        FeatureCode::Enterprise => Some(enterprise::new_controller()),
+        FeatureCode::KeyPerIO => Some(kpio::new_controller()),
+        _ => None,
+    }
+}
@@ -7,7 +7,10 @@ use core::any::Any;
 
 use crate::messaging::uid::UID;
 use crate::messaging::value::Value;
-use crate::spec::objects::{Authority, LockingRange, MBRControl, TableDesc, ACE, CPIN, KAES256, SP};
+use crate::spec::objects::{
+    Authority, KPIOPolicies, KeyEncryptionKey, KeyTagAllocation, LockingRange, MBRControl, TPerInfo, TableDesc, ACE,
+    CPIN, KAES256, SP,
+};
 
 pub trait GenericObject {
     fn uid(&self) -> UID;
@@ -47,7 +50,11 @@ impl_generic_object!(Authority);
 impl_generic_object!(ACE);
 impl_generic_object!(CPIN);
 impl_generic_object!(KAES256);
+impl_generic_object!(KPIOPolicies);
+impl_generic_object!(KeyTagAllocation);
+impl_generic_object!(KeyEncryptionKey);
 impl_generic_object!(LockingRange);
 impl_generic_object!(SP);
 impl_generic_object!(TableDesc);
 impl_generic_object!(MBRControl);
+impl_generic_object!(TPerInfo);
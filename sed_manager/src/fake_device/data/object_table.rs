@@ -9,9 +9,13 @@ use std::collections::BTreeMap;
 
 use crate::messaging::uid::{TableUID, UID};
 use crate::spec::column_types::{
-    ACERef, AuthorityRef, CPINRef, KAES256Ref, LockingRangeRef, MBRControlRef, SPRef, TableDescRef,
+    ACERef, AuthorityRef, CPINRef, KAES256Ref, KPIOPoliciesRef, KeyEncryptionKeyRef, KeyTagAllocationRef,
+    LockingRangeRef, MBRControlRef, SPRef, TPerInfoRef, TableDescRef,
+};
+use crate::spec::objects::{
+    Authority, KPIOPolicies, KeyEncryptionKey, KeyTagAllocation, LockingRange, MBRControl, TPerInfo, TableDesc, ACE,
+    CPIN, KAES256, SP,
 };
-use crate::spec::objects::{Authority, LockingRange, MBRControl, TableDesc, ACE, CPIN, KAES256, SP};
 use crate::spec::table_id;
 
 use super::object::GenericObject;
@@ -22,18 +26,32 @@ pub type TableTable = ObjectTable<TableDesc, TableDescRef, { table_id::TABLE.as_
 pub type MBRControlTable = ObjectTable<MBRControl, MBRControlRef, { table_id::MBR_CONTROL.as_u64() }>;
 pub type CPINTable = ObjectTable<CPIN, CPINRef, { table_id::C_PIN.as_u64() }>;
 pub type KAES256Table = ObjectTable<KAES256, KAES256Ref, { table_id::K_AES_256.as_u64() }>;
+pub type KPIOPoliciesTable = ObjectTable<KPIOPolicies, KPIOPoliciesRef, { table_id::KPIO_POLICIES.as_u64() }>;
+pub type KeyTagAllocationTable =
+    ObjectTable<KeyTagAllocation, KeyTagAllocationRef, { table_id::KEY_TAG_ALLOCATION.as_u64() }>;
+pub type KeyEncryptionKeyTable =
+    ObjectTable<KeyEncryptionKey, KeyEncryptionKeyRef, { table_id::KEY_ENCRYPTION_KEY.as_u64() }>;
 pub type LockingTable = ObjectTable<LockingRange, LockingRangeRef, { table_id::LOCKING.as_u64() }>;
 pub type SPTable = ObjectTable<SP, SPRef, { table_id::SP.as_u64() }>;
+pub type TPerInfoTable = ObjectTable<TPerInfo, TPerInfoRef, { table_id::T_PER_INFO.as_u64() }>;
 
 pub trait GenericTable: Send + Sync {
     fn uid(&self) -> TableUID;
     fn get_object(&self, uid: UID) -> Option<&dyn GenericObject>;
     fn get_object_mut(&mut self, uid: UID) -> Option<&mut dyn GenericObject>;
     fn next_from(&self, uid: Option<UID>) -> Option<UID>;
+    fn clone_table(&self) -> Box<dyn GenericTable>;
     fn as_any(&self) -> &dyn Any;
     fn as_any_mut(&mut self) -> &mut dyn Any;
 }
 
+impl Clone for Box<dyn GenericTable> {
+    fn clone(&self) -> Self {
+        self.clone_table()
+    }
+}
+
+#[derive(Clone)]
 pub struct ObjectTable<Object, ObjectRef, const THIS_TABLE: u64>(BTreeMap<ObjectRef, Object>)
 where
     Object: GenericObject,
@@ -86,7 +104,7 @@ where
 
 impl<Object, ObjectRef, const THIS_TABLE: u64> GenericTable for ObjectTable<Object, ObjectRef, THIS_TABLE>
 where
-    Object: GenericObject + Send + Sync + 'static,
+    Object: GenericObject + Clone + Send + Sync + 'static,
     ObjectRef: TryFrom<UID> + Into<UID> + Ord + Copy + Send + Sync + 'static,
 {
     fn uid(&self) -> TableUID {
@@ -127,6 +145,10 @@ where
         }
     }
 
+    fn clone_table(&self) -> Box<dyn GenericTable> {
+        Box::new(self.clone())
+    }
+
     fn as_any(&self) -> &dyn Any {
         self as &dyn Any
     }
@@ -7,23 +7,25 @@ use crate::fake_device::data::object_table::GenericTable;
 use crate::fake_device::data::security_provider::SecurityProvider;
 use crate::fake_device::god_authority::{append_god_access_control, append_god_ace, append_god_authority};
 
+use super::Profile;
+
 mod preconfig_access_control;
 mod preconfig_ace;
 mod preconfig_authority;
 mod preconfig_c_pin;
 mod preconfig_sp;
 mod preconfig_table;
+mod preconfig_tper_info;
 
-const ADMIN_IDX: core::ops::RangeInclusive<u64> = 1_u64..=4_u64;
-
-pub fn new_admin_sp() -> SecurityProvider {
-    let access_control = append_god_access_control(preconfig_access_control::preconfig_access_control());
+pub fn new_admin_sp(profile: &Profile) -> SecurityProvider {
+    let access_control = append_god_access_control(preconfig_access_control::preconfig_access_control(profile));
     let object_tables = [
         Box::new(preconfig_table::preconfig_table()) as Box<dyn GenericTable>,
         Box::new(append_god_ace(preconfig_ace::preconfig_ace())) as Box<dyn GenericTable>,
-        Box::new(append_god_authority(preconfig_authority::preconfig_authority())) as Box<dyn GenericTable>,
-        Box::new(preconfig_c_pin::preconfig_c_pin()) as Box<dyn GenericTable>,
+        Box::new(append_god_authority(preconfig_authority::preconfig_authority(profile))) as Box<dyn GenericTable>,
+        Box::new(preconfig_c_pin::preconfig_c_pin(profile)) as Box<dyn GenericTable>,
         Box::new(preconfig_sp::preconfig_sp()) as Box<dyn GenericTable>,
+        Box::new(preconfig_tper_info::preconfig_tper_info()) as Box<dyn GenericTable>,
     ];
     SecurityProvider {
         access_control,
@@ -4,10 +4,13 @@
 //L-----------------------------------------------------------------------------
 
 use crate::fake_device::data::access_control_table::{AccessControlEntry, AccessControlRef, AccessControlTable};
+use crate::spec::core::t_per_info;
 use crate::spec::invoking_id;
 use crate::spec::opal::admin::*;
 
-pub fn preconfig_access_control() -> AccessControlTable {
+use crate::fake_device::data::opal_v2::Profile;
+
+pub fn preconfig_access_control(profile: &Profile) -> AccessControlTable {
     use crate::spec::{method_id, table_id};
     let mut items = vec![
         // Table
@@ -54,6 +57,18 @@ pub fn preconfig_access_control() -> AccessControlTable {
             AccessControlRef::new(c_pin::MSID.into(), method_id::GET),
             AccessControlEntry { acl: vec![ace::C_PIN_MSID_GET_PIN].into(), ..Default::default() },
         ),
+        // TPerInfo
+        (
+            AccessControlRef::new(table_id::T_PER_INFO.into(), method_id::GET),
+            AccessControlEntry { acl: vec![ace::ANYBODY].into(), ..Default::default() },
+        ),
+        (
+            AccessControlRef::new(t_per_info::T_PER_INFO.into(), method_id::SET),
+            AccessControlEntry {
+                acl: vec![ace::T_PER_INFO_SET_PROGRAMMATIC_RESET_ENABLE].into(),
+                ..Default::default()
+            },
+        ),
         // SP
         (
             AccessControlRef::new(invoking_id::THIS_SP.into(), method_id::AUTHENTICATE),
@@ -82,7 +97,7 @@ pub fn preconfig_access_control() -> AccessControlTable {
     ];
 
     // Admins
-    for admin_idx in 1..4 {
+    for admin_idx in 1..=profile.admin_sp_admins {
         // Authority
         items.push((
             AccessControlRef::new(authority::ADMIN.nth(admin_idx).unwrap().as_uid(), method_id::SET),
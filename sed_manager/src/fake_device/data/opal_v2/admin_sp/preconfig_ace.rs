@@ -6,7 +6,7 @@
 use crate::spec::opal::admin::*;
 use crate::{
     fake_device::data::object_table::ACETable,
-    spec::objects::{ace::ace_expr, Authority, ACE, CPIN},
+    spec::objects::{ace::ace_expr, Authority, TPerInfo, ACE, CPIN},
 };
 
 macro_rules! all_columns {
@@ -69,6 +69,13 @@ pub fn preconfig_ace() -> ACETable {
             columns: [CPIN::PIN].into(),
             ..Default::default()
         },
+        // TPerInfo
+        ACE {
+            uid: ace::T_PER_INFO_SET_PROGRAMMATIC_RESET_ENABLE,
+            boolean_expr: ace_expr!((authority::SID)),
+            columns: [TPerInfo::PROGRAMMATIC_RESET_ENABLE].into(),
+            ..Default::default()
+        },
         // SP
         ACE {
             uid: ace::SP_SID,
@@ -9,9 +9,9 @@ use crate::spec::column_types::{AuthMethod, CredentialRef};
 use crate::spec::objects::Authority;
 use crate::spec::opal::admin::*;
 
-use super::ADMIN_IDX;
+use crate::fake_device::data::opal_v2::Profile;
 
-pub fn preconfig_authority() -> AuthorityTable {
+pub fn preconfig_authority(profile: &Profile) -> AuthorityTable {
     let mut items = vec![
         Authority { uid: authority::ANYBODY, name: "Anybody".into(), is_class: false, ..Default::default() },
         Authority { uid: authority::ADMINS, name: "Admins".into(), is_class: true, ..Default::default() },
@@ -34,7 +34,7 @@ pub fn preconfig_authority() -> AuthorityTable {
         },
     ];
 
-    for admin_idx in ADMIN_IDX {
+    for admin_idx in 1..=profile.admin_sp_admins {
         items.push(Authority {
             uid: authority::ADMIN.nth(admin_idx).unwrap(),
             name: format!("Admin{}", admin_idx).into(),
@@ -0,0 +1,10 @@
+//L-----------------------------------------------------------------------------
+//L Copyright (C) Péter Kardos
+//L Please refer to the full license distributed with this software.
+//L-----------------------------------------------------------------------------
+
+use crate::{fake_device::data::object_table::TPerInfoTable, spec::objects::TPerInfo};
+
+pub fn preconfig_tper_info() -> TPerInfoTable {
+    [TPerInfo { ..Default::default() }].into_iter().collect()
+}
@@ -5,9 +5,10 @@
 
 use crate::fake_device::data::byte_table::ByteTable;
 use crate::fake_device::data::object_table::GenericTable;
+use crate::fake_device::data::opal_v2::Profile;
 use crate::fake_device::data::security_provider::SecurityProvider;
 use crate::fake_device::god_authority::{append_god_access_control, append_god_ace, append_god_authority};
-use crate::spec::table_id;
+use crate::spec::{self, table_id};
 
 mod preconfig_access_control;
 mod preconfig_ace;
@@ -18,23 +19,27 @@ mod preconfig_locking;
 mod preconfig_mbr_control;
 mod preconfig_table;
 
-const ADMIN_IDX: core::ops::RangeInclusive<u64> = 1_u64..=4_u64;
-const USER_IDX: core::ops::RangeInclusive<u64> = 1_u64..=8_u64;
-const RANGE_IDX: core::ops::RangeInclusive<u64> = 1_u64..=8_u64;
 const MBR_SIZE: u32 = 0x08000000;
+const DATA_STORE_SIZE: u32 = 0x00A00000;
 
-pub fn new_locking_sp() -> SecurityProvider {
-    let access_control = append_god_access_control(preconfig_access_control::preconfig_access_control());
-    let object_tables = [
-        Box::new(preconfig_table::preconfig_table()) as Box<dyn GenericTable>,
-        Box::new(append_god_ace(preconfig_ace::preconfig_ace())) as Box<dyn GenericTable>,
-        Box::new(append_god_authority(preconfig_authority::preconfig_authority())) as Box<dyn GenericTable>,
-        Box::new(preconfig_c_pin::preconfig_c_pin()) as Box<dyn GenericTable>,
-        Box::new(preconfig_locking::preconfig_locking()) as Box<dyn GenericTable>,
-        Box::new(preconfig_k_aes_256::preconfig_k_aes_256()) as Box<dyn GenericTable>,
+pub fn new_locking_sp(profile: &Profile) -> SecurityProvider {
+    let access_control = append_god_access_control(preconfig_access_control::preconfig_access_control(profile));
+    let mut object_tables = vec![
+        Box::new(preconfig_table::preconfig_table(profile)) as Box<dyn GenericTable>,
+        Box::new(append_god_ace(preconfig_ace::preconfig_ace(profile))) as Box<dyn GenericTable>,
+        Box::new(append_god_authority(preconfig_authority::preconfig_authority(profile))) as Box<dyn GenericTable>,
+        Box::new(preconfig_c_pin::preconfig_c_pin(profile)) as Box<dyn GenericTable>,
+        Box::new(preconfig_locking::preconfig_locking(profile)) as Box<dyn GenericTable>,
         Box::new(preconfig_mbr_control::preconfig_mbr_control()) as Box<dyn GenericTable>,
     ];
-    let byte_tables = [(table_id::MBR, ByteTable::new(MBR_SIZE as usize))];
+    if profile.media_encryption {
+        object_tables.push(Box::new(preconfig_k_aes_256::preconfig_k_aes_256(profile)) as Box<dyn GenericTable>);
+    }
+    let mut byte_tables = vec![(table_id::MBR, ByteTable::new(MBR_SIZE as usize))];
+    for data_store_idx in 0..profile.data_store_tables {
+        let data_store = spec::data_store::locking::table::DATA_STORE.nth(data_store_idx).unwrap();
+        byte_tables.push((data_store.to_table().unwrap(), ByteTable::new(DATA_STORE_SIZE as usize)));
+    }
     SecurityProvider {
         access_control,
         object_tables: object_tables.into_iter().map(|x| (x.uid(), x)).collect(),
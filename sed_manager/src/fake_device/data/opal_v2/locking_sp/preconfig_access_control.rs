@@ -4,11 +4,12 @@
 //L-----------------------------------------------------------------------------
 
 use crate::fake_device::data::access_control_table::{AccessControlEntry, AccessControlRef, AccessControlTable};
-use crate::fake_device::data::opal_v2::locking_sp::{ADMIN_IDX, RANGE_IDX, USER_IDX};
+use crate::fake_device::data::opal_v2::Profile;
+use crate::spec::data_store::locking as data_store;
 use crate::spec::invoking_id;
 use crate::spec::opal::locking::*;
 
-pub fn preconfig_access_control() -> AccessControlTable {
+pub fn preconfig_access_control(profile: &Profile) -> AccessControlTable {
     use crate::spec::{method_id, table_id};
     let mut items = vec![
         // SP
@@ -50,14 +51,6 @@ pub fn preconfig_access_control() -> AccessControlTable {
             AccessControlRef::new(ace::MBR_CONTROL_SET_DONE_TO_DOR.into(), method_id::SET),
             AccessControlEntry { acl: vec![ace::ACE_SET_BOOLEAN_EXPRESSION].into(), ..Default::default() },
         ),
-        (
-            AccessControlRef::new(ace::DATA_STORE_GET_ALL.into(), method_id::SET),
-            AccessControlEntry { acl: vec![ace::ACE_SET_BOOLEAN_EXPRESSION].into(), ..Default::default() },
-        ),
-        (
-            AccessControlRef::new(ace::DATA_STORE_SET_ALL.into(), method_id::SET),
-            AccessControlEntry { acl: vec![ace::ACE_SET_BOOLEAN_EXPRESSION].into(), ..Default::default() },
-        ),
         // Authority
         (
             AccessControlRef::new(table_id::AUTHORITY.into(), method_id::NEXT),
@@ -107,8 +100,33 @@ pub fn preconfig_access_control() -> AccessControlTable {
         ),
     ];
 
+    // DataStore
+    for data_store_idx in 0..profile.data_store_tables {
+        let data_store = data_store::table::DATA_STORE.nth(data_store_idx).unwrap().to_table().unwrap();
+        let get_all = data_store::ace::DATA_STORE_GET_ALL.nth(data_store_idx).unwrap();
+        let set_all = data_store::ace::DATA_STORE_SET_ALL.nth(data_store_idx).unwrap();
+        // ACE
+        items.push((
+            AccessControlRef::new(get_all.into(), method_id::SET),
+            AccessControlEntry { acl: vec![ace::ACE_SET_BOOLEAN_EXPRESSION].into(), ..Default::default() },
+        ));
+        items.push((
+            AccessControlRef::new(set_all.into(), method_id::SET),
+            AccessControlEntry { acl: vec![ace::ACE_SET_BOOLEAN_EXPRESSION].into(), ..Default::default() },
+        ));
+        // DataStore
+        items.push((
+            AccessControlRef::new(data_store.into(), method_id::GET),
+            AccessControlEntry { acl: vec![get_all].into(), ..Default::default() },
+        ));
+        items.push((
+            AccessControlRef::new(data_store.into(), method_id::SET),
+            AccessControlEntry { acl: vec![set_all].into(), ..Default::default() },
+        ));
+    }
+
     // Admins
-    for admin_idx in ADMIN_IDX {
+    for admin_idx in 1..=profile.locking_admins {
         // Authority
         let admin_set_acl = if admin_idx == 1 {
             vec![ace::ADMINS_SET_COMMON_NAME]
@@ -131,7 +149,7 @@ pub fn preconfig_access_control() -> AccessControlTable {
     }
 
     // Users
-    for user_idx in USER_IDX {
+    for user_idx in 1..=profile.locking_users {
         // ACE
         items.push((
             AccessControlRef::new(ace::C_PIN_USER_SET_PIN.nth(user_idx).unwrap().into(), method_id::SET),
@@ -170,10 +188,6 @@ pub fn preconfig_access_control() -> AccessControlTable {
     // Ranges
     {
         // ACE
-        items.push((
-            AccessControlRef::new(ace::K_AES_256_GLOBAL_RANGE_GEN_KEY.into(), method_id::SET),
-            AccessControlEntry { acl: vec![ace::ACE_SET_BOOLEAN_EXPRESSION].into(), ..Default::default() },
-        ));
         items.push((
             AccessControlRef::new(ace::LOCKING_GLOBAL_RANGE_GET_RANGE_START_TO_ACTIVE_KEY.into(), method_id::SET),
             AccessControlEntry { acl: vec![ace::ACE_SET_BOOLEAN_EXPRESSION].into(), ..Default::default() },
@@ -211,18 +225,9 @@ pub fn preconfig_access_control() -> AccessControlTable {
                 ..Default::default()
             },
         ));
-        // K_AES_256
-        items.push((
-            AccessControlRef::new(k_aes_256::GLOBAL_RANGE_KEY.into(), method_id::GEN_KEY),
-            AccessControlEntry { acl: vec![ace::K_AES_256_GLOBAL_RANGE_GEN_KEY].into(), ..Default::default() },
-        ));
     }
-    for range_idx in RANGE_IDX {
+    for range_idx in 1..=profile.locking_ranges {
         // ACE
-        items.push((
-            AccessControlRef::new(ace::K_AES_256_RANGE_GEN_KEY.nth(range_idx).unwrap().into(), method_id::SET),
-            AccessControlEntry { acl: vec![ace::ACE_SET_BOOLEAN_EXPRESSION].into(), ..Default::default() },
-        ));
         items.push((
             AccessControlRef::new(
                 ace::LOCKING_RANGE_GET_RANGE_START_TO_ACTIVE_KEY.nth(range_idx).unwrap().into(),
@@ -263,14 +268,31 @@ pub fn preconfig_access_control() -> AccessControlTable {
                 ..Default::default()
             },
         ));
-        // K_AES_256
+    }
+
+    // K_AES_256
+    if profile.media_encryption {
+        items.push((
+            AccessControlRef::new(ace::K_AES_256_GLOBAL_RANGE_GEN_KEY.into(), method_id::SET),
+            AccessControlEntry { acl: vec![ace::ACE_SET_BOOLEAN_EXPRESSION].into(), ..Default::default() },
+        ));
         items.push((
-            AccessControlRef::new(k_aes_256::RANGE_KEY.nth(range_idx).unwrap().into(), method_id::GEN_KEY),
-            AccessControlEntry {
-                acl: vec![ace::K_AES_256_RANGE_GEN_KEY.nth(range_idx).unwrap()].into(),
-                ..Default::default()
-            },
+            AccessControlRef::new(k_aes_256::GLOBAL_RANGE_KEY.into(), method_id::GEN_KEY),
+            AccessControlEntry { acl: vec![ace::K_AES_256_GLOBAL_RANGE_GEN_KEY].into(), ..Default::default() },
         ));
+        for range_idx in 1..=profile.locking_ranges {
+            items.push((
+                AccessControlRef::new(ace::K_AES_256_RANGE_GEN_KEY.nth(range_idx).unwrap().into(), method_id::SET),
+                AccessControlEntry { acl: vec![ace::ACE_SET_BOOLEAN_EXPRESSION].into(), ..Default::default() },
+            ));
+            items.push((
+                AccessControlRef::new(k_aes_256::RANGE_KEY.nth(range_idx).unwrap().into(), method_id::GEN_KEY),
+                AccessControlEntry {
+                    acl: vec![ace::K_AES_256_RANGE_GEN_KEY.nth(range_idx).unwrap()].into(),
+                    ..Default::default()
+                },
+            ));
+        }
     }
 
     let count = items.len();
@@ -4,11 +4,12 @@
 //L-----------------------------------------------------------------------------
 
 use crate::fake_device::data::object_table::ACETable;
+use crate::spec::data_store::locking as data_store;
 use crate::spec::objects::{ace::ace_expr, ACE};
 use crate::spec::objects::{Authority, LockingRange, MBRControl, CPIN, KAES256};
 use crate::spec::opal::locking::*;
 
-use super::{RANGE_IDX, USER_IDX};
+use crate::fake_device::data::opal_v2::Profile;
 
 macro_rules! all_columns {
     () => {
@@ -16,7 +17,7 @@ macro_rules! all_columns {
     };
 }
 
-pub fn preconfig_ace() -> ACETable {
+pub fn preconfig_ace(profile: &Profile) -> ACETable {
     let mut items = vec![
         // Base ACEs
         ACE {
@@ -91,13 +92,6 @@ pub fn preconfig_ace() -> ACETable {
             ..Default::default()
         },
         // ... Users ...
-        // K_AES_*
-        ACE {
-            uid: ace::K_AES_MODE,
-            boolean_expr: ace_expr!((authority::ANYBODY)),
-            columns: [KAES256::MODE].into(),
-            ..Default::default()
-        },
         // Locking
         ACE {
             uid: ace::LOCKING_ADMINS_RANGE_START_TO_LOR,
@@ -123,23 +117,26 @@ pub fn preconfig_ace() -> ACETable {
             columns: [MBRControl::DONE, MBRControl::DONE_ON_RESET].into(),
             ..Default::default()
         },
-        // DataStore
-        ACE {
-            uid: ace::DATA_STORE_GET_ALL,
+    ];
+
+    // DataStore
+    for data_store_idx in 0..profile.data_store_tables {
+        items.push(ACE {
+            uid: data_store::ace::DATA_STORE_GET_ALL.nth(data_store_idx).unwrap(),
             boolean_expr: ace_expr!((authority::ADMINS)),
             columns: all_columns!(),
             ..Default::default()
-        },
-        ACE {
-            uid: ace::DATA_STORE_SET_ALL,
+        });
+        items.push(ACE {
+            uid: data_store::ace::DATA_STORE_SET_ALL.nth(data_store_idx).unwrap(),
             boolean_expr: ace_expr!((authority::ADMINS)),
             columns: all_columns!(),
             ..Default::default()
-        },
-    ];
+        });
+    }
 
     // Users
-    for user_idx in USER_IDX {
+    for user_idx in 1..=profile.locking_users {
         // Authority
         items.push(ACE {
             uid: ace::USER_SET_COMMON_NAME.nth(user_idx).unwrap(),
@@ -161,13 +158,6 @@ pub fn preconfig_ace() -> ACETable {
     let range_start_to_lor = LockingRange::RANGE_START..=LockingRange::LOCK_ON_RESET;
     let range_admins_set = LockingRange::READ_LOCK_ENABLED..=LockingRange::LOCK_ON_RESET;
     {
-        // K_AES_256
-        items.push(ACE {
-            uid: ace::K_AES_256_GLOBAL_RANGE_GEN_KEY,
-            boolean_expr: ace_expr!((authority::ADMINS)),
-            columns: all_columns!(),
-            ..Default::default()
-        });
         // Locking
         items.push(ACE {
             uid: ace::LOCKING_GLOBAL_RANGE_GET_RANGE_START_TO_ACTIVE_KEY,
@@ -194,14 +184,7 @@ pub fn preconfig_ace() -> ACETable {
             ..Default::default()
         });
     }
-    for range_idx in RANGE_IDX {
-        // K_AES_256
-        items.push(ACE {
-            uid: ace::K_AES_256_RANGE_GEN_KEY.nth(range_idx).unwrap(),
-            boolean_expr: ace_expr!((authority::ADMINS)),
-            columns: all_columns!(),
-            ..Default::default()
-        });
+    for range_idx in 1..=profile.locking_ranges {
         // Locking
         items.push(ACE {
             uid: ace::LOCKING_RANGE_GET_RANGE_START_TO_ACTIVE_KEY.nth(range_idx).unwrap(),
@@ -229,5 +212,29 @@ pub fn preconfig_ace() -> ACETable {
         });
     }
 
+    // K_AES_*
+    if profile.media_encryption {
+        items.push(ACE {
+            uid: ace::K_AES_MODE,
+            boolean_expr: ace_expr!((authority::ANYBODY)),
+            columns: [KAES256::MODE].into(),
+            ..Default::default()
+        });
+        items.push(ACE {
+            uid: ace::K_AES_256_GLOBAL_RANGE_GEN_KEY,
+            boolean_expr: ace_expr!((authority::ADMINS)),
+            columns: all_columns!(),
+            ..Default::default()
+        });
+        for range_idx in 1..=profile.locking_ranges {
+            items.push(ACE {
+                uid: ace::K_AES_256_RANGE_GEN_KEY.nth(range_idx).unwrap(),
+                boolean_expr: ace_expr!((authority::ADMINS)),
+                columns: all_columns!(),
+                ..Default::default()
+            });
+        }
+    }
+
     items.into_iter().collect()
 }
@@ -8,9 +8,9 @@ use crate::spec::column_types::{AuthMethod, CredentialRef};
 use crate::spec::objects::Authority;
 use crate::spec::opal::locking::*;
 
-use super::{ADMIN_IDX, USER_IDX};
+use crate::fake_device::data::opal_v2::Profile;
 
-pub fn preconfig_authority() -> AuthorityTable {
+pub fn preconfig_authority(profile: &Profile) -> AuthorityTable {
     let mut items = vec![
         Authority { uid: authority::ANYBODY, name: "Anybody".into(), is_class: false, ..Default::default() },
         Authority {
@@ -23,7 +23,7 @@ pub fn preconfig_authority() -> AuthorityTable {
         Authority { uid: authority::USERS, name: "Users".into(), is_class: true, enabled: true, ..Default::default() },
     ];
 
-    for index in ADMIN_IDX {
+    for index in 1..=profile.locking_admins {
         items.push(Authority {
             uid: authority::ADMIN.nth(index).unwrap(),
             name: format!("Admin{}", index).into(),
@@ -36,7 +36,7 @@ pub fn preconfig_authority() -> AuthorityTable {
         });
     }
 
-    for index in USER_IDX {
+    for index in 1..=profile.locking_users {
         items.push(Authority {
             uid: authority::USER.nth(index).unwrap(),
             name: format!("User{}", index).into(),
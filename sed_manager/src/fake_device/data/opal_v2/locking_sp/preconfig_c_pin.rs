@@ -6,12 +6,12 @@
 use crate::spec::opal::locking::*;
 use crate::{fake_device::data::object_table::CPINTable, spec::objects::CPIN};
 
-use super::{ADMIN_IDX, USER_IDX};
+use crate::fake_device::data::opal_v2::Profile;
 
-pub fn preconfig_c_pin() -> CPINTable {
+pub fn preconfig_c_pin(profile: &Profile) -> CPINTable {
     let mut items = vec![];
 
-    for index in ADMIN_IDX {
+    for index in 1..=profile.locking_admins {
         items.push(CPIN {
             uid: c_pin::ADMIN.nth(index).unwrap(),
             pin: "8965823nz987gt346".into(),
@@ -19,7 +19,7 @@ pub fn preconfig_c_pin() -> CPINTable {
         });
     }
 
-    for index in USER_IDX {
+    for index in 1..=profile.locking_users {
         items.push(CPIN {
             uid: c_pin::USER.nth(index).unwrap(),
             pin: "8965823nz987gt346".into(),
@@ -6,12 +6,12 @@
 use crate::spec::opal::locking::*;
 use crate::{fake_device::data::object_table::KAES256Table, spec::objects::KAES256};
 
-use super::RANGE_IDX;
+use crate::fake_device::data::opal_v2::Profile;
 
-pub fn preconfig_k_aes_256() -> KAES256Table {
+pub fn preconfig_k_aes_256(profile: &Profile) -> KAES256Table {
     let mut items = vec![KAES256 { uid: k_aes_256::GLOBAL_RANGE_KEY, ..Default::default() }];
 
-    for index in RANGE_IDX {
+    for index in 1..=profile.locking_ranges {
         items.push(KAES256 { uid: k_aes_256::RANGE_KEY.nth(index).unwrap(), ..Default::default() });
     }
 
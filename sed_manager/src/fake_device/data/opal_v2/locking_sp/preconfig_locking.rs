@@ -7,19 +7,25 @@ use crate::spec::objects::LockingRange;
 use crate::spec::opal::locking::*;
 use crate::{fake_device::data::object_table::LockingTable, messaging::uid::ObjectUID};
 
-use super::RANGE_IDX;
+use crate::fake_device::data::opal_v2::Profile;
+
+pub fn preconfig_locking(profile: &Profile) -> LockingTable {
+    // Without media encryption, there are no keys to reference.
+    let key = |key_ref| match profile.media_encryption {
+        true => ObjectUID::new_other(key_ref),
+        false => ObjectUID::null(),
+    };
 
-pub fn preconfig_locking() -> LockingTable {
     let mut items = vec![LockingRange {
         uid: locking::GLOBAL_RANGE,
-        active_key: ObjectUID::new_other(k_aes_256::GLOBAL_RANGE_KEY),
+        active_key: key(k_aes_256::GLOBAL_RANGE_KEY),
         ..Default::default()
     }];
 
-    for index in RANGE_IDX {
+    for index in 1..=profile.locking_ranges {
         items.push(LockingRange {
             uid: locking::RANGE.nth(index).unwrap(),
-            active_key: ObjectUID::new_other(k_aes_256::RANGE_KEY.nth(index).unwrap()),
+            active_key: key(k_aes_256::RANGE_KEY.nth(index).unwrap()),
             ..Default::default()
         });
     }
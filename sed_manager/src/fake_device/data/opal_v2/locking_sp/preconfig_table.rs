@@ -4,14 +4,14 @@
 //L-----------------------------------------------------------------------------
 
 use crate::{
-    fake_device::data::object_table::TableTable,
+    fake_device::data::{object_table::TableTable, opal_v2::Profile},
     spec::{self, column_types::TableKind, objects::TableDesc},
 };
 
-use super::MBR_SIZE;
+use super::{DATA_STORE_SIZE, MBR_SIZE};
 
-pub fn preconfig_table() -> TableTable {
-    let items = [
+pub fn preconfig_table(profile: &Profile) -> TableTable {
+    let mut items = vec![
         TableDesc {
             uid: spec::core::table::MBR_CONTROL,
             name: "MBRControl".into(),
@@ -27,5 +27,19 @@ pub fn preconfig_table() -> TableTable {
         },
     ];
 
+    for data_store_idx in 0..profile.data_store_tables {
+        let name = match data_store_idx {
+            0 => "DataStore".into(),
+            _ => format!("DataStore{}", data_store_idx + 1),
+        };
+        items.push(TableDesc {
+            uid: spec::data_store::locking::table::DATA_STORE.nth(data_store_idx).unwrap(),
+            name: name.into(),
+            kind: TableKind::Byte,
+            rows: DATA_STORE_SIZE,
+            ..Default::default()
+        });
+    }
+
     items.into_iter().collect()
 }
@@ -11,17 +11,51 @@ pub use locking_sp::new_locking_sp;
 
 use crate::fake_device::data::security_provider::SecurityProvider;
 use crate::fake_device::data::SecuritySubsystemClass;
+use crate::messaging::discovery::FeatureCode;
 use crate::spec::column_types::SPRef;
 use crate::spec::opal;
 
+/// Describes how an SSC derived from Opal differs from Opal itself.
+///
+/// Opalite, Pyrite and Ruby share the UIDs of Opal, but have fewer authorities,
+/// fewer locking ranges, and possibly no media encryption. The counts exclude
+/// the global range, but include the first DataStore table.
+pub struct Profile {
+    pub feature_code: FeatureCode,
+    pub admin_sp_admins: u64,
+    pub locking_admins: u64,
+    pub locking_users: u64,
+    pub locking_ranges: u64,
+    pub media_encryption: bool,
+    pub data_store_tables: u64,
+}
+
+pub const OPAL_V2: Profile = Profile {
+    feature_code: FeatureCode::OpalV2,
+    admin_sp_admins: 4,
+    locking_admins: 4,
+    locking_users: 8,
+    locking_ranges: 8,
+    media_encryption: true,
+    data_store_tables: 2,
+};
+
 pub fn new_controller() -> SecuritySubsystemClass {
-    SecuritySubsystemClass::new(sp_factory, &[opal::admin::sp::ADMIN, opal::admin::sp::LOCKING])
+    new_controller_with(&OPAL_V2)
+}
+
+pub fn new_controller_with(profile: &'static Profile) -> SecuritySubsystemClass {
+    SecuritySubsystemClass::new(
+        profile.feature_code,
+        move |sp_ref| sp_factory(profile, sp_ref),
+        &[opal::admin::sp::ADMIN, opal::admin::sp::LOCKING],
+    )
 }
 
-fn sp_factory(sp_ref: SPRef) -> SecurityProvider {
+fn sp_factory(profile: &Profile, sp_ref: SPRef) -> SecurityProvider {
     match sp_ref {
-        opal::admin::sp::ADMIN => new_admin_sp(),
-        opal::admin::sp::LOCKING => new_locking_sp(),
+        opal::admin::sp::ADMIN => new_admin_sp(profile),
+        opal::admin::sp::LOCKING => new_locking_sp(profile),
         _ => unreachable!("this factory should never be passed to a Controller with any other SPs"),
     }
 }
@@ -0,0 +1,23 @@
+//L-----------------------------------------------------------------------------
+//L Copyright (C) Péter Kardos
+//L Please refer to the full license distributed with this software.
+//L-----------------------------------------------------------------------------
+
+use crate::fake_device::data::opal_v2::{self, Profile};
+use crate::fake_device::data::SecuritySubsystemClass;
+use crate::messaging::discovery::FeatureCode;
+
+// Opalite has no admins in the Admin SP, and only the global range in the Locking SP.
+pub const PROFILE: Profile = Profile {
+    feature_code: FeatureCode::Opalite,
+    admin_sp_admins: 0,
+    locking_admins: 1,
+    locking_users: 2,
+    locking_ranges: 0,
+    media_encryption: true,
+    data_store_tables: 1,
+};
+
+pub fn new_controller() -> SecuritySubsystemClass {
+    opal_v2::new_controller_with(&PROFILE)
+}
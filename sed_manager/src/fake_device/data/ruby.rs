@@ -0,0 +1,23 @@
+//L-----------------------------------------------------------------------------
+//L Copyright (C) Péter Kardos
+//L Please refer to the full license distributed with this software.
+//L-----------------------------------------------------------------------------
+
+use crate::fake_device::data::opal_v2::{self, Profile};
+use crate::fake_device::data::SecuritySubsystemClass;
+use crate::messaging::discovery::FeatureCode;
+
+// Ruby mirrors Opal 2, apart from the discovery descriptor.
+pub const PROFILE: Profile = Profile {
+    feature_code: FeatureCode::Ruby,
+    admin_sp_admins: 4,
+    locking_admins: 4,
+    locking_users: 8,
+    locking_ranges: 8,
+    media_encryption: true,
+    data_store_tables: 2,
+};
+
+pub fn new_controller() -> SecuritySubsystemClass {
+    opal_v2::new_controller_with(&PROFILE)
+}
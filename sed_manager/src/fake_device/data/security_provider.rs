@@ -7,18 +7,21 @@ use std::collections::{HashMap, HashSet};
 
 use crate::fake_device::data::access_control_table::AccessControlTable;
 use crate::fake_device::data::byte_table::ByteTable;
-use crate::fake_device::data::object_table::{ACETable, AuthorityTable, CPINTable, GenericTable, KAES256Table};
+use crate::fake_device::data::object_table::{
+    ACETable, AuthorityTable, CPINTable, GenericTable, KAES256Table, LockingTable, MBRControlTable,
+};
 use crate::messaging::uid::{TableUID, UID};
 use crate::messaging::value::{Bytes, Named, Value};
 use crate::rpc::MethodStatus;
 use crate::spec::basic_types::List;
 use crate::spec::column_types::{
     ACERef, AuthorityRef, BoolOrBytes, BytesOrRowValues, CPINRef, CellBlock, CellBlockWrite, CredentialRef, KAES256Ref,
-    Key256, MethodRef,
+    Key256, LockingRangeRef, MethodRef, ResetType,
 };
 use crate::spec::objects::{ACEExpr as _, ACE};
 use crate::spec::table_id;
 
+#[derive(Clone)]
 pub struct SecurityProvider {
     pub access_control: AccessControlTable,
     pub object_tables: HashMap<TableUID, Box<dyn GenericTable>>,
@@ -104,6 +107,33 @@ impl SecurityProvider {
         }
     }
 
+    pub fn erase(&mut self, range_ref: LockingRangeRef) -> Result<(), MethodStatus> {
+        let locking_table: &mut LockingTable =
+            self.get_object_table_specific_mut(table_id::LOCKING).ok_or(MethodStatus::InvalidParameter)?;
+        let range = locking_table.get_mut(&range_ref).ok_or(MethodStatus::InvalidParameter)?;
+        range.read_lock_enabled = false;
+        range.write_lock_enabled = false;
+        range.read_locked = false;
+        range.write_locked = false;
+        let active_key = range.active_key;
+        self.gen_key(CredentialRef::new_other(active_key), None, None)
+    }
+
+    /// Applies the LockOnReset and DoneOnReset columns as if the reset event had happened.
+    pub fn reset(&mut self, reset_type: ResetType) {
+        if let Some(locking_table) = self.get_object_table_specific_mut::<LockingTable>(table_id::LOCKING) {
+            for range in locking_table.values_mut().filter(|range| range.lock_on_reset.contains(&reset_type)) {
+                range.read_locked = true;
+                range.write_locked = true;
+            }
+        }
+        if let Some(mbr_control_table) = self.get_object_table_specific_mut::<MBRControlTable>(table_id::MBR_CONTROL) {
+            for mbr_control in mbr_control_table.values_mut().filter(|row| row.done_on_reset.contains(&reset_type)) {
+                mbr_control.done = false;
+            }
+        }
+    }
+
     pub fn get_acl(&self, invoking_id: UID, method_id: MethodRef) -> Result<Vec<ACERef>, MethodStatus> {
         let direct_acl = self.access_control.get(&invoking_id, &method_id);
         let table_acl =
@@ -7,21 +7,25 @@ use std::collections::HashMap;
 
 use crate::fake_device::data::object_table::{CPINTable, SPTable};
 use crate::fake_device::data::security_provider::SecurityProvider;
+use crate::messaging::discovery::FeatureCode;
 use crate::rpc::MethodStatus;
 use crate::spec::column_types::{LifeCycleState, SPRef};
 use crate::spec::{self, opal, table_id};
 
 pub struct SecuritySubsystemClass {
+    pub feature_code: FeatureCode,
     pub security_providers: HashMap<SPRef, SecurityProvider>,
     pub make_factory_sp: Box<dyn Fn(SPRef) -> SecurityProvider + Send + Sync + 'static>,
 }
 
 impl SecuritySubsystemClass {
     pub fn new(
+        feature_code: FeatureCode,
         make_factory_sp: impl Fn(SPRef) -> SecurityProvider + Send + Sync + 'static,
         security_providers: &[SPRef],
     ) -> Self {
         Self {
+            feature_code,
             security_providers: security_providers.iter().map(|sp| (*sp, make_factory_sp(*sp))).collect(),
             make_factory_sp: Box::new(make_factory_sp) as Box<dyn Fn(SPRef) -> SecurityProvider + Send + Sync>,
         }
@@ -4,23 +4,29 @@
 //L-----------------------------------------------------------------------------
 
 use core::time::Duration;
+use std::collections::BTreeMap;
 use std::ops::DerefMut;
 use std::sync::{Arc, Mutex};
 
 use crate::device::{Device, Error, Interface};
-use crate::fake_device::data::opal_v2;
-use crate::fake_device::tper::TPer;
+use crate::fake_device::data::{self, opal_v2, SecuritySubsystemClass};
+use crate::fake_device::tper::{SIDBlock, TPer};
 use crate::messaging::com_id::HANDLE_COM_ID_PROTOCOL;
+use crate::messaging::discovery::FeatureCode;
 use crate::messaging::packet::PACKETIZED_PROTOCOL;
 use crate::rpc::{Properties, SessionIdentifier};
-use crate::spec::column_types::SPRef;
+use crate::spec::column_types::{ResetType, SPRef};
 
 use super::com_id_session::ComIDSession;
-use super::discovery::{get_discovery, write_discovery, BASE_COM_ID, NUM_COM_IDS};
+use super::discovery::{get_discovery, write_discovery, BASE_COM_ID, LOGICAL_BLOCK_SIZE, NUM_COM_IDS};
 
 const ROUTE_DISCOVERY: Route = Route { protocol: 0x01, com_id: 0x0001 };
 const ROUTE_GET_COMID: Route = Route { protocol: 0x02, com_id: 0x0000 };
 const ROUTE_TPER_RESET: Route = Route { protocol: 0x02, com_id: 0x0004 };
+const ROUTE_BLOCK_SID: Route = Route { protocol: 0x02, com_id: 0x0005 };
+
+/// The capacity of the drive, 1 GiB.
+const NUM_BLOCKS: u64 = 1 << 21;
 
 const CAPABILITIES: Properties = Properties {
     max_methods: usize::MAX,
@@ -31,9 +37,10 @@ const CAPABILITIES: Properties = Properties {
     max_gross_compacket_response_size: 65536,
     max_ind_token_size: 65480,
     max_agg_token_size: 65480,
-    continued_tokens: false,
-    seq_numbers: false,
-    ack_nak: false,
+    max_transactions: 1,
+    continued_tokens: true,
+    seq_numbers: true,
+    ack_nak: true,
     asynchronous: true,
     buffer_mgmt: false,
     max_retries: 3,
@@ -48,6 +55,7 @@ pub struct FakeDevice {
 struct DeviceState {
     tper: TPer,
     com_id_session: ComIDSession,
+    user_data: BTreeMap<u64, Vec<u8>>, // Sparse, only blocks that were written are stored.
 }
 
 #[derive(PartialEq, Eq)]
@@ -58,16 +66,30 @@ struct Route {
 
 impl FakeDevice {
     pub fn new() -> FakeDevice {
+        Self::with_ssc(opal_v2::new_controller())
+    }
+
+    /// Create a device that emulates the given security subsystem class.
+    pub fn with_ssc(ssc: SecuritySubsystemClass) -> FakeDevice {
         assert_eq!(
             NUM_COM_IDS, 1,
             "only a single ComID is supported due to lack of ComID multiplexing in firmware state"
         );
-        let tper = opal_v2::new_controller();
-        let state =
-            DeviceState { tper: TPer::new(tper, CAPABILITIES), com_id_session: ComIDSession::new(BASE_COM_ID, 0x0000) };
+        let state = DeviceState {
+            tper: TPer::new(ssc, CAPABILITIES),
+            com_id_session: ComIDSession::new(BASE_COM_ID, 0x0000),
+            user_data: BTreeMap::new(),
+        };
         FakeDevice { state: Arc::new(Mutex::new(state)) }
     }
 
+    /// Create a device in factory state that reports the given SSC feature code.
+    ///
+    /// Returns `None` if there is no preconfigured data for the SSC.
+    pub fn with_feature_code(feature_code: FeatureCode) -> Option<FakeDevice> {
+        data::new_controller(feature_code).map(Self::with_ssc)
+    }
+
     pub fn capabilities(&self) -> Properties {
         let state = self.state.lock().unwrap();
         state.tper.protocol_stack.capabilities.clone()
@@ -85,6 +107,34 @@ impl FakeDevice {
             .collect()
     }
 
+    /// Drops the next `count` packets the host sends on SP sessions, as if they were lost in transit.
+    pub fn lose_incoming_packets(&self, count: usize) {
+        self.state.lock().unwrap().com_id_session.lose_incoming_packets(count);
+    }
+
+    /// Drops the next `count` packets the TPer sends on SP sessions, as if they were lost in transit.
+    pub fn lose_outgoing_packets(&self, count: usize) {
+        self.state.lock().unwrap().com_id_session.lose_outgoing_packets(count);
+    }
+
+    /// Makes the next `count` reads of byte tables, like the MBR, return one byte less than requested.
+    pub fn shorten_byte_reads(&self, count: usize) {
+        self.state.lock().unwrap().tper.short_byte_reads = count;
+    }
+
+    /// Writes user data starting at `lba`. The length of `data` must be a multiple of the block size.
+    pub fn write_blocks(&self, lba: u64, data: &[u8]) -> Result<(), Error> {
+        let block_size = LOGICAL_BLOCK_SIZE as usize;
+        if !data.len().is_multiple_of(block_size) || lba + (data.len() / block_size) as u64 > NUM_BLOCKS {
+            return Err(Error::InvalidArgument);
+        }
+        let mut state = self.state.lock().unwrap();
+        for (idx, block) in data.chunks(block_size).enumerate() {
+            state.user_data.insert(lba + idx as u64, block.to_vec());
+        }
+        Ok(())
+    }
+
     pub fn with_tper<T>(&self, f: impl FnOnce(&TPer) -> T) -> T {
         let state = self.state.lock().unwrap();
         f(&state.tper)
@@ -125,12 +175,21 @@ impl Device for FakeDevice {
         let com_id = u16::from_be_bytes(protocol_specific);
         let route = Route { protocol: security_protocol, com_id };
         let mut state = self.state.lock().unwrap();
-        let DeviceState { tper: firmware, com_id_session: session } = state.deref_mut();
+        let DeviceState { tper: firmware, com_id_session: session, .. } = state.deref_mut();
 
         if route == ROUTE_DISCOVERY {
             Ok(()) // Discovery on IF-SEND is simply ignored.
         } else if route == ROUTE_TPER_RESET {
-            unimplemented!("TPer reset is not implemented for the fake device")
+            // The TPer silently ignores the reset unless it's enabled in the TPerInfo table.
+            if firmware.is_programmatic_reset_enabled() {
+                session.reset();
+                firmware.reset(ResetType::Programmatic);
+            }
+            Ok(())
+        } else if route == ROUTE_BLOCK_SID {
+            let hw_reset_unblocks = data.first().is_some_and(|clear_events| clear_events & 1 != 0);
+            firmware.sid_block = Some(SIDBlock { hw_reset_unblocks });
+            Ok(())
         } else if session.com_id() == com_id {
             match security_protocol {
                 HANDLE_COM_ID_PROTOCOL => session.on_security_send_com(firmware, data),
@@ -148,7 +207,8 @@ impl Device for FakeDevice {
         let mut state = self.state.lock().unwrap();
 
         if route == ROUTE_DISCOVERY {
-            let discovery = get_discovery(&state.tper.protocol_stack.capabilities, &state.tper.ssc);
+            let discovery =
+                get_discovery(&state.tper.protocol_stack.capabilities, &state.tper.ssc, state.tper.sid_block);
             write_discovery(&discovery, len)
         } else if route == ROUTE_GET_COMID {
             unimplemented!("dynamic com ID management is not implemented for the fake device")
@@ -162,4 +222,21 @@ impl Device for FakeDevice {
             Err(Error::InvalidProtocolOrComID)
         }
     }
+
+    fn logical_block_size(&self) -> Result<u32, Error> {
+        Ok(LOGICAL_BLOCK_SIZE)
+    }
+
+    fn read_blocks(&self, lba: u64, count: u64) -> Result<Vec<u8>, Error> {
+        if lba.checked_add(count).is_none_or(|end| end > NUM_BLOCKS) {
+            return Err(Error::InvalidArgument);
+        }
+        let state = self.state.lock().unwrap();
+        let mut data = vec![0; (count * LOGICAL_BLOCK_SIZE as u64) as usize];
+        for (block_lba, block) in state.user_data.range(lba..lba + count) {
+            let offset = ((block_lba - lba) * LOGICAL_BLOCK_SIZE as u64) as usize;
+            data[offset..offset + block.len()].copy_from_slice(block);
+        }
+        Ok(data)
+    }
 }
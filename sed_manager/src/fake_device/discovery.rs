@@ -3,20 +3,25 @@
 //L Please refer to the full license distributed with this software.
 //L-----------------------------------------------------------------------------
 
-use crate::fake_device::data::object_table::{CPINTable, LockingTable, MBRControlTable};
+use crate::fake_device::data::object_table::{
+    CPINTable, KPIOPoliciesTable, KeyEncryptionKeyTable, KeyTagAllocationTable, LockingTable, MBRControlTable,
+};
 use crate::messaging::discovery::{
-    BlockSIDAuthDescriptor, Discovery, FeatureDescriptor, GeometryDescriptor, LockingDescriptor, OpalV2Descriptor,
-    OwnerPasswordState, TPerDescriptor,
+    AdditionalDataStoreTablesDescriptor, BlockSIDAuthDescriptor, Discovery, EnterpriseDescriptor, FeatureCode,
+    FeatureDescriptor, GeometryDescriptor, KeyPerIODescriptor, LockingDescriptor, OpalV2Descriptor, OpaliteDescriptor,
+    OwnerPasswordState, PyriteV2Descriptor, RubyDescriptor, TPerDescriptor,
 };
 use crate::rpc::Properties;
 use crate::serialization::{OutputStream, Serialize};
 use crate::spec::column_types::LifeCycleState;
 use crate::spec::{self, table_id};
 
-use super::data::SecuritySubsystemClass;
+use super::data::{ruby, SecuritySubsystemClass};
+use super::tper::SIDBlock;
 
 pub const BASE_COM_ID: u16 = 4100;
 pub const NUM_COM_IDS: u16 = 1;
+pub const LOGICAL_BLOCK_SIZE: u32 = 512;
 
 pub fn write_discovery(discovery: &Discovery, len: usize) -> Result<Vec<u8>, crate::device::Error> {
     let mut stream = OutputStream::<u8>::new();
@@ -26,16 +31,19 @@ pub fn write_discovery(discovery: &Discovery, len: usize) -> Result<Vec<u8>, cra
     Ok(buffer)
 }
 
-pub fn get_discovery(properties: &Properties, ssc: &SecuritySubsystemClass) -> Discovery {
+pub fn get_discovery(properties: &Properties, ssc: &SecuritySubsystemClass, sid_block: Option<SIDBlock>) -> Discovery {
     let mut features = vec![
         get_tper_feature_desc(properties),
         get_locking_feature_desc(ssc),
-        get_ssc_feature_desc(),
+        get_ssc_feature_desc(ssc),
         get_geometry_feature_desc(),
     ];
-    if let Some(block_sid_auth_desc) = get_block_sid_authentication_desc(ssc) {
+    if let Some(block_sid_auth_desc) = get_block_sid_authentication_desc(ssc, sid_block) {
         features.push(block_sid_auth_desc.into());
     }
+    if let Some(data_store_desc) = get_additional_data_store_tables_desc(ssc) {
+        features.push(data_store_desc);
+    }
     Discovery::new(features)
 }
 
@@ -52,16 +60,23 @@ fn get_tper_feature_desc(properties: &Properties) -> FeatureDescriptor {
 }
 
 fn get_locking_feature_desc(ssc: &SecuritySubsystemClass) -> FeatureDescriptor {
-    let locking_sp = ssc.get_sp(spec::opal::admin::sp::LOCKING).unwrap();
-    let locking_enabled = ssc.get_life_cycle_state(spec::opal::admin::sp::LOCKING) == Ok(LifeCycleState::Manufactured);
+    let locking_sp_ref = match ssc.feature_code {
+        FeatureCode::Enterprise => spec::enterprise::admin::sp::LOCKING,
+        FeatureCode::KeyPerIO => spec::kpio::admin::sp::KEY_PER_IO,
+        _ => spec::opal::admin::sp::LOCKING,
+    };
+    let locking_sp = ssc.get_sp(locking_sp_ref).unwrap();
+    let locking_enabled = ssc.get_life_cycle_state(locking_sp_ref) == Ok(LifeCycleState::Manufactured);
 
-    let locking_table: &LockingTable = locking_sp.get_object_table_specific(table_id::LOCKING).unwrap();
-    let locked = locking_table.values().any(|range| range.read_locked || range.write_locked);
+    // Key per I/O has no locking ranges, so the Locking table may be missing.
+    let locking_table: Option<&LockingTable> = locking_sp.get_object_table_specific(table_id::LOCKING);
+    let locked = locking_table.is_some_and(|table| table.values().any(|range| range.read_locked || range.write_locked));
 
-    let mbr_control_table: &MBRControlTable = locking_sp.get_object_table_specific(table_id::MBR_CONTROL).unwrap();
-    let mbr_control_row = mbr_control_table.values().next().unwrap();
-    let mbr_enabled = mbr_control_row.enable;
-    let mbr_done = mbr_control_row.done;
+    // Enterprise has no MBR shadowing, so the MBRControl table may be missing.
+    let mbr_control_table: Option<&MBRControlTable> = locking_sp.get_object_table_specific(table_id::MBR_CONTROL);
+    let mbr_control_row = mbr_control_table.map(|table| table.values().next().unwrap());
+    let mbr_enabled = mbr_control_row.is_some_and(|row| row.enable);
+    let mbr_done = mbr_control_row.is_some_and(|row| row.done);
 
     let desc = LockingDescriptor {
         hw_reset_supported: true,
@@ -71,12 +86,28 @@ fn get_locking_feature_desc(ssc: &SecuritySubsystemClass) -> FeatureDescriptor {
         media_encryption: false,
         mbr_enabled,
         mbr_done,
-        mbr_shadowing_not_supported: false,
+        mbr_shadowing_not_supported: mbr_control_row.is_none(),
     };
     FeatureDescriptor::Locking(desc)
 }
 
-fn get_ssc_feature_desc() -> FeatureDescriptor {
+fn get_ssc_feature_desc(ssc: &SecuritySubsystemClass) -> FeatureDescriptor {
+    match ssc.feature_code {
+        FeatureCode::Enterprise => get_enterprise_feature_desc(),
+        FeatureCode::Opalite => get_opalite_feature_desc(),
+        FeatureCode::PyriteV2 => get_pyrite_v2_feature_desc(),
+        FeatureCode::Ruby => get_ruby_feature_desc(),
+        FeatureCode::KeyPerIO => get_key_per_io_feature_desc(ssc),
+        _ => get_opal_v2_feature_desc(),
+    }
+}
+
+fn get_enterprise_feature_desc() -> FeatureDescriptor {
+    let desc = EnterpriseDescriptor { base_com_id: BASE_COM_ID, num_com_ids: NUM_COM_IDS, no_range_crossing: false };
+    FeatureDescriptor::Enterprise(desc)
+}
+
+fn get_opal_v2_feature_desc() -> FeatureDescriptor {
     let desc = OpalV2Descriptor {
         base_com_id: BASE_COM_ID,
         num_com_ids: NUM_COM_IDS,
@@ -89,13 +120,96 @@ fn get_ssc_feature_desc() -> FeatureDescriptor {
     FeatureDescriptor::OpalV2(desc)
 }
 
+fn get_opalite_feature_desc() -> FeatureDescriptor {
+    let desc = OpaliteDescriptor {
+        base_com_id: BASE_COM_ID,
+        num_com_ids: NUM_COM_IDS,
+        initial_owner_pw: OwnerPasswordState::SameAsMSID,
+        reverted_owner_pw: OwnerPasswordState::SameAsMSID,
+    };
+    FeatureDescriptor::Opalite(desc)
+}
+
+fn get_pyrite_v2_feature_desc() -> FeatureDescriptor {
+    let desc = PyriteV2Descriptor {
+        base_com_id: BASE_COM_ID,
+        num_com_ids: NUM_COM_IDS,
+        initial_owner_pw: OwnerPasswordState::SameAsMSID,
+        reverted_owner_pw: OwnerPasswordState::SameAsMSID,
+    };
+    FeatureDescriptor::PyriteV2(desc)
+}
+
+fn get_ruby_feature_desc() -> FeatureDescriptor {
+    let desc = RubyDescriptor {
+        base_com_id: BASE_COM_ID,
+        num_com_ids: NUM_COM_IDS,
+        no_range_crossing: false,
+        num_locking_admins_supported: ruby::PROFILE.locking_admins as u16,
+        num_locking_users_supported: ruby::PROFILE.locking_users as u16,
+        initial_owner_pw: OwnerPasswordState::SameAsMSID,
+        reverted_owner_pw: OwnerPasswordState::SameAsMSID,
+    };
+    FeatureDescriptor::Ruby(desc)
+}
+
+fn get_key_per_io_feature_desc(ssc: &SecuritySubsystemClass) -> FeatureDescriptor {
+    let key_per_io_sp = ssc.get_sp(spec::kpio::admin::sp::KEY_PER_IO).unwrap();
+    let policies: Option<&KPIOPoliciesTable> = key_per_io_sp.get_object_table_specific(table_id::KPIO_POLICIES);
+    let keks: Option<&KeyEncryptionKeyTable> = key_per_io_sp.get_object_table_specific(table_id::KEY_ENCRYPTION_KEY);
+    let key_tags: Option<&KeyTagAllocationTable> =
+        key_per_io_sp.get_object_table_specific(table_id::KEY_TAG_ALLOCATION);
+    let kpio_enabled = policies.is_some_and(|table| table.values().any(|policy| policy.kpio_enabled));
+    let num_keks = keks.map(|table| table.len()).unwrap_or(0);
+    let num_key_tags = key_tags.map(|table| table.len()).unwrap_or(0);
+
+    let desc = KeyPerIODescriptor {
+        base_com_id_p1: BASE_COM_ID,
+        num_com_ids_p1: NUM_COM_IDS,
+        base_com_id_p3: 0,
+        num_com_ids_p3: 0,
+        initial_owner_pw: OwnerPasswordState::SameAsMSID,
+        reverted_owner_pw: OwnerPasswordState::SameAsMSID,
+        num_kpio_admins_supported: 4,
+        replay_protection_enabled: false,
+        replay_protection_supported: false,
+        incorrect_key_detection_supported: false,
+        tweak_key_required: false,
+        kpio_scope: false,
+        kpio_enabled,
+        max_key_uid_len: 32,
+        kmip_key_injection_supported: false,
+        nist_rsa_oaep_supported: false,
+        nist_aes_gcm_supported: false,
+        nist_aes_kw_supported: true,
+        rsa2k_wrapping_supported: false,
+        aes256_wrapping_supported: true,
+        rsa3k_wrapping_supported: false,
+        rsa4k_wrapping_supported: false,
+        pki_kek_transport_supported: false,
+        plaintext_kek_prov_supported: true,
+        num_keks_supported: num_keks as u32,
+        total_key_tags_supported: num_key_tags as u32,
+        max_key_tags_per_namespace: num_key_tags as u16,
+        get_nonce_cmd_nonce_len: 0,
+    };
+    FeatureDescriptor::KeyPerIO(desc)
+}
+
 fn get_geometry_feature_desc() -> FeatureDescriptor {
-    let desc =
-        GeometryDescriptor { align: true, logical_block_size: 512, alignment_granularity: 16, lowest_aligned_lba: 4 };
+    let desc = GeometryDescriptor {
+        align: true,
+        logical_block_size: LOGICAL_BLOCK_SIZE,
+        alignment_granularity: 16,
+        lowest_aligned_lba: 0,
+    };
     FeatureDescriptor::Geometry(desc)
 }
 
-fn get_block_sid_authentication_desc(ssc: &SecuritySubsystemClass) -> Option<FeatureDescriptor> {
+fn get_block_sid_authentication_desc(
+    ssc: &SecuritySubsystemClass,
+    sid_block: Option<SIDBlock>,
+) -> Option<FeatureDescriptor> {
     let admin_sp = ssc.get_admin_sp()?;
     let c_pin_table: &CPINTable = admin_sp.get_object_table_specific(table_id::C_PIN)?;
     let c_pin_sid = c_pin_table.get(&spec::opal::admin::c_pin::SID)?;
@@ -103,8 +217,26 @@ fn get_block_sid_authentication_desc(ssc: &SecuritySubsystemClass) -> Option<Fea
     Some(FeatureDescriptor::BlockSIDAuth(BlockSIDAuthDescriptor {
         locking_sp_frozen: false,
         locking_sp_freeze_supported: false,
-        sid_authentication_blocked: false,
+        sid_authentication_blocked: sid_block.is_some(),
         sid_msid_pin_differ: c_pin_sid.pin != c_pin_msid.pin,
-        hw_reset_unblocks: false,
+        hw_reset_unblocks: sid_block.is_some_and(|sid_block| sid_block.hw_reset_unblocks),
+    }))
+}
+
+fn get_additional_data_store_tables_desc(ssc: &SecuritySubsystemClass) -> Option<FeatureDescriptor> {
+    if matches!(ssc.feature_code, FeatureCode::Enterprise | FeatureCode::KeyPerIO) {
+        return None;
+    }
+    let locking_sp = ssc.get_sp(spec::opal::admin::sp::LOCKING)?;
+    let data_stores: Vec<_> = locking_sp
+        .byte_tables
+        .iter()
+        .filter(|(table, _)| spec::data_store::locking::table::DATA_STORE.contains(table.to_descriptor()))
+        .map(|(_, data_store)| data_store.len() as u32)
+        .collect();
+    Some(FeatureDescriptor::AdditionalDataStoreTables(AdditionalDataStoreTablesDescriptor {
+        max_num_tables: data_stores.len() as u16,
+        max_total_size_of_tables: data_stores.iter().sum(),
+        table_size_alignment: 1,
     }))
 }
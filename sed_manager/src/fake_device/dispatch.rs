@@ -7,19 +7,22 @@ use std::ops::Deref as _;
 
 use crate::call_with_tuple::CallSelfWithTuple;
 use crate::device::Error;
+use crate::fake_device::protocol_stack::PacketSequence;
 use crate::fake_device::tper::{SPSession, TPer};
-use crate::messaging::packet::{SubPacket, SubPacketKind};
-use crate::messaging::token::Token;
+use crate::messaging::packet::{AckType, SubPacket, SubPacketKind};
+use crate::messaging::token::{split_continued, Token};
 use crate::messaging::uid::UID;
 use crate::messaging::{packet::Packet, value::Value};
 use crate::rpc::args::{IntoMethodArgs, TryFromMethodArgs, UnwrapMethodArgs as _};
-use crate::rpc::{MethodCall, MethodResult, MethodStatus, PackagedMethod, SessionIdentifier, CONTROL_SESSION_ID};
+use crate::rpc::CONTROL_SESSION_ID;
+use crate::rpc::{MethodCall, MethodResult, MethodStatus, PackagedMethod, Properties, SessionIdentifier};
 use crate::serialization::vec_without_len::VecWithoutLen;
 use crate::serialization::{Deserialize as _, InputStream, OutputStream, Serialize as _};
 use crate::spec::column_types::MethodRef;
 use crate::spec::{invoking_id, method_id, sm_method_id};
 
 pub fn dispatch(firmware: &mut TPer, packet: Packet) -> Vec<Packet> {
+    firmware.expire_sessions();
     let session_id = SessionIdentifier::from(&packet);
     if session_id == CONTROL_SESSION_ID {
         if let Ok(packaged_methods) = split_packet(&packet) {
@@ -29,53 +32,131 @@ pub fn dispatch(firmware: &mut TPer, packet: Packet) -> Vec<Packet> {
                     PackagedMethod::Call(call) => dispatch_sm_method(firmware, call),
                     PackagedMethod::EndOfSession => continue, // Simply drop item.
                     PackagedMethod::Result(_) => continue,    // Simply drop item.
+                    PackagedMethod::StartTransaction(_) => continue, // No transactions on the control session.
+                    PackagedMethod::EndTransaction(_) => continue,
                 };
                 if let Some(call) = result {
                     sm_results.push(PackagedMethod::Call(call));
                 }
             }
-            bundle_methods(CONTROL_SESSION_ID, &sm_results)
+            bundle_methods(CONTROL_SESSION_ID, &sm_results, &firmware.protocol_stack.properties)
         } else {
             // Unlike SP sessions, the control session simply drops invalid packets, and there is nothing else to do.
             vec![]
         }
-    } else if let Some(mut session) = firmware.sp_session(session_id) {
-        if let Ok(packaged_methods) = split_packet(&packet) {
-            let mut abort = false;
-            let mut sp_results = Vec::new();
-            for packaged_method in packaged_methods {
-                let result = match packaged_method {
-                    PackagedMethod::Call(call) => PackagedMethod::Result(dispatch_sp_method(&mut session, call)),
-                    PackagedMethod::EndOfSession => {
-                        session.end();
-                        PackagedMethod::EndOfSession
-                    }
-                    PackagedMethod::Result(_) => {
-                        abort = true;
-                        break;
-                    }
-                };
-                sp_results.push(result);
-            }
+    } else if firmware.sp_session(session_id).is_some() {
+        match firmware.protocol_stack.take_packet_sequence(session_id) {
+            Some(packet_sequence) => dispatch_sequenced(firmware, packet_sequence, packet),
+            None => dispatch_sp_packet(firmware, &packet),
+        }
+    } else {
+        // The session may have just expired, which the host has to be notified of.
+        let sm_results: Vec<_> = firmware.take_pruned_session_ids().into_iter().map(prepare_close_session).collect();
+        bundle_methods(CONTROL_SESSION_ID, &sm_results, &firmware.protocol_stack.properties)
+    }
+}
 
-            let mut pruned_session_ids = firmware.take_pruned_session_ids();
-            if abort {
-                firmware.protocol_stack.remove_session(session_id);
-                pruned_session_ids.push(session_id);
-            }
+fn dispatch_sequenced(firmware: &mut TPer, mut packet_sequence: PacketSequence, packet: Packet) -> Vec<Packet> {
+    let session_id = SessionIdentifier::from(&packet);
+    let responses = if packet.sequence_number == packet_sequence.received + 1 {
+        let responses = dispatch_sp_packet(firmware, &packet);
+        packet_sequence.received = packet.sequence_number;
+        sequence_responses(&mut packet_sequence, session_id, responses)
+    } else if packet.sequence_number == packet_sequence.received {
+        // The host did not get the response, so it sent the same packet again.
+        packet_sequence.last_response.clone()
+    } else if packet_sequence.ack_nak && packet.sequence_number > packet_sequence.received {
+        let nak = Packet {
+            host_session_number: session_id.hsn,
+            tper_session_number: session_id.tsn,
+            ack_type: AckType::NAK,
+            acknowledgement: packet_sequence.received + 1,
+            ..Default::default()
+        };
+        vec![nak]
+    } else {
+        vec![]
+    };
+    firmware.protocol_stack.restore_packet_sequence(session_id, packet_sequence);
+    responses
+}
 
-            let sm_results: Vec<_> =
-                pruned_session_ids.iter().map(|session_id| prepare_close_session(*session_id)).collect();
-            let sp_packets = bundle_methods(session_id, &sp_results);
-            let sm_packets = bundle_methods(CONTROL_SESSION_ID, &sm_results);
-            sp_packets.into_iter().chain(sm_packets).collect()
-        } else {
+fn sequence_responses(
+    packet_sequence: &mut PacketSequence,
+    session_id: SessionIdentifier,
+    responses: Vec<Packet>,
+) -> Vec<Packet> {
+    let (mut own, others): (Vec<_>, Vec<_>) =
+        responses.into_iter().partition(|packet| SessionIdentifier::from(packet) == session_id);
+    if own.is_empty() && packet_sequence.ack_nak {
+        // The packet must be acknowledged even if it had no methods.
+        own.push(Packet {
+            host_session_number: session_id.hsn,
+            tper_session_number: session_id.tsn,
+            ..Default::default()
+        });
+    }
+    for packet in &mut own {
+        if packet.has_payload() {
+            packet_sequence.sent += 1;
+            packet.sequence_number = packet_sequence.sent;
+        }
+        if packet_sequence.ack_nak {
+            packet.ack_type = AckType::ACK;
+            packet.acknowledgement = packet_sequence.received;
+        }
+    }
+    packet_sequence.last_response = own.clone();
+    own.into_iter().chain(others).collect()
+}
+
+fn dispatch_sp_packet(firmware: &mut TPer, packet: &Packet) -> Vec<Packet> {
+    let session_id = SessionIdentifier::from(packet);
+    let Some(mut session) = firmware.sp_session(session_id) else {
+        return vec![];
+    };
+    session.keep_alive();
+    if let Ok(packaged_methods) = split_packet(packet) {
+        let mut abort = false;
+        let mut sp_results = Vec::new();
+        for packaged_method in packaged_methods {
+            let result = match packaged_method {
+                PackagedMethod::Call(call) => PackagedMethod::Result(dispatch_sp_method(&mut session, call)),
+                PackagedMethod::EndOfSession => {
+                    session.end();
+                    PackagedMethod::EndOfSession
+                }
+                PackagedMethod::StartTransaction(_) => match session.start_transaction() {
+                    Ok(()) => PackagedMethod::StartTransaction(0),
+                    Err(status) => PackagedMethod::StartTransaction(status as u8),
+                },
+                PackagedMethod::EndTransaction(status) => match session.end_transaction(status == 0) {
+                    Ok(()) => PackagedMethod::EndTransaction(status),
+                    Err(status) => PackagedMethod::EndTransaction(status as u8),
+                },
+                PackagedMethod::Result(_) => {
+                    abort = true;
+                    break;
+                }
+            };
+            sp_results.push(result);
+        }
+
+        let mut pruned_session_ids = firmware.take_pruned_session_ids();
+        if abort {
             firmware.protocol_stack.remove_session(session_id);
-            let close_session = prepare_close_session(session_id);
-            bundle_methods(CONTROL_SESSION_ID, &[close_session])
+            pruned_session_ids.push(session_id);
         }
+
+        let sm_results: Vec<_> =
+            pruned_session_ids.iter().map(|session_id| prepare_close_session(*session_id)).collect();
+        let sp_packets = bundle_methods(session_id, &sp_results, &firmware.protocol_stack.properties);
+        let sm_packets = bundle_methods(CONTROL_SESSION_ID, &sm_results, &firmware.protocol_stack.properties);
+        sp_packets.into_iter().chain(sm_packets).collect()
     } else {
-        vec![]
+        firmware.protocol_stack.remove_session(session_id);
+        let close_session = prepare_close_session(session_id);
+        bundle_methods(CONTROL_SESSION_ID, &[close_session], &firmware.protocol_stack.properties)
     }
 }
 
@@ -101,6 +182,10 @@ fn dispatch_sp_method(session: &mut SPSession, call: MethodCall) -> MethodResult
         return MethodResult::new_fail(MethodStatus::InvalidParameter);
     };
 
+    if session.is_read_only() && [SET, GEN_KEY, ERASE, REVERT, REVERT_SP, ACTIVATE].contains(&method_id) {
+        return MethodResult::new_fail(MethodStatus::NotAuthorized);
+    }
+
     let args: Vec<_> = core::iter::once(Value::from(call.invoking_id)).chain(call.args.into_iter()).collect();
     match method_id {
         AUTHENTICATE => call_sp_method(session, SPSession::authenticate, args),
@@ -108,6 +193,7 @@ fn dispatch_sp_method(session: &mut SPSession, call: MethodCall) -> MethodResult
         SET => call_sp_method(session, SPSession::set, args),
         NEXT => call_sp_method(session, SPSession::next, args),
         GEN_KEY => call_sp_method(session, SPSession::gen_key, args),
+        ERASE => call_sp_method(session, SPSession::erase, args),
         GET_ACL => call_sp_method(session, SPSession::get_acl, args),
         REVERT => call_sp_method(session, SPSession::revert, args),
         REVERT_SP => call_sp_method(session, SPSession::revert_sp, args),
@@ -189,24 +275,26 @@ fn split_packet(packet: &Packet) -> Result<Vec<PackagedMethod>, Error> {
     Ok(calls)
 }
 
-fn bundle_methods(id: SessionIdentifier, methods: &[PackagedMethod]) -> Vec<Packet> {
-    methods
-        .iter()
-        .map(|result| -> Vec<Token> {
-            let mut stream = OutputStream::<Token>::new();
-            result.serialize(&mut stream).expect("responses should always be valid tokens");
-            stream.take()
-        })
-        .map(|tokens| -> Vec<u8> {
-            let mut stream = OutputStream::<u8>::new();
-            VecWithoutLen::from(tokens).serialize(&mut stream).expect("responses should always be valid tokens");
-            stream.take()
-        })
-        .map(|bytes| Packet {
-            host_session_number: id.hsn,
-            tper_session_number: id.tsn,
-            payload: vec![SubPacket { kind: SubPacketKind::Data, payload: bytes.into() }].into(),
-            ..Default::default()
-        })
-        .collect()
+fn bundle_methods(id: SessionIdentifier, methods: &[PackagedMethod], properties: &Properties) -> Vec<Packet> {
+    // The responses to the methods of a packet are sent back in a single packet.
+    if methods.is_empty() {
+        return vec![];
+    }
+    let mut tokens = OutputStream::<Token>::new();
+    for method in methods {
+        method.serialize(&mut tokens).expect("responses should always be valid tokens");
+    }
+    let mut tokens = tokens.take();
+    if properties.continued_tokens {
+        let max_data_len = properties.max_ind_token_size - 4;
+        tokens = tokens.into_iter().flat_map(|token| split_continued(token, max_data_len)).collect();
+    }
+    let mut bytes = OutputStream::<u8>::new();
+    VecWithoutLen::from(tokens).serialize(&mut bytes).expect("responses should always be valid tokens");
+    vec![Packet {
+        host_session_number: id.hsn,
+        tper_session_number: id.tsn,
+        payload: vec![SubPacket { kind: SubPacketKind::Data, payload: bytes.take().into() }].into(),
+        ..Default::default()
+    }]
 }
@@ -4,8 +4,12 @@
 //L-----------------------------------------------------------------------------
 
 use core::sync::atomic::{AtomicU32, Ordering};
+use core::time::Duration;
 use std::collections::HashMap;
+use tokio::time::Instant;
 
+use crate::fake_device::data::security_provider::SecurityProvider;
+use crate::messaging::packet::Packet;
 use crate::rpc::{Properties, SessionIdentifier};
 use crate::spec::column_types::{AuthorityRef, SPRef};
 
@@ -21,7 +25,25 @@ pub struct ProtocolStack {
 
 pub struct SPSessionData {
     pub sp: SPRef,
+    pub write: bool,
     pub authenticated: Vec<AuthorityRef>,
+    /// The session is aborted if the host doesn't use it for this long.
+    pub timeout: Option<Duration>,
+    pub last_used: Instant,
+    /// The state of the session's security provider when the open transaction started.
+    pub transaction: Option<SecurityProvider>,
+    /// Present if the session was started with sequence numbers.
+    pub packet_sequence: Option<PacketSequence>,
+}
+
+pub struct PacketSequence {
+    pub ack_nak: bool,
+    /// The sequence number of the last packet received from the host.
+    pub received: u32,
+    /// The sequence number of the last packet sent to the host.
+    pub sent: u32,
+    /// Sent again if the host retransmits its last packet because the response was lost.
+    pub last_response: Vec<Packet>,
 }
 
 impl ProtocolStack {
@@ -34,9 +56,17 @@ impl ProtocolStack {
         }
     }
 
-    pub fn add_session(&mut self, sp: SPRef, hsn: u32) -> SessionIdentifier {
+    pub fn add_session(&mut self, sp: SPRef, hsn: u32, write: bool, timeout: Option<Duration>) -> SessionIdentifier {
         let session_id = self.next_session_id(hsn);
-        let session = SPSessionData { sp, authenticated: vec![] };
+        let last_used = Instant::now();
+        let packet_sequence = self.properties.seq_numbers.then(|| PacketSequence {
+            ack_nak: self.properties.ack_nak,
+            received: 0,
+            sent: 0,
+            last_response: vec![],
+        });
+        let session =
+            SPSessionData { sp, write, authenticated: vec![], timeout, last_used, transaction: None, packet_sequence };
         self.sp_sessions.insert(session_id, session);
         session_id
     }
@@ -57,6 +87,26 @@ impl ProtocolStack {
         self.sp_sessions.get_mut(&session_id)
     }
 
+    pub fn take_packet_sequence(&mut self, session_id: SessionIdentifier) -> Option<PacketSequence> {
+        self.sp_sessions.get_mut(&session_id).and_then(|session| session.packet_sequence.take())
+    }
+
+    pub fn restore_packet_sequence(&mut self, session_id: SessionIdentifier, packet_sequence: PacketSequence) {
+        // The session is gone if the packet ended it.
+        if let Some(session) = self.sp_sessions.get_mut(&session_id) {
+            session.packet_sequence = Some(packet_sequence);
+        }
+    }
+
+    pub fn list_expired_sessions(&self) -> Vec<SessionIdentifier> {
+        let now = Instant::now();
+        self.sp_sessions
+            .iter()
+            .filter(|(_, session)| session.timeout.is_some_and(|timeout| now - session.last_used > timeout))
+            .map(|(session_id, _)| *session_id)
+            .collect()
+    }
+
     pub fn prune_sessions(&mut self, sp: SPRef) -> Vec<SessionIdentifier> {
         let pruned_session_ids: Vec<_> = self
             .sp_sessions
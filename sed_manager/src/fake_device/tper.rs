@@ -3,7 +3,10 @@
 //L Please refer to the full license distributed with this software.
 //L-----------------------------------------------------------------------------
 
-use crate::fake_device::data::object_table::AuthorityTable;
+use core::time::Duration;
+use tokio::time::Instant;
+
+use crate::fake_device::data::object_table::{AuthorityTable, TPerInfoTable};
 use crate::fake_device::data::security_provider::SecurityProvider;
 use crate::fake_device::data::SecuritySubsystemClass;
 use crate::fake_device::protocol_stack::ProtocolStack;
@@ -12,8 +15,8 @@ use crate::messaging::value::{Bytes, Value};
 use crate::rpc::{MethodStatus, Properties, SessionIdentifier};
 use crate::spec::basic_types::{List, NamedValue};
 use crate::spec::column_types::{
-    ACERef, AuthorityRef, BoolOrBytes, BytesOrRowValues, CellBlock, CellBlockWrite, CredentialRef, MaxBytes32,
-    MethodRef, SPRef,
+    ACERef, AuthorityRef, BoolOrBytes, BytesOrRowValues, CellBlock, CellBlockWrite, CredentialRef, LockingRangeRef,
+    MaxBytes32, MethodRef, ResetType, SPRef,
 };
 use crate::spec::core::authority;
 use crate::spec::invoking_id::THIS_SP;
@@ -23,9 +26,18 @@ use crate::spec::table_id;
 pub struct TPer {
     pub ssc: SecuritySubsystemClass,
     pub protocol_stack: ProtocolStack,
+    pub sid_block: Option<SIDBlock>,
+    /// The number of upcoming byte table reads that return one byte less than requested.
+    pub short_byte_reads: usize,
     pruned_session_ids: Vec<SessionIdentifier>,
 }
 
+/// The state set by the Block SID command. SID authentication fails while it is present.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SIDBlock {
+    pub hw_reset_unblocks: bool,
+}
+
 pub struct SPSession<'fw> {
     session_id: SessionIdentifier,
     firmware: &'fw mut TPer,
@@ -33,7 +45,13 @@ pub struct SPSession<'fw> {
 
 impl TPer {
     pub fn new(tper: SecuritySubsystemClass, capabilities: Properties) -> Self {
-        Self { ssc: tper, protocol_stack: ProtocolStack::new(capabilities), pruned_session_ids: Vec::new() }
+        Self {
+            ssc: tper,
+            protocol_stack: ProtocolStack::new(capabilities),
+            sid_block: None,
+            short_byte_reads: 0,
+            pruned_session_ids: Vec::new(),
+        }
     }
 
     pub fn sp_session<'me>(&'me mut self, session_id: SessionIdentifier) -> Option<SPSession<'me>> {
@@ -41,10 +59,39 @@ impl TPer {
         Some(SPSession { session_id, firmware: self })
     }
 
+    /// Aborts the sessions that haven't been used within their timeout.
+    pub fn expire_sessions(&mut self) {
+        for session_id in self.protocol_stack.list_expired_sessions() {
+            if let Some(mut session) = self.sp_session(session_id) {
+                session.end();
+            }
+            self.pruned_session_ids.push(session_id);
+        }
+    }
+
     pub fn take_pruned_session_ids(&mut self) -> Vec<SessionIdentifier> {
         core::mem::replace(&mut self.pruned_session_ids, vec![])
     }
 
+    pub fn is_programmatic_reset_enabled(&self) -> bool {
+        let Some(admin_sp) = self.ssc.get_admin_sp() else {
+            return false;
+        };
+        let Some(tper_info_table) = admin_sp.get_object_table_specific::<TPerInfoTable>(table_id::T_PER_INFO) else {
+            return false;
+        };
+        tper_info_table.values().any(|tper_info| tper_info.programmatic_reset_enable)
+    }
+
+    /// Aborts all sessions and applies the reset to the security providers.
+    pub fn reset(&mut self, reset_type: ResetType) {
+        self.protocol_stack.reset();
+        self.pruned_session_ids.clear();
+        for sp in self.ssc.security_providers.values_mut() {
+            sp.reset(reset_type.clone());
+        }
+    }
+
     pub fn properties(
         &mut self,
         host_properties: Option<List<NamedValue<MaxBytes32, u32>>>,
@@ -60,21 +107,23 @@ impl TPer {
         &mut self,
         hsn: u32,
         sp_uid: SPRef,
-        _write: bool,
+        write: bool,
         host_challenge: Option<Bytes>,
         _host_exch_auth: Option<AuthorityRef>,
         _host_exch_cert: Option<Bytes>,
         host_sgn_auth: Option<AuthorityRef>,
         _host_sgn_cert: Option<Bytes>,
-        _session_timeout: Option<u32>,
-        _trans_timeout: Option<u32>,
+        session_timeout: Option<u32>,
+        trans_timeout: Option<u32>,
         _initial_credit: Option<u32>,
         _signed_hash: Option<Bytes>,
     ) -> Result<
         (u32, u32, Option<Bytes>, Option<Bytes>, Option<Bytes>, Option<u32>, Option<u32>, Option<Bytes>),
         MethodStatus,
     > {
-        let session_id = self.protocol_stack.add_session(sp_uid, hsn);
+        // Zero means no timeout, the same as the default.
+        let timeout = session_timeout.filter(|ms| *ms != 0).map(|ms| Duration::from_millis(ms as u64));
+        let session_id = self.protocol_stack.add_session(sp_uid, hsn, write, timeout);
         if let Some(authority) = host_sgn_auth {
             if (BoolOrBytes::Bool(true),)
                 != self.sp_session(session_id).unwrap().authenticate(THIS_SP, authority, host_challenge)?
@@ -84,15 +133,51 @@ impl TPer {
             }
         }
         self.sp_session(session_id).unwrap().commit_authentication(authority::ANYBODY).unwrap();
-        Ok((session_id.hsn, session_id.tsn, None, None, None, None, None, None))
+        Ok((session_id.hsn, session_id.tsn, None, None, None, trans_timeout, None, None))
     }
 }
 
 impl<'fw> SPSession<'fw> {
     pub fn end(&mut self) {
+        // Ending the session aborts the open transaction.
+        let _ = self.end_transaction(false);
         self.firmware.protocol_stack.remove_session(self.session_id);
     }
 
+    pub fn is_read_only(&self) -> bool {
+        self.firmware.protocol_stack.get_session(self.session_id).is_none_or(|session| !session.write)
+    }
+
+    pub fn keep_alive(&mut self) {
+        if let Some(session) = self.firmware.protocol_stack.get_session_mut(self.session_id) {
+            session.last_used = Instant::now();
+        }
+    }
+
+    pub fn start_transaction(&mut self) -> Result<(), MethodStatus> {
+        // Only the session's SP is saved, so methods that change other SPs, like Activate, are not rolled back.
+        // Byte tables are copy-on-write, so the snapshot doesn't copy the MBR or the DataStore.
+        let session = self.firmware.protocol_stack.get_session(self.session_id).ok_or(MethodStatus::Fail)?;
+        if session.transaction.is_some() {
+            return Err(MethodStatus::TransactionFailure); // Only one transaction at a time.
+        }
+        let sp_ref = session.sp;
+        let snapshot = self.firmware.ssc.get_sp(sp_ref).ok_or(MethodStatus::Fail)?.clone();
+        let session = self.firmware.protocol_stack.get_session_mut(self.session_id).ok_or(MethodStatus::Fail)?;
+        session.transaction = Some(snapshot);
+        Ok(())
+    }
+
+    pub fn end_transaction(&mut self, commit: bool) -> Result<(), MethodStatus> {
+        let session = self.firmware.protocol_stack.get_session_mut(self.session_id).ok_or(MethodStatus::Fail)?;
+        let sp_ref = session.sp;
+        let snapshot = session.transaction.take().ok_or(MethodStatus::TransactionFailure)?;
+        if !commit {
+            self.firmware.ssc.security_providers.insert(sp_ref, snapshot);
+        }
+        Ok(())
+    }
+
     pub fn activate(&mut self, invoking_id: UID) -> Result<(), MethodStatus> {
         let sp_ref = SPRef::try_from(invoking_id).map_err(|_| MethodStatus::InvalidParameter)?;
         if self.is_authorized(invoking_id, method_id::ACTIVATE, &[0]) {
@@ -141,6 +226,9 @@ impl<'fw> SPSession<'fw> {
         if invoking_id != THIS_SP {
             return Err(MethodStatus::InvalidParameter);
         }
+        if authority == authority::SID && self.firmware.sid_block.is_some() {
+            return Ok((BoolOrBytes::Bool(false),));
+        }
         let is_success = self.this_sp()?.authenticate(authority, proof);
         if is_success == Ok(BoolOrBytes::Bool(true)) {
             self.commit_authentication(authority)?;
@@ -152,7 +240,14 @@ impl<'fw> SPSession<'fw> {
         let (target_uid, target_columns) = self.get_cell_block_target(invoking_id, &cell_block)?;
         if self.is_authorized(target_uid, method_id::GET, &target_columns) {
             let security_provider = self.this_sp()?;
-            security_provider.get(invoking_id, cell_block).map(|out| (out,))
+            let mut result = security_provider.get(invoking_id, cell_block)?;
+            if let BytesOrRowValues::Bytes(bytes) = &mut result {
+                if !bytes.is_empty() && self.firmware.short_byte_reads != 0 {
+                    self.firmware.short_byte_reads -= 1;
+                    bytes.pop();
+                }
+            }
+            Ok((result,))
         } else {
             Err(MethodStatus::NotAuthorized)
         }
@@ -207,6 +302,16 @@ impl<'fw> SPSession<'fw> {
         }
     }
 
+    pub fn erase(&mut self, invoking_id: UID) -> Result<(), MethodStatus> {
+        let range_ref = LockingRangeRef::try_from(invoking_id).map_err(|_| MethodStatus::InvalidParameter)?;
+        if self.is_authorized(invoking_id, method_id::ERASE, &[0]) {
+            let sp = self.this_sp_mut()?;
+            sp.erase(range_ref)
+        } else {
+            Err(MethodStatus::NotAuthorized)
+        }
+    }
+
     pub fn get_acl(
         &mut self,
         invoking_id: UID,
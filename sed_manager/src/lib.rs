@@ -7,6 +7,7 @@ pub mod applications;
 pub mod device;
 pub mod fake_device;
 pub mod messaging;
+pub mod partitions;
 pub mod rpc;
 pub mod serialization;
 pub mod spec;
@@ -0,0 +1,417 @@
+//L-----------------------------------------------------------------------------
+//L Copyright (C) Péter Kardos
+//L Please refer to the full license distributed with this software.
+//L-----------------------------------------------------------------------------
+
+use core::time::Duration;
+
+use super::discovery::{
+    AdditionalDataStoreTablesDescriptor, BlockSIDAuthDescriptor, DataRemovalDescriptor, DataRemovalMechanism,
+    DataRemovalTime, Discovery, EnterpriseDescriptor, Feature, FeatureCode, FeatureDescriptor, GeometryDescriptor,
+    KeyPerIODescriptor, LockingDescriptor, OpalV1Descriptor, OpalV2Descriptor, OpaliteDescriptor, OwnerPasswordState,
+    PyriteV1Descriptor, PyriteV2Descriptor, RubyDescriptor, TPerDescriptor, UnrecognizedDescriptor,
+};
+use serde_json::{json, Value};
+
+fn feature_type(feature_code: FeatureCode) -> &'static str {
+    match feature_code {
+        FeatureCode::TPer => "tper",
+        FeatureCode::Locking => "locking",
+        FeatureCode::Geometry => "geometry",
+        FeatureCode::DataRemoval => "data_removal",
+        FeatureCode::BlockSIDAuth => "block_sid_auth",
+        FeatureCode::AdditionalDataStoreTables => "additional_data_store_tables",
+        FeatureCode::Enterprise => "enterprise",
+        FeatureCode::OpalV1 => "opal_v1",
+        FeatureCode::OpalV2 => "opal_v2",
+        FeatureCode::Opalite => "opalite",
+        FeatureCode::PyriteV1 => "pyrite_v1",
+        FeatureCode::PyriteV2 => "pyrite_v2",
+        FeatureCode::Ruby => "ruby",
+        FeatureCode::KeyPerIO => "key_per_io",
+        FeatureCode::Unrecognized => "unrecognized",
+    }
+}
+
+/// Puts the members common to all descriptors in front of the descriptor's own fields.
+fn feature_object(desc: &dyn Feature, fields: Value) -> Value {
+    let mut object = json!({
+        "type": feature_type(desc.feature_code()),
+        "feature_code": desc.feature_code() as u16,
+        "version": desc.version(),
+    });
+    if let (Value::Object(members), Value::Object(fields)) = (&mut object, fields) {
+        members.extend(fields);
+    }
+    object
+}
+
+fn seconds(duration: Option<Duration>) -> Value {
+    duration.map(|duration| duration.as_secs()).into()
+}
+
+impl From<OwnerPasswordState> for Value {
+    fn from(value: OwnerPasswordState) -> Self {
+        match value {
+            OwnerPasswordState::SameAsMSID => "same_as_msid".into(),
+            OwnerPasswordState::VendorSpecified => "vendor_specified".into(),
+        }
+    }
+}
+
+impl From<&TPerDescriptor> for Value {
+    fn from(value: &TPerDescriptor) -> Self {
+        feature_object(
+            value,
+            json!({
+                "sync_supported": value.sync_supported,
+                "async_supported": value.async_supported,
+                "ack_nak_supported": value.ack_nak_supported,
+                "buffer_mgmt_supported": value.buffer_mgmt_supported,
+                "streaming_supported": value.streaming_supported,
+                "com_id_mgmt_supported": value.com_id_mgmt_supported,
+            }),
+        )
+    }
+}
+
+impl From<&LockingDescriptor> for Value {
+    fn from(value: &LockingDescriptor) -> Self {
+        feature_object(
+            value,
+            json!({
+                "locking_supported": value.locking_supported,
+                "locking_enabled": value.locking_enabled,
+                "locked": value.locked,
+                "media_encryption": value.media_encryption,
+                "mbr_enabled": value.mbr_enabled,
+                "mbr_done": value.mbr_done,
+                "mbr_shadowing_not_supported": value.mbr_shadowing_not_supported,
+                "hw_reset_supported": value.hw_reset_supported,
+            }),
+        )
+    }
+}
+
+impl From<&GeometryDescriptor> for Value {
+    fn from(value: &GeometryDescriptor) -> Self {
+        feature_object(
+            value,
+            json!({
+                "align": value.align,
+                "logical_block_size": value.logical_block_size,
+                "alignment_granularity": value.alignment_granularity,
+                "lowest_aligned_lba": value.lowest_aligned_lba,
+            }),
+        )
+    }
+}
+
+impl From<&DataRemovalMechanism> for Value {
+    fn from(value: &DataRemovalMechanism) -> Self {
+        json!({
+            "overwrite": value.overwrite,
+            "block_erase": value.block_erase,
+            "crypto_erase": value.crypto_erase,
+            "vendor_erase": value.vendor_erase,
+        })
+    }
+}
+
+impl From<&DataRemovalTime> for Value {
+    fn from(value: &DataRemovalTime) -> Self {
+        json!({
+            "overwrite": seconds(value.overwrite()),
+            "block_erase": seconds(value.block_erase()),
+            "crypto_erase": seconds(value.crypto_erase()),
+            "vendor_erase": seconds(value.vendor_erase()),
+        })
+    }
+}
+
+impl From<&DataRemovalDescriptor> for Value {
+    fn from(value: &DataRemovalDescriptor) -> Self {
+        feature_object(
+            value,
+            json!({
+                "processing": value.processing,
+                "interrupted": value.interrupted,
+                "supported_mechanism": Value::from(&value.supported_mechanism),
+                "removal_time_seconds": Value::from(&value.removal_time),
+            }),
+        )
+    }
+}
+
+impl From<&BlockSIDAuthDescriptor> for Value {
+    fn from(value: &BlockSIDAuthDescriptor) -> Self {
+        feature_object(
+            value,
+            json!({
+                "sid_msid_pin_differ": value.sid_msid_pin_differ,
+                "sid_authentication_blocked": value.sid_authentication_blocked,
+                "locking_sp_freeze_supported": value.locking_sp_freeze_supported,
+                "locking_sp_frozen": value.locking_sp_frozen,
+                "hw_reset_unblocks": value.hw_reset_unblocks,
+            }),
+        )
+    }
+}
+
+impl From<&AdditionalDataStoreTablesDescriptor> for Value {
+    fn from(value: &AdditionalDataStoreTablesDescriptor) -> Self {
+        feature_object(
+            value,
+            json!({
+                "max_num_tables": value.max_num_tables,
+                "max_total_size_of_tables": value.max_total_size_of_tables,
+                "table_size_alignment": value.table_size_alignment,
+            }),
+        )
+    }
+}
+
+impl From<&EnterpriseDescriptor> for Value {
+    fn from(value: &EnterpriseDescriptor) -> Self {
+        feature_object(
+            value,
+            json!({
+                "base_com_id": value.base_com_id,
+                "num_com_ids": value.num_com_ids,
+                "no_range_crossing": value.no_range_crossing,
+            }),
+        )
+    }
+}
+
+impl From<&OpalV1Descriptor> for Value {
+    fn from(value: &OpalV1Descriptor) -> Self {
+        feature_object(
+            value,
+            json!({
+                "base_com_id": value.base_com_id,
+                "num_com_ids": value.num_com_ids,
+                "no_range_crossing": value.no_range_crossing,
+            }),
+        )
+    }
+}
+
+impl From<&OpalV2Descriptor> for Value {
+    fn from(value: &OpalV2Descriptor) -> Self {
+        feature_object(
+            value,
+            json!({
+                "base_com_id": value.base_com_id,
+                "num_com_ids": value.num_com_ids,
+                "no_range_crossing": value.no_range_crossing,
+                "num_locking_admins_supported": value.num_locking_admins_supported,
+                "num_locking_users_supported": value.num_locking_users_supported,
+                "initial_owner_pw": Value::from(value.initial_owner_pw),
+                "reverted_owner_pw": Value::from(value.reverted_owner_pw),
+            }),
+        )
+    }
+}
+
+macro_rules! impl_from_owner_pw_ssc {
+    ($desc:ty) => {
+        impl From<&$desc> for Value {
+            fn from(value: &$desc) -> Self {
+                feature_object(
+                    value,
+                    json!({
+                        "base_com_id": value.base_com_id,
+                        "num_com_ids": value.num_com_ids,
+                        "initial_owner_pw": Value::from(value.initial_owner_pw),
+                        "reverted_owner_pw": Value::from(value.reverted_owner_pw),
+                    }),
+                )
+            }
+        }
+    };
+}
+
+impl_from_owner_pw_ssc!(OpaliteDescriptor);
+impl_from_owner_pw_ssc!(PyriteV1Descriptor);
+impl_from_owner_pw_ssc!(PyriteV2Descriptor);
+
+impl From<&RubyDescriptor> for Value {
+    fn from(value: &RubyDescriptor) -> Self {
+        feature_object(
+            value,
+            json!({
+                "base_com_id": value.base_com_id,
+                "num_com_ids": value.num_com_ids,
+                "no_range_crossing": value.no_range_crossing,
+                "num_locking_admins_supported": value.num_locking_admins_supported,
+                "num_locking_users_supported": value.num_locking_users_supported,
+                "initial_owner_pw": Value::from(value.initial_owner_pw),
+                "reverted_owner_pw": Value::from(value.reverted_owner_pw),
+            }),
+        )
+    }
+}
+
+impl From<&KeyPerIODescriptor> for Value {
+    fn from(value: &KeyPerIODescriptor) -> Self {
+        feature_object(
+            value,
+            json!({
+                "base_com_id_p1": value.base_com_id_p1,
+                "num_com_ids_p1": value.num_com_ids_p1,
+                "base_com_id_p3": value.base_com_id_p3,
+                "num_com_ids_p3": value.num_com_ids_p3,
+                "initial_owner_pw": Value::from(value.initial_owner_pw),
+                "reverted_owner_pw": Value::from(value.reverted_owner_pw),
+                "num_kpio_admins_supported": value.num_kpio_admins_supported,
+                "kpio_enabled": value.kpio_enabled,
+                "kpio_scope": value.kpio_scope,
+                "tweak_key_required": value.tweak_key_required,
+                "incorrect_key_detection_supported": value.incorrect_key_detection_supported,
+                "replay_protection_supported": value.replay_protection_supported,
+                "replay_protection_enabled": value.replay_protection_enabled,
+                "max_key_uid_len": value.max_key_uid_len,
+                "kmip_key_injection_supported": value.kmip_key_injection_supported,
+                "nist_aes_kw_supported": value.nist_aes_kw_supported,
+                "nist_aes_gcm_supported": value.nist_aes_gcm_supported,
+                "nist_rsa_oaep_supported": value.nist_rsa_oaep_supported,
+                "aes256_wrapping_supported": value.aes256_wrapping_supported,
+                "rsa2k_wrapping_supported": value.rsa2k_wrapping_supported,
+                "rsa3k_wrapping_supported": value.rsa3k_wrapping_supported,
+                "rsa4k_wrapping_supported": value.rsa4k_wrapping_supported,
+                "plaintext_kek_prov_supported": value.plaintext_kek_prov_supported,
+                "pki_kek_transport_supported": value.pki_kek_transport_supported,
+                "num_keks_supported": value.num_keks_supported,
+                "total_key_tags_supported": value.total_key_tags_supported,
+                "max_key_tags_per_namespace": value.max_key_tags_per_namespace,
+                "get_nonce_cmd_nonce_len": value.get_nonce_cmd_nonce_len,
+            }),
+        )
+    }
+}
+
+impl From<&UnrecognizedDescriptor> for Value {
+    fn from(value: &UnrecognizedDescriptor) -> Self {
+        json!({
+            "type": feature_type(FeatureCode::Unrecognized),
+            "feature_code": value.feature_code,
+            "version": value.version,
+            "length": value.length,
+        })
+    }
+}
+
+impl From<&FeatureDescriptor> for Value {
+    fn from(value: &FeatureDescriptor) -> Self {
+        match value {
+            FeatureDescriptor::TPer(desc) => desc.into(),
+            FeatureDescriptor::Locking(desc) => desc.into(),
+            FeatureDescriptor::Geometry(desc) => desc.into(),
+            FeatureDescriptor::DataRemoval(desc) => desc.into(),
+            FeatureDescriptor::BlockSIDAuth(desc) => desc.into(),
+            FeatureDescriptor::AdditionalDataStoreTables(desc) => desc.into(),
+            FeatureDescriptor::Enterprise(desc) => desc.into(),
+            FeatureDescriptor::OpalV1(desc) => desc.into(),
+            FeatureDescriptor::OpalV2(desc) => desc.into(),
+            FeatureDescriptor::Opalite(desc) => desc.into(),
+            FeatureDescriptor::PyriteV1(desc) => desc.into(),
+            FeatureDescriptor::PyriteV2(desc) => desc.into(),
+            FeatureDescriptor::Ruby(desc) => desc.into(),
+            FeatureDescriptor::KeyPerIO(desc) => desc.into(),
+            FeatureDescriptor::Unrecognized(desc) => desc.into(),
+        }
+    }
+}
+
+impl From<&Discovery> for Value {
+    fn from(value: &Discovery) -> Self {
+        let primary_ssc = value.get_primary_ssc().map(|ssc| feature_type(ssc.feature_code()));
+        let features = value.iter().map(|desc| desc.into()).collect();
+        json!({
+            "primary_ssc": primary_ssc,
+            "features": Value::Array(features),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tper_descriptor() {
+        let desc = FeatureDescriptor::TPer(TPerDescriptor {
+            com_id_mgmt_supported: false,
+            streaming_supported: true,
+            buffer_mgmt_supported: false,
+            ack_nak_supported: false,
+            async_supported: false,
+            sync_supported: true,
+        });
+        let expected = concat!(
+            r#"{"type":"tper","feature_code":1,"version":1,"sync_supported":true,"async_supported":false,"#,
+            r#""ack_nak_supported":false,"buffer_mgmt_supported":false,"streaming_supported":true,"#,
+            r#""com_id_mgmt_supported":false}"#
+        );
+        assert_eq!(Value::from(&desc).to_string(), expected);
+    }
+
+    #[test]
+    fn data_removal_times() {
+        let desc = DataRemovalDescriptor {
+            interrupted: false,
+            processing: false,
+            supported_mechanism: DataRemovalMechanism {
+                vendor_erase: false,
+                crypto_erase: true,
+                block_erase: false,
+                overwrite: false,
+            },
+            removal_time: DataRemovalTime {
+                vendor_erase_unit: false,
+                crypto_erase_unit: true,
+                block_erase_unit: false,
+                overwrite_unit: false,
+                overwrite_amount: 0,
+                block_erase_amount: 5,
+                crypto_erase_amount: 3,
+                vendor_erase_amount: 0,
+            },
+        };
+        let json = Value::from(&desc);
+        let times = json.get("removal_time_seconds").unwrap();
+        assert_eq!(times.get("overwrite"), Some(&Value::Null));
+        assert_eq!(times.get("block_erase"), Some(&json!(10)));
+        assert_eq!(times.get("crypto_erase"), Some(&json!(360)));
+    }
+
+    #[test]
+    fn unrecognized_descriptor() {
+        let desc =
+            FeatureDescriptor::Unrecognized(UnrecognizedDescriptor { feature_code: 0x1234, version: 2, length: 8 });
+        let expected = r#"{"type":"unrecognized","feature_code":4660,"version":2,"length":8}"#;
+        assert_eq!(Value::from(&desc).to_string(), expected);
+    }
+
+    #[test]
+    fn discovery() {
+        let discovery = Discovery::new(vec![FeatureDescriptor::OpalV2(OpalV2Descriptor {
+            base_com_id: 0x1000,
+            num_com_ids: 1,
+            no_range_crossing: false,
+            num_locking_admins_supported: 4,
+            num_locking_users_supported: 8,
+            initial_owner_pw: OwnerPasswordState::SameAsMSID,
+            reverted_owner_pw: OwnerPasswordState::VendorSpecified,
+        })]);
+        let json = Value::from(&discovery);
+        assert_eq!(json.get("primary_ssc"), Some(&Value::from("opal_v2")));
+        let Value::Array(features) = json.get("features").unwrap() else {
+            panic!("features must be an array")
+        };
+        assert_eq!(features.len(), 1);
+        assert_eq!(features[0].get("feature_code"), Some(&json!(0x0203)));
+        assert_eq!(features[0].get("reverted_owner_pw"), Some(&Value::from("vendor_specified")));
+    }
+}
@@ -183,18 +183,19 @@ impl Serialize<Token> for Bytes {
 impl Deserialize<Token> for Bytes {
     type Error = TokenStreamError;
     fn deserialize(stream: &mut InputStream<Token>) -> Result<Self, Self::Error> {
-        if let Ok(token) = stream.read_one() {
-            if !is_data(token.tag) {
-                Err(TokenStreamError::ExpectedBytes)
-            } else if !token.is_byte {
-                Err(TokenStreamError::ExpectedBytes)
-            } else if token.is_signed != false {
-                Err(TokenStreamError::ContinuedBytesUnsupported)
-            } else {
-                Ok(token.data.clone())
+        // Continued tokens are joined into a single byte sequence.
+        let mut bytes = Bytes::new();
+        loop {
+            let Ok(token) = stream.read_one() else {
+                break Err(TokenStreamError::MoreTokensExpected);
+            };
+            if !is_data(token.tag) || !token.is_byte {
+                break Err(TokenStreamError::ExpectedBytes);
+            }
+            bytes.extend_from_slice(&token.data);
+            if !token.is_continued() {
+                break Ok(bytes);
             }
-        } else {
-            Err(TokenStreamError::MoreTokensExpected)
         }
     }
 }
@@ -324,6 +325,8 @@ impl Deserialize<Token> for Value {
 mod tests {
     use super::*;
 
+    use crate::messaging::token::split_continued;
+
     macro_rules! test_tokenize_integer {
         ($int_ty:ty, $name:ident) => {
             #[test]
@@ -418,6 +421,21 @@ mod tests {
         };
     }
 
+    #[test]
+    fn tokenize_bytes_continued() {
+        let input = vec![0xAD_u8; 40];
+        let token = Token { tag: Tag::MediumAtom, is_byte: true, is_signed: false, data: input.clone() };
+        let mut is = InputStream::<Token>::from(split_continued(token, 16));
+        assert_eq!(Bytes::deserialize(&mut is), Ok(input));
+    }
+
+    #[test]
+    fn tokenize_bytes_continued_unterminated() {
+        let tokens = vec![Token { tag: Tag::ShortAtom, is_byte: true, is_signed: true, data: vec![0xAD_u8; 4] }];
+        let mut is = InputStream::<Token>::from(tokens);
+        assert_eq!(Bytes::deserialize(&mut is), Err(TokenStreamError::MoreTokensExpected));
+    }
+
     #[test]
     fn tokenize_list() {
         let input = vec![
@@ -6,6 +6,8 @@
 pub mod com_id;
 pub mod discovery;
 mod impl_ser_bin;
+#[cfg(feature = "serde")]
+mod impl_ser_json;
 mod impl_ser_token;
 pub mod packet;
 pub mod table_mask;
@@ -52,6 +52,9 @@ enum TableIndex {
     MBR,
     K_AES_128,
     K_AES_256,
+    KPIOPolicies,
+    KeyTagAllocation,
+    KeyEncryptionKey,
 }
 
 pub const fn table_mask(table_uid: u64) -> u64 {
@@ -102,6 +105,9 @@ pub const fn table_mask(table_uid: u64) -> u64 {
         0x0000_0804 => 1u64 << (TableIndex::MBR as u32),
         0x0000_0805 => 1u64 << (TableIndex::K_AES_128 as u32),
         0x0000_0806 => 1u64 << (TableIndex::K_AES_256 as u32),
+        0x0000_1C01 => 1u64 << (TableIndex::KPIOPolicies as u32),
+        0x0000_1C02 => 1u64 << (TableIndex::KeyTagAllocation as u32),
+        0x0000_1C03 => 1u64 << (TableIndex::KeyEncryptionKey as u32),
         _ => panic!("please add the table in this list so that it has a mask"),
     }
 }
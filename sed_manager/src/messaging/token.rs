@@ -59,6 +59,7 @@ pub enum TokenStreamError {
     UnclosedName,
     #[error("Unexpected EndList/EndName/EndTransaction token")]
     UnexpectedEndTag,
+    #[deprecated(note = "continued byte tokens are reassembled, so this error is no longer returned")]
     #[error("Continued byte tokens are not supported")]
     ContinuedBytesUnsupported,
     #[error("Number too large for integer type")]
@@ -115,6 +116,27 @@ pub fn get_tag(data_length: usize) -> Tag {
     }
 }
 
+/// Splits a byte token into continued tokens that carry at most `max_data_len` bytes each.
+///
+/// All tokens but the last have the continued flag set. Other tokens are returned as they are.
+pub fn split_continued(token: Token, max_data_len: usize) -> Vec<Token> {
+    if !token.is_byte || token.data.len() <= max_data_len {
+        return vec![token];
+    }
+    let num_chunks = token.data.len().div_ceil(max_data_len);
+    token
+        .data
+        .chunks(max_data_len)
+        .enumerate()
+        .map(|(index, chunk)| Token {
+            tag: get_tag(chunk.len()),
+            is_byte: true,
+            is_signed: index + 1 != num_chunks,
+            data: chunk.into(),
+        })
+        .collect()
+}
+
 pub fn is_data(tag: Tag) -> bool {
     match tag {
         Tag::TinyAtom => true,
@@ -125,6 +147,13 @@ pub fn is_data(tag: Tag) -> bool {
     }
 }
 
+impl Token {
+    /// For byte tokens, the sign flag means that the data continues in the next token.
+    pub fn is_continued(&self) -> bool {
+        self.is_byte && self.is_signed
+    }
+}
+
 impl Default for Token {
     fn default() -> Self {
         Token { tag: Tag::Empty, is_byte: false, is_signed: false, data: vec![] }
@@ -349,6 +378,24 @@ mod tests {
         }
     }
 
+    #[test]
+    fn split_continued_bytes() {
+        let token = Token { tag: Tag::MediumAtom, is_byte: true, is_signed: false, data: vec![0x65; 40] };
+        let tokens = split_continued(token, 16);
+        assert_eq!(tokens.iter().map(|token| token.data.len()).collect::<Vec<_>>(), vec![16, 16, 8]);
+        assert_eq!(tokens.iter().map(|token| token.is_continued()).collect::<Vec<_>>(), vec![true, true, false]);
+        assert_eq!(tokens[0].tag, Tag::MediumAtom);
+        assert_eq!(tokens[2].tag, Tag::ShortAtom);
+    }
+
+    #[test]
+    fn split_continued_short() {
+        let token = Token { tag: Tag::ShortAtom, is_byte: true, is_signed: false, data: vec![0x65; 8] };
+        let tokens = split_continued(token, 16);
+        assert_eq!(tokens.len(), 1);
+        assert!(!tokens[0].is_continued());
+    }
+
     #[test]
     fn deserialize_invalid_tag() {
         let mut is = InputStream::<u8>::from(vec![0xFD]);
@@ -0,0 +1,24 @@
+//L-----------------------------------------------------------------------------
+//L Copyright (C) Péter Kardos
+//L Please refer to the full license distributed with this software.
+//L-----------------------------------------------------------------------------
+
+use crate::device::Error as DeviceError;
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum Error {
+    #[error("{}", .0)]
+    DeviceError(DeviceError),
+    #[error("The drive has no GUID partition table")]
+    NoPartitionTable,
+    #[error("The GUID partition table is invalid")]
+    InvalidPartitionTable,
+    #[error("The checksum of the GUID partition table does not match")]
+    ChecksumMismatch,
+}
+
+impl From<DeviceError> for Error {
+    fn from(value: DeviceError) -> Self {
+        Self::DeviceError(value)
+    }
+}
@@ -0,0 +1,341 @@
+//L-----------------------------------------------------------------------------
+//L Copyright (C) Péter Kardos
+//L Please refer to the full license distributed with this software.
+//L-----------------------------------------------------------------------------
+
+//! Implements parsing the GUID partition table as defined by the UEFI specification.
+//! The specification is accessible on [UEFI's website](https://uefi.org/specifications).
+
+use crate::device::Device;
+use crate::serialization::{Deserialize, DeserializeBinary as _, Serialize};
+
+use super::crc32::crc32;
+use super::Error;
+
+/// The partition type in the MBR that covers the GUID partition table.
+const PROTECTIVE_MBR_TYPE: u8 = 0xEE;
+const HEADER_SIGNATURE: [u8; 8] = *b"EFI PART";
+const HEADER_SIZE: usize = 92;
+const ENTRY_SIZE: usize = 128;
+/// Guards against reading an unreasonable amount of data due to a corrupt header.
+const MAX_ENTRIES_LEN: u64 = 1 << 20;
+
+/// A partition of the drive, as described by the GUID partition table.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Partition {
+    /// The position of the partition in the table starting from 1, the same as operating systems number them.
+    pub number: usize,
+    pub type_guid: [u8; 16],
+    pub unique_guid: [u8; 16],
+    pub first_lba: u64,
+    /// The last LBA of the partition, inclusive.
+    pub last_lba: u64,
+    pub name: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[layout(little_endian)]
+struct Header {
+    signature: [u8; 8],
+    revision: u32,
+    header_size: u32,
+    header_crc32: u32,
+    #[layout(offset = 24)]
+    my_lba: u64,
+    alternate_lba: u64,
+    first_usable_lba: u64,
+    last_usable_lba: u64,
+    disk_guid: [u8; 16],
+    partition_entry_lba: u64,
+    num_partition_entries: u32,
+    partition_entry_size: u32,
+    partition_entries_crc32: u32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[layout(little_endian)]
+struct PartitionEntry {
+    type_guid: [u8; 16],
+    unique_guid: [u8; 16],
+    first_lba: u64,
+    last_lba: u64,
+    attributes: u64,
+    name: [u16; 36],
+}
+
+impl Partition {
+    pub fn num_blocks(&self) -> u64 {
+        self.last_lba - self.first_lba + 1
+    }
+}
+
+/// Reads the used partitions from the primary GUID partition table of the drive.
+///
+/// The backup table at the end of the drive is not consulted, so a corrupt primary table is an error.
+pub fn read_partition_table(device: &dyn Device) -> Result<Vec<Partition>, Error> {
+    let block_size = device.logical_block_size()? as u64;
+    if !has_protective_mbr(&device.read_blocks(0, 1)?) {
+        return Err(Error::NoPartitionTable);
+    }
+    let header = parse_header(&device.read_blocks(1, 1)?)?;
+    let entries_len = header.num_partition_entries as u64 * header.partition_entry_size as u64;
+    let entries = device.read_blocks(header.partition_entry_lba, entries_len.div_ceil(block_size))?;
+    parse_entries(&header, entries.get(..entries_len as usize).ok_or(Error::InvalidPartitionTable)?)
+}
+
+fn has_protective_mbr(mbr: &[u8]) -> bool {
+    const BOOT_SIGNATURE: [u8; 2] = [0x55, 0xAA];
+    const RECORDS_OFFSET: usize = 446;
+    const RECORD_LEN: usize = 16;
+    const TYPE_OFFSET: usize = 4;
+    let is_protective = |idx| mbr.get(RECORDS_OFFSET + idx * RECORD_LEN + TYPE_OFFSET) == Some(&PROTECTIVE_MBR_TYPE);
+    mbr.get(510..512) == Some(&BOOT_SIGNATURE) && (0..4).any(is_protective)
+}
+
+fn parse_header(block: &[u8]) -> Result<Header, Error> {
+    if block.get(0..HEADER_SIGNATURE.len()) != Some(&HEADER_SIGNATURE) {
+        return Err(Error::NoPartitionTable);
+    }
+    let header = Header::from_bytes(block.to_vec()).map_err(|_| Error::InvalidPartitionTable)?;
+    let header_size = header.header_size as usize;
+    if header_size < HEADER_SIZE || block.len() < header_size {
+        return Err(Error::InvalidPartitionTable);
+    }
+    // The checksum is calculated with the checksum field zeroed.
+    let mut checked_bytes = block[..header_size].to_vec();
+    checked_bytes[16..20].fill(0);
+    if crc32(&checked_bytes) != header.header_crc32 {
+        return Err(Error::ChecksumMismatch);
+    }
+    let entry_size = header.partition_entry_size as usize;
+    let entries_len = header.num_partition_entries as u64 * entry_size as u64;
+    if header.my_lba != 1
+        || header.partition_entry_lba < 2
+        || entry_size < ENTRY_SIZE
+        || !entry_size.is_multiple_of(ENTRY_SIZE)
+        || entries_len > MAX_ENTRIES_LEN
+    {
+        return Err(Error::InvalidPartitionTable);
+    }
+    Ok(header)
+}
+
+fn parse_entries(header: &Header, entries: &[u8]) -> Result<Vec<Partition>, Error> {
+    if crc32(entries) != header.partition_entries_crc32 {
+        return Err(Error::ChecksumMismatch);
+    }
+    let mut partitions = Vec::new();
+    for (idx, bytes) in entries.chunks_exact(header.partition_entry_size as usize).enumerate() {
+        let entry =
+            PartitionEntry::from_bytes(bytes[..ENTRY_SIZE].to_vec()).map_err(|_| Error::InvalidPartitionTable)?;
+        if entry.type_guid == [0; 16] {
+            continue; // Unused entry.
+        }
+        if entry.last_lba < entry.first_lba
+            || entry.first_lba < header.first_usable_lba
+            || header.last_usable_lba < entry.last_lba
+        {
+            return Err(Error::InvalidPartitionTable);
+        }
+        let name_len = entry.name.iter().position(|c| *c == 0).unwrap_or(entry.name.len());
+        partitions.push(Partition {
+            number: idx + 1,
+            type_guid: entry.type_guid,
+            unique_guid: entry.unique_guid,
+            first_lba: entry.first_lba,
+            last_lba: entry.last_lba,
+            name: String::from_utf16_lossy(&entry.name[..name_len]),
+        });
+    }
+    Ok(partitions)
+}
+
+#[cfg(test)]
+pub mod tests {
+    use crate::device::{Error as DeviceError, Interface};
+    use crate::fake_device::FakeDevice;
+    use crate::serialization::SerializeBinary as _;
+
+    use super::*;
+
+    const BLOCK_SIZE: usize = 512;
+    const NUM_ENTRIES: usize = 128;
+    const FIRST_USABLE_LBA: u64 = 34;
+    const LAST_USABLE_LBA: u64 = 2_097_118;
+
+    /// Returns one block less than requested from multi-block reads.
+    struct ShortReadDevice(FakeDevice);
+
+    impl Device for ShortReadDevice {
+        fn path(&self) -> Option<String> {
+            self.0.path()
+        }
+
+        fn interface(&self) -> Interface {
+            self.0.interface()
+        }
+
+        fn model_number(&self) -> String {
+            self.0.model_number()
+        }
+
+        fn serial_number(&self) -> String {
+            self.0.serial_number()
+        }
+
+        fn firmware_revision(&self) -> String {
+            self.0.firmware_revision()
+        }
+
+        fn is_security_supported(&self) -> bool {
+            self.0.is_security_supported()
+        }
+
+        fn security_send(&self, protocol: u8, protocol_specific: [u8; 2], data: &[u8]) -> Result<(), DeviceError> {
+            self.0.security_send(protocol, protocol_specific, data)
+        }
+
+        fn security_recv(&self, protocol: u8, protocol_specific: [u8; 2], len: usize) -> Result<Vec<u8>, DeviceError> {
+            self.0.security_recv(protocol, protocol_specific, len)
+        }
+
+        fn logical_block_size(&self) -> Result<u32, DeviceError> {
+            self.0.logical_block_size()
+        }
+
+        fn read_blocks(&self, lba: u64, count: u64) -> Result<Vec<u8>, DeviceError> {
+            self.0.read_blocks(lba, if count > 1 { count - 1 } else { count })
+        }
+    }
+
+    /// Creates the first 34 blocks of a drive with a protective MBR and a GUID partition table.
+    pub fn make_gpt(partitions: &[(u64, u64, &str)]) -> Vec<u8> {
+        let mut image = vec![0_u8; FIRST_USABLE_LBA as usize * BLOCK_SIZE];
+
+        image[446 + 4] = PROTECTIVE_MBR_TYPE;
+        image[510..512].copy_from_slice(&[0x55, 0xAA]);
+
+        let mut entries = Vec::new();
+        for idx in 0..NUM_ENTRIES {
+            let entry = match partitions.get(idx) {
+                Some((first_lba, last_lba, name)) => {
+                    let mut name_utf16 = [0_u16; 36];
+                    name.encode_utf16().zip(name_utf16.iter_mut()).for_each(|(c, out)| *out = c);
+                    PartitionEntry {
+                        type_guid: [0xAF; 16],
+                        unique_guid: [idx as u8 + 1; 16],
+                        first_lba: *first_lba,
+                        last_lba: *last_lba,
+                        attributes: 0,
+                        name: name_utf16,
+                    }
+                }
+                None => PartitionEntry {
+                    type_guid: [0; 16],
+                    unique_guid: [0; 16],
+                    first_lba: 0,
+                    last_lba: 0,
+                    attributes: 0,
+                    name: [0; 36],
+                },
+            };
+            entries.extend(entry.to_bytes().unwrap());
+        }
+
+        let mut header = Header {
+            signature: HEADER_SIGNATURE,
+            revision: 0x0001_0000,
+            header_size: HEADER_SIZE as u32,
+            header_crc32: 0,
+            my_lba: 1,
+            alternate_lba: LAST_USABLE_LBA + 33,
+            first_usable_lba: FIRST_USABLE_LBA,
+            last_usable_lba: LAST_USABLE_LBA,
+            disk_guid: [0x5A; 16],
+            partition_entry_lba: 2,
+            num_partition_entries: NUM_ENTRIES as u32,
+            partition_entry_size: ENTRY_SIZE as u32,
+            partition_entries_crc32: crc32(&entries),
+        };
+        header.header_crc32 = crc32(&header.to_bytes().unwrap());
+
+        image[BLOCK_SIZE..BLOCK_SIZE + HEADER_SIZE].copy_from_slice(&header.to_bytes().unwrap());
+        image[2 * BLOCK_SIZE..2 * BLOCK_SIZE + entries.len()].copy_from_slice(&entries);
+        image
+    }
+
+    #[test]
+    fn header_layout() {
+        let image = make_gpt(&[]);
+        let header = Header::from_bytes(image[BLOCK_SIZE..2 * BLOCK_SIZE].to_vec()).unwrap();
+        assert_eq!(&image[BLOCK_SIZE + 24..BLOCK_SIZE + 32], &1_u64.to_le_bytes());
+        assert_eq!(header.my_lba, 1);
+        assert_eq!(header.partition_entry_lba, 2);
+        assert_eq!(header.partition_entry_size, ENTRY_SIZE as u32);
+    }
+
+    #[test]
+    fn read_partitions() -> Result<(), Error> {
+        let device = FakeDevice::new();
+        device.write_blocks(
+            0,
+            &make_gpt(&[
+                (2048, 1_050_623, "EFI system"),
+                (1_050_624, 2_097_118, "Linux"),
+            ]),
+        )?;
+        let partitions = read_partition_table(&device)?;
+        assert_eq!(partitions.len(), 2);
+        assert_eq!((partitions[0].first_lba, partitions[0].last_lba), (2048, 1_050_623));
+        assert_eq!(partitions[0].num_blocks(), 1_048_576);
+        assert_eq!(partitions[0].name, "EFI system");
+        assert_eq!(partitions[0].number, 1);
+        assert_eq!(partitions[0].unique_guid, [1; 16]);
+        assert_eq!((partitions[1].first_lba, partitions[1].last_lba), (1_050_624, 2_097_118));
+        assert_eq!(partitions[1].name, "Linux");
+        assert_eq!(partitions[1].number, 2);
+        Ok(())
+    }
+
+    #[test]
+    fn read_empty_drive() {
+        let device = FakeDevice::new();
+        assert_eq!(read_partition_table(&device), Err(Error::NoPartitionTable));
+    }
+
+    #[test]
+    fn read_corrupt_header() -> Result<(), Error> {
+        let device = FakeDevice::new();
+        let mut image = make_gpt(&[(2048, 4095, "Data")]);
+        image[BLOCK_SIZE + 40] ^= 0xFF; // First usable LBA.
+        device.write_blocks(0, &image)?;
+        assert_eq!(read_partition_table(&device), Err(Error::ChecksumMismatch));
+        Ok(())
+    }
+
+    #[test]
+    fn read_corrupt_entries() -> Result<(), Error> {
+        let device = FakeDevice::new();
+        let mut image = make_gpt(&[(2048, 4095, "Data")]);
+        image[2 * BLOCK_SIZE + 32] ^= 0xFF; // First LBA of the first entry.
+        device.write_blocks(0, &image)?;
+        assert_eq!(read_partition_table(&device), Err(Error::ChecksumMismatch));
+        Ok(())
+    }
+
+    #[test]
+    fn read_out_of_bounds_entry() -> Result<(), Error> {
+        let device = FakeDevice::new();
+        device.write_blocks(0, &make_gpt(&[(2048, LAST_USABLE_LBA + 1, "Data")]))?;
+        assert_eq!(read_partition_table(&device), Err(Error::InvalidPartitionTable));
+        Ok(())
+    }
+
+    #[test]
+    fn read_short_entries() -> Result<(), Error> {
+        let device = ShortReadDevice(FakeDevice::new());
+        device.0.write_blocks(0, &make_gpt(&[(2048, 4095, "Data")]))?;
+        assert_eq!(read_partition_table(&device), Err(Error::InvalidPartitionTable));
+        Ok(())
+    }
+}
@@ -0,0 +1,16 @@
+//L-----------------------------------------------------------------------------
+//L Copyright (C) Péter Kardos
+//L Please refer to the full license distributed with this software.
+//L-----------------------------------------------------------------------------
+
+//! Reads the partition table of a drive to help placing locking ranges over partitions.
+
+mod crc32;
+mod error;
+mod gpt;
+
+pub use error::Error;
+pub use gpt::{read_partition_table, Partition};
+
+#[cfg(test)]
+pub(crate) use gpt::tests::make_gpt;
@@ -0,0 +1,467 @@
+//L-----------------------------------------------------------------------------
+//L Copyright (C) Péter Kardos
+//L Please refer to the full license distributed with this software.
+//L-----------------------------------------------------------------------------
+
+//! Turn the raw security send/receive traffic of a capture into readable text.
+//!
+//! Captures are recorded with [`crate::device::trace::RecordingDevice`]. The decoder
+//! splits the ComPackets into Packets and SubPackets, parses the token streams into
+//! method calls and results, and resolves UIDs, method arguments and table columns
+//! to their names from the specification.
+
+use std::collections::HashMap;
+
+use crate::device::trace::Exchange;
+use crate::messaging::discovery::{Discovery, Feature as _};
+use crate::messaging::packet::{ComPacket, Packet, SubPacketKind, COM_PACKET_HEADER_LEN, PACKETIZED_PROTOCOL};
+use crate::messaging::token::{Tag, Token};
+use crate::messaging::uid::UID;
+use crate::messaging::value::Value;
+use crate::serialization::vec_without_len::VecWithoutLen;
+use crate::serialization::{Deserialize as _, DeserializeBinary as _, InputStream, Seek as _};
+use crate::spec::{self, method_id, sm_method_id, ObjectLookup};
+
+use super::{MethodCall, MethodResult, PackagedMethod, Protocol};
+
+const CELL_BLOCK: &[&str] = &["Table", "startRow", "endRow", "startColumn", "endColumn"];
+const START_SESSION_ARGS: &[&str] = &[
+    "HostChallenge",
+    "HostExchangeAuthority",
+    "HostExchangeCert",
+    "HostSigningAuthority",
+    "HostSigningCert",
+    "SessionTimeout",
+    "TransTimeout",
+    "InitialCredit",
+    "SignedHash",
+];
+const PROPERTIES_ARGS: &[&str] = &["HostProperties"];
+const SET_ARGS: &[&str] = &["Where", "Values"];
+const AUTHENTICATE_ARGS: &[&str] = &["Proof"];
+const NEXT_ARGS: &[&str] = &["Where", "Count"];
+const GEN_KEY_ARGS: &[&str] = &["PublicExponent", "PinLength"];
+
+/// Decodes the exchanges of a capture one after the other.
+///
+/// The decoder is stateful: it remembers which SP each session was opened to,
+/// and which method call is waiting for a result, so the exchanges must be
+/// fed in the order they were recorded.
+pub struct Decoder {
+    reveal_bytes: bool,
+    sessions: HashMap<u32, UID>,
+    calls: HashMap<u32, MethodCall>,
+}
+
+impl Decoder {
+    /// Byte values are redacted unless `reveal_bytes` is set because they may contain
+    /// passwords or data from the DataStore and MBR tables. UIDs are always resolved.
+    pub fn new(reveal_bytes: bool) -> Self {
+        Self { reveal_bytes, sessions: HashMap::new(), calls: HashMap::new() }
+    }
+
+    /// Describe an exchange. The result may span multiple lines.
+    pub fn decode(&mut self, exchange: &Exchange) -> String {
+        let (direction, error) = match exchange {
+            Exchange::Send { error, .. } => ("IF-SEND", error),
+            Exchange::Recv { error, .. } => ("IF-RECV", error),
+        };
+        let mut text = format!("{direction} {:02X}h/{:04X}h", exchange.protocol(), exchange.protocol_specific());
+        if let Some(error) = error {
+            text.push_str(&format!(": failed with {error}"));
+            return text;
+        }
+        let data = match exchange {
+            Exchange::Send { data, .. } => pad_com_packet(data),
+            Exchange::Recv { data, len, .. } => {
+                let mut data = data.clone();
+                let len = core::cmp::min(*len, max_com_packet_len());
+                if data.len() < len {
+                    data.resize(len, 0);
+                }
+                data
+            }
+        };
+        let is_recv = matches!(exchange, Exchange::Recv { .. });
+        let body = match (exchange.protocol(), exchange.protocol_specific()) {
+            (0x00, _) => format!("Supported security protocols: {}", hex(exchange.data())),
+            (PACKETIZED_PROTOCOL, 0x0001) if is_recv => self.decode_discovery(data),
+            (PACKETIZED_PROTOCOL, _) => self.decode_com_packet(data),
+            (0x02, 0x0004) => String::from("TPer reset"),
+            (0x02, 0x0005) => format!("Block SID: hardware reset = {}", data.first().is_some_and(|x| x & 1 != 0)),
+            (0x02, _) => format!("ComID management: {}", hex(exchange.data())),
+            _ => hex(exchange.data()),
+        };
+        text.push_str(": ");
+        text.push_str(&body);
+        text
+    }
+
+    fn decode_discovery(&self, data: Vec<u8>) -> String {
+        match Discovery::from_bytes(data) {
+            Ok(discovery) => {
+                let features: Vec<_> =
+                    discovery.remove_empty().iter().map(|desc| format!("{:?}", desc.feature_code())).collect();
+                format!("Level 0 Discovery [{}]", features.join(", "))
+            }
+            Err(error) => format!("Level 0 Discovery (malformed: {error})"),
+        }
+    }
+
+    fn decode_com_packet(&mut self, data: Vec<u8>) -> String {
+        let com_packet = match ComPacket::from_bytes(data) {
+            Ok(com_packet) => com_packet,
+            Err(error) => return format!("ComPacket (malformed: {error})"),
+        };
+        let mut text = format!("ComPacket ComID={:04X}h", com_packet.com_id);
+        if com_packet.payload.is_empty() {
+            text.push_str(&format!(" (empty, outstanding data: {})", com_packet.outstanding_data));
+        }
+        for packet in com_packet.payload.iter() {
+            text.push_str(&format!(
+                "\n  Packet TSN={} HSN={} SeqNo={}",
+                packet.tper_session_number, packet.host_session_number, packet.sequence_number
+            ));
+            for line in self.decode_packet(packet) {
+                text.push_str("\n    ");
+                text.push_str(&line);
+            }
+        }
+        text
+    }
+
+    fn decode_packet(&mut self, packet: &Packet) -> Vec<String> {
+        let hsn = packet.host_session_number;
+        let mut lines = Vec::new();
+        for sub_packet in packet.payload.iter() {
+            if sub_packet.kind == SubPacketKind::CreditControl {
+                lines.push(format!("CreditControl {}", hex(&sub_packet.payload)));
+                continue;
+            }
+            let tokens = match VecWithoutLen::<Token>::from_bytes(sub_packet.payload.to_vec()) {
+                Ok(tokens) => tokens.into_vec(),
+                Err(error) => {
+                    lines.push(format!("SubPacket (malformed tokens: {error})"));
+                    continue;
+                }
+            };
+            let tokens: Vec<_> = tokens.into_iter().filter(|token| token.tag != Tag::Empty).collect();
+            let mut stream = InputStream::from(tokens);
+            while stream.stream_position() < stream.stream_len() {
+                match PackagedMethod::deserialize(&mut stream) {
+                    Ok(method) => lines.push(self.decode_method(hsn, method)),
+                    Err(error) => {
+                        lines.push(format!("SubPacket (malformed method: {error})"));
+                        break;
+                    }
+                }
+            }
+        }
+        lines
+    }
+
+    fn decode_method(&mut self, hsn: u32, method: PackagedMethod) -> String {
+        let sp = self.sessions.get(&hsn).copied();
+        match method {
+            PackagedMethod::Call(call) => {
+                if call.method_id == sm_method_id::START_SESSION.as_uid() {
+                    let session = call.args.first().and_then(|value| u32::try_from(value).ok());
+                    let target = call.args.get(1).and_then(|value| UID::try_from(value.clone()).ok());
+                    if let (Some(session), Some(target)) = (session, target) {
+                        self.sessions.insert(session, target);
+                    }
+                }
+                let text = self.format_call(&call, sp);
+                self.calls.insert(hsn, call);
+                text
+            }
+            PackagedMethod::Result(result) => {
+                let call = self.calls.remove(&hsn);
+                self.format_result(&result, call.as_ref(), sp)
+            }
+            PackagedMethod::EndOfSession => {
+                self.calls.remove(&hsn);
+                String::from("EndOfSession")
+            }
+            PackagedMethod::StartTransaction(status) => format!("StartTransaction({status})"),
+            PackagedMethod::EndTransaction(status) => format!("EndTransaction({status})"),
+        }
+    }
+
+    /// Format a method call like `Locking::Range1.Set(Values=[ReadLocked=1])`.
+    pub fn format_call(&self, call: &MethodCall, sp: Option<UID>) -> String {
+        let invoking_id = self.format_object(call.invoking_id, sp);
+        let method_id = self.name_of(call.method_id, sp).unwrap_or(call.method_id.to_string());
+        let columns = call.invoking_id.containing_table().map(spec::column_names).unwrap_or(&[]);
+        let args: Vec<_> = if call.method_id == method_id::GET.as_uid() {
+            call.args.iter().map(|arg| self.format_cell_block(arg, columns, sp)).collect()
+        } else if call.method_id == method_id::SET.as_uid() {
+            call.args
+                .iter()
+                .map(|arg| match arg {
+                    Value::Named(named) if u64::try_from(&named.name) == Ok(1) => {
+                        format!("Values={}", self.format_with_names(&named.value, columns, sp))
+                    }
+                    _ => self.format_named(arg, SET_ARGS, sp),
+                })
+                .collect()
+        } else {
+            let names = arg_names(call.method_id);
+            call.args.iter().map(|arg| self.format_named(arg, names, sp)).collect()
+        };
+        let mut text = format!("{invoking_id}.{method_id}({})", args.join(", "));
+        if call.status != super::MethodStatus::Success {
+            text.push_str(&format!(" [{:?}]", call.status));
+        }
+        text
+    }
+
+    /// Format a method result like `=> Success [[ReadLocked=1]]`.
+    ///
+    /// The call the result belongs to is used to name the columns returned by `Get`.
+    pub fn format_result(&self, result: &MethodResult, call: Option<&MethodCall>, sp: Option<UID>) -> String {
+        let columns = call
+            .filter(|call| call.method_id == method_id::GET.as_uid())
+            .and_then(|call| call.invoking_id.containing_table())
+            .map(spec::column_names)
+            .unwrap_or(&[]);
+        let results: Vec<_> = result.results.iter().map(|value| self.format_with_names(value, columns, sp)).collect();
+        format!("=> {:?} [{}]", result.status, results.join(", "))
+    }
+
+    pub fn format_value(&self, value: &Value, sp: Option<UID>) -> String {
+        match value {
+            Value::Empty => String::from("<empty>"),
+            Value::Int8(n) => n.to_string(),
+            Value::Int16(n) => n.to_string(),
+            Value::Int32(n) => n.to_string(),
+            Value::Int64(n) => n.to_string(),
+            Value::Uint8(n) => n.to_string(),
+            Value::Uint16(n) => n.to_string(),
+            Value::Uint32(n) => n.to_string(),
+            Value::Uint64(n) => n.to_string(),
+            Value::Command(command) => format!("{command:?}"),
+            Value::Named(named) => {
+                let name = match &named.name {
+                    Value::Bytes(bytes) if is_printable(bytes) => String::from_utf8_lossy(bytes).into_owned(),
+                    name => self.format_value(name, sp),
+                };
+                format!("{name}={}", self.format_value(&named.value, sp))
+            }
+            Value::Bytes(bytes) => self.format_bytes(bytes, sp),
+            Value::List(list) => {
+                let items: Vec<_> = list.iter().map(|item| self.format_value(item, sp)).collect();
+                format!("[{}]", items.join(", "))
+            }
+        }
+    }
+
+    fn format_bytes(&self, bytes: &[u8], sp: Option<UID>) -> String {
+        if let Ok(uid) = <[u8; 8]>::try_from(bytes) {
+            let uid = UID::new(u64::from_be_bytes(uid));
+            if let Some(name) = self.name_of(uid, sp) {
+                return name;
+            }
+        }
+        if self.reveal_bytes {
+            format!("0x{}", hex(bytes).replace(' ', ""))
+        } else {
+            format!("<{} bytes>", bytes.len())
+        }
+    }
+
+    /// Format a list whose items are named by small integers, such as a row of columns.
+    fn format_with_names(&self, value: &Value, names: &[&str], sp: Option<UID>) -> String {
+        match value {
+            Value::List(list) => {
+                let items: Vec<_> = list.iter().map(|item| self.format_named(item, names, sp)).collect();
+                format!("[{}]", items.join(", "))
+            }
+            _ => self.format_value(value, sp),
+        }
+    }
+
+    fn format_named(&self, value: &Value, names: &[&str], sp: Option<UID>) -> String {
+        if let Value::Named(named) = value {
+            if let Some(name) = u64::try_from(&named.name).ok().and_then(|idx| names.get(idx as usize)) {
+                return format!("{name}={}", self.format_value(&named.value, sp));
+            }
+        }
+        self.format_value(value, sp)
+    }
+
+    fn format_cell_block(&self, value: &Value, columns: &[&str], sp: Option<UID>) -> String {
+        let Value::List(list) = value else {
+            return self.format_value(value, sp);
+        };
+        let items: Vec<_> = list
+            .iter()
+            .map(|item| match item {
+                Value::Named(named) => {
+                    let idx = u64::try_from(&named.name).ok().map(|idx| idx as usize);
+                    let name = idx.and_then(|idx| CELL_BLOCK.get(idx));
+                    let column = u64::try_from(&named.value).ok().and_then(|column| columns.get(column as usize));
+                    match (idx, name, column) {
+                        (Some(3 | 4), Some(name), Some(column)) => format!("{name}={column}"),
+                        (_, Some(name), _) => format!("{name}={}", self.format_value(&named.value, sp)),
+                        _ => self.format_value(item, sp),
+                    }
+                }
+                _ => self.format_value(item, sp),
+            })
+            .collect();
+        format!("[{}]", items.join(", "))
+    }
+
+    fn format_object(&self, uid: UID, sp: Option<UID>) -> String {
+        let object = self.name_of(uid, sp).unwrap_or(uid.to_string());
+        match uid.containing_table().and_then(|table| self.name_of(table, sp)) {
+            Some(table) => format!("{table}::{object}"),
+            None => object,
+        }
+    }
+
+    fn name_of(&self, uid: UID, sp: Option<UID>) -> Option<String> {
+        // Objects of the session manager and of the Admin SP are often referenced
+        // outside of any session, e.g. in the arguments of StartSession.
+        let admin_sp = Some(spec::opal::admin::sp::ADMIN.as_uid());
+        lookups()
+            .into_iter()
+            .find_map(|lookup| lookup.by_uid(uid, sp).or_else(|| lookup.by_uid(uid, admin_sp)))
+    }
+}
+
+impl Default for Decoder {
+    fn default() -> Self {
+        Self::new(false)
+    }
+}
+
+/// The SSC of the drive is not known from the capture, so all of them are tried.
+fn lookups() -> [&'static dyn ObjectLookup; 8] {
+    [
+        &spec::opal::OBJECT_LOOKUP,
+        &spec::enterprise::OBJECT_LOOKUP,
+        &spec::pyrite::OBJECT_LOOKUP,
+        &spec::ruby::OBJECT_LOOKUP,
+        &spec::kpio::OBJECT_LOOKUP,
+        &spec::data_store::OBJECT_LOOKUP,
+        &spec::psid::OBJECT_LOOKUP,
+        &spec::core::OBJECT_LOOKUP,
+    ]
+}
+
+fn arg_names(method: UID) -> &'static [&'static str] {
+    if method == sm_method_id::PROPERTIES.as_uid() {
+        PROPERTIES_ARGS
+    } else if method == sm_method_id::START_SESSION.as_uid() {
+        START_SESSION_ARGS
+    } else if method == method_id::AUTHENTICATE.as_uid() {
+        AUTHENTICATE_ARGS
+    } else if method == method_id::NEXT.as_uid() {
+        NEXT_ARGS
+    } else if method == method_id::GEN_KEY.as_uid() {
+        GEN_KEY_ARGS
+    } else {
+        &[]
+    }
+}
+
+/// The trailing zeros of sent data are not stored in the capture, but the
+/// ComPacket cannot be parsed unless the data is as long as its header says.
+///
+/// The length in the header is not trusted beyond the largest ComPacket the host ever sends.
+fn pad_com_packet(data: &[u8]) -> Vec<u8> {
+    let mut data = data.to_vec();
+    if data.len() < COM_PACKET_HEADER_LEN {
+        data.resize(COM_PACKET_HEADER_LEN, 0);
+    }
+    let length = u32::from_be_bytes(data[16..20].try_into().unwrap()) as usize;
+    let padded_len = core::cmp::min(COM_PACKET_HEADER_LEN.saturating_add(length), max_com_packet_len());
+    if data.len() < padded_len {
+        data.resize(padded_len, 0);
+    }
+    data
+}
+
+/// Captures are untrusted, this limits the memory allocated for a corrupt header.
+fn max_com_packet_len() -> usize {
+    Protocol::capabilities().max_gross_compacket_size
+}
+
+/// Names are sometimes encoded as strings, e.g. in the session manager properties.
+fn is_printable(bytes: &[u8]) -> bool {
+    !bytes.is_empty() && bytes.iter().all(|byte| byte.is_ascii_graphic())
+}
+
+fn hex(data: &[u8]) -> String {
+    let bytes: Vec<_> = data.iter().map(|byte| format!("{byte:02x}")).collect();
+    bytes.join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::messaging::value::Named;
+    use crate::rpc::MethodStatus;
+    use crate::spec::column_types::LockingRangeRef;
+    use crate::spec::objects::LockingRange;
+    use crate::spec::opal;
+
+    use super::*;
+
+    fn range1() -> LockingRangeRef {
+        opal::locking::locking::RANGE.nth(1).unwrap()
+    }
+
+    fn named(name: u16, value: Value) -> Value {
+        Value::from(Named { name: Value::from(name), value })
+    }
+
+    #[test]
+    fn format_call_set() {
+        let values = Value::from(vec![named(LockingRange::READ_LOCKED, Value::from(true))]);
+        let call = MethodCall::new_success(range1().as_uid(), method_id::SET.as_uid(), vec![named(1, values)]);
+        let sp = Some(opal::admin::sp::LOCKING.as_uid());
+        assert_eq!(Decoder::new(false).format_call(&call, sp), "Locking::Range1.Set(Values=[ReadLocked=1])");
+    }
+
+    #[test]
+    fn format_result_get() {
+        let cell_block = Value::from(vec![named(3, Value::from(3_u16)), named(4, Value::from(4_u16))]);
+        let call = MethodCall::new_success(range1().as_uid(), method_id::GET.as_uid(), vec![cell_block]);
+        let row = Value::from(vec![
+            named(3, Value::from(0_u64)),
+            named(4, Value::from(1024_u64)),
+        ]);
+        let result = MethodResult { results: vec![row], status: MethodStatus::Success };
+        let sp = Some(opal::admin::sp::LOCKING.as_uid());
+        let decoder = Decoder::new(false);
+        assert_eq!(
+            decoder.format_call(&call, sp),
+            "Locking::Range1.Get([startColumn=RangeStart, endColumn=RangeLength])"
+        );
+        assert_eq!(decoder.format_result(&result, Some(&call), sp), "=> Success [[RangeStart=0, RangeLength=1024]]");
+    }
+
+    #[test]
+    fn format_bytes_redacted() {
+        let password = Value::from(b"password".to_vec());
+        assert_eq!(Decoder::new(false).format_value(&password, None), "<8 bytes>");
+        assert_eq!(Decoder::new(true).format_value(&password, None), "0x70617373776f7264");
+    }
+
+    #[test]
+    fn pad_com_packet_to_length() {
+        let mut data = vec![0; 20];
+        data[19] = 32;
+        assert_eq!(pad_com_packet(&data[..4]).len(), 20);
+        assert_eq!(pad_com_packet(&data).len(), 52);
+    }
+
+    #[test]
+    fn pad_com_packet_corrupt_length() {
+        let mut data = vec![0; 20];
+        data[16..20].fill(0xFF);
+        assert_eq!(pad_com_packet(&data).len(), max_com_packet_len());
+    }
+}
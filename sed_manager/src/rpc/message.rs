@@ -4,7 +4,7 @@
 //L-----------------------------------------------------------------------------
 
 use crate::messaging::token::{Tag, Token, TokenStreamError};
-use crate::messaging::value::Command;
+use crate::messaging::value::{Command, Value};
 use crate::serialization::{Deserialize, InputStream, ItemRead, OutputStream, Serialize};
 
 use super::method::{MethodCall, MethodResult};
@@ -14,6 +14,10 @@ pub enum PackagedMethod {
     Call(MethodCall),
     Result(MethodResult),
     EndOfSession,
+    /// Opens a transaction. The status code is zero on request, and zero in the response if successful.
+    StartTransaction(u8),
+    /// Closes a transaction. A zero status code commits the transaction, a non-zero status code aborts it.
+    EndTransaction(u8),
 }
 
 impl Serialize<Token> for PackagedMethod {
@@ -23,6 +27,14 @@ impl Serialize<Token> for PackagedMethod {
             PackagedMethod::Call(method_call) => method_call.serialize(stream),
             PackagedMethod::Result(method_result) => method_result.serialize(stream),
             PackagedMethod::EndOfSession => Command::EndOfSession.serialize(stream),
+            PackagedMethod::StartTransaction(status) => {
+                Command::StartTransaction.serialize(stream)?;
+                Value::from(*status).serialize(stream)
+            }
+            PackagedMethod::EndTransaction(status) => {
+                Command::EndTransaction.serialize(stream)?;
+                Value::from(*status).serialize(stream)
+            }
         }
     }
 }
@@ -40,11 +52,23 @@ impl Deserialize<Token> for PackagedMethod {
                 let _ = stream.read_one();
                 Ok(PackagedMethod::EndOfSession)
             }
+            Tag::StartTransaction => {
+                let _ = stream.read_one();
+                Ok(PackagedMethod::StartTransaction(deserialize_status(stream)?))
+            }
+            Tag::EndTransaction => {
+                let _ = stream.read_one();
+                Ok(PackagedMethod::EndTransaction(deserialize_status(stream)?))
+            }
             _ => Err(TokenStreamError::InvalidFormat),
         }
     }
 }
 
+fn deserialize_status(stream: &mut InputStream<Token>) -> Result<u8, TokenStreamError> {
+    u8::try_from(Value::deserialize(stream)?).map_err(|_| TokenStreamError::InvalidFormat)
+}
+
 #[cfg(test)]
 mod tests {
     use crate::rpc::MethodStatus;
@@ -96,4 +120,18 @@ mod tests {
         assert_eq!(is.stream_position(), stream_len);
         Ok(())
     }
+
+    #[test]
+    fn serialize_packaged_method_transaction() -> Result<(), SerializeError> {
+        for item in [PackagedMethod::StartTransaction(0), PackagedMethod::EndTransaction(1)] {
+            let mut os = OutputStream::<Token>::new();
+            item.serialize(&mut os)?;
+            let stream_len = os.stream_len();
+            let mut is = InputStream::from(os.take());
+            let copy = PackagedMethod::deserialize(&mut is)?;
+            assert_eq!(item, copy);
+            assert_eq!(is.stream_position(), stream_len);
+        }
+        Ok(())
+    }
 }
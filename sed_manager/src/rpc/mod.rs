@@ -4,14 +4,21 @@
 //L-----------------------------------------------------------------------------
 
 pub mod args;
+#[cfg(feature = "serde")]
+pub mod decoder;
 mod error;
 mod message;
 mod method;
 mod properties;
 mod protocol;
 
+#[cfg(feature = "serde")]
+pub use decoder::Decoder;
 pub use error::Error;
 pub use message::PackagedMethod;
 pub use method::{MethodCall, MethodResult, MethodStatus};
 pub use properties::Properties;
-pub use protocol::{discover, CommandSender, Protocol, Runtime, SessionIdentifier, TokioRuntime, CONTROL_SESSION_ID};
+pub use protocol::{
+    block_sid, discover, tper_reset, CommandSender, Protocol, Runtime, SessionIdentifier, Timer, TokioRuntime,
+    TokioTimer, CONTROL_SESSION_ID,
+};
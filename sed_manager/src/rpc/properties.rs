@@ -18,6 +18,10 @@ pub struct Properties {
     pub max_gross_compacket_response_size: usize,
     pub max_ind_token_size: usize,
     pub max_agg_token_size: usize,
+    /// The number of transactions the TPer allows to be open at once. Zero if it doesn't support transactions.
+    ///
+    /// Only the TPer reports this property, the host never requests it.
+    pub max_transactions: usize,
     pub continued_tokens: bool,
     pub seq_numbers: bool,
     pub ack_nak: bool,
@@ -38,6 +42,7 @@ impl Properties {
         max_gross_compacket_response_size: 1024,
         max_ind_token_size: 968,
         max_agg_token_size: 968,
+        max_transactions: 0,
         continued_tokens: false,
         seq_numbers: false,
         ack_nak: false,
@@ -49,7 +54,7 @@ impl Properties {
     };
 
     pub fn to_list(&self) -> List<NamedValue<MaxBytes32, u32>> {
-        let list = vec![
+        let mut list = vec![
             ("MaxMethods", inf_to_zero(self.max_methods) as u32),
             ("MaxSubpackets", inf_to_zero(self.max_subpackets) as u32),
             ("MaxPacketSize", inf_to_zero(self.max_gross_packet_size) as u32),
@@ -64,6 +69,9 @@ impl Properties {
             ("Asynchronous", self.asynchronous as u32),
             ("DefTransTimeout", self.def_trans_timeout.as_millis() as u32),
         ];
+        if self.max_transactions != 0 {
+            list.push(("MaxTransactionLimit", self.max_transactions as u32));
+        }
         list.into_iter()
             .map(|(name, value)| NamedValue { name: name.into(), value: value })
             .collect::<Vec<_>>()
@@ -91,6 +99,8 @@ impl Properties {
                 parsed.max_ind_token_size = zero_to_inf(value as usize);
             } else if name == "MaxAggTokenSize".as_bytes() {
                 parsed.max_agg_token_size = zero_to_inf(value as usize);
+            } else if name == "MaxTransactionLimit".as_bytes() {
+                parsed.max_transactions = value as usize;
             } else if name == "ContinuedTokens".as_bytes() {
                 parsed.continued_tokens = value != 0;
             } else if name == "SequenceNumbers".as_bytes() {
@@ -120,6 +130,7 @@ impl Properties {
             ),
             max_ind_token_size: core::cmp::min(lhs.max_ind_token_size, rhs.max_ind_token_size),
             max_agg_token_size: core::cmp::min(lhs.max_agg_token_size, rhs.max_agg_token_size),
+            max_transactions: core::cmp::max(lhs.max_transactions, rhs.max_transactions), // Only the TPer has it.
             continued_tokens: lhs.continued_tokens && rhs.continued_tokens,
             seq_numbers: lhs.seq_numbers && rhs.seq_numbers,
             ack_nak: lhs.ack_nak && rhs.ack_nak,
@@ -18,8 +18,11 @@ pub enum Command {
     CloseComSession,
     TryShutdown,
     Method { id: SessionIdentifier, request: Promise<PackagedMethod, PackagedMethod, Error> },
+    Methods { id: SessionIdentifier, requests: Vec<Promise<PackagedMethod, PackagedMethod, Error>> },
     ComId { request: Promise<HandleComIdRequest, HandleComIdResponse, Error> },
     Discover { request: Promise<(), Discovery, Error> },
+    BlockSID { request: Promise<bool, (), Error> },
+    TPerReset { request: Promise<(), (), Error> },
 }
 
 #[derive(Clone)]
@@ -68,6 +71,30 @@ impl CommandSender {
         }
     }
 
+    /// Sends all methods as one batch so that they can share sub packets, and returns their responses in order.
+    pub async fn methods(
+        &self,
+        id: SessionIdentifier,
+        requests: Vec<PackagedMethod>,
+    ) -> Vec<Result<PackagedMethod, Error>> {
+        let (promises, receivers): (Vec<_>, Vec<_>) = requests
+            .into_iter()
+            .map(|request| {
+                let (tx, rx) = oneshot::channel();
+                (Promise::new(request, vec![tx]), rx)
+            })
+            .unzip();
+        let _ = self.tx.send(Command::Methods { id, requests: promises });
+        let mut responses = Vec::with_capacity(receivers.len());
+        for rx in receivers {
+            responses.push(match rx.await {
+                Ok(response) => response,
+                Err(_) => Err(Error::Closed),
+            });
+        }
+        responses
+    }
+
     pub async fn com_id(&self, request: HandleComIdRequest) -> Result<HandleComIdResponse, Error> {
         let (tx, rx) = oneshot::channel();
         let promise = Promise::new(request, vec![tx]);
@@ -87,4 +114,24 @@ impl CommandSender {
             Err(_) => Err(Error::Closed),
         }
     }
+
+    pub async fn block_sid(&self, hardware_reset: bool) -> Result<(), Error> {
+        let (tx, rx) = oneshot::channel();
+        let promise = Promise::new(hardware_reset, vec![tx]);
+        let _ = self.tx.send(Command::BlockSID { request: promise });
+        match rx.await {
+            Ok(response) => response,
+            Err(_) => Err(Error::Closed),
+        }
+    }
+
+    pub async fn tper_reset(&self) -> Result<(), Error> {
+        let (tx, rx) = oneshot::channel();
+        let promise = Promise::new((), vec![tx]);
+        let _ = self.tx.send(Command::TPerReset { request: promise });
+        match rx.await {
+            Ok(response) => response,
+            Err(_) => Err(Error::Closed),
+        }
+    }
 }
@@ -16,7 +16,7 @@ mod sync_protocol;
 mod tracing;
 
 pub use command::CommandSender;
-pub use protocol::discover;
 pub use protocol::Protocol;
-pub use runtime::{Runtime, TokioRuntime};
+pub use protocol::{block_sid, discover, tper_reset};
+pub use runtime::{Runtime, Timer, TokioRuntime, TokioTimer};
 pub use session_identifier::{SessionIdentifier, CONTROL_SESSION_ID};
@@ -19,6 +19,10 @@ impl<Request, Response, Error> Promise<Request, Response, Error> {
         Self { request, senders: vec![] }
     }
 
+    pub fn request(&self) -> &Request {
+        &self.request
+    }
+
     pub fn detach(self) -> (Request, Vec<oneshot::Sender<Result<Response, Error>>>) {
         (self.request, self.senders)
     }
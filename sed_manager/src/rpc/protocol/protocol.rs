@@ -5,15 +5,17 @@
 
 use core::task::Poll;
 use core::time::Duration;
+use std::collections::BTreeMap;
 use std::sync::Arc;
+use std::time::Instant;
 
 use tokio::sync::{mpsc, oneshot};
 
 use crate::device::Device;
 use crate::messaging::com_id::{HandleComIdRequest, HandleComIdResponse};
 use crate::messaging::discovery::Discovery;
-use crate::messaging::packet::ComPacket;
-use crate::rpc::{Error, PackagedMethod, Properties, SessionIdentifier};
+use crate::messaging::packet::{AckType, ComPacket, Packet};
+use crate::rpc::{Error, Properties, SessionIdentifier};
 use crate::serialization::DeserializeBinary;
 
 use super::command::Command;
@@ -42,6 +44,8 @@ pub struct Protocol {
     recv_sender: Buffer<(SessionIdentifier, receive_packet::Sender)>,
     recv_com_packet: Buffer<ComPacket>,
     recv_done: Buffer<SessionIdentifier>,
+    // Packet: ACK/NAK, with the number of retransmissions allowed per session
+    ack_nak_sessions: BTreeMap<SessionIdentifier, u32>,
     // ComID
     com_id_input: Buffer<Promise<HandleComIdRequest, HandleComIdResponse, Error>>,
     com_id_sender: Buffer<oneshot::Sender<Result<HandleComIdResponse, Error>>>,
@@ -54,9 +58,11 @@ enum CommandBatch {
     AbortSession { id: SessionIdentifier },
     CloseComSession,
     TryShutdown,
-    Method { buffer: Vec<(SessionIdentifier, Promise<PackagedMethod, PackagedMethod, Error>)> },
+    Method { buffer: Vec<send_packet::Input> },
     ComId { buffer: Vec<Promise<HandleComIdRequest, HandleComIdResponse, Error>> },
     Discover { request: Promise<(), Discovery, Error> },
+    BlockSID { request: Promise<bool, (), Error> },
+    TPerReset { request: Promise<(), (), Error> },
 }
 
 impl Protocol {
@@ -97,12 +103,16 @@ impl Protocol {
             recv_sender: Buffer::new(),
             recv_com_packet: Buffer::new(),
             recv_done: Buffer::new(),
+            ack_nak_sessions: BTreeMap::new(),
             com_id_input: Buffer::new(),
             com_id_sender: Buffer::new(),
             com_id_response: Buffer::new(),
         }
     }
 
+    /// The properties the host proposes by default.
+    ///
+    /// Optional features are proposed and are only used if the TPer also supports them.
     pub fn capabilities() -> Properties {
         use crate::messaging::packet::{COM_PACKET_HEADER_LEN, PACKET_HEADER_LEN, SUB_PACKET_HEADER_LEN};
         let max_transfer_len = 1048576;
@@ -115,9 +125,10 @@ impl Protocol {
             max_gross_compacket_response_size: max_transfer_len,
             max_ind_token_size: max_transfer_len - COM_PACKET_HEADER_LEN - PACKET_HEADER_LEN - SUB_PACKET_HEADER_LEN,
             max_agg_token_size: max_transfer_len - COM_PACKET_HEADER_LEN - PACKET_HEADER_LEN - SUB_PACKET_HEADER_LEN,
-            continued_tokens: false,
-            seq_numbers: false,
-            ack_nak: false,
+            max_transactions: 0,
+            continued_tokens: true,
+            seq_numbers: true,
+            ack_nak: true,
             asynchronous: true,
             buffer_mgmt: false,
             max_retries: 3,
@@ -157,9 +168,20 @@ impl Protocol {
         .await;
     }
 
+    /// Waits for the next command, then collects all the commands queued up behind it.
+    ///
+    /// When no responses are awaited, the loop sleeps until a command arrives. Otherwise,
+    /// it wakes up at the latest when a session times out so that the timeout gets reported.
     async fn recv_batches(&mut self) -> Vec<CommandBatch> {
         let mut batches = Vec::<CommandBatch>::new();
-        if let Ok(Some(command)) = self.timer.timeout(Duration::from_millis(64), self.rx.recv()).await {
+        let command = match self.receive_packet.deadline() {
+            Some(deadline) => {
+                let duration = deadline.saturating_duration_since(Instant::now());
+                self.timer.timeout(duration, self.rx.recv()).await.ok().flatten()
+            }
+            None => self.rx.recv().await,
+        };
+        if let Some(command) = command {
             append_command(&mut batches, command);
         }
         while let Ok(command) = self.rx.try_recv() {
@@ -171,6 +193,9 @@ impl Protocol {
     fn enqueue_command_batch(&mut self, command_batch: CommandBatch) {
         match command_batch {
             CommandBatch::OpenSession { id, properties } => {
+                if properties.seq_numbers && properties.ack_nak {
+                    self.ack_nak_sessions.insert(id, properties.max_retries);
+                }
                 self.send_packet.open_session(id, properties.clone());
                 self.receive_packet.open_session(id, properties);
             }
@@ -206,6 +231,18 @@ impl Protocol {
                 let (_, mut promises) = request.detach();
                 promises.pop().map(|pr| drop(pr.send(discover(&*self.device))));
             }
+            CommandBatch::BlockSID { request } => {
+                let (hardware_reset, mut promises) = request.detach();
+                if let Some(pr) = promises.pop() {
+                    let _ = pr.send(block_sid(&*self.device, hardware_reset));
+                }
+            }
+            CommandBatch::TPerReset { request } => {
+                let (_, mut promises) = request.detach();
+                if let Some(pr) = promises.pop() {
+                    let _ = pr.send(tper_reset(&*self.device));
+                }
+            }
         }
     }
 
@@ -238,12 +275,13 @@ impl Protocol {
         while let Poll::Ready(Some(id)) = self.recv_done.pop() {
             tracing::event!(tracing::Level::DEBUG, hsn = id.hsn, tsn = id.tsn, "[send] Issue abort");
             self.send_packet.abort_session(id);
+            self.ack_nak_sessions.remove(&id);
         }
     }
 
     async fn roundtrip_all_packet(&mut self) {
         while let Poll::Ready(Some((com_packet, promises))) = self.send_output.pop() {
-            let response = roundtrip_packet(&*self.device, self.com_id, com_packet, &self.properties).await;
+            let response = self.roundtrip_acknowledged(com_packet).await;
             match response {
                 Ok(com_packet) => {
                     self.recv_com_packet.push(com_packet);
@@ -268,6 +306,50 @@ impl Protocol {
         self.finalize_sessions();
     }
 
+    /// Roundtrips the ComPacket, then retransmits the packets the TPer did not acknowledge.
+    ///
+    /// Only the packets of sessions with ACK/NAK are checked. As the protocol is synchronous,
+    /// a response without an ACK means that either the packet or the response was lost.
+    async fn roundtrip_acknowledged(&self, com_packet: ComPacket) -> Result<ComPacket, Error> {
+        let mut response = roundtrip_packet(&*self.device, self.com_id, com_packet.clone(), &self.properties).await?;
+        let mut unacknowledged = self.filter_unacknowledged(com_packet, &response);
+        let mut num_retries = 0;
+        while !unacknowledged.payload.is_empty() {
+            if num_retries >= self.max_retries(&unacknowledged) {
+                return Err(Error::TimedOut);
+            }
+            num_retries += 1;
+            tracing::event!(tracing::Level::DEBUG, num_retries, "Retransmitting unacknowledged packets");
+            let retransmission = unacknowledged.clone();
+            let partial_response =
+                roundtrip_packet(&*self.device, self.com_id, retransmission, &self.properties).await?;
+            unacknowledged = self.filter_unacknowledged(unacknowledged, &partial_response);
+            response.append(partial_response);
+        }
+        Ok(response)
+    }
+
+    fn filter_unacknowledged(&self, com_packet: ComPacket, response: &ComPacket) -> ComPacket {
+        let ComPacket { payload, .. } = com_packet;
+        let payload: Vec<_> = payload
+            .into_vec()
+            .into_iter()
+            .filter(|packet| self.ack_nak_sessions.contains_key(&SessionIdentifier::from(packet)))
+            .filter(|packet| !is_acknowledged(packet, response))
+            .collect();
+        ComPacket { payload: payload.into(), ..com_packet }
+    }
+
+    fn max_retries(&self, com_packet: &ComPacket) -> u32 {
+        com_packet
+            .payload
+            .iter()
+            .filter_map(|packet| self.ack_nak_sessions.get(&SessionIdentifier::from(packet)))
+            .copied()
+            .min()
+            .unwrap_or(0)
+    }
+
     async fn roundtrip_all_com_id(&mut self) {
         while let Poll::Ready(Some(pr)) = self.com_id_input.pop() {
             let (request, senders) = pr.detach();
@@ -286,31 +368,62 @@ impl Protocol {
 fn append_command(batches: &mut Vec<CommandBatch>, command: Command) {
     // The order of the matchers matters!
     match (batches.last_mut(), command) {
-        (Some(CommandBatch::Method { buffer }), Command::Method { id, request }) => buffer.push((id, request)),
+        (Some(CommandBatch::Method { buffer }), Command::Method { id, request }) => buffer.push((id, vec![request])),
+        (Some(CommandBatch::Method { buffer }), Command::Methods { id, requests }) => buffer.push((id, requests)),
         (Some(CommandBatch::ComId { buffer }), Command::ComId { request }) => buffer.push(request),
-        (_, Command::Method { id, request }) => batches.push(CommandBatch::Method { buffer: vec![(id, request)] }),
+        (_, Command::Method { id, request }) => {
+            batches.push(CommandBatch::Method { buffer: vec![(id, vec![request])] })
+        }
+        (_, Command::Methods { id, requests }) => batches.push(CommandBatch::Method { buffer: vec![(id, requests)] }),
         (_, Command::ComId { request }) => batches.push(CommandBatch::ComId { buffer: vec![request] }),
         (_, Command::OpenSession { id, properties }) => batches.push(CommandBatch::OpenSession { id, properties }),
         (_, Command::CloseSession { id }) => batches.push(CommandBatch::CloseSession { id }),
         (_, Command::AbortSession { id }) => batches.push(CommandBatch::AbortSession { id }),
         (_, Command::Discover { request }) => batches.push(CommandBatch::Discover { request }),
+        (_, Command::BlockSID { request }) => batches.push(CommandBatch::BlockSID { request }),
+        (_, Command::TPerReset { request }) => batches.push(CommandBatch::TPerReset { request }),
         (_, Command::CloseComSession) => batches.push(CommandBatch::CloseComSession),
         (_, Command::TryShutdown) => batches.push(CommandBatch::TryShutdown),
     }
 }
 
+fn is_acknowledged(packet: &Packet, response: &ComPacket) -> bool {
+    let id = SessionIdentifier::from(packet);
+    response.payload.iter().any(|ack| {
+        SessionIdentifier::from(ack) == id
+            && ack.ack_type == AckType::ACK
+            && ack.acknowledgement >= packet.sequence_number
+    })
+}
+
 pub fn discover(device: &dyn Device) -> Result<Discovery, Error> {
     let data = device.security_recv(0x01, 0x0001_u16.to_be_bytes(), 4096)?;
     let discovery = Discovery::from_bytes(data)?;
     Ok(discovery.remove_empty())
 }
 
+/// Blocks SID authentication until the next power cycle, or also until the next hardware reset if requested.
+pub fn block_sid(device: &dyn Device, hardware_reset: bool) -> Result<(), Error> {
+    // Bit 0 of the Clear Events field selects whether a hardware reset also clears the block.
+    let mut data = vec![0_u8; 512];
+    data[0] = hardware_reset as u8;
+    Ok(device.security_send(0x02, 0x0005_u16.to_be_bytes(), &data)?)
+}
+
+/// Resets the TPer, which aborts all sessions and applies the programmatic reset to the locking ranges.
+///
+/// The TPer ignores the command unless `ProgrammaticResetEnable` is set in the TPerInfo table.
+pub fn tper_reset(device: &dyn Device) -> Result<(), Error> {
+    let data = vec![0_u8; 512];
+    Ok(device.security_send(0x02, 0x0004_u16.to_be_bytes(), &data)?)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::{
         fake_device::{FakeDevice, BASE_COM_ID},
-        rpc::{protocol::runtime::TokioRuntime, MethodCall},
+        rpc::{protocol::runtime::TokioRuntime, MethodCall, PackagedMethod},
         spec::{invoking_id::SESSION_MANAGER, sm_method_id::PROPERTIES},
     };
 
@@ -21,6 +21,7 @@ pub struct AssembleMethod {
 enum Phase {
     Invocation,
     Result(usize),
+    Transaction,
     Done,
 }
 
@@ -91,6 +92,7 @@ impl Phase {
             Self::Invocation => match tag {
                 Tag::EndOfData => Self::Result(0),
                 Tag::EndOfSession => Self::Done,
+                Tag::StartTransaction | Tag::EndTransaction => Self::Transaction,
                 _ => self.clone(),
             },
             Self::Result(depth) => {
@@ -104,9 +106,11 @@ impl Phase {
                     _ => Self::Result(new_depth),
                 }
             }
+            Self::Transaction => Self::Done, // The status code follows the transaction token.
             Self::Done => match tag {
                 Tag::EndOfData => Self::Result(0),
                 Tag::EndOfSession => Self::Done,
+                Tag::StartTransaction | Tag::EndTransaction => Self::Transaction,
                 _ => Self::Invocation,
             },
         }
@@ -157,6 +161,33 @@ mod tests {
         assert_eq!(output.pop(), Poll::Pending);
     }
 
+    #[test]
+    fn transaction() {
+        let items = [
+            PackagedMethod::StartTransaction(0),
+            PackagedMethod::Result(MethodResult { results: vec![], status: MethodStatus::Success }),
+            PackagedMethod::EndTransaction(MethodStatus::TransactionFailure as u8),
+        ];
+        let mut stream = OutputStream::<Token>::new();
+        for item in &items {
+            item.serialize(&mut stream).unwrap();
+        }
+
+        let mut input = Buffer::<Result<Token, Error>>::new();
+        let mut output = Buffer::new();
+        for token in stream.take() {
+            input.push(Ok(token));
+        }
+
+        let mut assemble = AssembleMethod::new();
+        assemble.update(&mut input, &mut output);
+
+        assert_eq!(output.pop(), Poll::Ready(Some(Ok(items[0].clone()))));
+        assert_eq!(output.pop(), Poll::Ready(Some(Ok(items[1].clone()))));
+        assert_eq!(output.pop(), Poll::Ready(Some(Ok(items[2].clone()))));
+        assert_eq!(output.pop(), Poll::Pending);
+    }
+
     #[test]
     fn invalid_delimiter() {
         let tokens = [
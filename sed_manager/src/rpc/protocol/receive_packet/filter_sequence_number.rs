@@ -0,0 +1,43 @@
+//L-----------------------------------------------------------------------------
+//L Copyright (C) Péter Kardos
+//L Please refer to the full license distributed with this software.
+//L-----------------------------------------------------------------------------
+
+use core::task::Poll::*;
+
+use crate::messaging::packet::Packet;
+use crate::rpc::protocol::shared::pipe::{SinkPipe, SourcePipe};
+use crate::rpc::Properties;
+
+pub type Input = Packet;
+pub type Output = Packet;
+
+/// Drops the packets that were already received if the session uses sequence numbers.
+///
+/// The TPer sends its response again when the host retransmits a packet, so the same
+/// response may arrive twice. Packets without payload, such as a plain NAK, carry no
+/// sequence number and are always let through.
+pub fn filter_sequence_number(
+    input: &mut dyn SourcePipe<Input>,
+    output: &mut dyn SinkPipe<Output>,
+    last_sequence_number: &mut u32,
+    properties: &Properties,
+) {
+    while let Ready(Some(packet)) = input.pop() {
+        if !properties.seq_numbers || !packet.has_payload() {
+            output.push(packet);
+        } else if packet.sequence_number > *last_sequence_number {
+            *last_sequence_number = packet.sequence_number;
+            output.push(packet);
+        } else {
+            tracing::event!(
+                tracing::Level::DEBUG,
+                sequence_number = packet.sequence_number,
+                "[recv] Dropped duplicate"
+            );
+        }
+    }
+    if input.is_done() {
+        output.close();
+    }
+}
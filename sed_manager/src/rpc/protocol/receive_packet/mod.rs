@@ -5,11 +5,13 @@
 
 use core::task::Poll;
 use std::collections::BTreeMap;
+use std::time::Instant;
 
 use assemble_method::AssembleMethod;
 pub use commit::commit;
 use deserialize_sub_packet::deserialize_sub_packet;
 use filter_close_session::filter_close_session;
+use filter_sequence_number::filter_sequence_number;
 use flatten_packet::flatten_packet;
 use tokio::sync::oneshot;
 
@@ -28,6 +30,7 @@ mod assemble_method;
 mod commit;
 mod deserialize_sub_packet;
 mod filter_close_session;
+mod filter_sequence_number;
 mod flatten_com_packet;
 mod flatten_packet;
 
@@ -41,7 +44,8 @@ pub struct ReceivePacket {
 struct Session {
     id: SessionIdentifier,
     sender: Buffer<Sender>,                        // Channel to send results back to TPer API.
-    packet: Buffer<flatten_packet::Input>,         // Input packets.
+    packet: Buffer<filter_sequence_number::Input>, // Input packets.
+    sequenced: Buffer<flatten_packet::Input>,      // Input packets without duplicates.
     sub_packet: Buffer<flatten_packet::Output>,    // Input packets broken into sub-packets.
     token: Buffer<deserialize_sub_packet::Output>, // Input sub-packets deserialized into tokens.
     method: Buffer<assemble_method::Output>,       // Input tokens assembled into methods.
@@ -50,6 +54,8 @@ struct Session {
     in_time: Buffer<assemble_method::Output>,      // Input methods after timeout is applied.
     assemble_method: AssembleMethod,
     timeout: Timeout,
+    properties: Properties,
+    last_sequence_number: u32,
     tracing_span: tracing::Span,
 }
 
@@ -81,12 +87,24 @@ impl ReceivePacket {
         self.sessions.is_empty()
     }
 
+    /// The earliest point in time when a session waiting for responses times out.
+    ///
+    /// None if no session is waiting for responses, in which case no session can time out.
+    pub fn deadline(&self) -> Option<Instant> {
+        self.sessions.values().filter_map(|session| session.deadline()).min()
+    }
+
     pub fn update(
         &mut self,
         sender: &mut dyn SourcePipe<(SessionIdentifier, Sender)>,
         com_packet: &mut dyn SourcePipe<ComPacket>,
         done: &mut dyn SinkPipe<SessionIdentifier>,
     ) {
+        // The timeout of idle sessions starts when they receive a sender.
+        for session in self.sessions.values_mut() {
+            session.reset_idle_timeout();
+        }
+
         // Always distribute senders first.
         distribute(
             sender,
@@ -160,6 +178,7 @@ impl Session {
             id,
             sender: Buffer::new(),
             packet: Buffer::new(),
+            sequenced: Buffer::new(),
             sub_packet: Buffer::new(),
             token: Buffer::new(),
             method: Buffer::new(),
@@ -168,6 +187,8 @@ impl Session {
             in_time: Buffer::new(),
             assemble_method: AssembleMethod::new(),
             timeout: Timeout::new(properties.trans_timeout),
+            properties,
+            last_sequence_number: 0,
             tracing_span,
         }
     }
@@ -192,10 +213,8 @@ impl Session {
         if self.id != CONTROL_SESSION_ID {
             self.closed_sessions.close();
         }
-        if self.sender.is_empty() {
-            self.timeout.reset();
-        }
-        flatten_packet(&mut self.packet, &mut self.sub_packet);
+        filter_sequence_number(&mut self.packet, &mut self.sequenced, &mut self.last_sequence_number, &self.properties);
+        flatten_packet(&mut self.sequenced, &mut self.sub_packet);
         deserialize_sub_packet(&mut self.sub_packet, &mut self.token);
         self.assemble_method.update(&mut self.token, &mut self.method);
         filter_close_session(&mut self.method, &mut self.filtered, &mut self.closed_sessions);
@@ -208,6 +227,16 @@ impl Session {
         self.restore_control_session();
     }
 
+    pub fn reset_idle_timeout(&mut self) {
+        if self.sender.is_empty() {
+            self.timeout.reset();
+        }
+    }
+
+    pub fn deadline(&self) -> Option<Instant> {
+        (!self.sender.is_empty()).then(|| self.timeout.deadline())
+    }
+
     pub fn is_done(&self) -> bool {
         commit::is_done(&self.sender, &self.in_time)
     }
@@ -370,6 +399,58 @@ mod tests {
         assert!(!done.is_closed());
     }
 
+    #[test]
+    fn deadline_only_when_waiting() {
+        let id = SessionIdentifier { hsn: 0, tsn: 0 };
+        let (mut sender, mut com_packet, mut node, mut done) = setup();
+        node.open_session(id, Properties::ASSUMED);
+        node.update(&mut sender, &mut com_packet, &mut done);
+        assert_eq!(node.deadline(), None);
+
+        let (tx, _rx) = make_channel();
+        sender.push((id, tx));
+        node.update(&mut sender, &mut com_packet, &mut done);
+        assert!(node.deadline().is_some());
+
+        com_packet.push(make_com_packet(id, true));
+        node.update(&mut sender, &mut com_packet, &mut done);
+        assert_eq!(node.deadline(), None);
+    }
+
+    #[test]
+    fn idle_session_not_timed_out() {
+        let id = SessionIdentifier { hsn: 0, tsn: 0 };
+        let (mut sender, mut com_packet, mut node, mut done) = setup();
+        node.open_session(id, SHORT_TIMEOUT);
+        std::thread::sleep(SHORT_TIMEOUT.trans_timeout * 2);
+
+        let (tx, mut rx) = make_channel();
+        sender.push((id, tx));
+        node.update(&mut sender, &mut com_packet, &mut done);
+        assert_eq!(rx.try_recv(), Err(oneshot::error::TryRecvError::Empty));
+        assert!(!done.is_closed());
+    }
+
+    #[test]
+    fn duplicate_packet_dropped() {
+        let id = SessionIdentifier { hsn: 0, tsn: 0 };
+        let properties = Properties { seq_numbers: true, ..Properties::ASSUMED };
+        let (mut sender, mut com_packet, mut node, mut done) = setup();
+        node.open_session(id, properties);
+        for _ in 0..2 {
+            let mut packet = make_com_packet(id, true);
+            packet.payload[0].sequence_number = 1;
+            com_packet.push(packet);
+        }
+        let (tx_first, mut rx_first) = make_channel();
+        let (tx_second, mut rx_second) = make_channel();
+        sender.push((id, tx_first));
+        sender.push((id, tx_second));
+        node.update(&mut sender, &mut com_packet, &mut done);
+        assert_eq!(rx_first.try_recv(), Ok(Ok(PackagedMethod::EndOfSession)));
+        assert_eq!(rx_second.try_recv(), Err(oneshot::error::TryRecvError::Empty));
+    }
+
     #[test]
     fn closing_session() {
         let id = SessionIdentifier { hsn: 0, tsn: 0 };
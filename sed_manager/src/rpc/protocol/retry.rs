@@ -8,18 +8,26 @@ use std::time::Instant;
 
 use crate::rpc::error::Error;
 
+/// Delays polling the TPer for a response with an exponential backoff.
+///
+/// Most responses are ready within microseconds, so the first few polls follow
+/// each other closely. Long operations, such as generating keys, may take seconds
+/// to complete, so the delay doubles with each poll up to a limit.
 pub struct Retry {
-    start_time: Instant,
     deadline: Instant,
     sleep_duration: Duration,
+    max_sleep_duration: Duration,
 }
 
+const MIN_SLEEP_DURATION: Duration = Duration::from_micros(10);
+const MAX_SLEEP_DURATION: Duration = Duration::from_millis(16);
+
 impl Retry {
     pub fn new(timeout: Duration) -> Self {
-        let start_time = Instant::now();
-        let deadline = start_time + timeout * 2;
-        let sleep_duration = core::cmp::min(timeout / 200, Duration::from_micros(10));
-        Self { start_time, deadline, sleep_duration }
+        let deadline = Instant::now() + timeout * 2;
+        let max_sleep_duration = core::cmp::min(timeout / 8, MAX_SLEEP_DURATION);
+        let sleep_duration = core::cmp::min(MIN_SLEEP_DURATION, max_sleep_duration);
+        Self { deadline, sleep_duration, max_sleep_duration }
     }
 
     pub async fn sleep(&mut self) -> Result<(), Error> {
@@ -27,8 +35,8 @@ impl Retry {
         if self.deadline <= current_time {
             Err(Error::TimedOut)
         } else {
-            sleep(self.sleep_duration).await;
-            self.sleep_duration = core::cmp::min(self.sleep_duration, (self.deadline - self.start_time) / 7);
+            sleep(core::cmp::min(self.sleep_duration, self.deadline - current_time)).await;
+            self.sleep_duration = core::cmp::min(self.sleep_duration * 2, self.max_sleep_duration);
             Ok(())
         }
     }
@@ -36,13 +44,17 @@ impl Retry {
 
 /// Combines busy wait and OS sleep to introduce delay.
 ///
-/// For short sleeps of just a few milliseconds, the OS sleep function might
+/// For sleeps shorter than a millisecond, the OS sleep function might
 /// sleep far longer than we need. This would mean that the device is polled
 /// with IF-RECV much less often, and that can slow down the synchronous
 /// communication protocol by a large margin. A finer loop-based sleep should
 /// allow the device to be polled for replies often.
+///
+/// Tokio's timer has a resolution of one millisecond, so longer sleeps overshoot
+/// by less than their own duration. Busy waiting for them would keep a CPU core
+/// occupied while the TPer works on a slow operation, such as generating a key.
 async fn sleep(duration: Duration) {
-    if duration < Duration::from_millis(8) {
+    if duration < Duration::from_millis(1) {
         let start = Instant::now();
         let end = start + duration;
         while Instant::now() < end {
@@ -52,3 +64,30 @@ async fn sleep(duration: Duration) {
         tokio::time::sleep(duration).await;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn backoff() {
+        let mut retry = Retry::new(Duration::from_secs(1));
+        let mut durations = Vec::new();
+        for _ in 0..14 {
+            durations.push(retry.sleep_duration);
+            retry.sleep().await.unwrap();
+        }
+        assert_eq!(durations[0], MIN_SLEEP_DURATION);
+        assert_eq!(durations[1], MIN_SLEEP_DURATION * 2);
+        assert_eq!(durations[13], MAX_SLEEP_DURATION);
+    }
+
+    #[tokio::test]
+    async fn timed_out() {
+        let timeout = Duration::from_millis(4);
+        let start = Instant::now();
+        let mut retry = Retry::new(timeout);
+        while retry.sleep().await.is_ok() {}
+        assert!(start.elapsed() >= timeout * 2);
+    }
+}
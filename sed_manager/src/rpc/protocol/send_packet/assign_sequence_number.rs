@@ -0,0 +1,36 @@
+//L-----------------------------------------------------------------------------
+//L Copyright (C) Péter Kardos
+//L Please refer to the full license distributed with this software.
+//L-----------------------------------------------------------------------------
+
+use core::task::Poll::*;
+
+use crate::messaging::packet::Packet;
+use crate::rpc::protocol::promise::Promise;
+use crate::rpc::protocol::shared::pipe::{SinkPipe, SourcePipe};
+use crate::rpc::{Error, PackagedMethod, Properties};
+
+pub type Input = Promise<Packet, PackagedMethod, Error>;
+pub type Output = Promise<Packet, PackagedMethod, Error>;
+
+/// Numbers the packets 1, 2, 3... if the session uses sequence numbers, otherwise leaves them at zero.
+pub fn assign_sequence_number(
+    input: &mut dyn SourcePipe<Input>,
+    output: &mut dyn SinkPipe<Output>,
+    last_sequence_number: &mut u32,
+    properties: &Properties,
+) {
+    while let Ready(Some(message)) = input.pop() {
+        let message = if properties.seq_numbers {
+            *last_sequence_number += 1;
+            let sequence_number = *last_sequence_number;
+            message.map(|packet| Packet { sequence_number, ..packet })
+        } else {
+            message
+        };
+        output.push(message);
+    }
+    if input.is_done() {
+        output.close();
+    }
+}
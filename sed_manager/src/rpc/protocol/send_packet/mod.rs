@@ -14,16 +14,19 @@ use crate::rpc::protocol::shared::buffer::Buffer;
 use crate::rpc::protocol::shared::gather::gather;
 use assemble_com_packet::AssembleComPacket;
 use assemble_packet::assemble_packet;
+use assign_sequence_number::assign_sequence_number;
 use assign_session_id::assign_session_id;
 use serialize_method::serialize_method;
 
 mod assemble_com_packet;
 mod assemble_packet;
+mod assign_sequence_number;
 mod assign_session_id;
 mod serialize_method;
 
 type Request = Promise<PackagedMethod, PackagedMethod, Error>;
-pub type Input = (SessionIdentifier, Request);
+/// A batch of methods of a session, see [`serialize_method`].
+pub type Input = (SessionIdentifier, Vec<Request>);
 pub type Output = assemble_com_packet::Output;
 
 pub struct SendPacket {
@@ -38,7 +41,9 @@ struct Session {
     method: Buffer<serialize_method::Input>,
     serialized: Buffer<serialize_method::Output>,
     assembled: Buffer<assemble_packet::Output>,
+    sequenced: Buffer<assign_sequence_number::Output>,
     assigned: Buffer<assign_session_id::Output>,
+    last_sequence_number: u32,
     tracing_span: tracing::Span,
 }
 
@@ -91,10 +96,10 @@ impl SendPacket {
 
     fn distribution_table<'a>(
         sessions: &'a mut BTreeMap<SessionIdentifier, Session>,
-    ) -> BTreeMap<SessionIdentifier, &'a mut dyn SinkPipe<Request>> {
+    ) -> BTreeMap<SessionIdentifier, &'a mut dyn SinkPipe<Vec<Request>>> {
         sessions
             .iter_mut()
-            .map(|(id, session)| (*id, session.input() as &mut dyn SinkPipe<Request>))
+            .map(|(id, session)| (*id, session.input() as &mut dyn SinkPipe<Vec<Request>>))
             .collect()
     }
 
@@ -137,7 +142,9 @@ impl Session {
             method: Buffer::new(),
             serialized: Buffer::new(),
             assembled: Buffer::new(),
+            sequenced: Buffer::new(),
             assigned: Buffer::new(),
+            last_sequence_number: 0,
             tracing_span,
         }
     }
@@ -146,7 +153,13 @@ impl Session {
         let _guard = self.tracing_span.enter();
         serialize_method(&mut self.method, &mut self.serialized, &self.properties);
         assemble_packet(&mut self.serialized, &mut self.assembled, &self.properties);
-        assign_session_id(&mut self.assembled, &mut self.assigned, &self.id);
+        assign_sequence_number(
+            &mut self.assembled,
+            &mut self.sequenced,
+            &mut self.last_sequence_number,
+            &self.properties,
+        );
+        assign_session_id(&mut self.sequenced, &mut self.assigned, &self.id);
     }
 
     pub fn input(&mut self) -> &mut Buffer<serialize_method::Input> {
@@ -200,7 +213,7 @@ mod tests {
     fn invalid_session() {
         let id = SessionIdentifier { hsn: 0, tsn: 0 };
         let (mut input, mut node, mut output, mut done) = setup();
-        input.push((id, Promise::new(PackagedMethod::EndOfSession, vec![])));
+        input.push((id, vec![Promise::new(PackagedMethod::EndOfSession, vec![])]));
         node.update(&mut input, &mut output, &mut done);
         assert!(!output.is_done());
         assert!(!done.is_done());
@@ -210,7 +223,7 @@ mod tests {
     fn active_session() {
         let id = SessionIdentifier { hsn: 0, tsn: 0 };
         let (mut input, mut node, mut output, mut done) = setup();
-        input.push((id, Promise::new(PackagedMethod::EndOfSession, vec![])));
+        input.push((id, vec![Promise::new(PackagedMethod::EndOfSession, vec![])]));
         node.open_session(id, Properties::ASSUMED);
         node.update(&mut input, &mut output, &mut done);
         assert_eq!(node.sessions.len(), 1);
@@ -221,6 +234,23 @@ mod tests {
         assert!(!done.is_done());
     }
 
+    #[test]
+    fn sequence_numbers() {
+        let id = SessionIdentifier { hsn: 0, tsn: 0 };
+        let properties = Properties { seq_numbers: true, ..Properties::ASSUMED };
+        let (mut input, mut node, mut output, mut done) = setup();
+        input.push((id, vec![Promise::new(PackagedMethod::EndOfSession, vec![])]));
+        input.push((id, vec![Promise::new(PackagedMethod::EndOfSession, vec![])]));
+        node.open_session(id, properties);
+        node.update(&mut input, &mut output, &mut done);
+        for expected in [1, 2] {
+            let Poll::Ready(Some((com_packet, _))) = output.pop() else {
+                panic!("expected a com packet");
+            };
+            assert_eq!(com_packet.payload[0].sequence_number, expected);
+        }
+    }
+
     #[test]
     fn closing_session() {
         let id = SessionIdentifier { hsn: 0, tsn: 0 };
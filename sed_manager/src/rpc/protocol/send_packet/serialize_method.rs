@@ -5,8 +5,10 @@
 
 use core::task::Poll::*;
 
-use crate::messaging::packet::{SubPacket, SubPacketKind};
-use crate::messaging::token::SerializeTokens;
+use crate::messaging::packet::{
+    SubPacket, SubPacketKind, COM_PACKET_HEADER_LEN, PACKET_HEADER_LEN, SUB_PACKET_HEADER_LEN,
+};
+use crate::messaging::token::{split_continued, SerializeTokens, Token};
 use crate::rpc::protocol::promise::Promise;
 use crate::rpc::protocol::shared::pipe::{SinkPipe, SourcePipe};
 use crate::rpc::protocol::tracing::trace_method;
@@ -14,26 +16,189 @@ use crate::rpc::{Error, PackagedMethod, Properties};
 use crate::serialization::vec_without_len::VecWithoutLen;
 use crate::serialization::SerializeBinary;
 
-pub type Input = Promise<PackagedMethod, PackagedMethod, Error>;
+pub type Input = Vec<Promise<PackagedMethod, PackagedMethod, Error>>;
 pub type Output = Promise<SubPacket, PackagedMethod, Error>;
 
+/// Serializes the methods into sub packets.
+///
+/// Each input is a batch of methods that the caller sent together. The methods of a batch
+/// are bundled into the same sub packet as long as the properties allow it, but methods of
+/// different batches never share a sub packet. Single methods, including EndOfSession,
+/// arrive as a batch of one and therefore get their own sub packet.
 pub fn serialize_method(input: &mut dyn SourcePipe<Input>, output: &mut dyn SinkPipe<Output>, properties: &Properties) {
-    while let Ready(Some(message)) = input.pop() {
-        let message = message.try_map(|message| {
-            trace_method(&message, "send");
-            let tokens = message.to_tokens()?;
-            if tokens.iter().any(|token| token.data.len() + 4 > properties.max_ind_token_size) {
+    while let Ready(Some(batch)) = input.pop() {
+        let mut bundle: Option<(Output, usize)> = None;
+        for message in batch {
+            let message = message.try_map(|message| {
+                trace_method(&message, "send");
+                let tokens = limit_token_size(message.to_tokens()?, properties)?;
+                let bytes = VecWithoutLen::from(tokens).to_bytes()?;
+                let sub_packet = SubPacket { payload: bytes.into(), kind: SubPacketKind::Data };
+                Ok(sub_packet)
+            });
+            let Some(message) = message else {
+                continue;
+            };
+            bundle = match bundle.take() {
+                Some((current, num_methods)) if fits(&current, num_methods, &message, properties) => {
+                    Some((append(current, message), num_methods + 1))
+                }
+                Some((current, _)) => {
+                    output.push(current);
+                    Some((message, 1))
+                }
+                None => Some((message, 1)),
+            };
+        }
+        if let Some((current, _)) = bundle {
+            output.push(current);
+        }
+    }
+    if input.is_done() {
+        output.close();
+    }
+}
+
+/// Splits byte tokens into continued tokens if needed and allowed, or fails if the tokens are too large.
+fn limit_token_size(tokens: Vec<Token>, properties: &Properties) -> Result<Vec<Token>, Error> {
+    // The header of a long atom takes 4 bytes.
+    let max_data_len = properties.max_ind_token_size.saturating_sub(4);
+    if !properties.continued_tokens {
+        match tokens.iter().any(|token| token.data.len() > max_data_len) {
+            true => Err(Error::TokenTooLarge),
+            false => Ok(tokens),
+        }
+    } else {
+        let mut limited = Vec::with_capacity(tokens.len());
+        for token in tokens {
+            if token.data.len() > properties.max_agg_token_size {
                 return Err(Error::TokenTooLarge);
             }
-            let bytes = VecWithoutLen::from(tokens).to_bytes()?;
-            let sub_packet = SubPacket { payload: bytes.into(), kind: SubPacketKind::Data };
-            Ok(sub_packet)
-        });
-        if let Some(message) = message {
-            output.push(message);
+            limited.extend(split_continued(token, max_data_len));
+        }
+        Ok(limited)
+    }
+}
+
+fn fits(bundle: &Output, num_methods: usize, message: &Output, properties: &Properties) -> bool {
+    let payload_len = bundle.request().payload.len() + message.request().payload.len();
+    let packet_len = PACKET_HEADER_LEN + SUB_PACKET_HEADER_LEN + payload_len;
+    num_methods < properties.max_methods
+        && packet_len <= properties.max_gross_packet_size
+        && COM_PACKET_HEADER_LEN + packet_len <= properties.max_gross_compacket_size
+}
+
+fn append(bundle: Output, message: Output) -> Output {
+    let (mut sub_packet, mut senders) = bundle.detach();
+    let (other, other_senders) = message.detach();
+    sub_packet.payload.extend_from_slice(&other.payload);
+    senders.extend(other_senders);
+    Promise::new(sub_packet, senders)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::rpc::args::IntoMethodArgs as _;
+    use crate::rpc::protocol::shared::buffer::Buffer;
+    use crate::rpc::MethodCall;
+    use crate::spec::invoking_id::THIS_SP;
+    use crate::spec::method_id::{GET, SET};
+
+    fn batch(count: usize) -> Input {
+        (0..count)
+            .map(|_| {
+                let call = MethodCall::new_success(THIS_SP, GET.as_uid(), vec![]);
+                Promise::from_message(PackagedMethod::Call(call))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn bundle_max_methods() {
+        let properties = Properties { max_methods: 2, ..Properties::ASSUMED };
+        let mut input = Buffer::new();
+        let mut output = Buffer::new();
+        input.push(batch(3));
+        serialize_method(&mut input, &mut output, &properties);
+        assert!(output.pop().is_ready());
+        assert!(output.pop().is_ready());
+        assert!(output.pop().is_pending());
+    }
+
+    #[test]
+    fn bundle_max_packet_size() {
+        let properties = Properties { max_methods: usize::MAX, max_gross_packet_size: 100, ..Properties::ASSUMED };
+        let mut input = Buffer::new();
+        let mut output = Buffer::new();
+        input.push(batch(3));
+        serialize_method(&mut input, &mut output, &properties);
+        let mut num_sub_packets = 0;
+        while let Ready(Some(sub_packet)) = output.pop() {
+            let payload_len = sub_packet.request().payload.len();
+            assert!(PACKET_HEADER_LEN + SUB_PACKET_HEADER_LEN + payload_len <= 100);
+            num_sub_packets += 1;
+        }
+        assert!(num_sub_packets > 1);
+    }
+
+    fn push_write(input: &mut Buffer<Input>, len: usize) {
+        let args = (Some(0_u64), Some(vec![0xAD_u8; len])).into_method_args();
+        let call = MethodCall::new_success(THIS_SP, SET.as_uid(), args);
+        input.push(vec![Promise::from_message(PackagedMethod::Call(call))]);
+    }
+
+    #[test]
+    fn token_too_large() {
+        let properties = Properties { max_ind_token_size: 64, ..Properties::ASSUMED };
+        let mut input = Buffer::new();
+        let mut output = Buffer::new();
+        push_write(&mut input, 100);
+        serialize_method(&mut input, &mut output, &properties);
+        assert!(output.pop().is_pending());
+    }
+
+    #[test]
+    fn token_continued() {
+        let properties = Properties { max_ind_token_size: 64, continued_tokens: true, ..Properties::ASSUMED };
+        let mut input = Buffer::new();
+        let mut output = Buffer::new();
+        push_write(&mut input, 100);
+        serialize_method(&mut input, &mut output, &properties);
+        let Ready(Some(sub_packet)) = output.pop() else {
+            panic!("expected a sub packet");
         };
+        assert!(sub_packet.request().payload.len() > 100);
     }
-    if input.is_done() {
-        output.close();
+
+    #[test]
+    fn token_continued_aggregate_too_large() {
+        let properties = Properties {
+            max_ind_token_size: 64,
+            max_agg_token_size: 80,
+            continued_tokens: true,
+            ..Properties::ASSUMED
+        };
+        let mut input = Buffer::new();
+        let mut output = Buffer::new();
+        push_write(&mut input, 100);
+        serialize_method(&mut input, &mut output, &properties);
+        assert!(output.pop().is_pending());
+    }
+
+    #[test]
+    fn bundle_batches_separately() {
+        let properties = Properties { max_methods: usize::MAX, ..Properties::ASSUMED };
+        let mut input = Buffer::new();
+        let mut output = Buffer::new();
+        input.push(batch(2));
+        input.push(batch(1));
+        input.push(vec![Promise::from_message(PackagedMethod::EndOfSession)]);
+        serialize_method(&mut input, &mut output, &properties);
+        assert!(output.pop().is_ready());
+        assert!(output.pop().is_ready());
+        assert!(output.pop().is_ready());
+        assert!(output.pop().is_pending());
     }
 }
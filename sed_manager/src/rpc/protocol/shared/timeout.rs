@@ -23,6 +23,11 @@ impl Timeout {
         self.last = Instant::now();
     }
 
+    /// The point in time after which [`Self::update`] closes the output.
+    pub fn deadline(&self) -> Instant {
+        self.last + self.timeout
+    }
+
     pub fn update<Item>(
         &mut self,
         input: &mut dyn SourcePipe<Item>,
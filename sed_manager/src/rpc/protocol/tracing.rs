@@ -37,6 +37,12 @@ pub fn trace_method(result: &PackagedMethod, direction: &str) {
             );
         }
         PackagedMethod::EndOfSession => tracing::event!(tracing::Level::DEBUG, "[{direction}] EOS"),
+        PackagedMethod::StartTransaction(status) => {
+            tracing::event!(tracing::Level::DEBUG, status = status, "[{direction}] START TRANSACTION")
+        }
+        PackagedMethod::EndTransaction(status) => {
+            tracing::event!(tracing::Level::DEBUG, status = status, "[{direction}] END TRANSACTION")
+        }
     }
 }
 
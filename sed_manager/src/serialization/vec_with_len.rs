@@ -81,7 +81,7 @@ where
         let data_pos = stream.stream_position();
         let mut idx = 0_usize;
         for value in &self.data {
-            annotate_field(value.serialize(stream), format!("data[{}]", idx))?;
+            value.serialize(stream).map_err(|err| Error::field(format!("data[{}]", idx), err.into()))?;
             idx += 1;
         }
 
@@ -114,7 +114,8 @@ where
         let end_pos = data_pos + len as u64;
         let mut data = Vec::<T>::new();
         while stream.stream_position() < end_pos {
-            let item = annotate_field(T::deserialize(stream), format!("data[{}]", data.len()))?;
+            let item =
+                T::deserialize(stream).map_err(|err| Error::field(format!("data[{}]", data.len()), err.into()))?;
             data.push(item);
         }
         if stream.stream_position() != end_pos {
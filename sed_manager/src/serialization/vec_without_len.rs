@@ -3,7 +3,7 @@
 //L Please refer to the full license distributed with this software.
 //L-----------------------------------------------------------------------------
 
-use super::{annotate_field, stream::Seek, Deserialize, Error, InputStream, OutputStream, Serialize};
+use super::{stream::Seek, Deserialize, Error, InputStream, OutputStream, Serialize};
 use core::{ops::Deref, ops::DerefMut};
 
 /// A vector of `T` with special a serialization format.
@@ -68,7 +68,7 @@ where
     fn serialize(&self, stream: &mut OutputStream<Item>) -> Result<(), Self::Error> {
         let mut idx = 0_usize;
         for value in &self.data {
-            annotate_field(value.serialize(stream), format!("data[{}]", idx))?;
+            value.serialize(stream).map_err(|err| Error::field(format!("data[{}]", idx), err.into()))?;
             idx += 1;
         }
         Ok(())
@@ -84,7 +84,8 @@ where
     fn deserialize(stream: &mut InputStream<Item>) -> Result<VecWithoutLen<T>, Self::Error> {
         let mut data = Vec::<T>::new();
         while stream.stream_position() < stream.stream_len() {
-            let item = annotate_field(T::deserialize(stream), format!("data[{}]", data.len()))?;
+            let item =
+                T::deserialize(stream).map_err(|err| Error::field(format!("data[{}]", data.len()), err.into()))?;
             data.push(item);
         }
         Ok(VecWithoutLen::from(data))
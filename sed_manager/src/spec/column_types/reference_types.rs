@@ -25,9 +25,15 @@ pub type LogListRef = ObjectUID<{ LOG_LIST.mask() }>;
 pub type LockingRangeRef = ObjectUID<{ LOCKING.mask() }>;
 pub type MediaKeyRef = ObjectUID<{ K_AES_128.mask() | K_AES_256.mask() }>;
 pub type KAES256Ref = ObjectUID<{ K_AES_256.mask() }>;
+pub type KPIOPoliciesRef = ObjectUID<{ KPIO_POLICIES.mask() }>;
+pub type KeyTagAllocationRef = ObjectUID<{ KEY_TAG_ALLOCATION.mask() }>;
+pub type KeyTagAllocationRefRange = ObjectUIDRange<{ KEY_TAG_ALLOCATION.mask() }>;
+pub type KeyEncryptionKeyRef = ObjectUID<{ KEY_ENCRYPTION_KEY.mask() }>;
+pub type KeyEncryptionKeyRefRange = ObjectUIDRange<{ KEY_ENCRYPTION_KEY.mask() }>;
 pub type MethodRef = ObjectUID<{ METHOD_ID.mask() }>;
 pub type MBRControlRef = ObjectUID<{ MBR_CONTROL.mask() }>;
 pub type TableDescRef = ObjectUID<{ TABLE.mask() }>;
+pub type TPerInfoRef = ObjectUID<{ T_PER_INFO.mask() }>;
 pub type TemplateRef = ObjectUID<{ TEMPLATE.mask() }>;
 pub type ColumnRef = ObjectUID<{ COLUMN.mask() }>;
 
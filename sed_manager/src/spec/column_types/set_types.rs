@@ -11,6 +11,7 @@ pub enum ResetType {
     PowerCycle = 0,
     Hardware = 1,
     HotPlug = 2,
+    Programmatic = 3,
     #[fallback]
     Unknown = 31,
 }
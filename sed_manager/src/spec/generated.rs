@@ -33,6 +33,17 @@ mod tests {
 
     use super::*;
 
+    #[test]
+    fn column_names_by_index() {
+        use crate::spec::objects::{LockingRange, MBRControl};
+        let locking = columns::column_names(core::all::table_id::LOCKING.as_uid());
+        assert_eq!(locking[LockingRange::RANGE_START as usize], "RangeStart");
+        assert_eq!(locking.len(), LockingRange::GENERAL_STATUS as usize + 1);
+        let mbr_control = columns::column_names(core::all::table_id::MBR_CONTROL.as_uid());
+        assert_eq!(mbr_control[MBRControl::DONE as usize], "Done");
+        assert!(columns::column_names(core::all::table_id::TABLE.as_uid()).is_empty());
+    }
+
     #[test]
     fn lookup_table_with_object() {
         let expected = Some((core::all::table_id::AUTHORITY.as_uid(), "SID"));
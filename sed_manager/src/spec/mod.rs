@@ -11,6 +11,9 @@ pub mod objects;
 
 pub use lookup::ObjectLookup;
 
+// Column names of the tables.
+pub use generated::columns::column_names;
+
 // Core.
 pub mod core {
     pub use super::generated::core::all::*;
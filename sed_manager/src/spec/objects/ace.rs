@@ -10,7 +10,7 @@ use crate::spec::column_types::{ACEOperand, ACERef, AuthorityRef, BooleanOp, Nam
 
 use super::cell::Cell;
 
-#[derive(AsArray)]
+#[derive(AsArray, Clone)]
 #[as_array_traits(Cell)]
 pub struct ACE {
     pub uid: ACERef,
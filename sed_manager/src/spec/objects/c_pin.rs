@@ -11,7 +11,7 @@ use crate::spec::column_types::{CPINRef, Name, Password};
 
 use super::cell::Cell;
 
-#[derive(AsArray)]
+#[derive(AsArray, Clone)]
 #[as_array_traits(Cell)]
 pub struct CPIN {
     pub uid: CPINRef,
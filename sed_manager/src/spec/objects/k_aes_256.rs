@@ -9,7 +9,7 @@ use crate::spec::column_types::{KAES256Ref, Key256, Name, SymmetricModeMedia};
 
 use super::cell::Cell;
 
-#[derive(AsArray)]
+#[derive(AsArray, Clone)]
 #[as_array_traits(Cell)]
 pub struct KAES256 {
     pub uid: KAES256Ref,
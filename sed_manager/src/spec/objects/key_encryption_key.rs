@@ -0,0 +1,37 @@
+//L-----------------------------------------------------------------------------
+//L Copyright (C) Péter Kardos
+//L Please refer to the full license distributed with this software.
+//L-----------------------------------------------------------------------------
+
+use as_array::AsArray;
+
+use crate::spec::column_types::{Key256, KeyEncryptionKeyRef, Name};
+
+use super::cell::Cell;
+
+#[derive(AsArray, Clone)]
+#[as_array_traits(Cell)]
+pub struct KeyEncryptionKey {
+    pub uid: KeyEncryptionKeyRef,
+    pub name: Name,
+    pub common_name: Name,
+    pub key: Key256,
+}
+
+impl KeyEncryptionKey {
+    pub const UID: u16 = 0;
+    pub const NAME: u16 = 1;
+    pub const COMMON_NAME: u16 = 2;
+    pub const KEY: u16 = 3;
+}
+
+impl Default for KeyEncryptionKey {
+    fn default() -> Self {
+        Self {
+            uid: KeyEncryptionKeyRef::null(),
+            name: Name::default(),
+            common_name: Name::default(),
+            key: Key256::Bytes32([0; 32]),
+        }
+    }
+}
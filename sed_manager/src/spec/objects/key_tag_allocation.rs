@@ -0,0 +1,51 @@
+//L-----------------------------------------------------------------------------
+//L Copyright (C) Péter Kardos
+//L Please refer to the full license distributed with this software.
+//L-----------------------------------------------------------------------------
+
+use as_array::AsArray;
+
+use crate::messaging::value::Bytes;
+use crate::spec::column_types::{KeyEncryptionKeyRef, KeyTagAllocationRef, Name};
+
+use super::cell::Cell;
+
+/// A slot that binds a key tag of a namespace to a media encryption key.
+///
+/// The key is wrapped by the KEK referenced by the slot. An empty key means
+/// the slot is free, and no I/O may use the key tag.
+#[derive(AsArray, Clone, PartialEq, Eq, Debug)]
+#[as_array_traits(Cell)]
+pub struct KeyTagAllocation {
+    pub uid: KeyTagAllocationRef,
+    pub name: Name,
+    pub common_name: Name,
+    pub namespace_id: u32,
+    pub key_tag: u16,
+    pub kek: KeyEncryptionKeyRef,
+    pub key: Bytes,
+}
+
+impl KeyTagAllocation {
+    pub const UID: u16 = 0;
+    pub const NAME: u16 = 1;
+    pub const COMMON_NAME: u16 = 2;
+    pub const NAMESPACE_ID: u16 = 3;
+    pub const KEY_TAG: u16 = 4;
+    pub const KEK: u16 = 5;
+    pub const KEY: u16 = 6;
+}
+
+impl Default for KeyTagAllocation {
+    fn default() -> Self {
+        Self {
+            uid: KeyTagAllocationRef::null(),
+            name: Name::default(),
+            common_name: Name::default(),
+            namespace_id: 0,
+            key_tag: 0,
+            kek: KeyEncryptionKeyRef::null(),
+            key: Bytes::new(),
+        }
+    }
+}
@@ -0,0 +1,28 @@
+//L-----------------------------------------------------------------------------
+//L Copyright (C) Péter Kardos
+//L Please refer to the full license distributed with this software.
+//L-----------------------------------------------------------------------------
+
+use as_array::AsArray;
+
+use crate::spec::column_types::KPIOPoliciesRef;
+
+use super::cell::Cell;
+
+#[derive(AsArray, Clone)]
+#[as_array_traits(Cell)]
+pub struct KPIOPolicies {
+    pub uid: KPIOPoliciesRef,
+    pub kpio_enabled: bool,
+}
+
+impl KPIOPolicies {
+    pub const UID: u16 = 0;
+    pub const KPIO_ENABLED: u16 = 1;
+}
+
+impl Default for KPIOPolicies {
+    fn default() -> Self {
+        Self { uid: crate::spec::kpio::key_per_io::kpio_policies::KPIO_POLICIES, kpio_enabled: false }
+    }
+}
@@ -12,7 +12,7 @@ use crate::spec::column_types::{
 
 use super::cell::Cell;
 
-#[derive(AsArray, PartialEq, Eq, Debug)]
+#[derive(AsArray, Clone, PartialEq, Eq, Debug)]
 #[as_array_traits(Cell)]
 pub struct LockingRange {
     pub uid: LockingRangeRef,
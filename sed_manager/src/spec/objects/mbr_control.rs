@@ -9,7 +9,7 @@ use crate::spec::column_types::{MBRControlRef, ResetType, ResetTypes};
 
 use super::cell::Cell;
 
-#[derive(AsArray)]
+#[derive(AsArray, Clone)]
 #[as_array_traits(Cell)]
 pub struct MBRControl {
     pub uid: MBRControlRef,
@@ -8,16 +8,24 @@ pub mod authority;
 pub mod c_pin;
 pub mod cell;
 pub mod k_aes_256;
+pub mod key_encryption_key;
+pub mod key_tag_allocation;
+pub mod kpio_policies;
 pub mod locking_range;
 pub mod mbr_control;
 pub mod sp;
 pub mod table_desc;
+pub mod tper_info;
 
 pub use ace::{ACEExpr, ACE};
 pub use authority::Authority;
 pub use c_pin::CPIN;
 pub use k_aes_256::KAES256;
+pub use key_encryption_key::KeyEncryptionKey;
+pub use key_tag_allocation::KeyTagAllocation;
+pub use kpio_policies::KPIOPolicies;
 pub use locking_range::LockingRange;
 pub use mbr_control::MBRControl;
 pub use sp::SP;
 pub use table_desc::TableDesc;
+pub use tper_info::TPerInfo;
@@ -9,7 +9,7 @@ use crate::spec::column_types::{AuthorityRef, Date, LifeCycleState, MaxBytes32,
 
 use super::cell::Cell;
 
-#[derive(AsArray)]
+#[derive(AsArray, Clone)]
 #[as_array_traits(Cell)]
 pub struct SP {
     pub uid: SPRef,
@@ -12,7 +12,7 @@ use crate::{
 
 use super::cell::Cell;
 
-#[derive(AsArray)]
+#[derive(AsArray, Clone)]
 #[as_array_traits(Cell)]
 pub struct TableDesc {
     pub uid: TableDescRef,
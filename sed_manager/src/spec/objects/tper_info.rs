@@ -0,0 +1,53 @@
+//L-----------------------------------------------------------------------------
+//L Copyright (C) Péter Kardos
+//L Please refer to the full license distributed with this software.
+//L-----------------------------------------------------------------------------
+
+use as_array::AsArray;
+
+use crate::spec::basic_types::List;
+use crate::spec::column_types::{Bytes12, Name, TPerInfoRef};
+
+use super::cell::Cell;
+
+#[derive(AsArray, Clone)]
+#[as_array_traits(Cell)]
+pub struct TPerInfo {
+    pub uid: TPerInfoRef,
+    pub bytes: u64,
+    pub gudid: Bytes12,
+    pub generation: u32,
+    pub firmware_version: u32,
+    pub protocol_version: u32,
+    pub space_for_issuance: u64,
+    pub ssc: List<Name>,
+    pub programmatic_reset_enable: bool,
+}
+
+impl TPerInfo {
+    pub const UID: u16 = 0;
+    pub const BYTES: u16 = 1;
+    pub const GUDID: u16 = 2;
+    pub const GENERATION: u16 = 3;
+    pub const FIRMWARE_VERSION: u16 = 4;
+    pub const PROTOCOL_VERSION: u16 = 5;
+    pub const SPACE_FOR_ISSUANCE: u16 = 6;
+    pub const SSC: u16 = 7;
+    pub const PROGRAMMATIC_RESET_ENABLE: u16 = 8;
+}
+
+impl Default for TPerInfo {
+    fn default() -> Self {
+        Self {
+            uid: crate::spec::core::t_per_info::T_PER_INFO,
+            bytes: 0,
+            gudid: [0; 12],
+            generation: 0,
+            firmware_version: 0,
+            protocol_version: 0,
+            space_for_issuance: 0,
+            ssc: List::new(),
+            programmatic_reset_enable: false,
+        }
+    }
+}
@@ -0,0 +1,127 @@
+//L-----------------------------------------------------------------------------
+//L Copyright (C) Péter Kardos
+//L Please refer to the full license distributed with this software.
+//L-----------------------------------------------------------------------------
+
+use std::ops::RangeBounds;
+
+use crate::messaging::uid::UID;
+use crate::messaging::value::Value;
+use crate::rpc::args::{IntoMethodArgs, TryFromMethodArgs};
+use crate::rpc::{Error as RPCError, MethodCall, MethodResult, MethodStatus};
+use crate::spec::basic_types::TableReference;
+
+use super::sp_session::SPSession;
+use super::sp_session::{
+    get_multiple_call, get_multiple_result, next_call, next_result, set_multiple_call, set_result,
+};
+
+/// Collects method calls to send them to the TPer at once.
+///
+/// The calls are packed into as few packets as the session's properties allow,
+/// which saves a round trip per call. The TPer executes the calls in order, and each
+/// call has its own result, so a failed call does not affect the rest of the batch.
+pub struct MethodBatch<'session> {
+    session: &'session SPSession,
+    calls: Vec<Result<MethodCall, RPCError>>,
+}
+
+/// Identifies a call in a [`MethodBatch`], and retrieves its result from the [`BatchResults`].
+pub struct BatchedCall<T> {
+    index: usize,
+    parse: Box<dyn FnOnce(MethodResult) -> Result<T, RPCError> + Send>,
+}
+
+pub struct BatchResults {
+    results: Vec<Option<Result<MethodResult, RPCError>>>,
+}
+
+impl<'session> MethodBatch<'session> {
+    pub(super) fn new(session: &'session SPSession) -> Self {
+        Self { session, calls: Vec::new() }
+    }
+
+    pub fn len(&self) -> usize {
+        self.calls.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.calls.is_empty()
+    }
+
+    pub fn get<T: TryFrom<Value, Error = Value> + 'static>(&mut self, object: UID, column: u16) -> BatchedCall<T> {
+        self.get_multiple::<(T,)>(object, column..=column).map(|(value,)| value)
+    }
+
+    pub fn get_multiple<Tuple: TryFromMethodArgs<Error = MethodStatus> + 'static>(
+        &mut self,
+        object: UID,
+        columns: impl RangeBounds<u16>,
+    ) -> BatchedCall<Tuple> {
+        let (call, first_column) = get_multiple_call(object, columns);
+        self.push(Ok(call), move |result| get_multiple_result(result, first_column))
+    }
+
+    pub fn set<T: Into<Value>>(&mut self, object: UID, column: u16, value: T) -> BatchedCall<()> {
+        self.set_multiple(object, [column], (value,))
+    }
+
+    pub fn set_multiple<Tuple: IntoMethodArgs, const N: usize>(
+        &mut self,
+        object: UID,
+        columns: [u16; N],
+        values: Tuple,
+    ) -> BatchedCall<()> {
+        self.push(set_multiple_call(object, columns, values), set_result)
+    }
+
+    pub fn next(&mut self, table: TableReference, first: Option<UID>, count: Option<u64>) -> BatchedCall<Vec<UID>> {
+        self.push(Ok(next_call(table, first, count)), next_result)
+    }
+
+    /// Sends all calls and waits for their results.
+    pub async fn execute(self) -> BatchResults {
+        let mut valid_calls = Vec::new();
+        let mut results = Vec::new();
+        for call in self.calls {
+            match call {
+                Ok(call) => {
+                    valid_calls.push(call);
+                    results.push(None);
+                }
+                Err(error) => results.push(Some(Err(error))),
+            }
+        }
+        let mut responses = self.session.do_method_calls(valid_calls).await.into_iter();
+        for result in results.iter_mut().filter(|result| result.is_none()) {
+            *result = Some(responses.next().unwrap_or(Err(RPCError::Closed)));
+        }
+        BatchResults { results }
+    }
+
+    fn push<T>(
+        &mut self,
+        call: Result<MethodCall, RPCError>,
+        parse: impl FnOnce(MethodResult) -> Result<T, RPCError> + Send + 'static,
+    ) -> BatchedCall<T> {
+        self.calls.push(call);
+        BatchedCall { index: self.calls.len() - 1, parse: Box::new(parse) }
+    }
+}
+
+impl<T: 'static> BatchedCall<T> {
+    fn map<U>(self, f: impl FnOnce(T) -> U + Send + 'static) -> BatchedCall<U> {
+        let parse = self.parse;
+        BatchedCall { index: self.index, parse: Box::new(move |result| parse(result).map(f)) }
+    }
+}
+
+impl BatchResults {
+    /// Returns the result of a call in the batch. The result of each call can be taken only once.
+    pub fn take<T>(&mut self, call: BatchedCall<T>) -> Result<T, RPCError> {
+        match self.results.get_mut(call.index).and_then(|result| result.take()) {
+            Some(result) => (call.parse)(result?),
+            None => Err(RPCError::Unspecified),
+        }
+    }
+}
@@ -5,9 +5,11 @@
 
 mod com_session;
 mod control_session;
+mod method_batch;
 mod sp_session;
 mod tper;
 
 // `Session` is unambiguous as `ControlSession` and `ComSession` don't make sense outside.
+pub use method_batch::{BatchResults, BatchedCall, MethodBatch};
 pub use sp_session::SPSession as Session;
-pub use tper::{discover, TPer};
+pub use tper::{discover, SessionBuilder, TPer};
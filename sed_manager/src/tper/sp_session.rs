@@ -13,9 +13,11 @@ use crate::rpc::{
     SessionIdentifier,
 };
 use crate::spec::basic_types::{List, NamedValue, ObjectReference, TableReference};
-use crate::spec::column_types::{ACERef, AuthorityRef, CellBlock, CredentialRef, MethodRef, SPRef};
+use crate::spec::column_types::{ACERef, AuthorityRef, CellBlock, CredentialRef, LockingRangeRef, MethodRef, SPRef};
 use crate::spec::{invoking_id::*, method_id::*, table_id};
 
+use super::method_batch::MethodBatch;
+
 pub struct SPSession {
     session: SessionIdentifier,
     sender: CommandSender,
@@ -37,6 +39,41 @@ impl SPSession {
             }
         }
     }
+
+    pub(super) async fn do_method_calls(&self, calls: Vec<MethodCall>) -> Vec<Result<MethodResult, RPCError>> {
+        let requests = calls.into_iter().map(PackagedMethod::Call).collect();
+        let responses = self.sender.methods(self.session, requests).await;
+        let mut aborted = false;
+        let results = responses
+            .into_iter()
+            .map(|response| match response? {
+                PackagedMethod::Result(result) => Ok(result),
+                _ => {
+                    aborted = true;
+                    Err(RPCError::Aborted)
+                }
+            })
+            .collect();
+        if aborted {
+            self.sender.enqueue_method(self.session, PackagedMethod::EndOfSession);
+        }
+        results
+    }
+
+    async fn do_transaction_control(&self, method: PackagedMethod) -> Result<(), RPCError> {
+        let result = self.sender.method(self.session, method.clone()).await?;
+        // The TPer echoes the status on success. Otherwise, the transaction is aborted.
+        match result {
+            PackagedMethod::StartTransaction(_) | PackagedMethod::EndTransaction(_) if result == method => Ok(()),
+            PackagedMethod::StartTransaction(_) | PackagedMethod::EndTransaction(_) => {
+                Err(RPCError::MethodFailed(MethodStatus::TransactionFailure))
+            }
+            _ => {
+                self.sender.enqueue_method(self.session, PackagedMethod::EndOfSession);
+                Err(RPCError::Aborted)
+            }
+        }
+    }
 }
 
 impl Drop for SPSession {
@@ -67,6 +104,46 @@ impl SPSession {
         self.sender.abort_session(self.session);
     }
 
+    /// Collects method calls to send them at once. See [`MethodBatch`].
+    pub fn batch(&self) -> MethodBatch<'_> {
+        MethodBatch::new(self)
+    }
+
+    /// Starts a transaction. Changes made after this are applied all at once on commit, or not at all.
+    ///
+    /// The TPer must support transactions, see [`Properties::max_transactions`].
+    pub async fn start_transaction(&self) -> Result<(), RPCError> {
+        self.do_transaction_control(PackagedMethod::StartTransaction(0)).await
+    }
+
+    /// Applies the changes made since starting the transaction.
+    pub async fn commit_transaction(&self) -> Result<(), RPCError> {
+        self.do_transaction_control(PackagedMethod::EndTransaction(0)).await
+    }
+
+    /// Discards the changes made since starting the transaction.
+    pub async fn abort_transaction(&self) -> Result<(), RPCError> {
+        self.do_transaction_control(PackagedMethod::EndTransaction(1)).await
+    }
+
+    /// Runs `f` in a transaction that's committed if `f` succeeds and aborted otherwise.
+    pub async fn with_transaction<Output, Error: From<RPCError>>(
+        &self,
+        f: impl AsyncFnOnce(&Self) -> Result<Output, Error>,
+    ) -> Result<Output, Error> {
+        self.start_transaction().await?;
+        match f(self).await {
+            Ok(output) => {
+                self.commit_transaction().await?;
+                Ok(output)
+            }
+            Err(error) => {
+                let _ = self.abort_transaction().await;
+                Err(error)
+            }
+        }
+    }
+
     pub async fn authenticate(&self, authority: AuthorityRef, proof: Option<&[u8]>) -> Result<bool, RPCError> {
         let call = MethodCall::new_success(THIS_SP, AUTHENTICATE.as_uid(), (authority, proof).into_method_args());
         let results = self.do_method_call(call).await?.take_results()?;
@@ -85,33 +162,8 @@ impl SPSession {
         object: UID,
         columns: impl RangeBounds<u16>,
     ) -> Result<Tuple, RPCError> {
-        let first_column = match columns.start_bound() {
-            std::ops::Bound::Included(n) => *n,
-            std::ops::Bound::Excluded(n) => *n + 1,
-            core::ops::Bound::Unbounded => 0,
-        };
-
-        let call = MethodCall::new_success(object, GET.as_uid(), (CellBlock::object(columns),).into_method_args());
-        let results = self.do_method_call(call).await?;
-        let results = results.take_results()?;
-        // According to the TCG examples, result is encoded without typeOr{} name-value pair.
-        let (column_values,): (List<NamedValue<u64, Value>>,) =
-            results.unwrap_method_args().map_err(|_| RPCError::ResultTypeMismatch)?;
-        let column_values: Vec<_> = column_values
-            .0
-            .into_iter()
-            .map(|nvp| NamedValue { name: nvp.name.wrapping_sub(first_column as u64), ..nvp })
-            .collect();
-        let mut linearized = Vec::new();
-        for column_value in column_values {
-            let index = column_value.name as usize;
-            let new_size = (index + 1).clamp(linearized.len(), 64);
-            linearized.resize(new_size, Value::empty());
-            if index < linearized.len() {
-                linearized[index] = column_value.value;
-            }
-        }
-        Ok(Tuple::try_from_method_args(linearized).map_err(|_| RPCError::ResultTypeMismatch)?)
+        let (call, first_column) = get_multiple_call(object, columns);
+        get_multiple_result(self.do_method_call(call).await?, first_column)
     }
 
     pub async fn set<T: Into<Value>>(&self, object: UID, column: u16, value: T) -> Result<(), RPCError> {
@@ -124,17 +176,8 @@ impl SPSession {
         columns: [u16; N],
         values: Tuple,
     ) -> Result<(), RPCError> {
-        let where_ = Option::<ObjectReference>::None; // According to the TCG examples, encoded without typeOr{} name-value pair.
-        let names = columns;
-        let values = values.into_method_args();
-        if names.len() != values.len() {
-            return Err(MethodStatus::InvalidParameter.into());
-        }
-        let nvps: Vec<_> = core::iter::zip(names, values).map(|(name, value)| NamedValue { name, value }).collect();
-        let nvps = List(nvps);
-        let call = MethodCall::new_success(object, SET.as_uid(), (where_, Some(nvps)).into_method_args());
-        let _ = self.do_method_call(call).await?.take_results()?; // `Set` returns nothing.
-        Ok(())
+        let call = set_multiple_call(object, columns, values)?;
+        set_result(self.do_method_call(call).await?)
     }
 
     pub async fn read(&self, table: TableUID, position: u64, len: u64) -> Result<Vec<u8>, RPCError> {
@@ -161,10 +204,8 @@ impl SPSession {
         first: Option<UID>,
         count: Option<u64>,
     ) -> Result<Vec<UID>, RPCError> {
-        let call = MethodCall::new_success(table.into(), NEXT.as_uid(), (first, count).into_method_args());
-        let results = self.do_method_call(call).await?.take_results()?;
-        let (objects,): (List<UID>,) = results.unwrap_method_args().map_err(|_| RPCError::ResultTypeMismatch)?;
-        Ok(objects.0)
+        let call = next_call(table, first, count);
+        next_result(self.do_method_call(call).await?)
     }
 
     pub async fn get_acl(&self, invoking_id: UID, method_id: MethodRef) -> Result<Vec<ACERef>, RPCError> {
@@ -187,6 +228,12 @@ impl SPSession {
         Ok(())
     }
 
+    pub async fn erase(&self, range: LockingRangeRef) -> Result<(), RPCError> {
+        let call = MethodCall::new_success(range.as_uid(), ERASE.as_uid(), vec![]);
+        let _ = self.do_method_call(call).await?.take_results()?;
+        Ok(())
+    }
+
     pub async fn revert(&self, sp: SPRef) -> Result<(), RPCError> {
         let call = MethodCall::new_success(sp.as_uid(), REVERT.as_uid(), vec![]);
         let _ = self.do_method_call(call).await?.take_results()?;
@@ -214,3 +261,69 @@ impl SPSession {
         Ok(bytes)
     }
 }
+
+pub(super) fn get_multiple_call(object: UID, columns: impl RangeBounds<u16>) -> (MethodCall, u16) {
+    let first_column = match columns.start_bound() {
+        std::ops::Bound::Included(n) => *n,
+        std::ops::Bound::Excluded(n) => *n + 1,
+        core::ops::Bound::Unbounded => 0,
+    };
+    let call = MethodCall::new_success(object, GET.as_uid(), (CellBlock::object(columns),).into_method_args());
+    (call, first_column)
+}
+
+pub(super) fn get_multiple_result<Tuple: TryFromMethodArgs<Error = MethodStatus>>(
+    result: MethodResult,
+    first_column: u16,
+) -> Result<Tuple, RPCError> {
+    let results = result.take_results()?;
+    // According to the TCG examples, result is encoded without typeOr{} name-value pair.
+    let (column_values,): (List<NamedValue<u64, Value>>,) =
+        results.unwrap_method_args().map_err(|_| RPCError::ResultTypeMismatch)?;
+    let column_values: Vec<_> = column_values
+        .0
+        .into_iter()
+        .map(|nvp| NamedValue { name: nvp.name.wrapping_sub(first_column as u64), ..nvp })
+        .collect();
+    let mut linearized = Vec::new();
+    for column_value in column_values {
+        let index = column_value.name as usize;
+        let new_size = (index + 1).clamp(linearized.len(), 64);
+        linearized.resize(new_size, Value::empty());
+        if index < linearized.len() {
+            linearized[index] = column_value.value;
+        }
+    }
+    Tuple::try_from_method_args(linearized).map_err(|_| RPCError::ResultTypeMismatch)
+}
+
+pub(super) fn set_multiple_call<Tuple: IntoMethodArgs, const N: usize>(
+    object: UID,
+    columns: [u16; N],
+    values: Tuple,
+) -> Result<MethodCall, RPCError> {
+    let where_ = Option::<ObjectReference>::None; // According to the TCG examples, encoded without typeOr{} name-value pair.
+    let names = columns;
+    let values = values.into_method_args();
+    if names.len() != values.len() {
+        return Err(MethodStatus::InvalidParameter.into());
+    }
+    let nvps: Vec<_> = core::iter::zip(names, values).map(|(name, value)| NamedValue { name, value }).collect();
+    let nvps = List(nvps);
+    Ok(MethodCall::new_success(object, SET.as_uid(), (where_, Some(nvps)).into_method_args()))
+}
+
+pub(super) fn set_result(result: MethodResult) -> Result<(), RPCError> {
+    let _ = result.take_results()?; // `Set` returns nothing.
+    Ok(())
+}
+
+pub(super) fn next_call(table: TableReference, first: Option<UID>, count: Option<u64>) -> MethodCall {
+    MethodCall::new_success(table.into(), NEXT.as_uid(), (first, count).into_method_args())
+}
+
+pub(super) fn next_result(result: MethodResult) -> Result<Vec<UID>, RPCError> {
+    let results = result.take_results()?;
+    let (objects,): (List<UID>,) = results.unwrap_method_args().map_err(|_| RPCError::ResultTypeMismatch)?;
+    Ok(objects.0)
+}
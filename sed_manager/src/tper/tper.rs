@@ -40,7 +40,17 @@ pub fn get_primary_ssc_com_id(discovery: &Discovery) -> Option<(u16, u16)> {
 
 impl TPer {
     pub fn new<R: Runtime>(device: Arc<dyn Device>, runtime: Arc<R>, com_id: u16, com_id_ext: u16) -> Self {
-        let capabilities = Protocol::capabilities();
+        Self::with_capabilities(device, runtime, com_id, com_id_ext, Protocol::capabilities())
+    }
+
+    /// Like [`Self::new`], but the host requests `capabilities` instead of [`Protocol::capabilities`].
+    pub fn with_capabilities<R: Runtime>(
+        device: Arc<dyn Device>,
+        runtime: Arc<R>,
+        com_id: u16,
+        com_id_ext: u16,
+        capabilities: Properties,
+    ) -> Self {
         let (message_sender, _) = Protocol::spawn(device, &*runtime, com_id, com_id_ext, capabilities.clone());
         Self {
             com_id,
@@ -80,6 +90,26 @@ impl TPer {
         self.message_sender.discover().await
     }
 
+    /// Freezes SID authentication until the next power cycle.
+    ///
+    /// If `hardware_reset` is set, a hardware reset also lifts the block.
+    pub async fn block_sid(&self, hardware_reset: bool) -> Result<(), RPCError> {
+        self.message_sender.block_sid(hardware_reset).await
+    }
+
+    /// Resets the TPer as if it was power cycled, but with the `Programmatic` reset type.
+    ///
+    /// All sessions are aborted, and the locking ranges with `Programmatic` in their
+    /// `LockOnReset` are locked. The TPer ignores the command unless programmatic reset
+    /// is enabled in the TPerInfo table. Sessions started before the reset should be
+    /// dropped with [`SPSession::abort_session`]. The reset also reverts the properties
+    /// to their defaults, so they are negotiated again for the next session.
+    pub async fn tper_reset(&self) -> Result<(), RPCError> {
+        self.message_sender.tper_reset().await?;
+        let _ = self.properties.lock().await.take();
+        Ok(())
+    }
+
     pub async fn current_properties(&self) -> Properties {
         let mut maybe_properties = self.properties.lock().await;
         if let Some(properties) = maybe_properties.deref() {
@@ -99,7 +129,15 @@ impl TPer {
     async fn change_properties_with_lock(&self, output: &mut Option<Properties>, requested: &Properties) -> Properties {
         let properties = match self.control_session.properties(Some(requested.to_list())).await {
             Ok((tper_capabilities, tper_properties)) => {
-                let tper_properties = Properties::from_list(&tper_properties.unwrap_or(tper_capabilities));
+                let tper_capabilities = Properties::from_list(&tper_capabilities);
+                let tper_properties = match tper_properties {
+                    // The transaction limit is reported only among the TPer's own properties.
+                    Some(tper_properties) => Properties {
+                        max_transactions: tper_capabilities.max_transactions,
+                        ..Properties::from_list(&tper_properties)
+                    },
+                    None => tper_capabilities,
+                };
                 Properties::common(&self.capabilities, &tper_properties)
             }
             Err(_) => Properties::ASSUMED,
@@ -108,17 +146,62 @@ impl TPer {
         properties
     }
 
+    /// Starts a read-write session, authenticated with `password` if `authority` is given.
+    ///
+    /// Use [`Self::session`] for the other options of the StartSession method.
     pub async fn start_session(
         &self,
         sp: SPRef,
         authority: Option<AuthorityRef>,
         password: Option<&[u8]>,
     ) -> Result<SPSession, RPCError> {
+        let mut builder = self.session(sp);
+        if let Some(authority) = authority {
+            builder = builder.host_signing_authority(authority);
+        }
+        if let Some(password) = password {
+            builder = builder.host_challenge(password);
+        }
+        builder.start().await
+    }
+
+    /// Configures all options of the StartSession method. See [`SessionBuilder`].
+    pub fn session(&self, sp: SPRef) -> SessionBuilder<'_> {
+        SessionBuilder {
+            tper: self,
+            sp,
+            write: true,
+            host_challenge: None,
+            host_exchange_authority: None,
+            host_exchange_cert: None,
+            host_signing_authority: None,
+            host_signing_cert: None,
+            session_timeout: None,
+            trans_timeout: None,
+            initial_credit: None,
+            signed_hash: None,
+        }
+    }
+
+    async fn start_session_with(&self, options: SessionBuilder<'_>) -> Result<SPSession, RPCError> {
         let hsn = self.next_hsn.fetch_add(1, Ordering::Relaxed);
         let properties = self.current_properties().await;
         let sync_session = self
             .control_session
-            .start_session(hsn, sp, true, password, None, None, authority, None, None, None, None, None)
+            .start_session(
+                hsn,
+                options.sp,
+                options.write,
+                options.host_challenge.as_deref(),
+                options.host_exchange_authority,
+                options.host_exchange_cert.as_deref(),
+                options.host_signing_authority,
+                options.host_signing_cert.as_deref(),
+                options.session_timeout.map(as_millis_u32),
+                options.trans_timeout.map(as_millis_u32),
+                options.initial_credit,
+                options.signed_hash.as_deref(),
+            )
             .await?;
         if sync_session.hsn != hsn {
             return Err(RPCError::Unspecified);
@@ -149,3 +232,77 @@ impl TPer {
         status
     }
 }
+
+/// Collects the parameters of the StartSession method.
+///
+/// By default, the session is read-write, unauthenticated, and the TPer's
+/// defaults apply to the timeouts. Call [`Self::start`] to open the session.
+pub struct SessionBuilder<'tper> {
+    tper: &'tper TPer,
+    sp: SPRef,
+    write: bool,
+    host_challenge: Option<Vec<u8>>,
+    host_exchange_authority: Option<AuthorityRef>,
+    host_exchange_cert: Option<Vec<u8>>,
+    host_signing_authority: Option<AuthorityRef>,
+    host_signing_cert: Option<Vec<u8>>,
+    session_timeout: Option<Duration>,
+    trans_timeout: Option<Duration>,
+    initial_credit: Option<u32>,
+    signed_hash: Option<Vec<u8>>,
+}
+
+impl<'tper> SessionBuilder<'tper> {
+    /// Opens a read-only session. The TPer refuses methods that would modify the SP.
+    pub fn read_only(self) -> Self {
+        Self { write: false, ..self }
+    }
+
+    /// The password for the [`Self::host_signing_authority`].
+    pub fn host_challenge(self, challenge: &[u8]) -> Self {
+        Self { host_challenge: Some(challenge.into()), ..self }
+    }
+
+    pub fn host_exchange_authority(self, authority: AuthorityRef) -> Self {
+        Self { host_exchange_authority: Some(authority), ..self }
+    }
+
+    pub fn host_exchange_cert(self, cert: &[u8]) -> Self {
+        Self { host_exchange_cert: Some(cert.into()), ..self }
+    }
+
+    /// The authority to authenticate when starting the session.
+    pub fn host_signing_authority(self, authority: AuthorityRef) -> Self {
+        Self { host_signing_authority: Some(authority), ..self }
+    }
+
+    pub fn host_signing_cert(self, cert: &[u8]) -> Self {
+        Self { host_signing_cert: Some(cert.into()), ..self }
+    }
+
+    /// The TPer aborts the session if the host does not use it for this long.
+    pub fn session_timeout(self, timeout: Duration) -> Self {
+        Self { session_timeout: Some(timeout), ..self }
+    }
+
+    /// The TPer aborts a transaction of the session if it is not committed within this long.
+    pub fn trans_timeout(self, timeout: Duration) -> Self {
+        Self { trans_timeout: Some(timeout), ..self }
+    }
+
+    pub fn initial_credit(self, credit: u32) -> Self {
+        Self { initial_credit: Some(credit), ..self }
+    }
+
+    pub fn signed_hash(self, hash: &[u8]) -> Self {
+        Self { signed_hash: Some(hash.into()), ..self }
+    }
+
+    pub async fn start(self) -> Result<SPSession, RPCError> {
+        self.tper.start_session_with(self).await
+    }
+}
+
+fn as_millis_u32(duration: Duration) -> u32 {
+    u32::try_from(duration.as_millis()).unwrap_or(u32::MAX)
+}
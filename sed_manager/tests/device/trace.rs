@@ -0,0 +1,86 @@
+//L-----------------------------------------------------------------------------
+//L Copyright (C) Péter Kardos
+//L Please refer to the full license distributed with this software.
+//L-----------------------------------------------------------------------------
+
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+
+use sed_manager::applications::{take_ownership, verify_ownership, Error as AppError};
+use sed_manager::device::trace::{read_trace, RecordingDevice, ReplayDevice, TraceError};
+use sed_manager::device::Device;
+use sed_manager::fake_device::FakeDevice;
+use sed_manager::rpc::{Decoder, TokioRuntime};
+use sed_manager::tper::{discover, TPer};
+
+#[derive(Clone, Default)]
+struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+impl Write for SharedBuffer {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+async fn run_take_ownership(device: Arc<dyn Device>) -> Result<bool, AppError> {
+    let runtime = Arc::new(TokioRuntime::new());
+    let _ = discover(&*device)?;
+    let tper = TPer::new_on_default_com_id(device, runtime)?;
+    take_ownership(&tper, b"sid_password").await?;
+    verify_ownership(&tper, b"sid_password").await
+}
+
+async fn record_take_ownership() -> Vec<u8> {
+    let buffer = SharedBuffer::default();
+    let device = RecordingDevice::new(Arc::new(FakeDevice::new()), buffer.clone()).unwrap();
+    assert!(run_take_ownership(Arc::new(device)).await.unwrap());
+    let trace = buffer.0.lock().unwrap().clone();
+    trace
+}
+
+#[tokio::test]
+async fn replay_recorded() {
+    let trace = record_take_ownership().await;
+    let device = Arc::new(ReplayDevice::from_reader(trace.as_slice()).unwrap());
+    assert_eq!(device.model_number(), FakeDevice::new().model_number());
+    assert!(run_take_ownership(device.clone()).await.unwrap());
+    assert_eq!(device.finish(), Ok(()));
+}
+
+#[tokio::test]
+async fn replay_diverging() {
+    let trace = record_take_ownership().await;
+    let device = Arc::new(ReplayDevice::from_reader(trace.as_slice()).unwrap());
+    let runtime = Arc::new(TokioRuntime::new());
+    let _ = discover(&*device).unwrap();
+    let tper = TPer::new_on_default_com_id(device.clone(), runtime).unwrap();
+    assert!(take_ownership(&tper, b"other_password").await.is_err());
+    assert!(matches!(device.finish(), Err(TraceError::Mismatch { .. })));
+}
+
+#[tokio::test]
+async fn replay_incomplete() {
+    let trace = record_take_ownership().await;
+    let device = ReplayDevice::from_reader(trace.as_slice()).unwrap();
+    let _ = discover(&device).unwrap();
+    assert!(matches!(device.finish(), Err(TraceError::Incomplete(_))));
+}
+
+#[tokio::test]
+async fn decode_recorded() {
+    let trace = record_take_ownership().await;
+    let (_, exchanges) = read_trace(trace.as_slice()).unwrap();
+    let mut decoder = Decoder::new(false);
+    let decoded: Vec<_> = exchanges.iter().map(|exchange| decoder.decode(exchange)).collect();
+    let decoded = decoded.join("\n");
+    assert!(decoded.contains("Level 0 Discovery [TPer, Locking, OpalV2"));
+    assert!(decoded
+        .contains("SessionManager.StartSession(2, Admin, 1, HostChallenge=<16 bytes>, HostSigningAuthority=SID)"));
+    assert!(decoded.contains("C_PIN::MSID.Get([startColumn=PIN, endColumn=PIN])"));
+    assert!(decoded.contains("C_PIN::SID.Set(Values=[PIN=<12 bytes>])"));
+    assert!(!decoded.contains("malformed"));
+}
@@ -3,6 +3,8 @@
 //L Please refer to the full license distributed with this software.
 //L-----------------------------------------------------------------------------
 
+mod device;
 mod serialization;
+mod timing;
 mod tper;
 mod types;
@@ -0,0 +1,51 @@
+//L-----------------------------------------------------------------------------
+//L Copyright (C) Péter Kardos
+//L Please refer to the full license distributed with this software.
+//L-----------------------------------------------------------------------------
+
+//! Rough timings of bulk operations against the fake device.
+//!
+//! Run with `cargo test -p sed-manager --test test timing -- --ignored --nocapture`.
+
+use std::time::Instant;
+
+use sed_manager::applications::test_fixtures::{setup_activated_tper, LOCKING_ADMIN1_PASSWORD};
+use sed_manager::applications::{Error as AppError, MBREditSession, UserEditSession};
+
+const MBR_UPLOAD_LEN: usize = 8 * 1024 * 1024;
+const USER_ENUMERATIONS: usize = 100;
+
+#[tokio::test]
+#[ignore = "timing"]
+async fn mbr_upload() -> Result<(), AppError> {
+    let tper = setup_activated_tper();
+    let session = MBREditSession::start(&tper, LOCKING_ADMIN1_PASSWORD.as_bytes()).await?;
+    let image: Vec<u8> = (0..MBR_UPLOAD_LEN).map(|i| i as u8).collect();
+    let mut position = 0;
+    let read = async |buffer: &mut [u8]| {
+        let len = core::cmp::min(buffer.len(), image.len() - position);
+        buffer[..len].copy_from_slice(&image[position..position + len]);
+        position += len;
+        Ok(len)
+    };
+    let start = Instant::now();
+    session.upload(read, |_| (), || false).await?;
+    let elapsed = start.elapsed();
+    println!("MBR upload of {} MiB: {elapsed:?}", MBR_UPLOAD_LEN / 1024 / 1024);
+    session.end().await
+}
+
+#[tokio::test]
+#[ignore = "timing"]
+async fn user_enumeration() -> Result<(), AppError> {
+    let tper = setup_activated_tper();
+    let session = UserEditSession::start(&tper, LOCKING_ADMIN1_PASSWORD.as_bytes()).await?;
+    let start = Instant::now();
+    for _ in 0..USER_ENUMERATIONS {
+        let users = session.list_users().await?;
+        session.get_users(&users).await?;
+    }
+    let elapsed = start.elapsed();
+    println!("{USER_ENUMERATIONS} user enumerations: {elapsed:?}");
+    session.end().await
+}
@@ -0,0 +1,110 @@
+//L-----------------------------------------------------------------------------
+//L Copyright (C) Péter Kardos
+//L Please refer to the full license distributed with this software.
+//L-----------------------------------------------------------------------------
+
+use std::sync::Arc;
+
+use sed_manager::fake_device::god_authority::AUTHORITY_GOD;
+use sed_manager::fake_device::{FakeDevice, BASE_COM_ID, MSID_PASSWORD};
+use sed_manager::rpc::{Error as RPCError, Properties, Protocol, TokioRuntime};
+use sed_manager::spec::column_types::Password;
+use sed_manager::spec::objects::CPIN;
+use sed_manager::spec::opal;
+use sed_manager::tper::TPer;
+
+use opal::admin::sp;
+
+#[tokio::test]
+async fn negotiated() -> Result<(), RPCError> {
+    let device = Arc::new(FakeDevice::new());
+    let runtime = Arc::new(TokioRuntime::new());
+    let tper = TPer::new_on_default_com_id(device, runtime)?;
+    let properties = tper.current_properties().await;
+    assert!(properties.seq_numbers);
+    assert!(properties.ack_nak);
+    Ok(())
+}
+
+#[tokio::test]
+async fn opted_out() -> Result<(), RPCError> {
+    let runtime = Arc::new(TokioRuntime::new());
+    let device = Arc::new(FakeDevice::new());
+    let capabilities = Properties { seq_numbers: false, ack_nak: false, ..Protocol::capabilities() };
+    let tper = TPer::with_capabilities(device, runtime, BASE_COM_ID, 0, capabilities);
+    let properties = tper.current_properties().await;
+    assert!(!properties.seq_numbers);
+    assert!(!properties.ack_nak);
+    let session = tper.start_session(sp::ADMIN, None, None).await?;
+    let object = opal::admin::c_pin::MSID.as_uid();
+    assert_eq!(session.get::<Password>(object, CPIN::PIN).await?, MSID_PASSWORD.into());
+    session.end_session().await
+}
+
+#[tokio::test]
+async fn lost_request_retransmitted() -> Result<(), RPCError> {
+    let device = Arc::new(FakeDevice::new());
+    let runtime = Arc::new(TokioRuntime::new());
+    let tper = TPer::new_on_default_com_id(device.clone(), runtime)?;
+    let session = tper.start_session(sp::ADMIN, None, None).await?;
+    let object = opal::admin::c_pin::MSID.as_uid();
+    device.lose_incoming_packets(2);
+    assert_eq!(session.get::<Password>(object, CPIN::PIN).await?, MSID_PASSWORD.into());
+    assert_eq!(session.get::<Password>(object, CPIN::PIN).await?, MSID_PASSWORD.into());
+    session.end_session().await
+}
+
+#[tokio::test]
+async fn lost_response_not_executed_twice() -> Result<(), RPCError> {
+    let device = Arc::new(FakeDevice::new());
+    let runtime = Arc::new(TokioRuntime::new());
+    let tper = TPer::new_on_default_com_id(device.clone(), runtime)?;
+    let session = tper.start_session(sp::ADMIN, Some(AUTHORITY_GOD), None).await?;
+    // Starting the transaction again would fail, as only one transaction may be open.
+    device.lose_outgoing_packets(1);
+    session.start_transaction().await?;
+    session.commit_transaction().await?;
+    session.end_session().await
+}
+
+#[tokio::test]
+async fn retries_exhausted() -> Result<(), RPCError> {
+    let device = Arc::new(FakeDevice::new());
+    let runtime = Arc::new(TokioRuntime::new());
+    let tper = TPer::new_on_default_com_id(device.clone(), runtime)?;
+    let max_retries = tper.current_properties().await.max_retries;
+    let session = tper.start_session(sp::ADMIN, None, None).await?;
+    let object = opal::admin::c_pin::MSID.as_uid();
+    device.lose_incoming_packets(max_retries as usize + 1);
+    assert_eq!(session.get::<Password>(object, CPIN::PIN).await, Err(RPCError::TimedOut));
+    session.abort_session();
+    Ok(())
+}
+
+#[tokio::test]
+async fn sequence_numbers_without_ack_nak() -> Result<(), RPCError> {
+    let device = Arc::new(FakeDevice::new());
+    device.with_tper_mut(|tper| tper.protocol_stack.capabilities.ack_nak = false);
+    let runtime = Arc::new(TokioRuntime::new());
+    let tper = TPer::new_on_default_com_id(device, runtime)?;
+    let session = tper.start_session(sp::ADMIN, None, None).await?;
+    let object = opal::admin::c_pin::MSID.as_uid();
+    assert_eq!(session.get::<Password>(object, CPIN::PIN).await?, MSID_PASSWORD.into());
+    assert_eq!(session.get::<Password>(object, CPIN::PIN).await?, MSID_PASSWORD.into());
+    session.end_session().await
+}
+
+#[tokio::test]
+async fn not_negotiated() -> Result<(), RPCError> {
+    let device = Arc::new(FakeDevice::new());
+    device.with_tper_mut(|tper| {
+        tper.protocol_stack.capabilities.seq_numbers = false;
+        tper.protocol_stack.capabilities.ack_nak = false;
+    });
+    let runtime = Arc::new(TokioRuntime::new());
+    let tper = TPer::new_on_default_com_id(device, runtime)?;
+    let session = tper.start_session(sp::ADMIN, None, None).await?;
+    let object = opal::admin::c_pin::MSID.as_uid();
+    assert_eq!(session.get::<Password>(object, CPIN::PIN).await?, MSID_PASSWORD.into());
+    session.end_session().await
+}
@@ -0,0 +1,42 @@
+//L-----------------------------------------------------------------------------
+//L Copyright (C) Péter Kardos
+//L Please refer to the full license distributed with this software.
+//L-----------------------------------------------------------------------------
+
+use std::sync::Arc;
+
+use sed_manager::fake_device::{FakeDevice, MSID_PASSWORD};
+use sed_manager::messaging::discovery::BlockSIDAuthDescriptor;
+use sed_manager::rpc::{Error as RPCError, MethodStatus, TokioRuntime};
+use sed_manager::spec;
+use sed_manager::tper::TPer;
+
+#[tokio::test]
+async fn block_sid_discovery() -> Result<(), RPCError> {
+    let runtime = Arc::new(TokioRuntime::new());
+    let device = Arc::new(FakeDevice::new());
+    let tper = TPer::new_on_default_com_id(device, runtime)?;
+    let before = tper.discover().await?;
+    assert!(!before.get::<BlockSIDAuthDescriptor>().unwrap().sid_authentication_blocked);
+    tper.block_sid(true).await?;
+    let after = tper.discover().await?;
+    let desc = after.get::<BlockSIDAuthDescriptor>().unwrap();
+    assert!(desc.sid_authentication_blocked);
+    assert!(desc.hw_reset_unblocks);
+    Ok(())
+}
+
+#[tokio::test]
+async fn block_sid_authentication() -> Result<(), RPCError> {
+    use spec::core::authority::SID;
+    use spec::opal::admin::sp::ADMIN;
+
+    let runtime = Arc::new(TokioRuntime::new());
+    let device = Arc::new(FakeDevice::new());
+    let tper = TPer::new_on_default_com_id(device, runtime)?;
+    tper.start_session(ADMIN, Some(SID), Some(MSID_PASSWORD.as_bytes())).await?.end_session().await?;
+    tper.block_sid(false).await?;
+    let result = tper.start_session(ADMIN, Some(SID), Some(MSID_PASSWORD.as_bytes())).await;
+    assert_eq!(result.err(), Some(RPCError::MethodFailed(MethodStatus::NotAuthorized)));
+    Ok(())
+}
@@ -3,11 +3,16 @@
 //L Please refer to the full license distributed with this software.
 //L-----------------------------------------------------------------------------
 
+use core::any::Any;
+use core::future::Future;
+use core::time::Duration;
 use std::sync::Arc;
 
+use sed_manager::fake_device::god_authority::AUTHORITY_GOD;
 use sed_manager::fake_device::{FakeDevice, MSID_PASSWORD};
-use sed_manager::rpc::{Error as RPCError, MethodStatus, Properties, TokioRuntime};
-use sed_manager::spec::{self, opal};
+use sed_manager::rpc::{Error as RPCError, MethodStatus, Properties, Runtime, Timer, TokioRuntime, TokioTimer};
+use sed_manager::spec::objects::CPIN;
+use sed_manager::spec::{self, column_types::Name, opal};
 use sed_manager::tper::TPer;
 
 #[tokio::test]
@@ -84,3 +89,70 @@ async fn start_session_correct_pw() -> Result<(), RPCError> {
     assert!(device.active_sessions().is_empty());
     Ok(())
 }
+
+#[tokio::test]
+async fn start_session_builder() -> Result<(), RPCError> {
+    let runtime = Arc::new(TokioRuntime::new());
+    let device = Arc::new(FakeDevice::new());
+    {
+        let tper = TPer::new_on_default_com_id(device.clone(), runtime)?;
+        let session = tper
+            .session(opal::admin::sp::ADMIN)
+            .host_signing_authority(spec::core::authority::SID)
+            .host_challenge(MSID_PASSWORD.as_bytes())
+            .trans_timeout(Duration::from_secs(5))
+            .start()
+            .await?;
+        session.end_session().await?;
+    }
+    assert!(device.active_sessions().is_empty());
+    Ok(())
+}
+
+#[tokio::test]
+async fn start_session_read_only() -> Result<(), RPCError> {
+    let runtime = Arc::new(TokioRuntime::new());
+    let device = Arc::new(FakeDevice::new());
+    let tper = TPer::new_on_default_com_id(device.clone(), runtime)?;
+    let session = tper
+        .session(opal::admin::sp::ADMIN)
+        .host_signing_authority(AUTHORITY_GOD)
+        .read_only()
+        .start()
+        .await?;
+    let object = opal::admin::c_pin::SID.as_uid();
+    assert!(session.get::<Name>(object, CPIN::COMMON_NAME).await.is_ok());
+    let result = session.set(object, CPIN::COMMON_NAME, Name::from("name")).await;
+    assert_eq!(result, Err(RPCError::MethodFailed(MethodStatus::NotAuthorized)));
+    Ok(())
+}
+
+/// Runs the protocol on the runtime of the test so that it shares the test's paused clock.
+struct CurrentRuntime;
+
+impl Runtime for CurrentRuntime {
+    fn spawn<F>(&self, future: F)
+    where
+        F: Future + Send + 'static,
+        F::Output: Send + Any,
+    {
+        tokio::spawn(future);
+    }
+
+    fn timer(&self) -> impl Timer + 'static {
+        TokioTimer {}
+    }
+}
+
+#[tokio::test(start_paused = true)]
+async fn start_session_timeout() -> Result<(), RPCError> {
+    let runtime = Arc::new(CurrentRuntime);
+    let device = Arc::new(FakeDevice::new());
+    let tper = TPer::new_on_default_com_id(device.clone(), runtime)?;
+    let session = tper.session(opal::admin::sp::ADMIN).session_timeout(Duration::from_millis(100)).start().await?;
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    let result = session.next(spec::table_id::SP, None, None).await;
+    assert!(result.is_err());
+    assert!(device.active_sessions().is_empty());
+    Ok(())
+}
@@ -3,8 +3,11 @@
 //L Please refer to the full license distributed with this software.
 //L-----------------------------------------------------------------------------
 
+use sed_manager::fake_device::data::enterprise;
 use sed_manager::fake_device::FakeDevice;
-use sed_manager::messaging::discovery::{LockingDescriptor, OpalV2Descriptor, OwnerPasswordState, TPerDescriptor};
+use sed_manager::messaging::discovery::{
+    EnterpriseDescriptor, FeatureCode, LockingDescriptor, OpalV2Descriptor, OwnerPasswordState, TPerDescriptor,
+};
 use sed_manager::rpc::Error as RPCError;
 use sed_manager::tper::discover;
 
@@ -25,7 +28,7 @@ fn discovery_normal() -> Result<(), RPCError> {
 
     assert_eq!(tper_desc.sync_supported, true);
     assert_eq!(tper_desc.async_supported, true);
-    assert_eq!(tper_desc.ack_nak_supported, false);
+    assert!(tper_desc.ack_nak_supported);
     assert_eq!(tper_desc.buffer_mgmt_supported, false);
     assert_eq!(tper_desc.streaming_supported, true);
     assert_eq!(tper_desc.com_id_mgmt_supported, false);
@@ -48,3 +51,29 @@ fn discovery_normal() -> Result<(), RPCError> {
 
     Ok(())
 }
+
+#[test]
+fn discovery_enterprise() -> Result<(), RPCError> {
+    let device = FakeDevice::with_ssc(enterprise::new_controller());
+    let discovery = discover(&device)?;
+
+    let Some(locking_desc) = discovery.get::<LockingDescriptor>() else {
+        panic!("expected a locking feature descriptor");
+    };
+    let Some(enterprise_desc) = discovery.get::<EnterpriseDescriptor>() else {
+        panic!("expected an Enterprise feature descriptor");
+    };
+
+    assert!(discovery.get::<OpalV2Descriptor>().is_none());
+    assert_eq!(discovery.get_primary_ssc().map(|ssc| ssc.feature_code()), Some(FeatureCode::Enterprise));
+
+    assert!(locking_desc.locking_supported);
+    assert!(locking_desc.locking_enabled);
+    assert!(!locking_desc.locked);
+    assert!(locking_desc.mbr_shadowing_not_supported);
+
+    assert_eq!(enterprise_desc.base_com_id, 4100);
+    assert_eq!(enterprise_desc.num_com_ids, 1);
+
+    Ok(())
+}
@@ -3,7 +3,10 @@
 //L Please refer to the full license distributed with this software.
 //L-----------------------------------------------------------------------------
 
+mod ack_nak;
+mod block_sid;
 mod control_session;
 mod discovery;
 mod handle_com_id;
 mod sp_session;
+mod tper_reset;
@@ -233,6 +233,35 @@ async fn write_failure_too_large() -> Result<(), RPCError> {
     Ok(())
 }
 
+#[tokio::test]
+async fn write_read_continued_tokens() -> Result<(), RPCError> {
+    let runtime = Arc::new(TokioRuntime::new());
+    let device = Arc::new(make_activated_device());
+    device.with_tper_mut(|tper| tper.protocol_stack.capabilities.max_ind_token_size = 1024);
+    let tper = TPer::new_on_default_com_id(device, runtime)?;
+    assert!(tper.current_properties().await.continued_tokens);
+    let session = tper.start_session(sp::LOCKING, Some(AUTHORITY_GOD), None).await?;
+    let data: Vec<u8> = (0..10000).map(|i| i as u8).collect();
+    session.write(table_id::MBR, 0, &data).await?;
+    assert_eq!(session.read(table_id::MBR, 0, data.len() as u64).await?, data);
+    Ok(())
+}
+
+#[tokio::test]
+async fn write_failure_no_continued_tokens() -> Result<(), RPCError> {
+    let runtime = Arc::new(TokioRuntime::new());
+    let device = Arc::new(make_activated_device());
+    device.with_tper_mut(|tper| {
+        tper.protocol_stack.capabilities.max_ind_token_size = 1024;
+        tper.protocol_stack.capabilities.continued_tokens = false;
+    });
+    let tper = TPer::new_on_default_com_id(device, runtime)?;
+    let session = tper.start_session(sp::LOCKING, Some(AUTHORITY_GOD), None).await?;
+    let result = session.write(table_id::MBR, 0, &[0; 10000]).await;
+    assert_eq!(result, Err(RPCError::TokenTooLarge));
+    Ok(())
+}
+
 #[tokio::test]
 async fn next_success_with_uid() -> Result<(), RPCError> {
     use opal::admin::authority;
@@ -354,3 +383,140 @@ async fn revert_sp() -> Result<(), RPCError> {
     });
     Ok(())
 }
+
+#[tokio::test]
+async fn transaction_commit() -> Result<(), RPCError> {
+    let runtime = Arc::new(TokioRuntime::new());
+    let device = Arc::new(FakeDevice::new());
+    let tper = TPer::new_on_default_com_id(device, runtime)?;
+    assert_ne!(tper.current_properties().await.max_transactions, 0);
+    let session = tper.start_session(sp::ADMIN, Some(AUTHORITY_GOD), None).await?;
+    let object = opal::admin::c_pin::SID.as_uid();
+    session.start_transaction().await?;
+    session.set(object, CPIN::COMMON_NAME, Name::from("name")).await?;
+    session.commit_transaction().await?;
+    assert_eq!(session.get::<Name>(object, CPIN::COMMON_NAME).await?, Name::from("name"));
+    Ok(())
+}
+
+#[tokio::test]
+async fn transaction_abort() -> Result<(), RPCError> {
+    let runtime = Arc::new(TokioRuntime::new());
+    let device = Arc::new(FakeDevice::new());
+    let tper = TPer::new_on_default_com_id(device, runtime)?;
+    let session = tper.start_session(sp::ADMIN, Some(AUTHORITY_GOD), None).await?;
+    let object = opal::admin::c_pin::SID.as_uid();
+    let original = session.get::<Name>(object, CPIN::COMMON_NAME).await?;
+    session.start_transaction().await?;
+    session.set(object, CPIN::COMMON_NAME, Name::from("name")).await?;
+    session.abort_transaction().await?;
+    assert_eq!(session.get::<Name>(object, CPIN::COMMON_NAME).await?, original);
+    Ok(())
+}
+
+#[tokio::test]
+async fn transaction_end_session_aborts() -> Result<(), RPCError> {
+    let runtime = Arc::new(TokioRuntime::new());
+    let device = Arc::new(FakeDevice::new());
+    let tper = TPer::new_on_default_com_id(device, runtime)?;
+    let object = opal::admin::c_pin::SID.as_uid();
+    let session = tper.start_session(sp::ADMIN, Some(AUTHORITY_GOD), None).await?;
+    let original = session.get::<Name>(object, CPIN::COMMON_NAME).await?;
+    session.start_transaction().await?;
+    session.set(object, CPIN::COMMON_NAME, Name::from("name")).await?;
+    session.end_session().await?;
+    let session = tper.start_session(sp::ADMIN, Some(AUTHORITY_GOD), None).await?;
+    assert_eq!(session.get::<Name>(object, CPIN::COMMON_NAME).await?, original);
+    Ok(())
+}
+
+#[tokio::test]
+async fn transaction_nested() -> Result<(), RPCError> {
+    let runtime = Arc::new(TokioRuntime::new());
+    let device = Arc::new(FakeDevice::new());
+    let tper = TPer::new_on_default_com_id(device, runtime)?;
+    let session = tper.start_session(sp::ADMIN, Some(AUTHORITY_GOD), None).await?;
+    session.start_transaction().await?;
+    let result = session.start_transaction().await;
+    assert_eq!(result, Err(MethodStatus::TransactionFailure.into()));
+    Ok(())
+}
+
+#[tokio::test]
+async fn transaction_end_without_start() -> Result<(), RPCError> {
+    let runtime = Arc::new(TokioRuntime::new());
+    let device = Arc::new(FakeDevice::new());
+    let tper = TPer::new_on_default_com_id(device, runtime)?;
+    let session = tper.start_session(sp::ADMIN, Some(AUTHORITY_GOD), None).await?;
+    let result = session.commit_transaction().await;
+    assert_eq!(result, Err(MethodStatus::TransactionFailure.into()));
+    Ok(())
+}
+
+#[tokio::test]
+async fn with_transaction_error_aborts() -> Result<(), RPCError> {
+    let runtime = Arc::new(TokioRuntime::new());
+    let device = Arc::new(FakeDevice::new());
+    let tper = TPer::new_on_default_com_id(device, runtime)?;
+    let session = tper.start_session(sp::ADMIN, Some(AUTHORITY_GOD), None).await?;
+    let object = opal::admin::c_pin::SID.as_uid();
+    let original = session.get::<Name>(object, CPIN::COMMON_NAME).await?;
+    let result = session
+        .with_transaction(async |session| {
+            session.set(object, CPIN::COMMON_NAME, Name::from("name")).await?;
+            session.set(object, CPIN::COMMON_NAME, 0xFFFF_u16).await // Wrong type.
+        })
+        .await;
+    assert_eq!(result, Err(MethodStatus::InvalidParameter.into()));
+    assert_eq!(session.get::<Name>(object, CPIN::COMMON_NAME).await?, original);
+    Ok(())
+}
+
+#[tokio::test]
+async fn batch_success() -> Result<(), RPCError> {
+    let runtime = Arc::new(TokioRuntime::new());
+    let device = Arc::new(FakeDevice::new());
+    let tper = TPer::new_on_default_com_id(device, runtime)?;
+    let session = tper.start_session(sp::ADMIN, Some(AUTHORITY_GOD), None).await?;
+    let object = opal::admin::c_pin::SID.as_uid();
+    let mut batch = session.batch();
+    let set = batch.set(object, CPIN::COMMON_NAME, Name::from("name"));
+    let get = batch.get::<Name>(object, CPIN::COMMON_NAME);
+    let next = batch.next(table_id::SP, None, None);
+    let mut results = batch.execute().await;
+    assert_eq!(results.take(set), Ok(()));
+    assert_eq!(results.take(get), Ok(Name::from("name")));
+    assert_eq!(results.take(next), Ok(vec![sp::ADMIN.as_uid(), sp::LOCKING.as_uid()]));
+    Ok(())
+}
+
+#[tokio::test]
+async fn batch_partial_failure() -> Result<(), RPCError> {
+    let runtime = Arc::new(TokioRuntime::new());
+    let device = Arc::new(FakeDevice::new());
+    let tper = TPer::new_on_default_com_id(device, runtime)?;
+    let session = tper.start_session(sp::ADMIN, Some(AUTHORITY_GOD), None).await?;
+    let object = opal::admin::c_pin::SID.as_uid();
+    let mut batch = session.batch();
+    let missing = batch.get::<Name>(UID::new(table_id::C_PIN.as_u64() + 0x2360_4327), CPIN::COMMON_NAME);
+    let mismatched = batch.set_multiple(object, [CPIN::COMMON_NAME, CPIN::PIN], (Name::from("name"),));
+    let get = batch.get::<Name>(object, CPIN::COMMON_NAME);
+    let mut results = batch.execute().await;
+    assert_eq!(results.take(missing), Err(MethodStatus::InvalidParameter.into()));
+    assert_eq!(results.take(mismatched), Err(MethodStatus::InvalidParameter.into()));
+    assert!(results.take(get).is_ok());
+    Ok(())
+}
+
+#[tokio::test]
+async fn batch_empty() -> Result<(), RPCError> {
+    let runtime = Arc::new(TokioRuntime::new());
+    let device = Arc::new(FakeDevice::new());
+    let tper = TPer::new_on_default_com_id(device, runtime)?;
+    let session = tper.start_session(sp::ADMIN, None, None).await?;
+    let batch = session.batch();
+    assert!(batch.is_empty());
+    let _ = batch.execute().await;
+    assert_eq!(session.next(table_id::SP, None, None).await?.len(), 2);
+    Ok(())
+}
@@ -0,0 +1,51 @@
+//L-----------------------------------------------------------------------------
+//L Copyright (C) Péter Kardos
+//L Please refer to the full license distributed with this software.
+//L-----------------------------------------------------------------------------
+
+use std::sync::Arc;
+
+use sed_manager::fake_device::{FakeDevice, MSID_PASSWORD};
+use sed_manager::rpc::{Error as RPCError, TokioRuntime};
+use sed_manager::spec;
+use sed_manager::spec::objects::TPerInfo;
+use sed_manager::tper::TPer;
+
+async fn enable_programmatic_reset(tper: &TPer) -> Result<(), RPCError> {
+    use spec::core::authority::SID;
+    use spec::core::t_per_info::T_PER_INFO;
+    use spec::opal::admin::sp::ADMIN;
+
+    let session = tper.start_session(ADMIN, Some(SID), Some(MSID_PASSWORD.as_bytes())).await?;
+    session.set(T_PER_INFO.as_uid(), TPerInfo::PROGRAMMATIC_RESET_ENABLE, true).await?;
+    session.end_session().await
+}
+
+#[tokio::test]
+async fn tper_reset_aborts_sessions() -> Result<(), RPCError> {
+    use spec::opal::admin::sp::ADMIN;
+
+    let runtime = Arc::new(TokioRuntime::new());
+    let device = Arc::new(FakeDevice::new());
+    let tper = TPer::new_on_default_com_id(device.clone(), runtime)?;
+    enable_programmatic_reset(&tper).await?;
+    let session = tper.start_session(ADMIN, None, None).await?;
+    assert_eq!(device.active_sessions().len(), 1);
+    tper.tper_reset().await?;
+    assert!(device.active_sessions().is_empty());
+    session.abort_session();
+    Ok(())
+}
+
+#[tokio::test]
+async fn tper_reset_ignored_when_disabled() -> Result<(), RPCError> {
+    use spec::opal::admin::sp::ADMIN;
+
+    let runtime = Arc::new(TokioRuntime::new());
+    let device = Arc::new(FakeDevice::new());
+    let tper = TPer::new_on_default_com_id(device.clone(), runtime)?;
+    let _session = tper.start_session(ADMIN, None, None).await?;
+    tper.tper_reset().await?;
+    assert_eq!(device.active_sessions().len(), 1);
+    Ok(())
+}
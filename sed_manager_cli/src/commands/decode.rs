@@ -0,0 +1,41 @@
+//L-----------------------------------------------------------------------------
+//L Copyright (C) Péter Kardos
+//L Please refer to the full license distributed with this software.
+//L-----------------------------------------------------------------------------
+
+use std::fs::File;
+use std::io::BufReader;
+use std::path::PathBuf;
+
+use clap::Args;
+use sed_manager::device::trace::read_trace;
+use sed_manager::rpc::Decoder;
+
+use crate::error::Error;
+
+#[derive(Args)]
+pub struct DecodeArgs {
+    /// Path of the capture file, as written by `--capture`.
+    file: PathBuf,
+    /// Print byte values instead of redacting them. This reveals passwords.
+    #[arg(long)]
+    reveal_bytes: bool,
+}
+
+pub fn run(args: DecodeArgs) -> Result<(), Error> {
+    let file =
+        File::open(&args.file).map_err(|err| Error::InvalidArgument(format!("{}: {err}", args.file.display())))?;
+    let (header, exchanges) = read_trace(BufReader::new(file))?;
+    println!(
+        "{} ({}, serial {}, firmware {})",
+        header.path.as_deref().unwrap_or("<unknown>"),
+        header.model_number.trim(),
+        header.serial_number.trim(),
+        header.firmware_revision.trim()
+    );
+    let mut decoder = Decoder::new(args.reveal_bytes);
+    for (index, exchange) in exchanges.iter().enumerate() {
+        println!("#{index} {}", decoder.decode(exchange));
+    }
+    Ok(())
+}
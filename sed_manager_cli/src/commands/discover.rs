@@ -0,0 +1,41 @@
+//L-----------------------------------------------------------------------------
+//L Copyright (C) Péter Kardos
+//L Please refer to the full license distributed with this software.
+//L-----------------------------------------------------------------------------
+
+use clap::Args;
+use sed_manager::applications::get_device_report;
+use sed_manager::device::{list_physical_drives, open_device};
+use serde_json::{json, Value};
+
+use crate::error::Error;
+
+#[derive(Args)]
+pub struct DiscoverArgs {
+    /// Path of the drive. All drives of the system are reported when omitted.
+    #[arg(short, long)]
+    device: Option<String>,
+    /// Print the JSON on a single line.
+    #[arg(long)]
+    compact: bool,
+}
+
+pub fn run(args: DiscoverArgs) -> Result<(), Error> {
+    let report = match &args.device {
+        Some(path) => get_device_report(&*open_device(path)?),
+        None => Value::Array(list_physical_drives()?.iter().map(|path| report_path(path)).collect()),
+    };
+    match args.compact {
+        true => println!("{report}"),
+        false => println!("{report:#}"),
+    }
+    Ok(())
+}
+
+/// Drives that cannot be opened are reported with the error instead of aborting the listing.
+fn report_path(path: &str) -> Value {
+    match open_device(path) {
+        Ok(device) => get_device_report(&*device),
+        Err(error) => json!({ "path": path, "error": error.to_string() }),
+    }
+}
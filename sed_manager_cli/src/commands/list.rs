@@ -0,0 +1,41 @@
+//L-----------------------------------------------------------------------------
+//L Copyright (C) Péter Kardos
+//L Please refer to the full license distributed with this software.
+//L-----------------------------------------------------------------------------
+
+use sed_manager::device::{list_physical_drives, open_device};
+use sed_manager::messaging::discovery::LockingDescriptor;
+use sed_manager::rpc::discover;
+
+use crate::error::Error;
+
+pub fn run() -> Result<(), Error> {
+    for path in list_physical_drives()? {
+        let device = match open_device(&path) {
+            Ok(device) => device,
+            Err(error) => {
+                println!("{path}\t-\t-\t-\t-\t{error}");
+                continue;
+            }
+        };
+        let model = device.model_number();
+        let serial = device.serial_number();
+        let interface = device.interface();
+        let status = match discover(&*device) {
+            Ok(discovery) => {
+                let ssc = discovery.get_primary_ssc().map(|ssc| ssc.feature_code().to_string());
+                let locking = discovery.get::<LockingDescriptor>();
+                let state = match locking {
+                    Some(desc) if desc.mbr_enabled && !desc.mbr_done => "shadowed",
+                    Some(desc) if desc.locked => "locked",
+                    Some(desc) if desc.locking_enabled => "unlocked",
+                    _ => "inactive",
+                };
+                format!("{}\t{state}", ssc.unwrap_or(String::from("none")))
+            }
+            Err(_) => String::from("none\tunsupported"),
+        };
+        println!("{path}\t{interface}\t{model}\t{serial}\t{status}");
+    }
+    Ok(())
+}
@@ -0,0 +1,134 @@
+//L-----------------------------------------------------------------------------
+//L Copyright (C) Péter Kardos
+//L Please refer to the full license distributed with this software.
+//L-----------------------------------------------------------------------------
+
+use std::io::Read as _;
+use std::io::Write as _;
+use std::sync::Arc;
+
+use clap::{Args, Subcommand};
+use sed_manager::applications::{is_mbr_editor_supported, Error as AppError, MBREditSession};
+use sed_manager::rpc::TokioRuntime;
+
+use crate::error::Error;
+use crate::password::PasswordReader;
+use crate::target::Target;
+use crate::DeviceArgs;
+
+#[derive(Args)]
+pub struct MBRArgs {
+    #[command(flatten)]
+    device: DeviceArgs,
+    #[command(subcommand)]
+    command: MBRCommand,
+}
+
+#[derive(Subcommand)]
+enum MBRCommand {
+    /// Print the size and the flags of the shadow MBR.
+    Status,
+    /// Change the flags of the shadow MBR. Omitted values are left unchanged.
+    Set {
+        #[arg(long)]
+        enabled: Option<bool>,
+        #[arg(long)]
+        done: Option<bool>,
+    },
+    /// Upload a file, typically a PBA image, into the shadow MBR.
+    Upload {
+        /// Path of the file to upload.
+        file: String,
+        /// Skip chunks that already match the file and read back the written ones.
+        /// Repeating an interrupted upload compares the whole file again but only writes what differs.
+        #[arg(long)]
+        verify: bool,
+    },
+    /// Download the contents of the shadow MBR into a file, for example to back up the PBA image.
+    Download {
+        /// Path of the file to create.
+        file: String,
+        /// Omit the trailing 512-byte blocks that contain only zeros.
+        #[arg(long)]
+        trim: bool,
+    },
+}
+
+pub async fn run(args: MBRArgs, passwords: &mut PasswordReader, runtime: Arc<TokioRuntime>) -> Result<(), Error> {
+    let target = Target::open(&args.device, runtime)?;
+    if !is_mbr_editor_supported(&target.discovery) {
+        return Err(AppError::IncompatibleSSC.into());
+    }
+    let password = passwords.read("the Admin1 password")?;
+    let session = MBREditSession::start(&target.tper, &password).await?;
+    let result = run_command(&session, args.command).await;
+    super::warn_if_end_failed(session.end().await);
+    result
+}
+
+async fn run_command(session: &MBREditSession, command: MBRCommand) -> Result<(), Error> {
+    match command {
+        MBRCommand::Status => {
+            println!("size: {}", session.get_size().await?);
+            println!("enabled: {}", session.get_enabled().await?);
+            println!("done: {}", session.get_done().await?);
+        }
+        MBRCommand::Set { enabled, done } => {
+            if let Some(enabled) = enabled {
+                session.set_enabled(enabled).await?;
+            }
+            if let Some(done) = done {
+                session.set_done(done).await?;
+            }
+        }
+        MBRCommand::Upload { file, verify } => upload(session, &file, verify).await?,
+        MBRCommand::Download { file, trim } => download(session, &file, trim).await?,
+    }
+    Ok(())
+}
+
+async fn upload(session: &MBREditSession, path: &str, verify: bool) -> Result<(), Error> {
+    let mut file = std::fs::File::open(path).map_err(|_| AppError::FileNotOpen)?;
+    let file_size = file.metadata().map_err(|_| AppError::FileReadError)?.len();
+    if file_size > session.get_size().await? {
+        return Err(AppError::FileTooLarge.into());
+    }
+    let read = async |buffer: &mut [u8]| file.read(buffer).map_err(|_| AppError::FileReadError);
+    let progress = |bytes: u64| eprint!("\ruploaded {bytes} / {file_size} bytes");
+    if verify {
+        let result = session.upload_verified(read, progress, || false).await;
+        eprintln!();
+        eprintln!("{} bytes written, the rest already matched", result?);
+        Ok(())
+    } else {
+        let result = session.upload(read, progress, || false).await;
+        eprintln!();
+        Ok(result?)
+    }
+}
+
+/// The MBR is downloaded to a temporary file that replaces `path` only when the download succeeds.
+async fn download(session: &MBREditSession, path: &str, trim: bool) -> Result<(), Error> {
+    let temp_path = format!("{path}.part");
+    let result = match download_to(session, &temp_path, trim).await {
+        Ok(num_bytes) => std::fs::rename(&temp_path, path).map(|_| num_bytes).map_err(|_| AppError::FileWriteError),
+        Err(error) => Err(error),
+    };
+    if result.is_err() {
+        let _ = std::fs::remove_file(&temp_path);
+    }
+    eprintln!("{} bytes written to {path}", result?);
+    Ok(())
+}
+
+async fn download_to(session: &MBREditSession, path: &str, trim: bool) -> Result<u64, AppError> {
+    let mut file = std::fs::File::create(path).map_err(|_| AppError::FileNotOpen)?;
+    let mbr_size = session.get_size().await?;
+    let write = async |buffer: &[u8]| file.write_all(buffer).map_err(|_| AppError::FileWriteError);
+    let progress = |bytes: u64| eprint!("\rdownloaded {bytes} / {mbr_size} bytes");
+    let result = session.download(mbr_size, trim, write, progress, || false).await;
+    eprintln!();
+    let num_bytes = result?;
+    file.sync_all().map_err(|_| AppError::FileWriteError)?;
+    Ok(num_bytes)
+}
@@ -0,0 +1,24 @@
+//L-----------------------------------------------------------------------------
+//L Copyright (C) Péter Kardos
+//L Please refer to the full license distributed with this software.
+//L-----------------------------------------------------------------------------
+
+pub mod decode;
+pub mod discover;
+pub mod list;
+pub mod mbr;
+pub mod ownership;
+pub mod password;
+pub mod permission;
+pub mod provision;
+pub mod range;
+pub mod user;
+
+use sed_manager::applications::Error as AppError;
+
+/// Reports a failure to close the session without masking the result of the command.
+fn warn_if_end_failed(result: Result<(), AppError>) {
+    if let Err(error) = result {
+        eprintln!("warning: failed to end the session: {error}");
+    }
+}
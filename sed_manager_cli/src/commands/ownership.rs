@@ -0,0 +1,100 @@
+//L-----------------------------------------------------------------------------
+//L Copyright (C) Péter Kardos
+//L Please refer to the full license distributed with this software.
+//L-----------------------------------------------------------------------------
+
+use std::sync::Arc;
+
+use clap::Args;
+use sed_manager::applications::{
+    activate_locking as app_activate_locking, is_activating_locking_supported, is_taking_ownership_supported,
+    revert as app_revert, take_ownership as app_take_ownership, verify_locking_activation, verify_ownership,
+    Error as AppError,
+};
+use sed_manager::messaging::discovery::LockingDescriptor;
+use sed_manager::rpc::TokioRuntime;
+
+use crate::error::Error;
+use crate::password::PasswordReader;
+use crate::target::Target;
+use crate::DeviceArgs;
+
+#[derive(Args)]
+pub struct TakeOwnershipArgs {
+    #[command(flatten)]
+    device: DeviceArgs,
+}
+
+#[derive(Args)]
+pub struct ActivateLockingArgs {
+    #[command(flatten)]
+    device: DeviceArgs,
+    /// Read a new password for Admin1 instead of reusing the SID password.
+    #[arg(long)]
+    set_admin1_password: bool,
+}
+
+#[derive(Args)]
+pub struct RevertArgs {
+    #[command(flatten)]
+    device: DeviceArgs,
+    /// The security provider to revert.
+    #[arg(long, default_value = "Admin")]
+    sp: String,
+    /// The authority to authenticate as, e.g. SID or PSID.
+    #[arg(long, default_value = "SID")]
+    authority: String,
+    /// Confirm that all data on the drive may be lost.
+    #[arg(long)]
+    yes: bool,
+}
+
+pub async fn take_ownership(
+    args: TakeOwnershipArgs,
+    passwords: &mut PasswordReader,
+    runtime: Arc<TokioRuntime>,
+) -> Result<(), Error> {
+    let target = Target::open(&args.device, runtime)?;
+    if !is_taking_ownership_supported(&target.discovery) {
+        return Err(AppError::AlreadyOwned.into());
+    }
+    let new_password = passwords.read("the new SID password")?;
+    app_take_ownership(&target.tper, &new_password).await?;
+    verify_ownership(&target.tper, &new_password).await?;
+    Ok(())
+}
+
+pub async fn activate_locking(
+    args: ActivateLockingArgs,
+    passwords: &mut PasswordReader,
+    runtime: Arc<TokioRuntime>,
+) -> Result<(), Error> {
+    let target = Target::open(&args.device, runtime)?;
+    if target.discovery.get::<LockingDescriptor>().is_none() {
+        return Err(AppError::IncompatibleSSC.into());
+    }
+    if !is_activating_locking_supported(&target.discovery) {
+        return Err(AppError::AlreadyActivated.into());
+    }
+    let sid_password = passwords.read("the SID password")?;
+    let admin1_password = match args.set_admin1_password {
+        true => Some(passwords.read("the new Admin1 password")?),
+        false => None,
+    };
+    app_activate_locking(&target.tper, &sid_password, admin1_password.as_deref()).await?;
+    let admin1_password = admin1_password.as_deref().unwrap_or(&sid_password);
+    verify_locking_activation(&target.tper, Some(admin1_password)).await?;
+    Ok(())
+}
+
+pub async fn revert(args: RevertArgs, passwords: &mut PasswordReader, runtime: Arc<TokioRuntime>) -> Result<(), Error> {
+    if !args.yes {
+        return Err(Error::InvalidArgument(String::from("reverting may erase all data, pass --yes to confirm")));
+    }
+    let target = Target::open(&args.device, runtime)?;
+    let sp = target.find_sp(&args.sp)?;
+    let authority = target.find_authority(&args.authority, target.admin_sp()?)?;
+    let password = passwords.read(&format!("the password of {}", args.authority))?;
+    app_revert(&target.tper, authority, &password, sp).await?;
+    Ok(())
+}
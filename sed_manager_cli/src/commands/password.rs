@@ -0,0 +1,52 @@
+//L-----------------------------------------------------------------------------
+//L Copyright (C) Péter Kardos
+//L Please refer to the full license distributed with this software.
+//L-----------------------------------------------------------------------------
+
+use std::sync::Arc;
+
+use clap::Args;
+use sed_manager::applications::{change_password as app_change_password, list_password_authorities};
+use sed_manager::rpc::TokioRuntime;
+
+use crate::error::Error;
+use crate::password::PasswordReader;
+use crate::target::Target;
+use crate::DeviceArgs;
+
+#[derive(Args)]
+pub struct ChangePasswordArgs {
+    #[command(flatten)]
+    device: DeviceArgs,
+    /// The security provider of the authority, e.g. Admin or Locking.
+    #[arg(long)]
+    sp: String,
+    /// The authority whose password to change, e.g. SID, Admin1, or User1.
+    #[arg(long)]
+    authority: String,
+}
+
+pub async fn list_authorities(args: DeviceArgs, runtime: Arc<TokioRuntime>) -> Result<(), Error> {
+    let target = Target::open(&args, runtime)?;
+    let authorities = list_password_authorities(&target.tper).await?;
+    for (sp, authority) in authorities {
+        let sp_name = target.name_of(sp.as_uid(), target.admin_sp().ok());
+        let authority_name = target.name_of(authority.as_uid(), Some(sp));
+        println!("{sp_name}\t{authority_name}");
+    }
+    Ok(())
+}
+
+pub async fn change_password(
+    args: ChangePasswordArgs,
+    passwords: &mut PasswordReader,
+    runtime: Arc<TokioRuntime>,
+) -> Result<(), Error> {
+    let target = Target::open(&args.device, runtime)?;
+    let sp = target.find_sp(&args.sp)?;
+    let authority = target.find_authority(&args.authority, sp)?;
+    let password = passwords.read(&format!("the current password of {}", args.authority))?;
+    let new_password = passwords.read(&format!("the new password of {}", args.authority))?;
+    app_change_password(&target.tper, sp, authority, &password, &new_password).await?;
+    Ok(())
+}
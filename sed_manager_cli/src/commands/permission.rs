@@ -0,0 +1,114 @@
+//L-----------------------------------------------------------------------------
+//L Copyright (C) Péter Kardos
+//L Please refer to the full license distributed with this software.
+//L-----------------------------------------------------------------------------
+
+use std::sync::Arc;
+
+use clap::{Args, Subcommand};
+use sed_manager::applications::{
+    is_permission_editor_supported, AtomicEdit as _, Error as AppError, PermissionEditSession,
+};
+use sed_manager::rpc::TokioRuntime;
+
+use crate::error::Error;
+use crate::password::PasswordReader;
+use crate::target::Target;
+use crate::DeviceArgs;
+
+#[derive(Args)]
+pub struct PermissionArgs {
+    #[command(flatten)]
+    device: DeviceArgs,
+    #[command(subcommand)]
+    command: PermissionCommand,
+}
+
+#[derive(Subcommand)]
+enum PermissionCommand {
+    /// Print which ranges a user can lock and unlock, and whether it can unshadow the MBR.
+    Show {
+        /// Name or UID of the user, e.g. User1.
+        user: String,
+    },
+    /// Grant or revoke permissions of a user. Omitted values are left unchanged.
+    Set {
+        /// Name or UID of the user.
+        user: String,
+        /// Name or UID of the range the read and write permissions apply to.
+        #[arg(long)]
+        range: Option<String>,
+        /// Permission to change the read lock of the range.
+        #[arg(long, requires = "range")]
+        read: Option<bool>,
+        /// Permission to change the write lock of the range.
+        #[arg(long, requires = "range")]
+        write: Option<bool>,
+        /// Permission to set the MBR done flag.
+        #[arg(long)]
+        mbr: Option<bool>,
+    },
+}
+
+pub async fn run(
+    args: PermissionArgs,
+    passwords: &mut PasswordReader,
+    runtime: Arc<TokioRuntime>,
+) -> Result<(), Error> {
+    let target = Target::open(&args.device, runtime)?;
+    if !is_permission_editor_supported(&target.discovery) {
+        return Err(AppError::IncompatibleSSC.into());
+    }
+    let password = passwords.read("the Admin1 password")?;
+    let session = PermissionEditSession::start(&target.tper, &password).await?;
+    let result = run_command(&target, &session, args.command).await;
+    super::warn_if_end_failed(session.end().await);
+    result
+}
+
+async fn run_command(
+    target: &Target,
+    session: &PermissionEditSession,
+    command: PermissionCommand,
+) -> Result<(), Error> {
+    let locking_sp = target.locking_sp()?;
+    match command {
+        PermissionCommand::Show { user } => {
+            let user = target.find_authority(&user, locking_sp)?;
+            if session.is_mbr_supported().await {
+                println!("mbr: {}", session.get_mbr_permission(user).await?);
+            }
+            for range in session.list_ranges().await? {
+                let name = target.name_of(range.as_uid(), Some(locking_sp));
+                let read = session.get_read_permission(user, range).await?;
+                let write = session.get_write_permission(user, range).await?;
+                println!("{name}: read={read} write={write}");
+            }
+        }
+        PermissionCommand::Set { user, range, read, write, mbr } => {
+            let user = target.find_authority(&user, locking_sp)?;
+            let range = range.map(|range| target.find_range(&range)).transpose()?;
+            let num_changes = [range.and(read), range.and(write), mbr].iter().flatten().count();
+            if num_changes > 1 && !session.is_atomic() {
+                eprintln!("warning: the drive does not support transactions, a failure may leave some changes applied");
+            }
+            session
+                .atomic(async |session| {
+                    if let Some(range) = range {
+                        if let Some(read) = read {
+                            session.set_read_permission(user, range, read).await?;
+                        }
+                        if let Some(write) = write {
+                            session.set_write_permission(user, range, write).await?;
+                        }
+                    }
+                    if let Some(mbr) = mbr {
+                        session.set_mbr_permission(user, mbr).await?;
+                    }
+                    Ok(())
+                })
+                .await?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,47 @@
+//L-----------------------------------------------------------------------------
+//L Copyright (C) Péter Kardos
+//L Please refer to the full license distributed with this software.
+//L-----------------------------------------------------------------------------
+
+use std::sync::Arc;
+
+use clap::Args;
+use sed_manager::applications::{apply_provisioning, plan_provisioning, Error as AppError, ProvisioningConfig};
+use sed_manager::rpc::TokioRuntime;
+
+use crate::error::Error;
+use crate::target::Target;
+use crate::DeviceArgs;
+
+#[derive(Args)]
+pub struct ProvisionArgs {
+    #[command(flatten)]
+    device: DeviceArgs,
+    /// Path of the TOML or JSON file that describes the desired state of the drive.
+    config: String,
+    /// Only print the steps that would be carried out.
+    #[arg(long)]
+    dry_run: bool,
+}
+
+pub async fn run(args: ProvisionArgs, runtime: Arc<TokioRuntime>) -> Result<(), Error> {
+    let config = read_config(&args.config)?;
+    let target = Target::open(&args.device, runtime)?;
+    if args.dry_run {
+        for step in plan_provisioning(&target.tper, &config).await? {
+            println!("{step}");
+        }
+    } else {
+        apply_provisioning(&target.tper, &config, |step| eprintln!("{step}")).await?;
+    }
+    Ok(())
+}
+
+fn read_config(path: &str) -> Result<ProvisioningConfig, Error> {
+    let text = std::fs::read_to_string(path).map_err(|_| AppError::FileNotOpen)?;
+    let config = match path.to_ascii_lowercase().ends_with(".json") {
+        true => ProvisioningConfig::from_json(&text)?,
+        false => ProvisioningConfig::from_toml(&text)?,
+    };
+    Ok(config)
+}
@@ -0,0 +1,253 @@
+//L-----------------------------------------------------------------------------
+//L Copyright (C) Péter Kardos
+//L Please refer to the full license distributed with this software.
+//L-----------------------------------------------------------------------------
+
+use std::sync::Arc;
+
+use clap::{Args, Subcommand};
+use sed_manager::applications::{
+    erase_band, get_partition_ranges, is_band_editor_supported, is_range_editor_supported, list_bands, AtomicEdit as _,
+    EnterpriseBandSession, Error as AppError, PartitionRange, RangeEditSession,
+};
+use sed_manager::messaging::discovery::GeometryDescriptor;
+use sed_manager::rpc::TokioRuntime;
+use sed_manager::spec::column_types::{ResetType, ResetTypes};
+use sed_manager::spec::objects::LockingRange;
+
+use crate::error::Error;
+use crate::password::PasswordReader;
+use crate::target::Target;
+use crate::DeviceArgs;
+
+#[derive(Args)]
+pub struct RangeArgs {
+    #[command(flatten)]
+    device: DeviceArgs,
+    #[command(subcommand)]
+    command: RangeCommand,
+}
+
+#[derive(Subcommand)]
+enum RangeCommand {
+    #[command(flatten)]
+    Session(SessionCommand),
+    /// List the partitions of the drive along with the locking range that covers each of them.
+    Partitions,
+}
+
+/// The commands that talk to the locking SP.
+#[derive(Subcommand)]
+enum SessionCommand {
+    /// List all locking ranges.
+    List,
+    /// Print the configuration of a locking range.
+    Show {
+        /// Name or UID of the range, e.g. GlobalRange or Range1.
+        range: String,
+    },
+    /// Change the configuration of a locking range. Omitted values are left unchanged.
+    Set(SetArgs),
+    /// Cryptographically erase a locking range by regenerating its media key.
+    Erase {
+        /// Name or UID of the range.
+        range: String,
+        /// Confirm that all data in the range will be lost.
+        #[arg(long)]
+        yes: bool,
+    },
+}
+
+#[derive(Args)]
+struct SetArgs {
+    /// Name or UID of the range.
+    range: String,
+    /// First LBA of the range.
+    #[arg(long)]
+    start: Option<u64>,
+    /// Number of LBAs in the range.
+    #[arg(long)]
+    length: Option<u64>,
+    /// Cover the partition with this number, as listed by `range partitions`, instead of giving the start and length.
+    #[arg(long, conflicts_with_all = ["start", "length"])]
+    partition: Option<usize>,
+    #[arg(long)]
+    read_lock_enabled: Option<bool>,
+    #[arg(long)]
+    write_lock_enabled: Option<bool>,
+    #[arg(long)]
+    read_locked: Option<bool>,
+    #[arg(long)]
+    write_locked: Option<bool>,
+    /// Lock the range on power cycle. Only supported on Enterprise bands.
+    #[arg(long)]
+    lock_on_reset: Option<bool>,
+}
+
+pub async fn run(args: RangeArgs, passwords: &mut PasswordReader, runtime: Arc<TokioRuntime>) -> Result<(), Error> {
+    let target = Target::open(&args.device, runtime)?;
+    let command = match args.command {
+        RangeCommand::Session(command) => command,
+        RangeCommand::Partitions => return print_partition_ranges(&target),
+    };
+    if let SessionCommand::Erase { yes: false, .. } = &command {
+        return Err(Error::InvalidArgument(String::from(
+            "erasing destroys all data in the range, pass --yes to confirm",
+        )));
+    }
+    if is_band_editor_supported(&target.discovery) {
+        return run_enterprise(&target, passwords, command).await;
+    }
+    if !is_range_editor_supported(&target.discovery) {
+        return Err(AppError::IncompatibleSSC.into());
+    }
+    if let SessionCommand::Set(SetArgs { lock_on_reset: Some(_), .. }) = &command {
+        return Err(Error::InvalidArgument(String::from("--lock-on-reset is only supported on Enterprise bands")));
+    }
+    let password = passwords.read("the Admin1 password")?;
+    let session = RangeEditSession::start(&target.tper, &password).await?;
+    let result = run_command(&target, &session, command).await;
+    super::warn_if_end_failed(session.end().await);
+    result
+}
+
+async fn run_command(target: &Target, session: &RangeEditSession, command: SessionCommand) -> Result<(), Error> {
+    let locking_sp = target.locking_sp()?;
+    match command {
+        SessionCommand::List => {
+            for range in session.list_ranges().await? {
+                println!("{}", target.name_of(range.as_uid(), Some(locking_sp)));
+            }
+        }
+        SessionCommand::Show { range } => {
+            let range = session.get_range(target.find_range(&range)?).await?;
+            print_range(target, &range, false)?;
+        }
+        SessionCommand::Set(set) => {
+            let range = target.find_range(&set.range)?;
+            let (start, length) = get_extent(target, &set)?;
+            // The transaction keeps others from changing the range between reading and writing it.
+            session
+                .atomic(async |session| {
+                    let current = session.get_range(range).await?;
+                    let updated = LockingRange {
+                        range_start: start.unwrap_or(current.range_start),
+                        range_length: length.unwrap_or(current.range_length),
+                        read_lock_enabled: set.read_lock_enabled.unwrap_or(current.read_lock_enabled),
+                        write_lock_enabled: set.write_lock_enabled.unwrap_or(current.write_lock_enabled),
+                        read_locked: set.read_locked.unwrap_or(current.read_locked),
+                        write_locked: set.write_locked.unwrap_or(current.write_locked),
+                        ..current
+                    };
+                    session.set_range(&updated).await
+                })
+                .await?;
+        }
+        SessionCommand::Erase { range, .. } => {
+            session.erase_range(target.find_range(&range)?).await?;
+        }
+    }
+    Ok(())
+}
+
+/// Enterprise has a separate BandMaster authority for each band, so there is no single session for all commands.
+async fn run_enterprise(target: &Target, passwords: &mut PasswordReader, command: SessionCommand) -> Result<(), Error> {
+    let locking_sp = target.locking_sp()?;
+    match command {
+        SessionCommand::List => {
+            for band in list_bands(&target.tper).await? {
+                println!("{}", target.name_of(band.as_uid(), Some(locking_sp)));
+            }
+        }
+        SessionCommand::Show { range } => {
+            let band = target.find_range(&range)?;
+            let password = passwords.read("the BandMaster password of the band")?;
+            let session = EnterpriseBandSession::start(&target.tper, band, &password).await?;
+            let result = session.get_band().await;
+            super::warn_if_end_failed(session.end().await);
+            print_range(target, &result?, true)?;
+        }
+        SessionCommand::Set(set) => {
+            let band = target.find_range(&set.range)?;
+            let password = passwords.read("the BandMaster password of the band")?;
+            let session = EnterpriseBandSession::start(&target.tper, band, &password).await?;
+            let result = set_band(target, &session, set).await;
+            super::warn_if_end_failed(session.end().await);
+            result?;
+        }
+        SessionCommand::Erase { range, .. } => {
+            let band = target.find_range(&range)?;
+            let password = passwords.read("the EraseMaster password")?;
+            erase_band(&target.tper, band, &password).await?;
+        }
+    }
+    Ok(())
+}
+
+async fn set_band(target: &Target, session: &EnterpriseBandSession, set: SetArgs) -> Result<(), Error> {
+    let current = session.get_band().await?;
+    let (start, length) = get_extent(target, &set)?;
+    let lock_on_reset = match set.lock_on_reset {
+        Some(true) => [ResetType::PowerCycle].into_iter().collect(),
+        Some(false) => ResetTypes::new(),
+        None => current.lock_on_reset.clone(),
+    };
+    let updated = LockingRange {
+        range_start: start.unwrap_or(current.range_start),
+        range_length: length.unwrap_or(current.range_length),
+        read_lock_enabled: set.read_lock_enabled.unwrap_or(current.read_lock_enabled),
+        write_lock_enabled: set.write_lock_enabled.unwrap_or(current.write_lock_enabled),
+        read_locked: set.read_locked.unwrap_or(current.read_locked),
+        write_locked: set.write_locked.unwrap_or(current.write_locked),
+        lock_on_reset,
+        ..current
+    };
+    Ok(session.set_band(&updated).await?)
+}
+
+fn read_partition_ranges(target: &Target) -> Result<Vec<PartitionRange>, Error> {
+    let geometry = target.discovery.get::<GeometryDescriptor>();
+    Ok(get_partition_ranges(&*target.device, geometry)?)
+}
+
+/// Get the start and length from the arguments, or from the partition if one is given.
+fn get_extent(target: &Target, set: &SetArgs) -> Result<(Option<u64>, Option<u64>), Error> {
+    let Some(number) = set.partition else {
+        return Ok((set.start, set.length));
+    };
+    let ranges = read_partition_ranges(target)?;
+    let range = ranges
+        .iter()
+        .find(|range| range.partition.number == number)
+        .ok_or(Error::InvalidArgument(format!("the drive has no partition {number}")))?;
+    Ok((Some(range.range_start), Some(range.range_length)))
+}
+
+fn print_partition_ranges(target: &Target) -> Result<(), Error> {
+    for (idx, range) in read_partition_ranges(target)?.iter().enumerate() {
+        if idx != 0 {
+            println!();
+        }
+        println!("partition: {}", range.partition.number);
+        println!("name: {}", range.partition.name);
+        println!("first_lba: {}", range.partition.first_lba);
+        println!("last_lba: {}", range.partition.last_lba);
+        println!("start: {}", range.range_start);
+        println!("length: {}", range.range_length);
+    }
+    Ok(())
+}
+
+fn print_range(target: &Target, range: &LockingRange, with_lock_on_reset: bool) -> Result<(), Error> {
+    println!("name: {}", target.name_of(range.uid.as_uid(), Some(target.locking_sp()?)));
+    println!("start: {}", range.range_start);
+    println!("length: {}", range.range_length);
+    println!("read_lock_enabled: {}", range.read_lock_enabled);
+    println!("write_lock_enabled: {}", range.write_lock_enabled);
+    println!("read_locked: {}", range.read_locked);
+    println!("write_locked: {}", range.write_locked);
+    if with_lock_on_reset {
+        println!("lock_on_reset: {}", range.lock_on_reset.contains(&ResetType::PowerCycle));
+    }
+    Ok(())
+}
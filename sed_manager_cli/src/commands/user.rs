@@ -0,0 +1,91 @@
+//L-----------------------------------------------------------------------------
+//L Copyright (C) Péter Kardos
+//L Please refer to the full license distributed with this software.
+//L-----------------------------------------------------------------------------
+
+use std::sync::Arc;
+
+use clap::{Args, Subcommand};
+use sed_manager::applications::{is_user_editor_supported, Error as AppError, UserEditSession};
+use sed_manager::rpc::TokioRuntime;
+
+use crate::error::Error;
+use crate::password::PasswordReader;
+use crate::target::Target;
+use crate::DeviceArgs;
+
+#[derive(Args)]
+pub struct UserArgs {
+    #[command(flatten)]
+    device: DeviceArgs,
+    #[command(subcommand)]
+    command: UserCommand,
+}
+
+#[derive(Subcommand)]
+enum UserCommand {
+    /// List all users and admins of the locking SP.
+    List,
+    /// Print the configuration of a user.
+    Show {
+        /// Name or UID of the user, e.g. Admin1 or User1.
+        user: String,
+    },
+    /// Enable a user.
+    Enable { user: String },
+    /// Disable a user.
+    Disable { user: String },
+    /// Change the common name of a user.
+    Rename { user: String, name: String },
+    /// Change the password of a user. Passwords: Admin1 password, new user password.
+    SetPassword { user: String },
+}
+
+pub async fn run(args: UserArgs, passwords: &mut PasswordReader, runtime: Arc<TokioRuntime>) -> Result<(), Error> {
+    let target = Target::open(&args.device, runtime)?;
+    if !is_user_editor_supported(&target.discovery) {
+        return Err(AppError::IncompatibleSSC.into());
+    }
+    let password = passwords.read("the Admin1 password")?;
+    let session = UserEditSession::start(&target.tper, &password).await?;
+    let result = run_command(&target, &session, args.command, passwords).await;
+    super::warn_if_end_failed(session.end().await);
+    result
+}
+
+async fn run_command(
+    target: &Target,
+    session: &UserEditSession,
+    command: UserCommand,
+    passwords: &mut PasswordReader,
+) -> Result<(), Error> {
+    let locking_sp = target.locking_sp()?;
+    match command {
+        UserCommand::List => {
+            for user in session.list_users().await? {
+                println!("{}", target.name_of(user.as_uid(), Some(locking_sp)));
+            }
+        }
+        UserCommand::Show { user } => {
+            let user = session.get_user(target.find_authority(&user, locking_sp)?).await?;
+            println!("name: {}", target.name_of(user.uid.as_uid(), Some(locking_sp)));
+            println!("common_name: {}", String::from_utf8_lossy(user.common_name.as_slice()));
+            println!("enabled: {}", user.enabled);
+        }
+        UserCommand::Enable { user } => {
+            session.set_enabled(target.find_authority(&user, locking_sp)?, true).await?;
+        }
+        UserCommand::Disable { user } => {
+            session.set_enabled(target.find_authority(&user, locking_sp)?, false).await?;
+        }
+        UserCommand::Rename { user, name } => {
+            session.set_name(target.find_authority(&user, locking_sp)?, &name).await?;
+        }
+        UserCommand::SetPassword { user } => {
+            let authority = target.find_authority(&user, locking_sp)?;
+            let new_password = passwords.read(&format!("the new password of {user}"))?;
+            session.set_password(authority, &new_password).await?;
+        }
+    }
+    Ok(())
+}
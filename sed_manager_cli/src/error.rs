@@ -0,0 +1,185 @@
+//L-----------------------------------------------------------------------------
+//L Copyright (C) Péter Kardos
+//L Please refer to the full license distributed with this software.
+//L-----------------------------------------------------------------------------
+
+use std::process::ExitCode;
+
+use sed_manager::applications::Error as AppError;
+use sed_manager::device::trace::TraceError;
+use sed_manager::device::Error as DeviceError;
+use sed_manager::partitions::Error as PartitionTableError;
+use sed_manager::rpc::{Error as RPCError, MethodStatus};
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum Error {
+    #[error("Invalid argument: {}", .0)]
+    InvalidArgument(String),
+    #[error("No password was provided for {}", .0)]
+    MissingPassword(String),
+    #[error("Cannot read password: {}", .0)]
+    CannotReadPassword(String),
+    #[error("{}", .0)]
+    DeviceError(DeviceError),
+    #[error("{}", .0)]
+    TraceError(TraceError),
+    #[error("{}", .0)]
+    RPCError(RPCError),
+    #[error("{}", .0)]
+    AppError(AppError),
+}
+
+/// Exit codes returned by the process.
+///
+/// The codes are part of the command-line interface and must not be renumbered.
+pub mod exit_code {
+    pub const FAILURE: u8 = 1;
+    pub const USAGE: u8 = 2; // Same as what clap uses for malformed command lines.
+    pub const DEVICE: u8 = 3;
+    pub const NOT_SUPPORTED: u8 = 4;
+    pub const ALREADY_CONFIGURED: u8 = 5;
+    pub const NOT_AUTHORIZED: u8 = 6;
+    pub const LOCKED_OUT: u8 = 7;
+    pub const METHOD_FAILED: u8 = 8;
+    pub const PROTOCOL: u8 = 9;
+    pub const CANCELLED: u8 = 10;
+    pub const FILE: u8 = 11;
+    pub const INTERNAL: u8 = 12;
+}
+
+pub const EXIT_CODE_HELP: &str = r"Exit codes:
+  0   Success
+  1   Unspecified failure
+  2   Invalid command line, argument, or object name
+  3   Device could not be opened or accessed
+  4   Operation not supported by the device or its SSC
+  5   Device is already owned or activated
+  6   Authentication failed or not authorized
+  7   Authority is locked out
+  8   Method call failed on the device for another reason
+  9   Communication or protocol error
+  10  Operation cancelled
+  11  Cannot read input file or write output file
+  12  Internal error";
+
+impl Error {
+    pub fn exit_code(&self) -> u8 {
+        use exit_code::*;
+        match self {
+            Error::InvalidArgument(_) => USAGE,
+            Error::MissingPassword(_) => USAGE,
+            Error::CannotReadPassword(_) => FILE,
+            Error::DeviceError(_) => DEVICE,
+            Error::TraceError(_) => FILE,
+            Error::RPCError(error) => rpc_exit_code(error),
+            Error::AppError(error) => app_exit_code(error),
+        }
+    }
+}
+
+fn app_exit_code(error: &AppError) -> u8 {
+    use exit_code::*;
+    match error {
+        AppError::RPCError(error) => rpc_exit_code(error),
+        AppError::IncompatibleSSC => NOT_SUPPORTED,
+        AppError::NoAvailableSSC => NOT_SUPPORTED,
+        AppError::AlreadyOwned => ALREADY_CONFIGURED,
+        AppError::AlreadyActivated => ALREADY_CONFIGURED,
+        AppError::InternalError => INTERNAL,
+        AppError::Cancelled => CANCELLED,
+        AppError::FileNotOpen => FILE,
+        AppError::FileReadError => FILE,
+        AppError::FileWriteError => FILE,
+        AppError::FileTooLarge => FILE,
+        AppError::VerificationFailed => DEVICE,
+        AppError::InvalidACEExpression => USAGE,
+        AppError::InvalidConfig(_) => USAGE,
+        AppError::InvalidBand => USAGE,
+        AppError::NoFreeKeyTag => DEVICE,
+        AppError::KeyTagNotAllocated => USAGE,
+        AppError::InvalidRange(_) => USAGE,
+        AppError::PartitionTableError(PartitionTableError::NoPartitionTable) => NOT_SUPPORTED,
+        AppError::PartitionTableError(_) => DEVICE,
+    }
+}
+
+fn rpc_exit_code(error: &RPCError) -> u8 {
+    use exit_code::*;
+    match error {
+        RPCError::TokenStreamFailed(_) => PROTOCOL,
+        RPCError::SerializationFailed(_) => PROTOCOL,
+        RPCError::SecurityCommandFailed(_) => DEVICE,
+        RPCError::Aborted => PROTOCOL,
+        RPCError::Closed => PROTOCOL,
+        RPCError::TimedOut => PROTOCOL,
+        RPCError::MethodTooLarge => PROTOCOL,
+        RPCError::TokenTooLarge => PROTOCOL,
+        RPCError::MethodCallExpected => PROTOCOL,
+        RPCError::MethodResultExpected => PROTOCOL,
+        RPCError::EOSExpected => PROTOCOL,
+        RPCError::ResultTypeMismatch => PROTOCOL,
+        RPCError::MethodFailed(MethodStatus::NotAuthorized) => NOT_AUTHORIZED,
+        RPCError::MethodFailed(MethodStatus::AuthorityLockedOut) => LOCKED_OUT,
+        RPCError::MethodFailed(_) => METHOD_FAILED,
+        RPCError::NotSupported => NOT_SUPPORTED,
+        RPCError::NotImplemented => NOT_SUPPORTED,
+        RPCError::Unspecified => FAILURE,
+    }
+}
+
+impl From<&Error> for ExitCode {
+    fn from(value: &Error) -> Self {
+        ExitCode::from(value.exit_code())
+    }
+}
+
+impl From<DeviceError> for Error {
+    fn from(value: DeviceError) -> Self {
+        Self::DeviceError(value)
+    }
+}
+
+impl From<TraceError> for Error {
+    fn from(value: TraceError) -> Self {
+        Self::TraceError(value)
+    }
+}
+
+impl From<RPCError> for Error {
+    fn from(value: RPCError) -> Self {
+        Self::RPCError(value)
+    }
+}
+
+impl From<AppError> for Error {
+    fn from(value: AppError) -> Self {
+        Self::AppError(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exit_code_not_authorized() {
+        let error = Error::from(AppError::RPCError(RPCError::MethodFailed(MethodStatus::NotAuthorized)));
+        assert_eq!(error.exit_code(), exit_code::NOT_AUTHORIZED);
+        let error = Error::from(RPCError::MethodFailed(MethodStatus::NotAuthorized));
+        assert_eq!(error.exit_code(), exit_code::NOT_AUTHORIZED);
+    }
+
+    #[test]
+    fn exit_code_device() {
+        let error = Error::from(RPCError::SecurityCommandFailed(DeviceError::PermissionDenied));
+        assert_eq!(error.exit_code(), exit_code::DEVICE);
+        let error = Error::from(DeviceError::DeviceNotFound);
+        assert_eq!(error.exit_code(), exit_code::DEVICE);
+    }
+
+    #[test]
+    fn exit_code_invalid_ace_expression() {
+        let error = Error::from(AppError::InvalidACEExpression);
+        assert_eq!(error.exit_code(), exit_code::USAGE);
+    }
+}
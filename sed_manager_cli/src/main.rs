@@ -0,0 +1,107 @@
+//L-----------------------------------------------------------------------------
+//L Copyright (C) Péter Kardos
+//L Please refer to the full license distributed with this software.
+//L-----------------------------------------------------------------------------
+
+mod commands;
+mod error;
+mod password;
+mod target;
+
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::sync::Arc;
+
+use clap::{Args, Parser, Subcommand};
+use sed_manager::rpc::TokioRuntime;
+
+use commands::{decode, discover, mbr, ownership, password as password_commands, permission, provision, range, user};
+use error::{Error, EXIT_CODE_HELP};
+use password::{PasswordReader, PasswordSource};
+
+/// Configure self-encrypting drives from the command line.
+///
+/// Passwords are never accepted as arguments. They are read line by line from the
+/// password source, in the order listed in the help of each command.
+#[derive(Parser)]
+#[command(name = "sed-manager-cli", version, after_help = EXIT_CODE_HELP)]
+struct Cli {
+    /// Where to read passwords from: `stdin`, `fd:<N>`, or `file:<PATH>`.
+    #[arg(long, global = true, default_value = "stdin")]
+    passwords: PasswordSource,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Args)]
+pub struct DeviceArgs {
+    /// Path of the drive, e.g. /dev/nvme0 or \\.\PhysicalDrive0.
+    #[arg(short, long)]
+    pub device: String,
+    /// Record the security commands exchanged with the drive to a capture file.
+    /// Captures may contain passwords in plain text.
+    #[arg(long, value_name = "FILE")]
+    pub capture: Option<PathBuf>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// List the drives of the system and their security status.
+    List,
+    /// Print the discovery data of drives as JSON, for consumption by scripts.
+    Discover(discover::DiscoverArgs),
+    /// Set the SID password. Passwords: new SID password.
+    TakeOwnership(ownership::TakeOwnershipArgs),
+    /// Activate the locking SP. Passwords: SID password, [new Admin1 password].
+    ActivateLocking(ownership::ActivateLockingArgs),
+    /// Revert an SP to its factory state. Passwords: password of the authority.
+    Revert(ownership::RevertArgs),
+    /// List the authorities that have a password.
+    ListAuthorities(DeviceArgs),
+    /// Change the password of an authority. Passwords: current password, new password.
+    ChangePassword(password_commands::ChangePasswordArgs),
+    /// Configure locking ranges. Passwords: Admin1 password, or on Enterprise, BandMaster or EraseMaster password.
+    Range(range::RangeArgs),
+    /// Configure users. Passwords: Admin1 password, [new user password].
+    User(user::UserArgs),
+    /// Configure the shadow MBR. Passwords: Admin1 password.
+    Mbr(mbr::MBRArgs),
+    /// Configure the access of users to ranges and the shadow MBR. Passwords: Admin1 password.
+    Permission(permission::PermissionArgs),
+    /// Print the method calls and results of a capture file in readable form.
+    Decode(decode::DecodeArgs),
+    /// Bring a drive to the state described by a TOML or JSON file. Passwords are taken from the file.
+    Provision(provision::ProvisionArgs),
+}
+
+async fn run(cli: Cli, runtime: Arc<TokioRuntime>) -> Result<(), Error> {
+    let mut passwords = PasswordReader::open(cli.passwords);
+    match cli.command {
+        Command::List => commands::list::run(),
+        Command::Discover(args) => discover::run(args),
+        Command::TakeOwnership(args) => ownership::take_ownership(args, &mut passwords, runtime).await,
+        Command::ActivateLocking(args) => ownership::activate_locking(args, &mut passwords, runtime).await,
+        Command::Revert(args) => ownership::revert(args, &mut passwords, runtime).await,
+        Command::ListAuthorities(args) => password_commands::list_authorities(args, runtime).await,
+        Command::ChangePassword(args) => password_commands::change_password(args, &mut passwords, runtime).await,
+        Command::Range(args) => range::run(args, &mut passwords, runtime).await,
+        Command::User(args) => user::run(args, &mut passwords, runtime).await,
+        Command::Mbr(args) => mbr::run(args, &mut passwords, runtime).await,
+        Command::Permission(args) => permission::run(args, &mut passwords, runtime).await,
+        Command::Provision(args) => provision::run(args, runtime).await,
+        Command::Decode(args) => decode::run(args),
+    }
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let runtime = Arc::new(TokioRuntime::new());
+    match run(cli, runtime).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("error: {error}");
+            ExitCode::from(&error)
+        }
+    }
+}
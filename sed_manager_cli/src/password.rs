@@ -0,0 +1,143 @@
+//L-----------------------------------------------------------------------------
+//L Copyright (C) Péter Kardos
+//L Please refer to the full license distributed with this software.
+//L-----------------------------------------------------------------------------
+
+use std::io::{BufRead, BufReader, Read};
+use std::str::FromStr;
+
+use crate::error::Error;
+
+/// Where the passwords of a command are read from.
+///
+/// Every password is read as a single line, in the order documented for the command.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PasswordSource {
+    Stdin,
+    FileDescriptor(i32),
+    File(String),
+}
+
+/// Reads the passwords from the source given on the command line.
+///
+/// The source is only opened when the first password is read, so commands that need
+/// no password don't consume stdin or take over a file descriptor.
+pub struct PasswordReader {
+    source: PasswordSource,
+    reader: Option<Box<dyn BufRead>>,
+}
+
+impl FromStr for PasswordSource {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s == "-" || s == "stdin" {
+            Ok(Self::Stdin)
+        } else if let Some(fd) = s.strip_prefix("fd:") {
+            fd.parse().map(Self::FileDescriptor).map_err(|_| format!("invalid file descriptor: {fd}"))
+        } else if let Some(path) = s.strip_prefix("file:") {
+            Ok(Self::File(path.into()))
+        } else {
+            Err(String::from("expected `stdin`, `fd:<N>`, or `file:<PATH>`"))
+        }
+    }
+}
+
+impl PasswordReader {
+    pub fn open(source: PasswordSource) -> Self {
+        Self { source, reader: None }
+    }
+
+    #[cfg(test)]
+    pub fn new(reader: impl BufRead + 'static) -> Self {
+        Self { source: PasswordSource::Stdin, reader: Some(Box::new(reader)) }
+    }
+
+    /// Read the next password.
+    ///
+    /// The `what` argument describes the password for error messages.
+    pub fn read(&mut self, what: &str) -> Result<Vec<u8>, Error> {
+        let reader = match &mut self.reader {
+            Some(reader) => reader,
+            None => self.reader.insert(open_source(&self.source)?),
+        };
+        let mut line = Vec::new();
+        let len = reader.read_until(b'\n', &mut line).map_err(|err| Error::CannotReadPassword(err.to_string()))?;
+        if len == 0 {
+            return Err(Error::MissingPassword(what.into()));
+        }
+        if line.ends_with(b"\n") {
+            line.pop();
+        }
+        if line.ends_with(b"\r") {
+            line.pop();
+        }
+        Ok(line)
+    }
+}
+
+fn open_source(source: &PasswordSource) -> Result<Box<dyn BufRead>, Error> {
+    let reader: Box<dyn Read> = match source {
+        PasswordSource::Stdin => Box::new(std::io::stdin()),
+        PasswordSource::FileDescriptor(fd) => open_fd(*fd)?,
+        PasswordSource::File(path) => {
+            Box::new(std::fs::File::open(path).map_err(|err| Error::CannotReadPassword(err.to_string()))?)
+        }
+    };
+    Ok(Box::new(BufReader::new(reader)))
+}
+
+#[cfg(unix)]
+fn open_fd(fd: i32) -> Result<Box<dyn Read>, Error> {
+    use nix::fcntl::{fcntl, FcntlArg};
+    use std::os::fd::FromRawFd as _;
+    // Taking ownership of a descriptor that is not open would be undefined behavior.
+    if fd < 0 || fcntl(fd, FcntlArg::F_GETFD).is_err() {
+        return Err(Error::InvalidArgument(format!("invalid file descriptor: {fd}")));
+    }
+    // SAFETY: the file descriptor is open and was explicitly handed to the process by the caller.
+    // Ownership is taken over and the descriptor is closed once the passwords are read.
+    Ok(Box::new(unsafe { std::fs::File::from_raw_fd(fd) }))
+}
+
+#[cfg(not(unix))]
+fn open_fd(_fd: i32) -> Result<Box<dyn Read>, Error> {
+    Err(Error::InvalidArgument(String::from("file descriptors are only supported on Unix")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_source() {
+        assert_eq!(PasswordSource::from_str("stdin"), Ok(PasswordSource::Stdin));
+        assert_eq!(PasswordSource::from_str("-"), Ok(PasswordSource::Stdin));
+        assert_eq!(PasswordSource::from_str("fd:3"), Ok(PasswordSource::FileDescriptor(3)));
+        assert_eq!(PasswordSource::from_str("file:/a/b"), Ok(PasswordSource::File("/a/b".into())));
+        assert!(PasswordSource::from_str("fd:x").is_err());
+        assert!(PasswordSource::from_str("password").is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn open_fd_not_open() {
+        let mut reader = PasswordReader::open(PasswordSource::FileDescriptor(1 << 20));
+        assert!(matches!(reader.read("1"), Err(Error::InvalidArgument(_))));
+    }
+
+    #[test]
+    fn open_source_lazily() {
+        let mut reader = PasswordReader::open(PasswordSource::File("/nonexistent/passwords".into()));
+        assert!(matches!(reader.read("1"), Err(Error::CannotReadPassword(_))));
+    }
+
+    #[test]
+    fn read_lines() {
+        let mut reader = PasswordReader::new(std::io::Cursor::new(b"first\nsecond\r\n\nlast".to_vec()));
+        assert_eq!(reader.read("1"), Ok(b"first".to_vec()));
+        assert_eq!(reader.read("2"), Ok(b"second".to_vec()));
+        assert_eq!(reader.read("3"), Ok(b"".to_vec()));
+        assert_eq!(reader.read("4"), Ok(b"last".to_vec()));
+        assert_eq!(reader.read("5"), Err(Error::MissingPassword("5".into())));
+    }
+}
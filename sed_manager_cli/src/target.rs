@@ -0,0 +1,159 @@
+//L-----------------------------------------------------------------------------
+//L Copyright (C) Péter Kardos
+//L Please refer to the full license distributed with this software.
+//L-----------------------------------------------------------------------------
+
+use std::sync::Arc;
+
+use sed_manager::applications::{get_admin_sp, get_feature_lookup, get_locking_sp, Error as AppError};
+use sed_manager::device::trace::RecordingDevice;
+use sed_manager::device::{open_device, Device};
+use sed_manager::messaging::discovery::{Discovery, Feature, FeatureCode};
+use sed_manager::messaging::uid::UID;
+use sed_manager::rpc::{discover, TokioRuntime};
+use sed_manager::spec::column_types::{AuthorityRef, LockingRangeRef, SPRef};
+use sed_manager::spec::table_id;
+use sed_manager::spec::{self, ObjectLookup};
+use sed_manager::tper::TPer;
+
+use crate::error::Error;
+use crate::DeviceArgs;
+
+/// A device opened for configuration, along with its TPer and its discovery.
+pub struct Target {
+    pub device: Arc<dyn Device>,
+    pub discovery: Discovery,
+    pub tper: TPer,
+}
+
+impl Target {
+    pub fn open(args: &DeviceArgs, runtime: Arc<TokioRuntime>) -> Result<Self, Error> {
+        let device: Arc<dyn Device> = Arc::from(open_device(&args.device)?);
+        let device: Arc<dyn Device> = match &args.capture {
+            Some(path) => Arc::new(RecordingDevice::create(device, path)?),
+            None => device,
+        };
+        Self::new(device, runtime)
+    }
+
+    pub fn new(device: Arc<dyn Device>, runtime: Arc<TokioRuntime>) -> Result<Self, Error> {
+        let discovery = discover(&*device)?;
+        let tper = TPer::new_on_default_com_id(device.clone(), runtime)?;
+        Ok(Self { device, discovery, tper })
+    }
+
+    pub fn ssc(&self) -> Result<FeatureCode, Error> {
+        let ssc = self.discovery.get_primary_ssc().ok_or(AppError::NoAvailableSSC)?;
+        Ok(ssc.feature_code())
+    }
+
+    pub fn admin_sp(&self) -> Result<SPRef, Error> {
+        Ok(get_admin_sp(self.ssc()?)?)
+    }
+
+    pub fn locking_sp(&self) -> Result<SPRef, Error> {
+        Ok(get_locking_sp(self.ssc()?)?)
+    }
+
+    /// Get the human-readable name of an object, or its UID if it has no name.
+    pub fn name_of(&self, uid: UID, sp: Option<SPRef>) -> String {
+        let sp = sp.map(|sp| sp.as_uid());
+        let feature_lookups = self.discovery.iter().filter_map(|desc| get_feature_lookup(desc.feature_code()));
+        for lookup in feature_lookups {
+            if let Some(name) = lookup.by_uid(uid, sp) {
+                return name;
+            }
+        }
+        spec::psid::OBJECT_LOOKUP
+            .by_uid(uid, sp)
+            .or(spec::core::OBJECT_LOOKUP.by_uid(uid, sp))
+            .unwrap_or(uid.to_string())
+    }
+
+    /// Find an object by its name or by its hexadecimal UID.
+    pub fn find(&self, name: &str, table: UID, sp: Option<SPRef>) -> Result<UID, Error> {
+        if let Some(uid) = parse_uid(name) {
+            return Ok(uid);
+        }
+        let sp = sp.map(|sp| sp.as_uid());
+        let feature_lookups = self.discovery.iter().filter_map(|desc| get_feature_lookup(desc.feature_code()));
+        for lookup in feature_lookups {
+            if let Some(uid) = lookup.by_name(name, table, sp) {
+                return Ok(uid);
+            }
+        }
+        spec::psid::OBJECT_LOOKUP
+            .by_name(name, table, sp)
+            .or(spec::core::OBJECT_LOOKUP.by_name(name, table, sp))
+            .ok_or(Error::InvalidArgument(format!("no object named `{name}`")))
+    }
+
+    pub fn find_sp(&self, name: &str) -> Result<SPRef, Error> {
+        let uid = self.find(name, table_id::SP.as_uid(), Some(self.admin_sp()?))?;
+        SPRef::try_from(uid).map_err(|_| Error::InvalidArgument(format!("`{name}` is not a security provider")))
+    }
+
+    pub fn find_authority(&self, name: &str, sp: SPRef) -> Result<AuthorityRef, Error> {
+        let uid = self.find(name, table_id::AUTHORITY.as_uid(), Some(sp))?;
+        AuthorityRef::try_from(uid).map_err(|_| Error::InvalidArgument(format!("`{name}` is not an authority")))
+    }
+
+    pub fn find_range(&self, name: &str) -> Result<LockingRangeRef, Error> {
+        let uid = self.find(name, table_id::LOCKING.as_uid(), Some(self.locking_sp()?))?;
+        LockingRangeRef::try_from(uid).map_err(|_| Error::InvalidArgument(format!("`{name}` is not a locking range")))
+    }
+}
+
+fn parse_uid(text: &str) -> Option<UID> {
+    let digits = text.strip_prefix("0x").or(text.strip_prefix("0X"))?;
+    let digits = digits.replace('_', "");
+    u64::from_str_radix(&digits, 16).ok().map(UID::from)
+}
+
+#[cfg(test)]
+mod tests {
+    use sed_manager::fake_device::FakeDevice;
+    use sed_manager::spec::opal;
+
+    use super::*;
+
+    fn setup_target() -> Target {
+        Target::new(Arc::new(FakeDevice::new()), Arc::new(TokioRuntime::new())).unwrap()
+    }
+
+    #[test]
+    fn find_by_name() -> Result<(), Error> {
+        let target = setup_target();
+        assert_eq!(target.find_sp("Admin")?, opal::admin::sp::ADMIN);
+        assert_eq!(target.find_sp("Locking")?, opal::admin::sp::LOCKING);
+        assert_eq!(target.find_authority("SID", opal::admin::sp::ADMIN)?, opal::admin::authority::SID);
+        assert_eq!(
+            target.find_authority("PSID", opal::admin::sp::ADMIN)?,
+            sed_manager::spec::psid::admin::authority::PSID
+        );
+        assert_eq!(
+            target.find_authority("User1", opal::admin::sp::LOCKING)?,
+            opal::locking::authority::USER.nth(1).unwrap()
+        );
+        assert_eq!(target.find_range("GlobalRange")?, opal::locking::locking::GLOBAL_RANGE);
+        assert_eq!(target.find_range("Range1")?, opal::locking::locking::RANGE.nth(1).unwrap());
+        assert!(target.find_range("NotARange").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn name_of_object() -> Result<(), Error> {
+        let target = setup_target();
+        let range = opal::locking::locking::RANGE.nth(1).unwrap();
+        assert_eq!(target.name_of(range.as_uid(), Some(opal::admin::sp::LOCKING)), "Range1");
+        Ok(())
+    }
+
+    #[test]
+    fn parse_uid_hex() {
+        assert_eq!(parse_uid("0x0000080200000001"), Some(UID::new(0x0000_0802_0000_0001)));
+        assert_eq!(parse_uid("0x00000802_00000001"), Some(UID::new(0x0000_0802_0000_0001)));
+        assert_eq!(parse_uid("Range1"), None);
+        assert_eq!(parse_uid("0xZZ"), None);
+    }
+}
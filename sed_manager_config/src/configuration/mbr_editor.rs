@@ -11,7 +11,7 @@ use std::time::Duration;
 use slint::{ComponentHandle as _, Model};
 
 use sed_manager::applications::{Error as AppError, MBREditSession};
-use tokio::io::AsyncReadExt;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
 
 use crate::backend::{Backend, EditorSession};
 use crate::frontend::Frontend;
@@ -24,12 +24,17 @@ pub fn init(frontend: &Frontend, num_devices: usize) {
         let login_status = ui::ExtendedStatus::error("missing callback".into());
         let control_status = ui::ExtendedStatus::success();
         let upload_status = ui::ExtendedStatus::success();
+        let download_status = ui::ExtendedStatus::success();
         mbr_editor_state.set_login_statuses(into_vec_model(vec![login_status; num_devices]));
         mbr_editor_state.set_control_statuses(into_vec_model(vec![control_status; num_devices]));
         mbr_editor_state.set_upload_statuses(into_vec_model(vec![upload_status; num_devices]));
         mbr_editor_state.set_upload_files(into_vec_model(vec![slint::SharedString::new(); num_devices]));
         mbr_editor_state.set_upload_progresses(into_vec_model(vec![0.0; num_devices]));
         mbr_editor_state.set_upload_cancel_reqs(into_vec_model(vec![false; num_devices]));
+        mbr_editor_state.set_download_statuses(into_vec_model(vec![download_status; num_devices]));
+        mbr_editor_state.set_download_files(into_vec_model(vec![slint::SharedString::new(); num_devices]));
+        mbr_editor_state.set_download_progresses(into_vec_model(vec![0.0; num_devices]));
+        mbr_editor_state.set_download_cancel_reqs(into_vec_model(vec![false; num_devices]));
         mbr_editor_state.set_mbr_control(into_vec_model(vec![ui::MBRControl::default(); num_devices]));
     });
 }
@@ -45,6 +50,8 @@ pub fn set_callbacks(backend: Rc<PeekCell<Backend>>, frontend: Frontend) {
     set_callback_set_done(backend.clone(), frontend.clone());
     set_callback_open(frontend.clone());
     set_callback_upload(backend.clone(), frontend.clone());
+    set_callback_save(frontend.clone());
+    set_callback_download(backend.clone(), frontend.clone());
 }
 
 fn set_callback_login(backend: Rc<PeekCell<Backend>>, frontend: Frontend) {
@@ -146,6 +153,24 @@ fn set_callback_upload(backend: Rc<PeekCell<Backend>>, frontend: Frontend) {
     });
 }
 
+fn set_callback_download(backend: Rc<PeekCell<Backend>>, frontend: Frontend) {
+    frontend.clone().with(|window| {
+        let mbr_editor_state = window.global::<ui::MBREditorState>();
+
+        mbr_editor_state.on_download(move |device_idx, file, trim| {
+            let frontend = frontend.clone();
+            let backend = backend.clone();
+            let device_idx = device_idx as usize;
+            set_download_status(&frontend, device_idx, ui::ExtendedStatus::loading());
+            let _ = slint::spawn_local(async move {
+                let result = download(backend, frontend.clone(), device_idx, file.into(), trim).await;
+                set_download_progress(&frontend, device_idx, 0.0);
+                set_download_status(&frontend, device_idx, ui::ExtendedStatus::from_result(result));
+            });
+        });
+    });
+}
+
 fn set_callback_open(frontend: Frontend) {
     frontend.clone().with(|window| {
         let mbr_editor_state = window.global::<ui::MBREditorState>();
@@ -162,6 +187,22 @@ fn set_callback_open(frontend: Frontend) {
     });
 }
 
+fn set_callback_save(frontend: Frontend) {
+    frontend.clone().with(|window| {
+        let mbr_editor_state = window.global::<ui::MBREditorState>();
+
+        mbr_editor_state.on_save(move |device_idx| {
+            let frontend = frontend.clone();
+            let device_idx = device_idx as usize;
+            let _ = slint::spawn_local(async move {
+                if let Some(file) = rfd::AsyncFileDialog::new().save_file().await {
+                    set_download_file(&frontend, device_idx, file.path().to_string_lossy().into());
+                }
+            });
+        });
+    });
+}
+
 async fn login(backend: Rc<PeekCell<Backend>>, device_idx: usize, password: String) -> Result<(), AppError> {
     let tper = backend.peek_mut(|backend| backend.get_tper(device_idx))?;
     let session = MBREditSession::start(&tper, password.as_bytes()).await?;
@@ -228,11 +269,83 @@ async fn upload_worker(
         return Err(AppError::FileTooLarge);
     }
     let read = async move |chunk: &mut [u8]| file.read(chunk).await.map_err(|_| AppError::FileReadError);
-    let progress = |written| progress_per_mil.store((written * 1000 / len) as u32, Ordering::Relaxed);
+    let progress = |written| progress_per_mil.store(per_mil(written, len), Ordering::Relaxed);
     let cancelled = || cancel_req.load(Ordering::Relaxed);
     session.upload(read, progress, cancelled).await
 }
 
+async fn download(
+    backend: Rc<PeekCell<Backend>>,
+    frontend: Frontend,
+    device_idx: usize,
+    file: String,
+    trim: bool,
+) -> Result<(), AppError> {
+    let Ok(runtime) = tokio::runtime::Builder::new_multi_thread().enable_all().build() else {
+        return Err(AppError::InternalError);
+    };
+    let session = backend.peek(|backend| backend.get_mbr_session(device_idx))?;
+    let progress_per_mil = Arc::new(AtomicU32::new(0));
+    let cancel_req = Arc::new(AtomicBool::new(false));
+    let worker_task = runtime.spawn(download_worker(session, file, trim, progress_per_mil.clone(), cancel_req.clone()));
+    let display_callback =
+        move || download_display(frontend.clone(), device_idx, progress_per_mil.clone(), cancel_req.clone());
+
+    let timer = slint::Timer::default();
+    timer.start(slint::TimerMode::Repeated, Duration::from_millis(16), display_callback);
+    let Ok(result) = worker_task.await else {
+        return Err(AppError::InternalError);
+    };
+    timer.stop();
+    result
+}
+
+/// The MBR is downloaded to a temporary file that replaces `file` only when the download succeeds.
+async fn download_worker(
+    session: Arc<MBREditSession>,
+    file: String,
+    trim: bool,
+    progress_per_mil: Arc<AtomicU32>,
+    cancel_req: Arc<AtomicBool>,
+) -> Result<(), AppError> {
+    let temp_file = format!("{file}.part");
+    let result = match download_to(&session, &temp_file, trim, &progress_per_mil, &cancel_req).await {
+        Ok(()) => tokio::fs::rename(&temp_file, &file).await.map_err(|_| AppError::FileWriteError),
+        Err(error) => Err(error),
+    };
+    if result.is_err() {
+        let _ = tokio::fs::remove_file(&temp_file).await;
+    }
+    result
+}
+
+async fn download_to(
+    session: &MBREditSession,
+    path: &str,
+    trim: bool,
+    progress_per_mil: &AtomicU32,
+    cancel_req: &AtomicBool,
+) -> Result<(), AppError> {
+    let Ok(mut file) = tokio::fs::File::create(path).await else {
+        return Err(AppError::FileNotOpen);
+    };
+    let len = session.get_size().await?;
+    let write = async |chunk: &[u8]| file.write_all(chunk).await.map_err(|_| AppError::FileWriteError);
+    let progress = |read| progress_per_mil.store(per_mil(read, len), Ordering::Relaxed);
+    let cancelled = || cancel_req.load(Ordering::Relaxed);
+    session.download(len, trim, write, progress, cancelled).await?;
+    file.flush().await.map_err(|_| AppError::FileWriteError)?;
+    file.sync_all().await.map_err(|_| AppError::FileWriteError)
+}
+
+/// An empty file or MBR table is complete from the start.
+fn per_mil(done: u64, total: u64) -> u32 {
+    match total {
+        0 => 1000,
+        _ => (done * 1000 / total) as u32,
+    }
+}
+
 fn upload_display(
     frontend: Frontend,
     device_idx: usize,
@@ -245,6 +358,18 @@ fn upload_display(
     cancel_req.store(cancel, Ordering::Relaxed);
 }
 
+fn download_display(
+    frontend: Frontend,
+    device_idx: usize,
+    progress_per_mil: Arc<AtomicU32>,
+    cancel_req: Arc<AtomicBool>,
+) {
+    let progress = progress_per_mil.load(Ordering::Relaxed) as f32 / 1000.0;
+    set_download_progress(&frontend, device_idx, progress);
+    let cancel = get_download_cancel_req(&frontend, device_idx);
+    cancel_req.store(cancel, Ordering::Relaxed);
+}
+
 fn set_login_status(frontend: &Frontend, device_idx: usize, status: ui::ExtendedStatus) {
     frontend.with(|window| {
         let mbr_editor_state = window.global::<ui::MBREditorState>();
@@ -294,6 +419,36 @@ fn set_upload_progress(frontend: &Frontend, device_idx: usize, progress: f32) {
     });
 }
 
+fn set_download_file(frontend: &Frontend, device_idx: usize, file: String) {
+    frontend.with(|window| {
+        let mbr_editor_state = window.global::<ui::MBREditorState>();
+        let download_files = mbr_editor_state.get_download_files();
+        if device_idx < download_files.row_count() {
+            download_files.set_row_data(device_idx, file.into());
+        }
+    });
+}
+
+fn set_download_status(frontend: &Frontend, device_idx: usize, status: ui::ExtendedStatus) {
+    frontend.with(|window| {
+        let mbr_editor_state = window.global::<ui::MBREditorState>();
+        let download_statuses = mbr_editor_state.get_download_statuses();
+        if device_idx < download_statuses.row_count() {
+            download_statuses.set_row_data(device_idx, status);
+        }
+    });
+}
+
+fn set_download_progress(frontend: &Frontend, device_idx: usize, progress: f32) {
+    frontend.with(|window| {
+        let mbr_editor_state = window.global::<ui::MBREditorState>();
+        let download_progress = mbr_editor_state.get_download_progresses();
+        if device_idx < download_progress.row_count() {
+            download_progress.set_row_data(device_idx, progress);
+        }
+    });
+}
+
 fn set_query(frontend: &Frontend, device_idx: usize, result: Result<ui::MBRControl, AppError>) {
     if let Ok(value) = result {
         set_value(frontend, device_idx, value);
@@ -333,3 +488,14 @@ fn get_cancel_req(frontend: &Frontend, device_idx: usize) -> bool {
         .flatten()
         .unwrap_or(false)
 }
+
+fn get_download_cancel_req(frontend: &Frontend, device_idx: usize) -> bool {
+    frontend
+        .with(|window| {
+            let mbr_editor_state = window.global::<ui::MBREditorState>();
+            let cancel_reqs = mbr_editor_state.get_download_cancel_reqs();
+            cancel_reqs.row_data(device_idx)
+        })
+        .flatten()
+        .unwrap_or(false)
+}
@@ -8,7 +8,7 @@ use sed_manager::spec::column_types::{AuthorityRef, LockingRangeRef};
 use slint::{ComponentHandle as _, Model};
 use std::rc::Rc;
 
-use sed_manager::applications::{get_locking_sp, Error as AppError, PermissionEditSession};
+use sed_manager::applications::{get_locking_sp, AtomicEdit as _, Error as AppError, PermissionEditSession};
 
 use crate::backend::{get_object_name, Backend, EditorSession};
 use crate::frontend::Frontend;
@@ -221,7 +221,7 @@ async fn set_mbr_permission(
     let session = backend.peek(|backend| backend.get_permission_session(device_idx))?;
     let (users, _ranges) = backend.peek(|backend| get_cached_matrix(backend, device_idx))?;
     let user = users.get(user_idx).ok_or(AppError::InternalError)?;
-    session.set_mbr_permission(*user, permitted).await?;
+    session.atomic(async |session| session.set_mbr_permission(*user, permitted).await).await?;
     Ok(session.get_mbr_permission(*user).await.unwrap_or(permitted))
 }
 
@@ -236,7 +236,7 @@ async fn set_read_permission(
     let (users, ranges) = backend.peek(|backend| get_cached_matrix(backend, device_idx))?;
     let user = users.get(user_idx).ok_or(AppError::InternalError)?;
     let range = ranges.get(range_idx).ok_or(AppError::InternalError)?;
-    session.set_read_permission(*user, *range, permitted).await?;
+    session.atomic(async |session| session.set_read_permission(*user, *range, permitted).await).await?;
     // Permission may also be affected by class permissions, better refresh.
     Ok(session.get_read_permission(*user, *range).await.unwrap_or(permitted))
 }
@@ -252,7 +252,7 @@ async fn set_write_permission(
     let (users, ranges) = backend.peek(|backend| get_cached_matrix(backend, device_idx))?;
     let user = users.get(user_idx).ok_or(AppError::InternalError)?;
     let range = ranges.get(range_idx).ok_or(AppError::InternalError)?;
-    session.set_write_permission(*user, *range, permitted).await?;
+    session.atomic(async |session| session.set_write_permission(*user, *range, permitted).await).await?;
     // Permission may also be affected by class permissions, better refresh.
     Ok(session.get_write_permission(*user, *range).await.unwrap_or(permitted))
 }
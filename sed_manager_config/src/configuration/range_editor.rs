@@ -5,16 +5,19 @@
 
 use std::rc::Rc;
 
-use sed_manager::messaging::discovery::GeometryDescriptor;
 use sed_manager::spec::objects::LockingRange;
 use slint::{ComponentHandle as _, Model as _};
 
-use sed_manager::applications::{get_locking_sp, Error as AppError, RangeEditSession};
+use sed_manager::applications::{
+    get_locking_sp, get_partition_ranges, snap_range, AtomicEdit as _, Error as AppError, PartitionRange,
+    RangeEditSession, RangeProblem,
+};
+use sed_manager::messaging::discovery::GeometryDescriptor;
 
 use crate::backend::{get_object_name, Backend, EditorSession};
 use crate::frontend::Frontend;
 use crate::ui;
-use crate::utility::{as_vec_model, into_vec_model, PeekCell};
+use crate::utility::{as_vec_model, into_vec_model, run_in_thread, PeekCell};
 
 pub fn init(frontend: &Frontend, num_devices: usize) {
     frontend.with(|window| {
@@ -69,7 +72,11 @@ fn set_callback_list(backend: Rc<PeekCell<Backend>>, frontend: Frontend) {
                 let on_found = move |name, value| {
                     push_range(&frontend, device_idx, name, value);
                 };
-                let result = list(backend, device_idx, on_found).await;
+                let result = list(backend.clone(), device_idx, on_found).await;
+                // Partitions are only offered if the drive has a readable GUID partition table.
+                if let Ok(partitions) = list_partitions(backend, device_idx).await {
+                    set_partitions(&frontend_, device_idx, partitions);
+                }
                 set_login_status(&frontend_, device_idx, ui::ExtendedStatus::from_result(result));
             });
         });
@@ -87,7 +94,10 @@ fn set_callback_set_value(backend: Rc<PeekCell<Backend>>, frontend: Frontend) {
             let range_idx = range_idx as usize;
             set_range_status(&frontend, device_idx, range_idx, ui::ExtendedStatus::loading());
             let _ = slint::spawn_local(async move {
-                let result = set_value(backend, device_idx, range_idx, value).await;
+                let on_validated = |problems: &[RangeProblem], suggested: &ui::LockingRange| {
+                    set_range_problems(&frontend, device_idx, range_idx, suggested, problems);
+                };
+                let result = set_value(backend, device_idx, range_idx, value, on_validated).await;
                 set_range(&frontend, device_idx, range_idx, result);
             });
         });
@@ -149,40 +159,51 @@ async fn list(
     Ok(())
 }
 
+async fn list_partitions(backend: Rc<PeekCell<Backend>>, device_idx: usize) -> Result<Vec<PartitionRange>, AppError> {
+    let device = backend.peek_mut(|backend| backend.get_device(device_idx)).ok_or(AppError::InternalError)?;
+    let discovery = backend.peek(|backend| backend.get_discovery(device_idx).cloned())?;
+    let geometry = discovery.get::<GeometryDescriptor>().cloned();
+    run_in_thread(move || get_partition_ranges(&*device, geometry.as_ref())).await
+}
+
 async fn set_value(
     backend: Rc<PeekCell<Backend>>,
     device_idx: usize,
     range_idx: usize,
     value: ui::LockingRange,
+    on_validated: impl FnOnce(&[RangeProblem], &ui::LockingRange),
 ) -> Result<ui::LockingRange, AppError> {
     let range = backend.peek(|backend| {
         let range_list = backend.get_range_list(device_idx)?;
         range_list.get(range_idx).ok_or(AppError::InternalError).cloned()
     })?;
-    let geometry = backend.peek(|backend| {
-        let default =
-            GeometryDescriptor { align: false, logical_block_size: 1, alignment_granularity: 1, lowest_aligned_lba: 0 };
-        backend
-            .get_discovery(device_idx)
-            .ok()
-            .map(|discovery| discovery.get::<GeometryDescriptor>())
-            .flatten()
-            .cloned()
-            .unwrap_or(default)
-    });
-    let value = align_locking_range(value, &geometry);
     let session = backend.peek_mut(|backend| backend.get_range_session(device_idx))?;
     let lr = LockingRange {
         uid: range,
-        range_start: value.start_lba as u64,
-        range_length: (value.end_lba - value.start_lba) as u64,
+        range_start: std::cmp::max(0, value.start_lba) as u64,
+        range_length: std::cmp::max(0, value.end_lba - value.start_lba) as u64,
         read_lock_enabled: value.read_lock_enabled,
         write_lock_enabled: value.write_lock_enabled,
         read_locked: value.read_locked,
         write_locked: value.write_locked,
         ..Default::default()
     };
-    session.set_range(&lr).await.map(|_| value)
+    let suggested = match session.get_geometry() {
+        Some(geometry) => align_locking_range(value.clone(), geometry),
+        None => value.clone(),
+    };
+    // The transaction keeps the other ranges from changing between validating and setting the range.
+    session
+        .atomic(async |session| {
+            let problems = session.try_set_range(&lr).await?;
+            on_validated(&problems, &suggested);
+            match problems.into_iter().next() {
+                Some(problem) => Err(AppError::InvalidRange(problem)),
+                None => Ok(()),
+            }
+        })
+        .await
+        .map(|_| value)
 }
 
 async fn erase(backend: Rc<PeekCell<Backend>>, device_idx: usize, range_idx: usize) -> Result<(), AppError> {
@@ -222,6 +243,27 @@ fn push_range(frontend: &Frontend, device_idx: usize, name: String, value: ui::L
             as_vec_model(&range_list.names).push(name.into());
             as_vec_model(&range_list.values).push(value);
             as_vec_model(&range_list.statuses).push(ui::ExtendedStatus::success());
+            as_vec_model(&range_list.problems).push(ui::RangeProblems::default());
+            range_lists.set_row_data(device_idx, range_list);
+        }
+    });
+}
+
+fn set_partitions(frontend: &Frontend, device_idx: usize, partitions: Vec<PartitionRange>) {
+    frontend.with(|window| {
+        let range_editor_state = window.global::<ui::RangeEditorState>();
+        let range_lists = range_editor_state.get_range_lists();
+        if let Some(range_list) = range_lists.row_data(device_idx) {
+            for PartitionRange { partition, range_start, range_length } in partitions {
+                let name = match partition.name.as_str() {
+                    "" => format!("Partition {}", partition.number),
+                    name => format!("Partition {}: {name}", partition.number),
+                };
+                let value =
+                    ui::PartitionRange { start_lba: range_start as i64, end_lba: (range_start + range_length) as i64 };
+                as_vec_model(&range_list.partition_names).push(name.into());
+                as_vec_model(&range_list.partition_ranges).push(value);
+            }
             range_lists.set_row_data(device_idx, range_list);
         }
     });
@@ -248,6 +290,34 @@ fn set_range(frontend: &Frontend, device_idx: usize, range_idx: usize, result: R
     });
 }
 
+fn set_range_problems(
+    frontend: &Frontend,
+    device_idx: usize,
+    range_idx: usize,
+    suggested: &ui::LockingRange,
+    problems: &[RangeProblem],
+) {
+    // Overlaps are marked on both the start and the length.
+    let messages: Vec<_> = problems.iter().map(|problem| problem.to_string()).collect();
+    let value = ui::RangeProblems {
+        start: problems.iter().any(|problem| !matches!(problem, RangeProblem::MisalignedLength { .. })),
+        length: problems.iter().any(|problem| !matches!(problem, RangeProblem::MisalignedStart { .. })),
+        message: messages.join("; ").into(),
+        has_suggestion: problems.iter().any(|problem| !matches!(problem, RangeProblem::Overlap { .. })),
+        suggested_start_lba: suggested.start_lba,
+        suggested_end_lba: suggested.end_lba,
+    };
+    frontend.with(|window| {
+        let range_editor_state = window.global::<ui::RangeEditorState>();
+        let range_lists = range_editor_state.get_range_lists();
+        if let Some(range_list) = range_lists.row_data(device_idx) {
+            if range_idx < range_list.problems.row_count() {
+                range_list.problems.set_row_data(range_idx, value);
+            }
+        }
+    });
+}
+
 fn set_range_status(frontend: &Frontend, device_idx: usize, range_idx: usize, status: ui::ExtendedStatus) {
     frontend.with(|window| {
         let range_editor_state = window.global::<ui::RangeEditorState>();
@@ -261,25 +331,24 @@ fn set_range_status(frontend: &Frontend, device_idx: usize, range_idx: usize, st
 }
 
 fn align_lba(lba: u64, block_alignment: u64, first_aligned_block: u64) -> u64 {
-    let block_alignment = std::cmp::max(1, block_alignment); // Zero means no alignment, clamp to 1..infinity.
-    let clamped_lba = std::cmp::max(lba, first_aligned_block);
-    let base_lba = clamped_lba - first_aligned_block;
-    let aligned_base_lba = base_lba / block_alignment * block_alignment;
-    first_aligned_block + aligned_base_lba
+    let geometry = GeometryDescriptor {
+        align: true,
+        logical_block_size: 1,
+        alignment_granularity: block_alignment,
+        lowest_aligned_lba: first_aligned_block,
+    };
+    snap_range(lba, 0, &geometry).0
 }
 
+/// Returns the closest range to `range` that satisfies the geometry, as [`snap_range`] does.
 fn align_locking_range(range: ui::LockingRange, geometry: &GeometryDescriptor) -> ui::LockingRange {
     let start_lba = align_lba(
         std::cmp::max(0, range.start_lba) as u64,
         geometry.alignment_granularity,
         geometry.lowest_aligned_lba,
-    ) as i64;
-    let end_lba = align_lba(
-        std::cmp::max(start_lba, std::cmp::max(0, range.end_lba)) as u64,
-        geometry.alignment_granularity,
-        geometry.lowest_aligned_lba,
-    ) as i64;
-    ui::LockingRange { start_lba, end_lba, ..range }
+    );
+    let (_, length) = snap_range(start_lba, std::cmp::max(0, range.end_lba - range.start_lba) as u64, geometry);
+    ui::LockingRange { start_lba: start_lba as i64, end_lba: (start_lba + length) as i64, ..range }
 }
 
 #[cfg(test)]
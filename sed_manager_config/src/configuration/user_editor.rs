@@ -156,9 +156,9 @@ async fn list(
     let locking_sp = get_locking_sp(ssc.feature_code());
     let users: Vec<_> = session.list_users().await?;
     backend.peek_mut(|backend| backend.set_user_list(device_idx, users.clone()))?;
-    for user in users.iter() {
+    let values = session.get_users(&users).await?;
+    for (user, value) in users.iter().zip(values) {
         let name = get_object_name(Some(&discovery), user.as_uid(), locking_sp.clone().ok());
-        let value = session.get_user(*user).await?;
         on_found(
             name,
             ui::User {
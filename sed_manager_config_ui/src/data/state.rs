@@ -5,15 +5,19 @@
 
 use slint::{ModelRc, ToSharedString, VecModel};
 
-use crate::{ExtendedStatus, LockingRange, PermissionList, PermissionMatrix, RangeList, User, UserList};
+use crate::{ExtendedStatus, LockingRange, PermissionList, PermissionMatrix, RangeList, RangeProblems, User, UserList};
 
 impl RangeList {
     pub fn new(names: Vec<String>, values: Vec<LockingRange>, statuses: Vec<ExtendedStatus>) -> Self {
         let names: Vec<_> = names.into_iter().map(|x| x.to_shared_string()).collect();
+        let problems = vec![RangeProblems::default(); values.len()];
         Self {
             names: ModelRc::new(VecModel::from(names)),
             values: ModelRc::new(VecModel::from(values)),
             statuses: ModelRc::new(VecModel::from(statuses)),
+            problems: ModelRc::new(VecModel::from(problems)),
+            partition_names: ModelRc::new(VecModel::default()),
+            partition_ranges: ModelRc::new(VecModel::default()),
         }
     }
 
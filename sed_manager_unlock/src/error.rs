@@ -15,6 +15,16 @@ pub enum Error {
     NoDevice,
     #[error("Username invalid")]
     InvalidUser,
+    #[error("Invalid arguments: {}", .0)]
+    InvalidArguments(String),
+    #[error("Cannot read credentials: {}", .0)]
+    CredentialsUnavailable(String),
+    #[error("Invalid credentials on line {}", .0)]
+    InvalidCredentials(usize),
+    #[error("Some ranges failed to unlock")]
+    UnlockFailed,
+    #[error("The user has no access to any of the ranges")]
+    NothingUnlocked,
     #[error("{}", .0)]
     DeviceError(DeviceError),
     #[error("{}", .0)]
@@ -9,10 +9,10 @@ use std::sync::Arc;
 use std::{io, usize};
 
 use sed_manager::applications::Error as AppError;
-use sed_manager::applications::{get_general_lookup, get_locking_sp};
-use sed_manager::device::Device;
-use sed_manager::messaging::discovery::Discovery;
-use sed_manager::rpc::{Error as RPCError, MethodStatus, TokioRuntime};
+use sed_manager::applications::{get_general_lookup, get_locking_sp, is_mbr_editor_supported};
+use sed_manager::device::{list_physical_drives, open_device, Device};
+use sed_manager::messaging::discovery::{BlockSIDAuthDescriptor, Discovery};
+use sed_manager::rpc::{block_sid, discover, Error as RPCError, MethodStatus, TokioRuntime};
 use sed_manager::spec::column_types::{AuthorityRef, Name};
 use sed_manager::spec::core::mbr_control;
 use sed_manager::spec::objects::{Authority, LockingRange, MBRControl};
@@ -21,9 +21,11 @@ use sed_manager::tper::{Session, TPer};
 
 mod device_list;
 mod error;
+mod unattended;
 
 use device_list::DeviceList;
 use error::Error;
+use unattended::CredentialSource;
 
 fn check_quit(input: &str) -> Result<(), Error> {
     if input == ":q" || input == ":exit" {
@@ -92,7 +94,7 @@ async fn get_user_by_common_name(name: &str, discovery: &Discovery, tper: &TPer)
     }
     let ssc = discovery.get_primary_ssc().ok_or(AppError::NoAvailableSSC)?;
     let locking_sp = get_locking_sp(ssc.feature_code())?;
-    let session = tper.start_session(locking_sp, None, None).await?;
+    let session = tper.session(locking_sp).read_only().start().await?;
     session
         .with(async |session| {
             let authorities = session.next(table_id::AUTHORITY, None, None).await?;
@@ -107,25 +109,43 @@ async fn get_user_by_common_name(name: &str, discovery: &Discovery, tper: &TPer)
         .await
 }
 
-async fn prompt_login(tper: &TPer, discovery: &Discovery) -> Result<Session, Error> {
+async fn login(tper: &TPer, discovery: &Discovery, name: &str, password: &[u8]) -> Result<Session, Error> {
     let ssc = discovery.get_primary_ssc().ok_or(AppError::NoAvailableSSC)?;
     let locking_sp = get_locking_sp(ssc.feature_code())?;
 
+    let user = get_user_by_name(name, discovery).await.or(get_user_by_common_name(name, discovery, tper).await)?;
+
+    tper.start_session(locking_sp, Some(user), Some(password)).await.map_err(|e| e.into())
+}
+
+async fn prompt_login(tper: &TPer, discovery: &Discovery) -> Result<Session, Error> {
     print!("  Username: ");
     let _ = std::io::stdout().flush();
     let name = read_line()?;
 
-    let user = get_user_by_name(&name, &discovery)
-        .await
-        .or(get_user_by_common_name(&name, discovery, tper).await)?;
-
     let password = rpassword::prompt_password("  Password: ").unwrap();
     check_quit(&password)?;
 
-    tper.start_session(locking_sp, Some(user), Some(password.as_bytes())).await.map_err(|e| e.into())
+    login(tper, discovery, &name, password.as_bytes()).await
+}
+
+/// The outcome of unlocking the shadow MBR or a single locking range.
+struct UnlockResult {
+    object: String,
+    result: Result<(), RPCError>,
+    read: bool,
+    write: bool,
 }
 
-fn print_unlock_result(object: &str, result: Result<(), RPCError>, read: bool, write: bool) {
+impl UnlockResult {
+    /// The user is not necessarily given access to all ranges, so being refused is not a failure.
+    fn is_failed(&self) -> bool {
+        !matches!(self.result, Ok(_) | Err(RPCError::MethodFailed(MethodStatus::NotAuthorized)))
+    }
+}
+
+fn print_unlock_result(unlock_result: &UnlockResult) {
+    let UnlockResult { object, result, read, write } = unlock_result;
     let operations = match (read, write) {
         (false, false) => "",
         (true, false) => "R",
@@ -139,13 +159,16 @@ fn print_unlock_result(object: &str, result: Result<(), RPCError>, read: bool, w
     };
 }
 
-async fn unlock_device(session: &Session, discovery: &Discovery) -> Result<(), Error> {
+async fn unlock_device(session: &Session, discovery: &Discovery) -> Result<Vec<UnlockResult>, Error> {
     let ssc = discovery.get_primary_ssc().ok_or(AppError::NoAvailableSSC)?;
     let locking_sp = get_locking_sp(ssc.feature_code())?;
     let lookup = get_general_lookup(ssc.feature_code());
+    let mut results = Vec::new();
 
-    let mbr_result = session.set(mbr_control::MBR_CONTROL.as_uid(), MBRControl::DONE, true).await;
-    print_unlock_result("MBR", mbr_result, false, false);
+    if is_mbr_editor_supported(discovery) {
+        let mbr_result = session.set(mbr_control::MBR_CONTROL.as_uid(), MBRControl::DONE, true).await;
+        results.push(UnlockResult { object: "MBR".into(), result: mbr_result, read: false, write: false });
+    }
 
     let ranges = session.next(table_id::LOCKING, None, None).await?;
     for range in ranges {
@@ -153,10 +176,15 @@ async fn unlock_device(session: &Session, discovery: &Discovery) -> Result<(), E
         let write_result = session.set(range, LockingRange::WRITE_LOCKED, false).await;
         let (read_ok, write_ok) = (read_result.is_ok(), write_result.is_ok());
         let name = lookup.by_uid(range, Some(locking_sp.as_uid())).unwrap_or(range.to_string());
-        print_unlock_result(&name, read_result.or(write_result), read_ok, write_ok);
+        results.push(UnlockResult {
+            object: name,
+            result: read_result.or(write_result),
+            read: read_ok,
+            write: write_ok,
+        });
     }
 
-    Ok(())
+    Ok(results)
 }
 
 async fn run_pba_sequence(runtime: Arc<TokioRuntime>) -> Result<(), Error> {
@@ -177,11 +205,44 @@ async fn run_pba_sequence(runtime: Arc<TokioRuntime>) -> Result<(), Error> {
     };
     println!();
     println!("Unlocking...");
-    let _ = session.with(async |session| unlock_device(&session, discovery).await).await;
+    if let Ok(results) = session.with(async |session| unlock_device(session, discovery).await).await {
+        results.iter().for_each(print_unlock_result);
+    }
     println!();
     Ok(())
 }
 
+/// Blocks SID authentication on every drive that supports it, so that the ownership
+/// of unowned drives cannot be taken until the next power cycle.
+///
+/// The block is not lifted by a hardware reset, so it persists after rebooting from the PBA.
+fn block_sid_on_all_drives() -> bool {
+    let Ok(paths) = list_physical_drives() else {
+        println!("Failed to block SID authentication: cannot list drives");
+        return false;
+    };
+    let mut success = true;
+    for path in paths {
+        let Ok(device) = open_device(&path) else {
+            continue;
+        };
+        let Ok(discovery) = discover(&*device) else {
+            continue;
+        };
+        if discovery.get::<BlockSIDAuthDescriptor>().is_none() {
+            continue;
+        }
+        match block_sid(&*device, false) {
+            Ok(()) => println!("- {} / {}: SID blocked", device.model_number(), device.serial_number()),
+            Err(error) => {
+                println!("- {} / {}: {error}", device.model_number(), device.serial_number());
+                success = false;
+            }
+        }
+    }
+    success
+}
+
 fn init_logging() -> Option<tracing_appender::non_blocking::WorkerGuard> {
     let temp_dir = std::env::temp_dir();
     let log_file_path = temp_dir.join("sed-manager.log");
@@ -203,9 +264,33 @@ fn init_logging() -> Option<tracing_appender::non_blocking::WorkerGuard> {
 async fn main() -> () {
     let runtime = Arc::new(TokioRuntime::new());
     let _guard = init_logging();
+
+    let mut args: Vec<String> = std::env::args().skip(1).collect();
+    let block_sid = args.iter().any(|arg| arg == "--block-sid");
+    args.retain(|arg| arg != "--block-sid");
+    match CredentialSource::from_args(&args) {
+        Ok(Some(source)) => {
+            let mut exit_code = unattended::run(source, runtime).await;
+            if block_sid && !block_sid_on_all_drives() {
+                exit_code = unattended::EXIT_FAILURE;
+            }
+            std::process::exit(exit_code)
+        }
+        Ok(None) => (),
+        Err(error) => {
+            eprintln!("{error}\n{UNATTENDED_USAGE}");
+            std::process::exit(unattended::EXIT_FAILURE);
+        }
+    }
+
     println!("{BANNER}\nWelcome to SEDManager v{VERSION}!\n{USAGE}\n");
 
     let result = run_pba_sequence(runtime.clone()).await;
+    if block_sid {
+        println!("Blocking SID authentication...");
+        block_sid_on_all_drives();
+        println!();
+    }
     match result {
         Ok(_) => std::thread::sleep(core::time::Duration::from_secs(1)),
         Err(Error::Quit) => (),
@@ -228,3 +313,18 @@ const VERSION: &str = env!("CARGO_PKG_VERSION");
 
 const USAGE: &str = r"Follow the prompts to unlock your drives.
 Enter :q or :exit at any time to quit.";
+
+const UNATTENDED_USAGE: &str = r"Usage: sed-manager-unlock [--key-file <path> | --stdin | --env <variable>] [--block-sid]
+
+With --block-sid, SID authentication is blocked on all drives after unlocking, so that
+the ownership of unowned drives cannot be taken until the next power cycle.
+
+Without a credential source, the drive is unlocked interactively. Otherwise, the credentials
+are read from the given source, one drive per line, in the form
+    <serial=SERIAL | model=MODEL | *> <username> <password>
+The password is everything after the space that follows the username, including spaces.
+Empty lines and lines starting with # are ignored. Every locked or shadowed drive
+that matches a line is unlocked using the first matching line.
+
+The exit code is 0 on success, 1 if a drive failed to unlock, and 2 if no locked drive
+matched the credentials.";
@@ -0,0 +1,250 @@
+//L-----------------------------------------------------------------------------
+//L Copyright (C) Péter Kardos
+//L Please refer to the full license distributed with this software.
+//L-----------------------------------------------------------------------------
+
+use std::io::Read;
+use std::sync::Arc;
+
+use sed_manager::device::Device;
+use sed_manager::messaging::discovery::Discovery;
+use sed_manager::rpc::TokioRuntime;
+use sed_manager::tper::TPer;
+
+use crate::device_list::DeviceList;
+use crate::error::Error;
+use crate::{login, print_unlock_result, unlock_device};
+
+pub const EXIT_SUCCESS: i32 = 0;
+pub const EXIT_FAILURE: i32 = 1;
+/// None of the locked drives matched the credentials.
+pub const EXIT_NO_MATCH: i32 = 2;
+
+/// Where the credentials of the drives are read from in unattended mode.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CredentialSource {
+    KeyFile(String),
+    Stdin,
+    Env(String),
+}
+
+/// Selects the drives a credential applies to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Selector {
+    Serial(String),
+    Model(String),
+    Any,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Credential {
+    pub selector: Selector,
+    pub user: String,
+    pub password: String,
+}
+
+impl CredentialSource {
+    /// Returns `None` if no source was given, meaning the interactive mode should be used.
+    pub fn from_args(args: &[String]) -> Result<Option<Self>, Error> {
+        match args {
+            [] => Ok(None),
+            [flag] if flag == "--stdin" => Ok(Some(Self::Stdin)),
+            [flag, path] if flag == "--key-file" => Ok(Some(Self::KeyFile(path.clone()))),
+            [flag, variable] if flag == "--env" => Ok(Some(Self::Env(variable.clone()))),
+            _ => Err(Error::InvalidArguments(args.join(" "))),
+        }
+    }
+
+    pub fn read(&self) -> Result<String, Error> {
+        match self {
+            Self::KeyFile(path) => {
+                std::fs::read_to_string(path).map_err(|error| Error::CredentialsUnavailable(format!("{path}: {error}")))
+            }
+            Self::Stdin => {
+                let mut text = String::new();
+                std::io::stdin()
+                    .read_to_string(&mut text)
+                    .map_err(|error| Error::CredentialsUnavailable(format!("stdin: {error}")))?;
+                Ok(text)
+            }
+            Self::Env(variable) => {
+                std::env::var(variable).map_err(|error| Error::CredentialsUnavailable(format!("${variable}: {error}")))
+            }
+        }
+    }
+}
+
+impl Selector {
+    pub fn matches(&self, device: &dyn Device) -> bool {
+        match self {
+            Self::Serial(serial) => device.serial_number().trim() == serial,
+            Self::Model(model) => device.model_number().trim() == model,
+            Self::Any => true,
+        }
+    }
+}
+
+/// Parses one credential per line in the form `<serial=SERIAL | model=MODEL | *> <username> <password>`.
+///
+/// The password is the rest of the line after the single space that follows the username,
+/// so it may contain spaces, including leading and trailing ones. The serial, model
+/// and username may contain spaces if they are quoted, e.g. `model="Fast SSD"`.
+pub fn parse_credentials(text: &str) -> Result<Vec<Credential>, Error> {
+    let mut credentials = Vec::new();
+    for (index, line) in text.lines().enumerate() {
+        // Only the line ending is removed, the spaces at the end are part of the password.
+        let line = line.trim_start();
+        if line.trim_end().is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let invalid = || Error::InvalidCredentials(index + 1);
+        let (selector, rest) = split_field(line).ok_or_else(invalid)?;
+        let (user, password) = split_field(rest.trim_start()).ok_or_else(invalid)?;
+        let selector = if selector == "*" {
+            Selector::Any
+        } else if let Some(serial) = selector.strip_prefix("serial=") {
+            Selector::Serial(unquote(serial).into())
+        } else if let Some(model) = selector.strip_prefix("model=") {
+            Selector::Model(unquote(model).into())
+        } else {
+            return Err(invalid());
+        };
+        credentials.push(Credential { selector, user: unquote(user).into(), password: password.into() });
+    }
+    Ok(credentials)
+}
+
+/// Splits off the first whitespace-separated field, treating quoted whitespace as part of the field.
+///
+/// Only the first whitespace character after the field is removed from the rest.
+fn split_field(text: &str) -> Option<(&str, &str)> {
+    let mut in_quotes = false;
+    for (index, c) in text.char_indices() {
+        if c == '"' {
+            in_quotes = !in_quotes;
+        } else if c.is_whitespace() && !in_quotes {
+            let rest = &text[index + c.len_utf8()..];
+            return (!rest.is_empty()).then_some((&text[..index], rest));
+        }
+    }
+    None
+}
+
+fn unquote(text: &str) -> &str {
+    text.strip_prefix('"').and_then(|text| text.strip_suffix('"')).unwrap_or(text)
+}
+
+/// Unlocks every locked or shadowed drive that has matching credentials.
+///
+/// Returns the exit code of the process: non-zero if any drive failed to unlock
+/// or no locked drive matched the credentials.
+pub async fn run(source: CredentialSource, runtime: Arc<TokioRuntime>) -> i32 {
+    let credentials = match source.read().and_then(|text| parse_credentials(&text)) {
+        Ok(credentials) => credentials,
+        Err(error) => {
+            eprintln!("{error}");
+            return EXIT_FAILURE;
+        }
+    };
+    let device_list = match DeviceList::query() {
+        Ok(device_list) => device_list,
+        Err(error) => {
+            eprintln!("{error}");
+            return EXIT_FAILURE;
+        }
+    };
+
+    let mut success = true;
+    let mut num_matched = 0;
+    for (device, discovery) in device_list.shadowed.iter().chain(device_list.locked.iter()) {
+        let Some(credential) = credentials.iter().find(|credential| credential.selector.matches(&**device)) else {
+            continue;
+        };
+        num_matched += 1;
+        println!("{} - {}", device.model_number(), device.serial_number());
+        if let Err(error) = unlock(device.clone(), discovery, credential, runtime.clone()).await {
+            println!("{error}");
+            success = false;
+        }
+    }
+    if num_matched == 0 {
+        eprintln!("No locked drive matched the credentials");
+        return EXIT_NO_MATCH;
+    }
+    match success {
+        true => EXIT_SUCCESS,
+        false => EXIT_FAILURE,
+    }
+}
+
+async fn unlock(
+    device: Arc<dyn Device>,
+    discovery: &Discovery,
+    credential: &Credential,
+    runtime: Arc<TokioRuntime>,
+) -> Result<(), Error> {
+    let tper = TPer::new_on_default_com_id(device, runtime)?;
+    let session = login(&tper, discovery, &credential.user, credential.password.as_bytes()).await?;
+    let results = session.with(async |session| unlock_device(session, discovery).await).await?;
+    results.iter().for_each(print_unlock_result);
+    if results.iter().any(|result| result.is_failed()) {
+        Err(Error::UnlockFailed)
+    } else if results.iter().all(|result| result.result.is_err()) {
+        Err(Error::NothingUnlocked)
+    } else {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_args_interactive() {
+        assert_eq!(CredentialSource::from_args(&[]), Ok(None));
+    }
+
+    #[test]
+    fn from_args_sources() {
+        let args = |args: &[&str]| args.iter().map(|arg| arg.to_string()).collect::<Vec<_>>();
+        assert_eq!(CredentialSource::from_args(&args(&["--stdin"])), Ok(Some(CredentialSource::Stdin)));
+        assert_eq!(
+            CredentialSource::from_args(&args(&["--key-file", "/etc/keys"])),
+            Ok(Some(CredentialSource::KeyFile("/etc/keys".into())))
+        );
+        assert_eq!(
+            CredentialSource::from_args(&args(&["--env", "SED_KEYS"])),
+            Ok(Some(CredentialSource::Env("SED_KEYS".into())))
+        );
+        assert!(CredentialSource::from_args(&args(&["--key-file"])).is_err());
+    }
+
+    #[test]
+    fn parse_credentials_valid() {
+        let text = "# data drives\n\nserial=S1234 Admin1 pass word\r\nmodel=\"Fast SSD\"   \"User 1\" secret\n* Admin1  fb \n";
+        let credentials = parse_credentials(text).unwrap();
+        assert_eq!(
+            credentials,
+            vec![
+                Credential {
+                    selector: Selector::Serial("S1234".into()),
+                    user: "Admin1".into(),
+                    password: "pass word".into()
+                },
+                Credential {
+                    selector: Selector::Model("Fast SSD".into()),
+                    user: "User 1".into(),
+                    password: "secret".into()
+                },
+                Credential { selector: Selector::Any, user: "Admin1".into(), password: " fb ".into() },
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_credentials_invalid() {
+        assert_eq!(parse_credentials("* Admin1\n"), Err(Error::InvalidCredentials(1)));
+        assert_eq!(parse_credentials("\nname=x Admin1 pw\n"), Err(Error::InvalidCredentials(2)));
+    }
+}